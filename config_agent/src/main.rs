@@ -0,0 +1,106 @@
+mod render;
+mod settings;
+
+use config_client::{ConfigClient, StaticToken, Subscription};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+use crate::settings::{AgentConfig, ReloadConfig};
+
+/// Renders configs of a namespace to local files and reloads the process using them when
+/// their content changes
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let path = std::env::args().nth(1);
+    let config = AgentConfig::load(path.as_deref())?;
+
+    let mut client = ConfigClient::new(&config.server_url)?;
+    if let Some(token) = &config.token {
+        client = client.with_token_provider(Arc::new(StaticToken::new(token.clone())));
+    }
+    if let Some(user) = &config.user {
+        client = client.with_user(user);
+    }
+
+    // Subscribe before the first render so no change in between is missed
+    let mut watch = client.watch(Subscription {
+        namespaces: vec![config.namespace.clone()],
+        subscription_id: config.subscription_id.clone(),
+        ..Default::default()
+    });
+
+    let mut changed = false;
+    for file in &config.files {
+        changed |= render::render_file(&client, file).await?;
+    }
+    if changed {
+        reload(&config.reload).await;
+    }
+    tracing::info!(
+        namespace = %config.namespace,
+        files = config.files.len(),
+        "Watching for config changes"
+    );
+
+    while let Some(event) = watch.next().await {
+        let mut changed = false;
+        for file in config
+            .files
+            .iter()
+            .filter(|file| file.config_id == event.event.config_id)
+        {
+            match render::render_file(&client, file).await {
+                Ok(file_changed) => changed |= file_changed,
+                Err(e) => tracing::error!(
+                    config = %file.config_id,
+                    destination = %file.destination.display(),
+                    error = %e,
+                    "Failed to render config"
+                ),
+            }
+        }
+        if changed {
+            tracing::info!(config = %event.event.config_id, "Rendered changed config");
+            reload(&config.reload).await;
+        }
+    }
+    Ok(())
+}
+
+/// Signal the process or run the command configured to pick up rendered files
+async fn reload(reload: &ReloadConfig) {
+    if let Some(pid_file) = &reload.pid_file {
+        let pid = match tokio::fs::read_to_string(pid_file).await {
+            Ok(pid) => pid.trim().parse::<i32>().ok(),
+            Err(e) => {
+                tracing::error!(pid_file = %pid_file.display(), error = %e, "Failed to read PID");
+                None
+            }
+        };
+        if let Some(pid) = pid {
+            if let Err(e) = kill(Pid::from_raw(pid), Signal::SIGHUP) {
+                tracing::error!(pid, error = %e, "Failed to send SIGHUP");
+            }
+        }
+    }
+    if let Some(command) = &reload.command {
+        match tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .status()
+            .await
+        {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::error!(%command, %status, "Reload command failed"),
+            Err(e) => tracing::error!(%command, error = %e, "Failed to run reload command"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use config_client::ConfigClient;
+use config_common::Result;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::settings::FileConfig;
+
+/// Render a file from the current content of its config; `true` when the file changed
+pub async fn render_file(client: &ConfigClient, file: &FileConfig) -> Result<bool> {
+    let (_, content) = client.get(&file.config_id).await?;
+    if content.is_encrypted {
+        return Err(config_common::Error::Validation(format!(
+            "config {} is encrypted",
+            file.config_id
+        )));
+    }
+    let rendered = match &file.template {
+        Some(template) => {
+            let template = tokio::fs::read_to_string(template).await.map_err(|e| {
+                config_common::Error::Config(format!("template {}: {}", template.display(), e))
+            })?;
+            let document = config_core::format::parse(content.format, &content.content)
+                .map_err(config_common::Error::InvalidContent)?;
+            render(&template, &document)?
+        }
+        None => content.content,
+    };
+
+    if tokio::fs::read_to_string(&file.destination)
+        .await
+        .ok()
+        .as_deref()
+        == Some(&rendered)
+    {
+        return Ok(false);
+    }
+    write_atomically(&file.destination, &rendered)
+        .await
+        .map_err(|e| {
+            config_common::Error::Internal(format!("{}: {}", file.destination.display(), e))
+        })?;
+    Ok(true)
+}
+
+/// Replace `{{ path.to.key }}` placeholders with values of the document; list items are
+/// addressed by index, e.g. `{{ servers.0.host }}`
+pub fn render(template: &str, document: &Value) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            config_common::Error::Validation("unclosed {{ in template".to_string())
+        })?;
+        let path = after[..end].trim();
+        let value = lookup(document, path).ok_or_else(|| {
+            config_common::Error::Validation(format!("template key {} not in config", path))
+        })?;
+        match value {
+            Value::String(s) => rendered.push_str(s),
+            value => rendered.push_str(&value.to_string()),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn lookup<'a>(document: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(document, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Write through a temporary file so readers never see a partial file
+async fn write_atomically(destination: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut temporary = destination.as_os_str().to_owned();
+    temporary.push(".tmp");
+    tokio::fs::write(&temporary, content).await?;
+    tokio::fs::rename(&temporary, destination).await
+}
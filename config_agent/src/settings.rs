@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Settings file read when no path is given on the command line
+const DEFAULT_SETTINGS: &str = "config/agent";
+
+/// Environment variable prefix for overriding settings, e.g. `CONFIG_AGENT__NAMESPACE`
+const ENV_PREFIX: &str = "CONFIG_AGENT";
+
+/// Agent settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// Base URL of the config server, e.g. `https://config.example.com`
+    pub server_url: String,
+    /// Bearer token sent with each request
+    #[serde(default)]
+    pub token: Option<String>,
+    /// User to identify as when the server is reached without a gateway
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Namespace whose changes the agent watches
+    pub namespace: String,
+    /// Name under which the server keeps the agent's watch position across restarts
+    #[serde(default)]
+    pub subscription_id: Option<String>,
+    pub files: Vec<FileConfig>,
+    #[serde(default)]
+    pub reload: ReloadConfig,
+}
+
+/// File rendered from a config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConfig {
+    pub config_id: String,
+    pub destination: PathBuf,
+    /// Template with `{{ path.to.key }}` placeholders; the raw content is written without one
+    #[serde(default)]
+    pub template: Option<PathBuf>,
+}
+
+/// What to do after rendered files changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// File holding the PID of a process to send SIGHUP
+    #[serde(default)]
+    pub pid_file: Option<PathBuf>,
+    /// Shell command to run
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl AgentConfig {
+    /// Load settings from `path`, or `config/agent.*`, and the environment
+    pub fn load(path: Option<&str>) -> anyhow::Result<Self> {
+        dotenv::dotenv().ok();
+
+        let settings = ::config::Config::builder()
+            .add_source(::config::File::with_name(path.unwrap_or(DEFAULT_SETTINGS)))
+            .add_source(::config::Environment::with_prefix(ENV_PREFIX).separator("__"))
+            .build()?
+            .try_deserialize()?;
+
+        Ok(settings)
+    }
+}
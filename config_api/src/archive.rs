@@ -0,0 +1,514 @@
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_auth::PolicyEnforcer;
+use config_common::{ConfigContent, ConfigMeta};
+use config_core::{
+    ConfigFilter, ConfigManager, ConfigVersion, ConfigVersionControl, NamespaceManager, NewRelease,
+    ReleaseManager, ReleasePolicy,
+};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+use crate::audit::set_audit_summary;
+use crate::auth::CurrentUser;
+use crate::model::{ExportNamespaceRequest, ImportNamespaceRequest};
+
+/// Layout version of archives written by this server
+const ARCHIVE_FORMAT: u32 = 1;
+
+/// Largest namespace archive accepted for import
+pub const MAX_ARCHIVE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest unpacked size of an imported archive, guarding against compression bombs
+pub(crate) const MAX_UNPACKED_BYTES: u64 = 512 * 1024 * 1024;
+
+const MANIFEST_PATH: &str = "manifest.json";
+const CONFIGS_DIR: &str = "configs/";
+
+/// `manifest.json` of a namespace archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: u32,
+    pub namespace: String,
+    pub exported_at: i64,
+    pub exported_by: String,
+    pub configs: usize,
+    /// Whether configs carry their version history
+    pub versions: bool,
+}
+
+/// Config stored in a namespace archive, with plaintext content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedConfig {
+    pub name: String,
+    pub department: String,
+    pub application: String,
+    pub environment: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub owners: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub version: String,
+    pub content: ConfigContent,
+    /// Version history, oldest first, when exported with versions
+    #[serde(default)]
+    pub versions: Vec<ArchivedVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedVersion {
+    #[serde(flatten)]
+    pub version: ConfigVersion,
+    pub content: ConfigContent,
+}
+
+/// What an import does with a config already in the target namespace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    #[default]
+    Skip,
+    Overwrite,
+    /// Import under a new name next to the existing config
+    Rename,
+}
+
+/// What an import did, or would do, with one archived config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportEntry {
+    pub application: String,
+    pub environment: String,
+    pub name: String,
+    pub action: ImportAction,
+    /// Name the config was imported under when renamed
+    pub imported_as: Option<String>,
+    pub config_id: Option<String>,
+    /// Why the config could not be imported
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub namespace: String,
+    /// Namespace the archive was exported from
+    pub source_namespace: String,
+    pub dry_run: bool,
+    pub entries: Vec<ImportEntry>,
+}
+
+/// `GET /namespaces/{namespace}/export`: every config of a namespace as a tar.gz archive
+#[allow(clippy::too_many_arguments)]
+pub async fn export_namespace(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    query: web::Query<ExportNamespaceRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let namespace = namespaces.get_namespace(&namespace).await?.name;
+
+    let filter = ConfigFilter {
+        namespace: Some(namespace.clone()),
+        ..Default::default()
+    };
+    let mut configs = Vec::new();
+    for meta in config_core::list_all_configs(config_manager.get_ref(), filter).await? {
+        let (meta, content) = config_manager.get_config(&meta.id).await?;
+        let content = config_manager
+            .decrypt_content(&meta, content, &user.0)
+            .await?;
+
+        let mut versions = Vec::new();
+        if query.versions {
+            for version in version_control
+                .get_version_history(&meta.id)
+                .await?
+                .into_iter()
+                .rev()
+            {
+                let (version, content) = version_control
+                    .get_version(&meta.id, &version.version)
+                    .await?;
+                let content = config_manager
+                    .decrypt_content(&meta, content, &user.0)
+                    .await?;
+                versions.push(ArchivedVersion { version, content });
+            }
+        }
+
+        configs.push(ArchivedConfig {
+            name: meta.name,
+            department: meta.department,
+            application: meta.application,
+            environment: meta.environment,
+            description: meta.description,
+            owners: meta.owners,
+            labels: meta.labels,
+            version: meta.version,
+            content,
+            versions,
+        });
+    }
+
+    let manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT,
+        namespace: namespace.clone(),
+        exported_at: chrono::Utc::now().timestamp(),
+        exported_by: user.0.clone(),
+        configs: configs.len(),
+        versions: query.versions,
+    };
+    let archive = pack(&manifest, &configs)?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "exported namespace {} ({} configs{})",
+            namespace,
+            configs.len(),
+            if query.versions {
+                ", with version history"
+            } else {
+                ""
+            }
+        ),
+    );
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!("{}.tar.gz", namespace))],
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("application/gzip")
+        .insert_header(disposition)
+        .body(archive))
+}
+
+/// `POST /namespaces/{namespace}/import`: create or update configs from a namespace archive
+#[allow(clippy::too_many_arguments)]
+pub async fn import_namespace(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    query: web::Query<ImportNamespaceRequest>,
+    body: web::Bytes,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let namespace = namespaces.get_namespace(&namespace).await?.name;
+    let (manifest, configs) = unpack(&body)?;
+
+    let entries = import_configs(
+        config_manager.get_ref(),
+        releases.get_ref(),
+        &release_policy,
+        &namespace,
+        &configs,
+        &query,
+        &user.0,
+    )
+    .await?;
+    let report = ImportReport {
+        namespace: namespace.clone(),
+        source_namespace: manifest.namespace,
+        dry_run: query.dry_run,
+        entries,
+    };
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "{}imported archive of namespace {} into {}: {}",
+            if query.dry_run { "dry run: " } else { "" },
+            report.source_namespace,
+            namespace,
+            summarize(&report.entries)
+        ),
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Create or update configs of a namespace, returning what was done with each config in
+/// order; nothing changes on a dry run
+pub(crate) async fn import_configs(
+    config_manager: &dyn ConfigManager,
+    releases: &dyn ReleaseManager,
+    release_policy: &ReleasePolicy,
+    namespace: &str,
+    configs: &[ArchivedConfig],
+    options: &ImportNamespaceRequest,
+    user: &str,
+) -> config_common::Result<Vec<ImportEntry>> {
+    let filter = ConfigFilter {
+        namespace: Some(namespace.to_string()),
+        ..Default::default()
+    };
+    let existing: HashMap<(String, String, String), ConfigMeta> =
+        config_core::list_all_configs(config_manager, filter)
+            .await?
+            .into_iter()
+            .map(|meta| {
+                let key = (
+                    meta.application.clone(),
+                    meta.environment.clone(),
+                    meta.name.clone(),
+                );
+                (key, meta)
+            })
+            .collect();
+    let mut taken: HashSet<(String, String, String)> = existing.keys().cloned().collect();
+
+    let mut entries = Vec::with_capacity(configs.len());
+    for config in configs {
+        let key = (
+            config.application.clone(),
+            config.environment.clone(),
+            config.name.clone(),
+        );
+        let current = existing.get(&key);
+        let (action, name) = match (current, options.conflict) {
+            (None, _) => (ImportAction::Create, config.name.clone()),
+            (Some(_), ConflictStrategy::Skip) => (ImportAction::Skip, config.name.clone()),
+            (Some(_), ConflictStrategy::Overwrite) => {
+                (ImportAction::Overwrite, config.name.clone())
+            }
+            (Some(_), ConflictStrategy::Rename) => (ImportAction::Rename, free_name(&taken, &key)),
+        };
+        taken.insert((
+            config.application.clone(),
+            config.environment.clone(),
+            name.clone(),
+        ));
+
+        let mut entry = ImportEntry {
+            application: config.application.clone(),
+            environment: config.environment.clone(),
+            name: config.name.clone(),
+            action,
+            imported_as: (name != config.name).then(|| name.clone()),
+            config_id: current.map(|meta| meta.id.clone()),
+            error: None,
+        };
+        if !options.dry_run {
+            let result = match (action, current) {
+                (ImportAction::Skip, _) => None,
+                (ImportAction::Overwrite, Some(current)) => Some(
+                    config_manager
+                        .update_config(
+                            &current.id,
+                            config.description.as_deref(),
+                            config.content.clone(),
+                            options.change_reason.as_deref(),
+                            user,
+                        )
+                        .await,
+                ),
+                _ => Some(create_imported(config_manager, namespace, &name, config, user).await),
+            };
+            match result {
+                Some(Ok(meta)) => {
+                    // Later updates of release-managed configs stay drafts until released
+                    if action != ImportAction::Overwrite && release_policy.is_managed(namespace) {
+                        releases
+                            .create_release(NewRelease {
+                                config_id: meta.id.clone(),
+                                version: meta.version.clone(),
+                                requested_by: user.to_string(),
+                                ..Default::default()
+                            })
+                            .await?;
+                    }
+                    entry.config_id = Some(meta.id);
+                }
+                Some(Err(e)) => entry.error = Some(e.to_string()),
+                None => {}
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Counts of an import's entries by action, for audit summaries
+pub(crate) fn summarize(entries: &[ImportEntry]) -> String {
+    let count = |action: ImportAction| {
+        entries
+            .iter()
+            .filter(|entry| entry.action == action && entry.error.is_none())
+            .count()
+    };
+    format!(
+        "{} created, {} overwritten, {} renamed, {} skipped, {} failed",
+        count(ImportAction::Create),
+        count(ImportAction::Overwrite),
+        count(ImportAction::Rename),
+        count(ImportAction::Skip),
+        entries.iter().filter(|entry| entry.error.is_some()).count()
+    )
+}
+
+/// Create an archived config, replaying its version history when it has one
+async fn create_imported(
+    config_manager: &dyn ConfigManager,
+    namespace: &str,
+    name: &str,
+    config: &ArchivedConfig,
+    user: &str,
+) -> config_common::Result<ConfigMeta> {
+    let (first, rest) = match config.versions.split_first() {
+        Some((first, rest)) => (first.content.clone(), rest),
+        None => (config.content.clone(), &[][..]),
+    };
+    let mut meta = config_manager
+        .create_config(
+            name,
+            namespace,
+            &config.department,
+            &config.application,
+            &config.environment,
+            config.description.as_deref(),
+            first,
+            user,
+        )
+        .await?;
+    for version in rest {
+        meta = config_manager
+            .update_config(
+                &meta.id,
+                version.version.description.as_deref(),
+                version.content.clone(),
+                version.version.change_reason.as_deref(),
+                user,
+            )
+            .await?;
+    }
+
+    if !config.owners.is_empty() {
+        meta = config_manager
+            .update_owners(&meta.id, config.owners.clone(), user)
+            .await?;
+    }
+    if !config.labels.is_empty() {
+        meta = config_manager
+            .update_labels(&meta.id, config.labels.clone(), user)
+            .await?;
+    }
+    Ok(meta)
+}
+
+/// First `<name>-imported[-N]` not taken in the config's application and environment
+fn free_name(taken: &HashSet<(String, String, String)>, key: &(String, String, String)) -> String {
+    let (application, environment, name) = key;
+    (1..)
+        .map(|n| match n {
+            1 => format!("{}-imported", name),
+            n => format!("{}-imported-{}", name, n),
+        })
+        .find(|candidate| {
+            !taken.contains(&(application.clone(), environment.clone(), candidate.clone()))
+        })
+        .unwrap_or_default()
+}
+
+/// Write a manifest and configs as a tar.gz archive
+fn pack(manifest: &ArchiveManifest, configs: &[ArchivedConfig]) -> config_common::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let mut append = |path: String, data: Vec<u8>| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(manifest.exported_at.max(0) as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data.as_slice())
+    };
+
+    append(
+        MANIFEST_PATH.to_string(),
+        serde_json::to_vec_pretty(manifest)?,
+    )
+    .map_err(archive_error)?;
+    for (index, config) in configs.iter().enumerate() {
+        append(
+            format!("{}{:06}.json", CONFIGS_DIR, index),
+            serde_json::to_vec_pretty(config)?,
+        )
+        .map_err(archive_error)?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(archive_error)
+}
+
+/// Read the manifest and configs of a tar.gz archive, in archive order
+fn unpack(archive: &[u8]) -> config_common::Result<(ArchiveManifest, Vec<ArchivedConfig>)> {
+    let invalid = |e: String| config_common::Error::Validation(format!("invalid archive: {}", e));
+    let mut reader = tar::Archive::new(GzDecoder::new(archive).take(MAX_UNPACKED_BYTES));
+
+    let mut manifest: Option<ArchiveManifest> = None;
+    let mut configs: Vec<(String, ArchivedConfig)> = Vec::new();
+    for entry in reader.entries().map_err(|e| invalid(e.to_string()))? {
+        let mut entry = entry.map_err(|e| invalid(e.to_string()))?;
+        let path = entry
+            .path()
+            .map_err(|e| invalid(e.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+        if path != MANIFEST_PATH && !(path.starts_with(CONFIGS_DIR) && path.ends_with(".json")) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| invalid(e.to_string()))?;
+        if path == MANIFEST_PATH {
+            manifest = Some(
+                serde_json::from_slice(&data).map_err(|e| invalid(format!("{}: {}", path, e)))?,
+            );
+        } else {
+            let config =
+                serde_json::from_slice(&data).map_err(|e| invalid(format!("{}: {}", path, e)))?;
+            configs.push((path, config));
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid(format!("missing {}", MANIFEST_PATH)))?;
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(invalid(format!(
+            "archive format {} is newer than the supported {}",
+            manifest.format, ARCHIVE_FORMAT
+        )));
+    }
+    configs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok((
+        manifest,
+        configs.into_iter().map(|(_, config)| config).collect(),
+    ))
+}
+
+fn archive_error(e: std::io::Error) -> config_common::Error {
+    config_common::Error::Internal(format!("failed to write archive: {}", e))
+}
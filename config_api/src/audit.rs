@@ -0,0 +1,109 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage, HttpRequest};
+use config_audit::{AuditService, ConfigDiff};
+use config_common::AuditLog;
+
+use crate::auth::caller_identity;
+use crate::freeze::FreezeOverridden;
+
+/// Header carrying the request id, generated when the client doesn't send one
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Human readable summary of a change, attached by handlers for the audit record
+#[derive(Debug, Clone)]
+pub struct AuditSummary(pub String);
+
+/// Attach a change summary to the audit record of the current request
+pub fn set_audit_summary(req: &HttpRequest, summary: impl Into<String>) {
+    req.extensions_mut().insert(AuditSummary(summary.into()));
+}
+
+/// Attach a content diff to the audit record of the current request
+pub fn set_audit_diff(req: &HttpRequest, diff: ConfigDiff) {
+    req.extensions_mut().insert(diff);
+}
+
+/// Middleware recording an audit log entry for every mutating request
+pub async fn capture(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let verb = match *req.method() {
+        Method::POST => "create",
+        Method::PUT | Method::PATCH => "update",
+        Method::DELETE => "delete",
+        _ => return next.call(req).await,
+    };
+
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let user = caller_identity(req.request()).unwrap_or_else(|| "anonymous".to_string());
+    let ip = req
+        .connection_info()
+        .realip_remote_addr()
+        .unwrap_or("unknown")
+        .to_string();
+    let resource = req.path().to_string();
+    let action = match req.match_pattern() {
+        Some(pattern) => format!("{}.{}", resource_kind(&pattern), verb),
+        None => verb.to_string(),
+    };
+    let audit = req.app_data::<web::Data<dyn AuditService>>().cloned();
+
+    let mut res = next.call(req).await?;
+
+    let (summary, diff, freeze_override) = {
+        let extensions = res.request().extensions();
+        (
+            extensions.get::<AuditSummary>().map(|s| s.0.clone()),
+            extensions.get::<ConfigDiff>().cloned(),
+            extensions.get::<FreezeOverridden>().map(|o| o.0.clone()),
+        )
+    };
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+
+    if let Some(audit) = audit {
+        let details = serde_json::json!({
+            "status": res.status().as_u16(),
+            "ip": ip,
+            "request_id": request_id,
+            "summary": summary,
+            "diff": diff,
+            "freeze_override": freeze_override,
+        });
+        let log = AuditLog {
+            id: uuid::Uuid::new_v4().to_string(),
+            user,
+            action,
+            resource,
+            details: details.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        if let Err(e) = audit.record(log).await {
+            tracing::error!(error = %e, request_id = %request_id, "Failed to record audit log");
+        }
+    }
+
+    Ok(res)
+}
+
+/// Resource kind from a route pattern, e.g. `/api/v1/configs/{id}/owners` -> `configs.owners`
+fn resource_kind(pattern: &str) -> String {
+    pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && !s.starts_with('{'))
+        .skip_while(|s| *s == "api" || *s == "v1")
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,48 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use config_auth::PolicyEnforcer;
+use std::future::{ready, Ready};
+
+/// Header carrying the caller identity, set by the API gateway after authentication
+pub const USER_HEADER: &str = "X-User-Id";
+
+/// Header carrying the gateway's shared secret, vouching for the caller identity
+pub const GATEWAY_TOKEN_HEADER: &str = "X-Gateway-Token";
+
+/// Authenticated caller of a REST request
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub String);
+
+impl FromRequest for CurrentUser {
+    type Error = config_common::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(match caller_identity(req) {
+            Some(user) => Ok(CurrentUser(user)),
+            None if req.headers().contains_key(USER_HEADER) => Err(config_common::Error::Auth(
+                "caller identity not vouched for by a trusted gateway".to_string(),
+            )),
+            None => Err(config_common::Error::Auth(
+                "missing caller identity".to_string(),
+            )),
+        })
+    }
+}
+
+/// Caller identity of a request, taken only from a source the server trusts: requests
+/// carrying the gateway token, or any request when `auth.trust_identity_header` is set.
+/// Direct connections can't act as another user by setting the header themselves.
+pub fn caller_identity(req: &HttpRequest) -> Option<String> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    };
+    let user = header(USER_HEADER)?;
+    let enforcer = req.app_data::<web::Data<PolicyEnforcer>>()?;
+    enforcer
+        .config()
+        .accepts_identity(header(GATEWAY_TOKEN_HEADER))
+        .then(|| user.to_string())
+}
@@ -0,0 +1,137 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_auth::PolicyEnforcer;
+use config_core::BackupManager;
+
+use crate::audit::set_audit_summary;
+use crate::auth::CurrentUser;
+use crate::model::RestoreBackupRequest;
+
+/// `POST /admin/backup`: start a consistent snapshot of the database
+pub async fn start_backup(
+    http_req: HttpRequest,
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let job = backup_service(backups)?.start_backup(&user.0).await?;
+    set_audit_summary(
+        &http_req,
+        format!("started backup {} to {}", job.id, job.location),
+    );
+    Ok(HttpResponse::Accepted().json(job))
+}
+
+/// `GET /admin/backups`: backups at the configured location, newest first
+pub async fn list_backups(
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let backups = backup_service(backups)?.list_backups().await?;
+    Ok(HttpResponse::Ok().json(backups))
+}
+
+/// `GET /admin/backup/jobs`: backups started since this server started
+pub async fn list_backup_jobs(
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let jobs = backup_service(backups)?.list_backup_jobs().await?;
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+/// `GET /admin/backup/jobs/{id}`: progress of a backup
+pub async fn get_backup_job(
+    id: web::Path<String>,
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let job = backup_service(backups)?.get_backup_job(&id).await?;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// `POST /admin/restore`: replace the database with a backup, optionally moving configs to a
+/// point in time. Other nodes should be restarted afterwards to drop what they cached.
+pub async fn restore_backup(
+    http_req: HttpRequest,
+    req: web::Json<RestoreBackupRequest>,
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let until = match &req.until {
+        Some(until) => Some(
+            chrono::DateTime::parse_from_rfc3339(until)
+                .map_err(|e| {
+                    config_common::Error::Validation(format!(
+                        "invalid restore time {}: {}",
+                        until, e
+                    ))
+                })?
+                .timestamp(),
+        ),
+        None => None,
+    };
+
+    let report = backup_service(backups)?
+        .restore_backup(&req.backup, until, &user.0)
+        .await?;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "restored backup {} to {} ({} tables, {} rows)",
+            report.backup,
+            chrono::DateTime::from_timestamp(report.restored_to, 0)
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            report.tables,
+            report.rows
+        ),
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// `POST /admin/backups/{name}/verify`: check that a backup could be restored and its
+/// secrets decrypted with the current keys, without restoring it; the result is signed
+pub async fn verify_backup(
+    http_req: HttpRequest,
+    name: web::Path<String>,
+    user: CurrentUser,
+    backups: Option<web::Data<dyn BackupManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let signed = backup_service(backups)?
+        .verify_backup(&name, &user.0)
+        .await?;
+    let verification = &signed.verification;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "verified backup {}: {} ({} of {} sampled secrets decrypted)",
+            verification.backup,
+            if verification.passed {
+                "passed"
+            } else {
+                "failed"
+            },
+            verification.secrets_decrypted,
+            verification.secrets_sampled
+        ),
+    );
+    Ok(HttpResponse::Ok().json(signed))
+}
+
+fn backup_service(
+    backups: Option<web::Data<dyn BackupManager>>,
+) -> config_common::Result<web::Data<dyn BackupManager>> {
+    backups
+        .ok_or_else(|| config_common::Error::Validation("backups are not configured".to_string()))
+}
@@ -0,0 +1,272 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_auth::PolicyEnforcer;
+use config_common::{ConfigContent, ConfigMeta};
+use config_core::{
+    BatchWrite, ConfigFilter, ConfigManager, NewRelease, ReleaseManager, ReleasePolicy,
+};
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::archive::MAX_ARCHIVE_BYTES;
+use crate::audit::set_audit_summary;
+use crate::auth::CurrentUser;
+use crate::importer::parse_format;
+use crate::model::BulkImportRequest;
+
+/// What a bulk import did, or would do, with one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkAction {
+    Create,
+    Update,
+    /// The config already has the file's content
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkImportEntry {
+    /// Path of the file as uploaded
+    pub path: String,
+    pub namespace: String,
+    pub application: String,
+    pub environment: String,
+    pub name: String,
+    pub action: BulkAction,
+    pub config_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkImportReport {
+    pub dry_run: bool,
+    pub entries: Vec<BulkImportEntry>,
+    /// Files not laid out as `<namespace>/<application>/<environment>/<name>.<format>`, or
+    /// whose format isn't supported
+    pub ignored: Vec<String>,
+}
+
+/// File of an upload mapped onto a config
+struct TreeFile {
+    path: String,
+    namespace: String,
+    application: String,
+    environment: String,
+    name: String,
+    content: ConfigContent,
+}
+
+/// `POST /admin/import`: create or update configs from a multipart upload of a directory
+/// tree laid out as `<namespace>/<application>/<environment>/<name>.<format>`, each part
+/// named by its path. All writes are applied in one batch, or none are.
+#[allow(clippy::too_many_arguments)]
+pub async fn bulk_import(
+    http_req: HttpRequest,
+    query: web::Query<BulkImportRequest>,
+    payload: Multipart,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let (files, ignored) = read_tree(payload).await?;
+
+    let mut seen = HashMap::new();
+    for file in &files {
+        let key = (
+            &file.namespace,
+            &file.application,
+            &file.environment,
+            &file.name,
+        );
+        if let Some(other) = seen.insert(key, &file.path) {
+            return Err(config_common::Error::Validation(format!(
+                "{} and {} are the same config",
+                other, file.path
+            )));
+        }
+    }
+
+    let namespaces: BTreeSet<&String> = files.iter().map(|file| &file.namespace).collect();
+    let mut existing: HashMap<(String, String, String, String), ConfigMeta> = HashMap::new();
+    for namespace in namespaces {
+        let filter = ConfigFilter {
+            namespace: Some(namespace.clone()),
+            ..Default::default()
+        };
+        for meta in config_core::list_all_configs(config_manager.get_ref(), filter).await? {
+            let key = (
+                meta.namespace.clone(),
+                meta.application.clone(),
+                meta.environment.clone(),
+                meta.name.clone(),
+            );
+            existing.insert(key, meta);
+        }
+    }
+
+    let mut entries = Vec::with_capacity(files.len());
+    let mut writes = Vec::new();
+    for file in files {
+        let key = (
+            file.namespace.clone(),
+            file.application.clone(),
+            file.environment.clone(),
+            file.name.clone(),
+        );
+        let current = existing.get(&key);
+        let action = match current {
+            None => BulkAction::Create,
+            Some(meta) => {
+                let (meta, content) = config_manager.get_config(&meta.id).await?;
+                let content = config_manager
+                    .decrypt_content(&meta, content, &user.0)
+                    .await?;
+                if content.content == file.content.content
+                    && content.format.as_str() == file.content.format.as_str()
+                {
+                    BulkAction::Unchanged
+                } else {
+                    BulkAction::Update
+                }
+            }
+        };
+
+        match (action, current) {
+            (BulkAction::Create, _) => writes.push(BatchWrite::Create {
+                name: file.name.clone(),
+                namespace: file.namespace.clone(),
+                department: query.department.clone(),
+                application: file.application.clone(),
+                environment: file.environment.clone(),
+                description: None,
+                content: file.content,
+            }),
+            (BulkAction::Update, Some(meta)) => writes.push(BatchWrite::Update {
+                config_id: meta.id.clone(),
+                base_version: meta.version.clone(),
+                description: meta.description.clone(),
+                content: file.content,
+            }),
+            _ => {}
+        }
+        entries.push(BulkImportEntry {
+            path: file.path,
+            namespace: file.namespace,
+            application: file.application,
+            environment: file.environment,
+            name: file.name,
+            action,
+            config_id: current.map(|meta| meta.id.clone()),
+        });
+    }
+
+    if !query.dry_run && !writes.is_empty() {
+        let applied = config_manager
+            .apply_batch(writes, query.change_reason.as_deref(), &user.0)
+            .await?;
+        let written = entries
+            .iter_mut()
+            .filter(|entry| entry.action != BulkAction::Unchanged);
+        for (entry, meta) in written.zip(applied) {
+            // Later updates of release-managed configs stay drafts until released
+            if entry.action == BulkAction::Create && release_policy.is_managed(&entry.namespace) {
+                releases
+                    .create_release(NewRelease {
+                        config_id: meta.id.clone(),
+                        version: meta.version.clone(),
+                        requested_by: user.0.clone(),
+                        ..Default::default()
+                    })
+                    .await?;
+            }
+            entry.config_id = Some(meta.id);
+        }
+    }
+
+    let count = |action: BulkAction| entries.iter().filter(|e| e.action == action).count();
+    set_audit_summary(
+        &http_req,
+        format!(
+            "{}bulk imported {} files: {} created, {} updated, {} unchanged, {} ignored",
+            if query.dry_run { "dry run: " } else { "" },
+            entries.len() + ignored.len(),
+            count(BulkAction::Create),
+            count(BulkAction::Update),
+            count(BulkAction::Unchanged),
+            ignored.len()
+        ),
+    );
+    Ok(HttpResponse::Ok().json(BulkImportReport {
+        dry_run: query.dry_run,
+        entries,
+        ignored,
+    }))
+}
+
+/// Read the files of an upload, mapping each onto a config by the last four components of
+/// its path so the uploaded directory itself may be included
+async fn read_tree(mut payload: Multipart) -> config_common::Result<(Vec<TreeFile>, Vec<String>)> {
+    let invalid = |e: String| config_common::Error::Validation(format!("invalid upload: {}", e));
+
+    let mut files = Vec::new();
+    let mut ignored = Vec::new();
+    let mut received = 0;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| invalid(e.to_string()))?;
+        let path = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .map(String::from);
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| invalid(e.to_string()))?;
+            received += chunk.len();
+            if received > MAX_ARCHIVE_BYTES {
+                return Err(invalid(format!("larger than {} bytes", MAX_ARCHIVE_BYTES)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        let Some(path) = path else {
+            continue;
+        };
+
+        let components: Vec<&str> = path
+            .split(['/', '\\'])
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect();
+        let [.., namespace, application, environment, file_name] = components[..] else {
+            ignored.push(path);
+            continue;
+        };
+        let Some((name, extension)) = file_name.rsplit_once('.') else {
+            ignored.push(path);
+            continue;
+        };
+        let (Some(format), Ok(content)) = (parse_format(extension), String::from_utf8(data)) else {
+            ignored.push(path);
+            continue;
+        };
+        if components.contains(&"..") || name.is_empty() {
+            ignored.push(path);
+            continue;
+        }
+
+        files.push(TreeFile {
+            namespace: namespace.to_string(),
+            application: application.to_string(),
+            environment: environment.to_string(),
+            name: name.to_string(),
+            content: ConfigContent {
+                format,
+                content,
+                is_encrypted: false,
+            },
+            path,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((files, ignored))
+}
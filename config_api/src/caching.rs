@@ -0,0 +1,73 @@
+use actix_web::http::header::{self, CacheDirective, EntityTag, HttpDate};
+use actix_web::http::StatusCode;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime};
+
+/// HTTP caching of config reads
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachePolicy {
+    /// Seconds a client may reuse a config read before asking again; 0 makes clients
+    /// revalidate every read
+    #[serde(default)]
+    pub max_age_secs: u32,
+}
+
+impl CachePolicy {
+    /// Responses depend on the caller, so only the client may keep them
+    fn directives(&self) -> Vec<CacheDirective> {
+        let freshness = match self.max_age_secs {
+            0 => CacheDirective::NoCache,
+            secs => CacheDirective::MaxAge(secs),
+        };
+        vec![CacheDirective::Private, freshness]
+    }
+}
+
+/// Answer a config read, or `304 Not Modified` when the client's copy is current. The ETag
+/// hashes the body, so it also covers content drawn from other configs; pass `modified` only
+/// when the body changes with nothing but the config's update time.
+pub(crate) fn respond(
+    http_req: &HttpRequest,
+    policy: &CachePolicy,
+    modified: Option<i64>,
+    mut response: HttpResponseBuilder,
+    content_type: &str,
+    body: Vec<u8>,
+) -> HttpResponse {
+    // Weak, as compression changes the bytes on the wire but not the config
+    let etag = EntityTag::new_weak(format!("{:x}", Sha256::digest(&body)));
+    let last_modified = modified
+        .and_then(|secs| u64::try_from(secs).ok())
+        .and_then(|secs| SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+        .map(HttpDate::from);
+
+    response
+        .insert_header(header::CacheControl(policy.directives()))
+        .insert_header(header::ETag(etag.clone()));
+    if let Some(last_modified) = last_modified {
+        response.insert_header(header::LastModified(last_modified));
+    }
+    if unchanged(http_req, &etag, last_modified) {
+        return response.status(StatusCode::NOT_MODIFIED).finish();
+    }
+    response.content_type(content_type).body(body)
+}
+
+/// Whether the client's conditional headers match; `If-None-Match` takes precedence over
+/// `If-Modified-Since`
+fn unchanged(http_req: &HttpRequest, etag: &EntityTag, last_modified: Option<HttpDate>) -> bool {
+    match http_req.get_header::<header::IfNoneMatch>() {
+        Some(header::IfNoneMatch::Any) => return true,
+        Some(header::IfNoneMatch::Items(tags)) => return tags.iter().any(|tag| tag.weak_eq(etag)),
+        None => {}
+    }
+    match (
+        last_modified,
+        http_req.get_header::<header::IfModifiedSince>(),
+    ) {
+        (Some(last_modified), Some(header::IfModifiedSince(since))) => last_modified <= since,
+        _ => false,
+    }
+}
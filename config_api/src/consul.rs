@@ -0,0 +1,192 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use base64::Engine;
+use config_common::ConfigMeta;
+use config_core::{ConfigFilter, ConfigManager, ConfigVersionControl, EventBus, ReleaseManager};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Header carrying the index clients pass back to block until something changes
+const INDEX_HEADER: &str = "X-Consul-Index";
+
+const DEFAULT_WAIT: Duration = Duration::from_secs(300);
+const MAX_WAIT: Duration = Duration::from_secs(600);
+
+/// Query of Consul's `GET /v1/kv/{key}`; flags are set by being present, e.g. `?recurse`
+#[derive(Debug, Deserialize)]
+pub struct KvQuery {
+    pub recurse: Option<String>,
+    pub keys: Option<String>,
+    pub raw: Option<String>,
+    pub separator: Option<String>,
+    /// Block until the index moves past this one
+    pub index: Option<i64>,
+    /// How long to block, e.g. `30s` or `5m`
+    pub wait: Option<String>,
+}
+
+/// Entry of a Consul KV listing
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct KvPair {
+    pub key: String,
+    pub value: Option<String>,
+    pub flags: u64,
+    pub lock_index: u64,
+    pub create_index: i64,
+    pub modify_index: i64,
+}
+
+/// `GET /v1/kv/{key}`: configs as Consul keys `namespace/application/environment/name`, with
+/// the content served to the caller
+pub async fn get_kv(
+    http_req: HttpRequest,
+    key: web::Path<String>,
+    query: web::Query<KvQuery>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let key = key.into_inner();
+    let prefix_match = flag(&query.recurse) || flag(&query.keys);
+    let namespace = key
+        .split('/')
+        .next()
+        .filter(|n| !n.is_empty() && n.len() < key.len());
+    let filter = ConfigFilter {
+        namespace: namespace.map(String::from),
+        ..Default::default()
+    };
+
+    // Subscribe before reading so a change between the read and the wait isn't missed
+    let mut changes = events.subscribe();
+    let deadline = Instant::now() + wait_duration(query.wait.as_deref())?;
+    let (configs, index) = loop {
+        let configs: Vec<ConfigMeta> =
+            config_core::list_all_configs(config_manager.get_ref(), filter.clone())
+                .await?
+                .into_iter()
+                .filter(|meta| {
+                    let config_key = kv_key(meta);
+                    if prefix_match {
+                        config_key.starts_with(&key)
+                    } else {
+                        config_key == key
+                    }
+                })
+                .collect();
+        let index = configs
+            .iter()
+            .map(|meta| meta.updated_at)
+            .max()
+            .unwrap_or(0);
+        if query.index.is_none_or(|known| known != index) {
+            break (configs, index);
+        }
+        let changed = tokio::time::timeout_at(deadline, async {
+            loop {
+                match changes.recv().await {
+                    Ok(event) if namespace.is_some_and(|n| n != event.event.namespace) => {}
+                    _ => return,
+                }
+            }
+        })
+        .await;
+        if changed.is_err() {
+            break (configs, index);
+        }
+    };
+
+    if configs.is_empty() {
+        return Ok(HttpResponse::NotFound()
+            .insert_header((INDEX_HEADER, index.to_string()))
+            .finish());
+    }
+
+    let mut response = HttpResponse::Ok();
+    response.insert_header((INDEX_HEADER, index.to_string()));
+
+    if flag(&query.keys) {
+        let mut keys: Vec<String> = configs
+            .iter()
+            .map(kv_key)
+            .map(|config_key| match query.separator.as_deref() {
+                Some(separator) if !separator.is_empty() => {
+                    // Keys below the next separator collapse into their folder
+                    match config_key[key.len()..].find(separator) {
+                        Some(at) => config_key[..key.len() + at + separator.len()].to_string(),
+                        None => config_key,
+                    }
+                }
+                _ => config_key,
+            })
+            .collect();
+        keys.sort();
+        keys.dedup();
+        return Ok(response.json(keys));
+    }
+
+    let client = crate::handlers::rollout_client(&http_req, None, None);
+    let mut pairs = Vec::with_capacity(configs.len());
+    for meta in configs {
+        let (meta, content) = config_manager.get_config(&meta.id).await?;
+        let (meta, content) = config_core::releases::served(
+            releases.get_ref(),
+            version_control.get_ref(),
+            meta,
+            content,
+            &client,
+        )
+        .await?;
+        let content = config_manager.redact_content(content)?;
+        // Ciphertext means nothing to Consul clients, so encrypted configs read as empty
+        let value = (!content.is_encrypted).then_some(content.content);
+
+        if flag(&query.raw) && !prefix_match {
+            return Ok(response.body(value.unwrap_or_default()));
+        }
+        pairs.push(KvPair {
+            key: kv_key(&meta),
+            value: value.map(|v| base64::engine::general_purpose::STANDARD.encode(v)),
+            flags: 0,
+            lock_index: 0,
+            create_index: meta.created_at,
+            modify_index: meta.updated_at,
+        });
+    }
+    pairs.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(response.json(pairs))
+}
+
+/// Key of a config in the KV facades: `namespace/application/environment/name`
+pub(crate) fn kv_key(meta: &ConfigMeta) -> String {
+    format!(
+        "{}/{}/{}/{}",
+        meta.namespace, meta.application, meta.environment, meta.name
+    )
+}
+
+fn flag(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| v != "false")
+}
+
+/// Consul wait durations: a number with an `ms`, `s`, `m` or `h` suffix
+fn wait_duration(wait: Option<&str>) -> config_common::Result<Duration> {
+    let Some(wait) = wait else {
+        return Ok(DEFAULT_WAIT);
+    };
+    let invalid = || config_common::Error::Validation(format!("invalid wait: {}", wait));
+    let split = wait
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(wait.len());
+    let amount: u64 = wait[..split].parse().map_err(|_| invalid())?;
+    let duration = match &wait[split..] {
+        "ms" => Duration::from_millis(amount),
+        "" | "s" => Duration::from_secs(amount),
+        "m" => Duration::from_secs(amount * 60),
+        "h" => Duration::from_secs(amount * 3600),
+        _ => return Err(invalid()),
+    };
+    Ok(duration.min(MAX_WAIT))
+}
@@ -0,0 +1,556 @@
+use config_common::{ConfigEventType, ConfigMeta};
+use config_core::{
+    ConfigFilter, ConfigManager, ConfigVersionControl, EventBus, EventFilter, PublishedEvent,
+    ReleaseManager, RolloutClient,
+};
+use config_proto::etcd::kv_server::{Kv, KvServer};
+use config_proto::etcd::watch_server::{Watch, WatchServer};
+use config_proto::etcd::{
+    event::EventType, range_request::SortOrder, range_request::SortTarget,
+    watch_create_request::FilterType, watch_request::RequestUnion, Event, KeyValue, RangeRequest,
+    RangeResponse, ResponseHeader, WatchCreateRequest, WatchRequest, WatchResponse,
+};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::consul::kv_key;
+
+/// Responses buffered for a slow watch stream before events wait on it
+const WATCH_BUFFER: usize = 256;
+
+/// Events replayed per outbox read when a watch starts at a past revision
+const REPLAY_BATCH_SIZE: i64 = 100;
+
+/// etcd gateway settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EtcdConfig {
+    /// Address of the gRPC listener; the gateway is off when unset
+    #[serde(default)]
+    pub listen: Option<SocketAddr>,
+}
+
+/// etcd v3 KV and Watch services over the config store. Keys are
+/// `namespace/application/environment/name`, values the config content served to the client,
+/// and revisions the Unix time of the change in seconds.
+#[derive(Clone)]
+pub struct EtcdGateway {
+    config_manager: Arc<dyn ConfigManager>,
+    version_control: Arc<dyn ConfigVersionControl>,
+    releases: Arc<dyn ReleaseManager>,
+    events: Arc<EventBus>,
+}
+
+impl EtcdGateway {
+    pub fn new(
+        config_manager: Arc<dyn ConfigManager>,
+        version_control: Arc<dyn ConfigVersionControl>,
+        releases: Arc<dyn ReleaseManager>,
+        events: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            config_manager,
+            version_control,
+            releases,
+            events,
+        }
+    }
+
+    /// Serve the KV and Watch services until the listener fails
+    pub async fn serve(self, addr: SocketAddr) -> config_common::Result<()> {
+        tonic::transport::Server::builder()
+            .add_service(KvServer::new(self.clone()))
+            .add_service(WatchServer::new(self))
+            .serve(addr)
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("etcd gateway: {}", e)))
+    }
+
+    /// Configs whose keys fall in a range, without content
+    async fn configs_in(&self, range: &KeyRange) -> config_common::Result<Vec<ConfigMeta>> {
+        let filter = ConfigFilter {
+            namespace: range.namespace(),
+            ..Default::default()
+        };
+        let configs = config_core::list_all_configs(self.config_manager.as_ref(), filter).await?;
+        Ok(configs
+            .into_iter()
+            .filter(|meta| range.contains(kv_key(meta).as_bytes()))
+            .collect())
+    }
+
+    /// Key-value of a config with the content served to the client
+    async fn key_value(
+        &self,
+        meta: &ConfigMeta,
+        client: &RolloutClient,
+    ) -> config_common::Result<KeyValue> {
+        let (meta, content) = self.config_manager.get_config(&meta.id).await?;
+        let (meta, content) = config_core::releases::served(
+            self.releases.as_ref(),
+            self.version_control.as_ref(),
+            meta,
+            content,
+            client,
+        )
+        .await?;
+        let content = self.config_manager.redact_content(content)?;
+        let mut kv = meta_key_value(&meta);
+        // Ciphertext means nothing to etcd clients, so encrypted configs read as empty
+        if !content.is_encrypted {
+            kv.value = content.content.into_bytes();
+        }
+        Ok(kv)
+    }
+
+    /// etcd event for a change event, `None` for events that don't change content
+    async fn watch_event(
+        &self,
+        event: &PublishedEvent,
+        keys: &mut HashMap<String, String>,
+        client: &RolloutClient,
+    ) -> config_common::Result<Option<Event>> {
+        let event = &event.event;
+        match event.event_type {
+            ConfigEventType::Deleted => {
+                let Some(key) = keys.remove(&event.config_id) else {
+                    return Ok(None);
+                };
+                Ok(Some(Event {
+                    r#type: EventType::Delete as i32,
+                    kv: Some(KeyValue {
+                        key: key.into_bytes(),
+                        mod_revision: event.timestamp,
+                        ..Default::default()
+                    }),
+                    prev_kv: None,
+                }))
+            }
+            ConfigEventType::Created
+            | ConfigEventType::Updated
+            | ConfigEventType::Released
+            | ConfigEventType::Rolled => {
+                let meta = match self.config_manager.get_config(&event.config_id).await {
+                    Ok((meta, _)) => meta,
+                    // Deleted since, as when replaying old events
+                    Err(config_common::Error::NotFound(_)) => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+                keys.insert(meta.id.clone(), kv_key(&meta));
+                Ok(Some(Event {
+                    r#type: EventType::Put as i32,
+                    kv: Some(self.key_value(&meta, client).await?),
+                    prev_kv: None,
+                }))
+            }
+            ConfigEventType::SecretExpiring
+            | ConfigEventType::SecretExpired
+            | ConfigEventType::ApprovalRequested
+            | ConfigEventType::AuditAnomaly => Ok(None),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Kv for EtcdGateway {
+    async fn range(
+        &self,
+        request: Request<RangeRequest>,
+    ) -> Result<Response<RangeResponse>, Status> {
+        let client = rollout_client(&request);
+        let request = request.into_inner();
+        if request.revision > 0 {
+            return Err(Status::unimplemented(
+                "reads at a past revision are not supported",
+            ));
+        }
+        let (sort_target, sort_order) = (request.sort_target(), request.sort_order());
+        let range = KeyRange::new(request.key, request.range_end);
+        let mut configs: Vec<ConfigMeta> = self
+            .configs_in(&range)
+            .await
+            .map_err(status)?
+            .into_iter()
+            .filter(|meta| {
+                (request.min_mod_revision == 0 || meta.updated_at >= request.min_mod_revision)
+                    && (request.max_mod_revision == 0
+                        || meta.updated_at <= request.max_mod_revision)
+                    && (request.min_create_revision == 0
+                        || meta.created_at >= request.min_create_revision)
+                    && (request.max_create_revision == 0
+                        || meta.created_at <= request.max_create_revision)
+            })
+            .collect();
+        let count = configs.len() as i64;
+        let header = Some(header(chrono::Utc::now().timestamp()));
+        if request.count_only {
+            return Ok(Response::new(RangeResponse {
+                header,
+                kvs: Vec::new(),
+                more: false,
+                count,
+            }));
+        }
+
+        let mut kvs = Vec::with_capacity(configs.len());
+        configs.sort_by_key(kv_key);
+        for meta in &configs {
+            let kv = if request.keys_only {
+                meta_key_value(meta)
+            } else {
+                self.key_value(meta, &client).await.map_err(status)?
+            };
+            kvs.push(kv);
+        }
+        match sort_target {
+            SortTarget::Key => {}
+            SortTarget::Version => kvs.sort_by_key(|kv| kv.version),
+            SortTarget::Create => kvs.sort_by_key(|kv| kv.create_revision),
+            SortTarget::Mod => kvs.sort_by_key(|kv| kv.mod_revision),
+            SortTarget::Value => kvs.sort_by(|a, b| a.value.cmp(&b.value)),
+        }
+        if sort_order == SortOrder::Descend {
+            kvs.reverse();
+        }
+        let more = request.limit > 0 && count > request.limit;
+        if more {
+            kvs.truncate(request.limit as usize);
+        }
+        Ok(Response::new(RangeResponse {
+            header,
+            kvs,
+            more,
+            count,
+        }))
+    }
+}
+
+type WatchStream = Pin<Box<dyn Stream<Item = Result<WatchResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Watch for EtcdGateway {
+    async fn watch(
+        &self,
+        request: Request<Streaming<WatchRequest>>,
+    ) -> Result<Response<WatchStream>, Status> {
+        let (sender, receiver) = mpsc::channel(WATCH_BUFFER);
+        let connection = WatchConnection {
+            gateway: self.clone(),
+            client: rollout_client(&request),
+            live: self.events.subscribe(),
+            sender,
+            watchers: HashMap::new(),
+            keys: HashMap::new(),
+            next_id: 1,
+        };
+        tokio::spawn(connection.serve(request.into_inner()));
+        let responses = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|response| (response, receiver))
+        });
+        Ok(Response::new(Box::pin(responses)))
+    }
+}
+
+/// Watches created on one Watch stream
+struct WatchConnection {
+    gateway: EtcdGateway,
+    /// Rollouts select the connection by its address, as etcd clients send no identity
+    client: RolloutClient,
+    live: broadcast::Receiver<PublishedEvent>,
+    sender: mpsc::Sender<Result<WatchResponse, Status>>,
+    watchers: HashMap<i64, Watcher>,
+    /// Keys of known configs by ID, to name the key of a deleted config
+    keys: HashMap<String, String>,
+    next_id: i64,
+}
+
+struct Watcher {
+    range: KeyRange,
+    puts: bool,
+    deletes: bool,
+    /// Replayed up to this event; live events up to it are duplicates
+    replayed_seq: i64,
+}
+
+impl WatchConnection {
+    async fn serve(mut self, mut requests: Streaming<WatchRequest>) {
+        loop {
+            let open = tokio::select! {
+                request = requests.message() => match request {
+                    Ok(Some(request)) => self.receive(request).await,
+                    Ok(None) | Err(_) => false,
+                },
+                event = self.live.recv() => match event {
+                    Ok(event) => self.deliver(&event, None).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "etcd watch stream fell behind");
+                        self.cancel_all("watch fell behind the event stream").await
+                    }
+                    Err(broadcast::error::RecvError::Closed) => false,
+                },
+            };
+            if !open {
+                return;
+            }
+        }
+    }
+
+    async fn receive(&mut self, request: WatchRequest) -> bool {
+        match request.request_union {
+            Some(RequestUnion::CreateRequest(create)) => self.create(create).await,
+            Some(RequestUnion::CancelRequest(cancel)) => {
+                self.watchers.remove(&cancel.watch_id);
+                self.send(WatchResponse {
+                    header: Some(header(chrono::Utc::now().timestamp())),
+                    watch_id: cancel.watch_id,
+                    canceled: true,
+                    ..Default::default()
+                })
+                .await
+            }
+            Some(RequestUnion::ProgressRequest(_)) => {
+                // A response without events tells the client the revision it has seen
+                self.send(WatchResponse {
+                    header: Some(header(chrono::Utc::now().timestamp())),
+                    watch_id: -1,
+                    ..Default::default()
+                })
+                .await
+            }
+            None => true,
+        }
+    }
+
+    async fn create(&mut self, create: WatchCreateRequest) -> bool {
+        let watch_id = if create.watch_id > 0 {
+            create.watch_id
+        } else {
+            self.next_id
+        };
+        self.next_id = self.next_id.max(watch_id + 1);
+        let range = KeyRange::new(create.key.clone(), create.range_end.clone());
+        let watcher = Watcher {
+            range,
+            puts: !create.filters.contains(&(FilterType::Noput as i32)),
+            deletes: !create.filters.contains(&(FilterType::Nodelete as i32)),
+            replayed_seq: 0,
+        };
+
+        let existing = match self.gateway.configs_in(&watcher.range).await {
+            Ok(existing) => existing,
+            Err(e) => return self.reject(watch_id, &e.to_string()).await,
+        };
+        self.keys
+            .extend(existing.iter().map(|meta| (meta.id.clone(), kv_key(meta))));
+        self.watchers.insert(watch_id, watcher);
+        let created = self
+            .send(WatchResponse {
+                header: Some(header(chrono::Utc::now().timestamp())),
+                watch_id,
+                created: true,
+                ..Default::default()
+            })
+            .await;
+        if !created || create.start_revision <= 0 {
+            return created;
+        }
+        self.replay(watch_id, create.start_revision).await
+    }
+
+    /// Send the watcher the changes since a revision, from the event outbox; puts carry the
+    /// current content, as past content isn't kept per revision
+    async fn replay(&mut self, watch_id: i64, start_revision: i64) -> bool {
+        let Some(outbox) = self.gateway.events.outbox().cloned() else {
+            return self
+                .reject(watch_id, "events are not stored for replay")
+                .await;
+        };
+        let mut filter = EventFilter {
+            since: Some(start_revision),
+            namespace: self.watchers[&watch_id].range.namespace(),
+            ..Default::default()
+        };
+        loop {
+            let events = match outbox.list_events(&filter, REPLAY_BATCH_SIZE).await {
+                Ok(events) => events,
+                Err(e) => return self.reject(watch_id, &e.to_string()).await,
+            };
+            for event in &events {
+                if !self.deliver(event, Some(watch_id)).await {
+                    return false;
+                }
+            }
+            match events.last() {
+                Some(last) if events.len() as i64 == REPLAY_BATCH_SIZE => filter.after = last.seq,
+                Some(last) => {
+                    if let Some(watcher) = self.watchers.get_mut(&watch_id) {
+                        watcher.replayed_seq = last.seq;
+                    }
+                    return true;
+                }
+                None => return true,
+            }
+        }
+    }
+
+    /// Send an event to the watchers whose range it falls in, or only to one watcher
+    async fn deliver(&mut self, event: &PublishedEvent, only: Option<i64>) -> bool {
+        let etcd_event = match self
+            .gateway
+            .watch_event(event, &mut self.keys, &self.client)
+            .await
+        {
+            Ok(Some(etcd_event)) => etcd_event,
+            Ok(None) => return true,
+            Err(e) => {
+                tracing::warn!(
+                    config = %event.event.config_id,
+                    error = %e,
+                    "Failed to read changed config for etcd watch"
+                );
+                return true;
+            }
+        };
+        let key = etcd_event
+            .kv
+            .as_ref()
+            .map(|kv| kv.key.as_slice())
+            .unwrap_or_default();
+        let is_put = etcd_event.r#type == EventType::Put as i32;
+        let watch_ids: Vec<i64> = self
+            .watchers
+            .iter()
+            .filter(|(id, _)| only.is_none_or(|only| only == **id))
+            .filter(|(_, watcher)| {
+                let wanted = if is_put {
+                    watcher.puts
+                } else {
+                    watcher.deletes
+                };
+                let replayed = only.is_none() && event.seq > 0 && event.seq <= watcher.replayed_seq;
+                wanted && !replayed && watcher.range.contains(key)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for watch_id in watch_ids {
+            let response = WatchResponse {
+                header: Some(header(event.event.timestamp)),
+                watch_id,
+                events: vec![etcd_event.clone()],
+                ..Default::default()
+            };
+            if !self.send(response).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn cancel_all(&mut self, reason: &str) -> bool {
+        let watch_ids: Vec<i64> = self.watchers.keys().copied().collect();
+        for watch_id in watch_ids {
+            if !self.reject(watch_id, reason).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Cancel a watch the gateway can't serve
+    async fn reject(&mut self, watch_id: i64, reason: &str) -> bool {
+        self.watchers.remove(&watch_id);
+        self.send(WatchResponse {
+            header: Some(header(chrono::Utc::now().timestamp())),
+            watch_id,
+            canceled: true,
+            cancel_reason: reason.to_string(),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn send(&self, response: WatchResponse) -> bool {
+        self.sender.send(Ok(response)).await.is_ok()
+    }
+}
+
+/// Keys from `key` up to `range_end`, with etcd's conventions: an empty end selects `key`
+/// alone and `\0` every key from `key` on
+struct KeyRange {
+    key: Vec<u8>,
+    range_end: Vec<u8>,
+}
+
+impl KeyRange {
+    fn new(key: Vec<u8>, range_end: Vec<u8>) -> Self {
+        Self { key, range_end }
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        match self.range_end.as_slice() {
+            [] => key == self.key.as_slice(),
+            [0] => key >= self.key.as_slice(),
+            end => key >= self.key.as_slice() && key < end,
+        }
+    }
+
+    /// Namespace every key of the range is in, when the range stays within one
+    fn namespace(&self) -> Option<String> {
+        let key = std::str::from_utf8(&self.key).ok()?;
+        let (namespace, _) = key.split_once('/')?;
+        let within = self.range_end.is_empty()
+            || (self.range_end != [0] && self.range_end <= prefix_end(namespace));
+        within.then(|| namespace.to_string())
+    }
+}
+
+/// End of the range of keys starting with `namespace/`
+fn prefix_end(namespace: &str) -> Vec<u8> {
+    let mut end = format!("{}/", namespace).into_bytes();
+    // '/' + 1 is '0', so the range stops before any sibling namespace
+    if let Some(last) = end.last_mut() {
+        *last += 1;
+    }
+    end
+}
+
+/// Client of a gateway request for rollout rules
+fn rollout_client<T>(request: &Request<T>) -> RolloutClient {
+    RolloutClient {
+        address: request.remote_addr().map(|addr| addr.ip()),
+        ..Default::default()
+    }
+}
+
+fn meta_key_value(meta: &ConfigMeta) -> KeyValue {
+    KeyValue {
+        key: kv_key(meta).into_bytes(),
+        create_revision: meta.created_at,
+        mod_revision: meta.updated_at,
+        version: 1,
+        value: Vec::new(),
+        lease: 0,
+    }
+}
+
+fn header(revision: i64) -> ResponseHeader {
+    ResponseHeader {
+        revision,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn status(e: config_common::Error) -> Status {
+    match e {
+        config_common::Error::NotFound(message) => Status::not_found(message),
+        config_common::Error::Validation(message) => Status::invalid_argument(message),
+        config_common::Error::Auth(message) => Status::unauthenticated(message),
+        config_common::Error::Authorization(message) => Status::permission_denied(message),
+        e => Status::internal(e.to_string()),
+    }
+}
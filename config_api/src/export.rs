@@ -0,0 +1,64 @@
+use actix_web::web::Bytes;
+use config_common::AuditLog;
+use serde::Deserialize;
+
+/// Supported audit export formats
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+
+    /// Leading bytes written before the first record
+    pub fn header(&self) -> Option<Bytes> {
+        match self {
+            ExportFormat::Csv => Some(Bytes::from_static(
+                b"id,user,action,resource,details,timestamp\n",
+            )),
+            ExportFormat::Jsonl => None,
+        }
+    }
+
+    /// Encode a single record, including the trailing newline
+    pub fn encode(&self, log: &AuditLog) -> config_common::Result<Bytes> {
+        let mut line = match self {
+            ExportFormat::Csv => [
+                csv_field(&log.id),
+                csv_field(&log.user),
+                csv_field(&log.action),
+                csv_field(&log.resource),
+                csv_field(&log.details),
+                log.timestamp.to_string(),
+            ]
+            .join(","),
+            ExportFormat::Jsonl => serde_json::to_string(log)?,
+        };
+        line.push('\n');
+        Ok(Bytes::from(line))
+    }
+}
+
+/// Quote a CSV field when it contains separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
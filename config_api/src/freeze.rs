@@ -0,0 +1,54 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage};
+use config_auth::PolicyEnforcer;
+
+use crate::auth::caller_identity;
+
+/// Header asking to write through open freeze windows; its value is the reason
+pub const FREEZE_OVERRIDE_HEADER: &str = "x-freeze-override";
+
+/// Policy resource whose `override` action lets a caller write through freeze windows
+pub const FREEZE_RESOURCE: &str = "freeze_windows";
+
+/// Reason a write went through an open freeze window, for the audit record
+#[derive(Debug, Clone)]
+pub struct FreezeOverridden(pub String);
+
+/// Middleware waiving freeze windows for callers allowed to override them who ask to
+pub async fn overriding(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let reason = match req
+        .headers()
+        .get(FREEZE_OVERRIDE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.trim().is_empty())
+    {
+        Some(reason) => reason.trim().to_string(),
+        None => return next.call(req).await,
+    };
+    let user = caller_identity(req.request()).ok_or_else(|| {
+        config_common::Error::Auth(
+            "overriding a freeze window requires a caller identity".to_string(),
+        )
+    })?;
+    let enforcer = req
+        .app_data::<web::Data<PolicyEnforcer>>()
+        .cloned()
+        .ok_or_else(|| {
+            config_common::Error::Internal("policy enforcer is not configured".to_string())
+        })?;
+    enforcer.check(&user, FREEZE_RESOURCE, "override").await?;
+
+    let (res, used) = config_core::freeze::with_override(&user, &reason, next.call(req)).await;
+    let res = res?;
+    if used {
+        res.request()
+            .extensions_mut()
+            .insert(FreezeOverridden(reason));
+    }
+    Ok(res)
+}
@@ -0,0 +1,108 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_auth::PolicyEnforcer;
+use config_core::GitSync;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::audit::set_audit_summary;
+use crate::auth::CurrentUser;
+
+/// HMAC-SHA256 of the body GitHub, and Gitea in GitHub mode, sign webhooks with
+const GITHUB_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+
+/// Secret GitLab sends as is with webhooks
+const GITLAB_TOKEN_HEADER: &str = "X-Gitlab-Token";
+
+/// `POST /git/{repository}/sync`: pull a repository and apply its configs now
+pub async fn sync_repository(
+    http_req: HttpRequest,
+    repository: web::Path<String>,
+    user: CurrentUser,
+    git_sync: Option<web::Data<dyn GitSync>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let report = git_sync_service(git_sync)?.sync(&repository).await?;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "synced git repository {} at {}",
+            report.repository, report.commit
+        ),
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// `POST /git/{repository}/webhook`: push notification of the Git host, authenticated by the
+/// repository's webhook secret
+pub async fn webhook(
+    http_req: HttpRequest,
+    repository: web::Path<String>,
+    body: web::Bytes,
+    git_sync: Option<web::Data<dyn GitSync>>,
+) -> config_common::Result<HttpResponse> {
+    let git_sync = git_sync_service(git_sync)?;
+    let secret = git_sync.webhook_secret(&repository)?.ok_or_else(|| {
+        config_common::Error::Authorization(format!(
+            "webhooks are not enabled for git repository {}",
+            repository
+        ))
+    })?;
+    verify_webhook(&http_req, &body, &secret)?;
+
+    let report = git_sync.sync(&repository).await?;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "synced git repository {} at {} on webhook",
+            report.repository, report.commit
+        ),
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+fn git_sync_service(
+    git_sync: Option<web::Data<dyn GitSync>>,
+) -> config_common::Result<web::Data<dyn GitSync>> {
+    git_sync
+        .ok_or_else(|| config_common::Error::Validation("git sync is not configured".to_string()))
+}
+
+/// Check the GitHub signature or GitLab token of a webhook request
+fn verify_webhook(req: &HttpRequest, body: &[u8], secret: &str) -> config_common::Result<()> {
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+    let valid = if let Some(signature) = header(GITHUB_SIGNATURE_HEADER) {
+        let signature = signature
+            .strip_prefix("sha256=")
+            .and_then(decode_hex)
+            .unwrap_or_default();
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    } else if let Some(token) = header(GITLAB_TOKEN_HEADER) {
+        constant_time_eq(token.as_bytes(), secret.as_bytes())
+    } else {
+        false
+    };
+    if !valid {
+        return Err(config_common::Error::Auth(
+            "invalid webhook signature".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
@@ -0,0 +1,432 @@
+use config_audit::{AuditFilter, AuditService};
+use config_auth::{AuthConfig, PolicyEnforcer};
+use config_common::{ConfigEventType, ConfigMeta};
+use config_core::{
+    ConfigFilter, ConfigManager, ConfigVersionControl, EventBus, LabelSelector, NamespaceManager,
+    PublishedEvent,
+};
+use config_proto::config_service_server::{ConfigService, ConfigServiceServer};
+use config_proto::watch_config_response::EventType;
+use config_proto::{
+    AuditLog, ConfigSummary, ConfigVersion, ConfigVersionResponse, GetConfigVersionRequest,
+    ListAuditLogsRequest, ListAuditLogsResponse, ListConfigsStreamRequest, WatchConfigRequest,
+    WatchConfigResponse,
+};
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tonic::{Request, Response, Status};
+
+use crate::etcd::status;
+use crate::handlers::archived_namespaces;
+
+/// Metadata carrying the caller identity, as `X-User-Id` does for REST requests
+const USER_METADATA: &str = "x-user-id";
+
+/// Metadata carrying the gateway token, as `X-Gateway-Token` does for REST requests
+const GATEWAY_TOKEN_METADATA: &str = "x-gateway-token";
+
+/// Responses buffered for a slow watch stream before events wait on it
+const WATCH_BUFFER: usize = 256;
+
+/// Events read from the outbox at a time while replaying
+const REPLAY_BATCH_SIZE: i64 = 100;
+
+/// gRPC API settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    /// Address of the gRPC listener; the API is off when unset
+    #[serde(default)]
+    pub listen: Option<SocketAddr>,
+}
+
+/// `ConfigService` over the config store. Only the RPCs implemented here are served; the
+/// rest answer `Unimplemented`.
+#[derive(Clone)]
+pub struct GrpcApi {
+    config_manager: Arc<dyn ConfigManager>,
+    version_control: Arc<dyn ConfigVersionControl>,
+    namespaces: Arc<dyn NamespaceManager>,
+    audit: Arc<dyn AuditService>,
+    enforcer: Arc<PolicyEnforcer>,
+    events: Arc<EventBus>,
+}
+
+impl GrpcApi {
+    pub fn new(
+        config_manager: Arc<dyn ConfigManager>,
+        version_control: Arc<dyn ConfigVersionControl>,
+        namespaces: Arc<dyn NamespaceManager>,
+        audit: Arc<dyn AuditService>,
+        enforcer: Arc<PolicyEnforcer>,
+        events: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            config_manager,
+            version_control,
+            namespaces,
+            audit,
+            enforcer,
+            events,
+        }
+    }
+
+    /// Serve `ConfigService` until the listener fails
+    pub async fn serve(self, addr: SocketAddr) -> config_common::Result<()> {
+        tonic::transport::Server::builder()
+            .add_service(ConfigServiceServer::new(self))
+            .serve(addr)
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("grpc api: {}", e)))
+    }
+}
+
+type SummaryStream = Pin<Box<dyn Stream<Item = Result<ConfigSummary, Status>> + Send>>;
+
+type WatchConfigStream = Pin<Box<dyn Stream<Item = Result<WatchConfigResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl ConfigService for GrpcApi {
+    #[allow(clippy::result_large_err)]
+    async fn list_configs_stream(
+        &self,
+        request: Request<ListConfigsStreamRequest>,
+    ) -> Result<Response<SummaryStream>, Status> {
+        let req = request.into_inner();
+        let mut filter = ConfigFilter {
+            namespace: Some(req.namespace).filter(|namespace| !namespace.is_empty()),
+            department: req.department,
+            application: req.application,
+            environment: req.environment,
+            exclude_namespaces: Vec::new(),
+        };
+        if filter.namespace.is_none() && !req.include_archived {
+            filter.exclude_namespaces = archived_namespaces(self.namespaces.as_ref())
+                .await
+                .map_err(status)?;
+        }
+
+        // Read from the cursor as the client takes configs, so HTTP/2 flow control holds it
+        // back for slow clients
+        let configs = self
+            .config_manager
+            .stream_configs(filter)
+            .await
+            .map_err(status)?
+            .map(|meta| meta.map(summary).map_err(status));
+        Ok(Response::new(Box::pin(configs)))
+    }
+
+    async fn get_config_version(
+        &self,
+        request: Request<GetConfigVersionRequest>,
+    ) -> Result<Response<ConfigVersionResponse>, Status> {
+        let req = request.into_inner();
+        let (version, content) = self
+            .version_control
+            .get_version(&req.id, &req.version)
+            .await
+            .map_err(status)?;
+        let content = self
+            .config_manager
+            .redact_content(content)
+            .map_err(status)?;
+        Ok(Response::new(ConfigVersionResponse {
+            version: Some(ConfigVersion {
+                version: version.version,
+                created_at: version.created_at,
+                created_by: version.created_by,
+                description: version.description,
+                tags: version.tags,
+                change_reason: version.change_reason,
+            }),
+            is_encrypted: content.is_encrypted,
+            value: content.into_bytes(),
+        }))
+    }
+
+    async fn watch_config(
+        &self,
+        request: Request<WatchConfigRequest>,
+    ) -> Result<Response<WatchConfigStream>, Status> {
+        let req = request.into_inner();
+        let selector = match req.label_selector.trim() {
+            "" => None,
+            selector => Some(
+                selector
+                    .parse::<LabelSelector>()
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?,
+            ),
+        };
+        if req.id.is_empty() && selector.is_none() {
+            return Err(Status::invalid_argument(
+                "watch needs a config id or a label selector",
+            ));
+        }
+
+        let (sender, receiver) = mpsc::channel(WATCH_BUFFER);
+        let watch = ConfigWatch {
+            // Subscribe before replaying so nothing published meanwhile is missed
+            live: self.events.subscribe(),
+            events: self.events.clone(),
+            config_id: req.id,
+            selector,
+            cursor: None,
+            seq: 0,
+            sender,
+        };
+        let subscription_id = Some(req.subscription_id).filter(|id| !id.is_empty());
+        let resume_from = Some(req.resume_from).filter(|seq| *seq > 0);
+        tokio::spawn(watch.serve(subscription_id, resume_from));
+
+        let responses = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|response| (response, receiver))
+        });
+        Ok(Response::new(Box::pin(responses)))
+    }
+
+    async fn list_audit_logs(
+        &self,
+        request: Request<ListAuditLogsRequest>,
+    ) -> Result<Response<ListAuditLogsResponse>, Status> {
+        let user = caller(self.enforcer.config(), &request)?;
+        self.enforcer.check_auditor(&user).await.map_err(status)?;
+
+        let req = request.into_inner();
+        let filter = AuditFilter {
+            user: req.user,
+            action: req.action,
+            resource: req.resource,
+            start_time: req.start_time,
+            end_time: req.end_time,
+        };
+        let page_size = match req.page_size {
+            0 => 10,
+            size => size.clamp(1, 1000),
+        };
+        let page_number = req.page_number.max(1);
+
+        let (logs, total) = self
+            .audit
+            .get_logs(filter, page_size, page_number)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(ListAuditLogsResponse {
+            logs: logs
+                .into_iter()
+                .map(|log| AuditLog {
+                    id: log.id,
+                    user: log.user,
+                    action: log.action,
+                    resource: log.resource,
+                    details: log.details,
+                    timestamp: log.timestamp,
+                })
+                .collect(),
+            total,
+        }))
+    }
+}
+
+/// One `WatchConfig` stream, following the event outbox like WebSocket subscriptions do
+struct ConfigWatch {
+    events: Arc<EventBus>,
+    live: broadcast::Receiver<PublishedEvent>,
+    config_id: String,
+    selector: Option<LabelSelector>,
+    /// Outbox consumer storing the position of a named subscription
+    cursor: Option<String>,
+    /// Sequence number of the last event delivered
+    seq: i64,
+    sender: mpsc::Sender<Result<WatchConfigResponse, Status>>,
+}
+
+impl ConfigWatch {
+    async fn serve(mut self, subscription_id: Option<String>, resume_from: Option<i64>) {
+        if let Err(e) = self.resume(subscription_id, resume_from).await {
+            let _ = self.sender.send(Err(status(e))).await;
+            return;
+        }
+        loop {
+            let open = tokio::select! {
+                event = self.live.recv() => match event {
+                    Ok(event) => self.deliver(&event).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        self.catch_up(skipped).await
+                    }
+                    Err(broadcast::error::RecvError::Closed) => false,
+                },
+                _ = self.sender.closed() => false,
+            };
+            if !open {
+                return;
+            }
+        }
+    }
+
+    /// Name the subscription and queue what it missed since its stored or given position
+    async fn resume(
+        &mut self,
+        subscription_id: Option<String>,
+        resume_from: Option<i64>,
+    ) -> config_common::Result<()> {
+        if subscription_id.is_none() && resume_from.is_none() {
+            return Ok(());
+        }
+        let outbox = self.events.outbox().cloned().ok_or_else(|| {
+            config_common::Error::Validation("events are not stored for resuming".to_string())
+        })?;
+        if let Some(id) = subscription_id {
+            let cursor = format!("grpc:{}", id);
+            self.seq = outbox.get_offset(&cursor).await?;
+            self.cursor = Some(cursor);
+            // A new subscription starts from now rather than the oldest stored event
+            if self.seq == 0 && resume_from.is_none() {
+                self.seq = outbox.latest_seq().await?;
+                self.save_cursor().await;
+            }
+        }
+        if let Some(seq) = resume_from {
+            self.seq = seq;
+        }
+
+        loop {
+            let batch = outbox.read_after(self.seq, REPLAY_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                return Ok(());
+            }
+            for event in &batch {
+                if !self.deliver(event).await {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Replay events dropped from the live channel, or end the stream without an outbox
+    async fn catch_up(&mut self, skipped: u64) -> bool {
+        let lost = || Status::data_loss(format!("watch fell behind by {} events", skipped));
+        let Some(outbox) = self.events.outbox().cloned() else {
+            let _ = self.sender.send(Err(lost())).await;
+            return false;
+        };
+        loop {
+            let batch = match outbox.read_after(self.seq, REPLAY_BATCH_SIZE).await {
+                Ok(batch) if !batch.is_empty() => batch,
+                Ok(_) => return true,
+                Err(_) => {
+                    let _ = self.sender.send(Err(lost())).await;
+                    return false;
+                }
+            };
+            for event in &batch {
+                if !self.deliver(event).await {
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Send a matching event not delivered yet and store the new position, which also moves
+    /// past events that don't match so a resumed subscription doesn't see them again
+    async fn deliver(&mut self, event: &PublishedEvent) -> bool {
+        // Replayed events come around again on the live channel
+        if event.seq != 0 && event.seq <= self.seq {
+            return true;
+        }
+        if let Some(response) = self.response(event) {
+            if self.sender.send(Ok(response)).await.is_err() {
+                return false;
+            }
+        }
+        if event.seq != 0 {
+            self.seq = event.seq;
+            self.save_cursor().await;
+        }
+        true
+    }
+
+    /// Message for an event the watch selects
+    fn response(&self, event: &PublishedEvent) -> Option<WatchConfigResponse> {
+        let change = &event.event;
+        let matches = change.config_id == self.config_id
+            || self
+                .selector
+                .as_ref()
+                .is_some_and(|selector| selector.matches(&change.labels));
+        let event_type = match change.event_type {
+            ConfigEventType::Created => EventType::Created,
+            ConfigEventType::Updated => EventType::Updated,
+            ConfigEventType::Deleted => EventType::Deleted,
+            ConfigEventType::Released => EventType::Released,
+            ConfigEventType::Rolled => EventType::Rolled,
+            ConfigEventType::SecretExpiring
+            | ConfigEventType::SecretExpired
+            | ConfigEventType::ApprovalRequested
+            | ConfigEventType::AuditAnomaly => return None,
+        };
+        if !matches {
+            return None;
+        }
+
+        Some(WatchConfigResponse {
+            config_id: change.config_id.clone(),
+            event_type: event_type as i32,
+            version: change.version.clone(),
+            timestamp: change.timestamp,
+            user: change.user.clone(),
+            seq: event.seq,
+        })
+    }
+
+    /// Store the position of a named subscription
+    async fn save_cursor(&self) {
+        if let (Some(cursor), Some(outbox)) = (&self.cursor, self.events.outbox()) {
+            if let Err(e) = outbox.set_offset(cursor, self.seq).await {
+                tracing::warn!(cursor = %cursor, error = %e, "Saving subscription cursor failed");
+            }
+        }
+    }
+}
+
+/// Caller of a request, from its `x-user-id` metadata when a trusted gateway vouches for it
+#[allow(clippy::result_large_err)]
+fn caller<T>(auth: &AuthConfig, request: &Request<T>) -> Result<String, Status> {
+    let metadata = |key: &str| {
+        request
+            .metadata()
+            .get(key)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    };
+    let user = metadata(USER_METADATA)
+        .ok_or_else(|| Status::unauthenticated("missing caller identity"))?;
+    if !auth.accepts_identity(metadata(GATEWAY_TOKEN_METADATA)) {
+        return Err(Status::unauthenticated(
+            "caller identity not vouched for by a trusted gateway",
+        ));
+    }
+    Ok(user.to_string())
+}
+
+fn summary(meta: ConfigMeta) -> ConfigSummary {
+    ConfigSummary {
+        id: meta.id,
+        name: meta.name,
+        namespace: meta.namespace,
+        department: meta.department,
+        application: meta.application,
+        environment: meta.environment,
+        version: meta.version,
+        description: meta.description,
+        created_at: meta.created_at,
+        updated_at: meta.updated_at,
+        created_by: meta.created_by,
+        updated_by: meta.updated_by,
+        owners: meta.owners,
+        labels: meta.labels,
+    }
+}
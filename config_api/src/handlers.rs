@@ -1,24 +1,635 @@
-use actix_web::{web, HttpResponse};
+use actix_web::http::header::{
+    self, ContentDisposition, DispositionParam, DispositionType, EntityTag,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use futures_util::stream::{self, StreamExt};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
+use crate::audit::{set_audit_diff, set_audit_summary};
+use crate::auth::CurrentUser;
+use crate::caching::{self, CachePolicy};
 use crate::model::*;
-use config_core::{ConfigFilter, ConfigManager};
+use config_audit::{AuditFilter, AuditService, ConfigDiff};
+use config_auth::{PolicyEnforcer, PolicyService, RoleAssignment};
+use config_common::{ConfigContent, ConfigEvent, ConfigEventType, ConfigMeta};
+use config_core::format::ContentPatch;
+use config_core::{
+    check_release_order, dependency_order, with_approval, ApprovalManager, ApprovalPolicy,
+    ApprovalRequest, ApprovalStatus, CanaryMonitor, ChangeReasonPolicy, ChangeSetManager,
+    ConfigFilter, ConfigManager, ConfigVersionControl, EventBus, EventFilter, FeatureFlag,
+    FlagClient, FlagManager, FreezeWindowManager, GateSubject, HealthReport, KeyRotationManager,
+    NamespaceManager, NamespaceStatsProvider, NamespaceStatus, NewRelease, NotificationManager,
+    PromotionManager, PromotionStatus, RecipientKeyManager, Release, ReleaseGates, ReleaseManager,
+    ReleasePolicy, ReleaseStatus, RolloutClient, RolloutRule, SchemaManager, SecretExpiryConfig,
+    SecretExpiryManager, SecretPathManager, SecretShareManager, StagedChange, TeamManager,
+    TenantManager, ValidationHookManager, ValidationRuleManager,
+};
+use config_monitor::{AlertEngine, ClientMetrics, ConfigMetrics, LogLevel};
 
-/// REST API handlers
+// REST API handlers
 
+/// Response header carrying the version of raw content
+const CONFIG_VERSION_HEADER: &str = "X-Config-Version";
+
+/// `format` query value rendering content as a `KEY=VALUE` env file
+const ENV_FORMAT: &str = "env";
+
+/// Label marking configs whose content lists Prometheus scrape targets
+const SCRAPE_TARGET_LABEL: &str = "scrape-target";
+
+/// Lifetime of a share link when the request doesn't set one
+const DEFAULT_SHARE_TTL_SECS: i64 = 60 * 60;
+
+/// Longest a share link may stay valid
+const MAX_SHARE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn get_config(
+    http_req: HttpRequest,
     id: web::Path<String>,
+    query: web::Query<PointInTimeRequest>,
+    user: Option<CurrentUser>,
     config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    metrics: web::Data<ConfigMetrics>,
+    cache_policy: web::Data<CachePolicy>,
 ) -> config_common::Result<HttpResponse> {
-    let (meta, content) = config_manager.get_config(&id).await?;
-    Ok(HttpResponse::Ok().json((meta, content)))
+    let format = match query.format.as_deref() {
+        None | Some(ENV_FORMAT) => None,
+        Some(format) => Some(format.parse::<config_common::ConfigFormat>()?),
+    };
+
+    let mut released = false;
+    let (meta, content) = match query.at {
+        Some(at) => {
+            let snapshot = version_control.get_version_at(&id, at.timestamp()).await?;
+            (snapshot.meta, snapshot.content)
+        }
+        None => {
+            let timer = metrics.start("get");
+            let result = config_manager.get_config(&id).await;
+            let (namespace, environment) = match &result {
+                Ok((meta, _)) => (
+                    Some(meta.namespace.as_str()),
+                    Some(meta.environment.as_str()),
+                ),
+                Err(_) => (None, None),
+            };
+            timer.finish(namespace, environment, &result);
+            let (meta, content) = result?;
+            if query.draft {
+                (meta, content)
+            } else {
+                released = has_release(releases.get_ref(), &meta.id).await?;
+                config_core::releases::served(
+                    releases.get_ref(),
+                    version_control.get_ref(),
+                    meta,
+                    content,
+                    &rollout_client(
+                        &http_req,
+                        query.client_id.as_deref(),
+                        query.client_labels.as_deref(),
+                    ),
+                )
+                .await?
+            }
+        }
+    };
+    let mut content = if query.decrypt {
+        let user = user.as_ref().ok_or_else(|| {
+            config_common::Error::Auth("decrypting requires a caller identity".to_string())
+        })?;
+        config_manager
+            .decrypt_content(&meta, content, &user.0)
+            .await?
+    } else {
+        config_manager.redact_content(content)?
+    };
+
+    // Releases change what is served without touching the config
+    let mut modified = (!released).then_some(meta.updated_at);
+    if !query.raw_refs
+        && !content.is_encrypted
+        && config_core::references::has_references(&content.content)
+    {
+        let expanded = config_core::references::expand(
+            namespaces.get_ref(),
+            config_manager.get_ref(),
+            &meta.environment,
+            &content.content,
+        )
+        .await?;
+        // Values from other namespaces are only handed to callers allowed to read them
+        for source in expanded
+            .sources
+            .iter()
+            .filter(|source| source.namespace != meta.namespace)
+        {
+            let user = user.as_ref().ok_or_else(|| {
+                config_common::Error::Auth(format!(
+                    "resolving references into namespace {} requires a caller identity",
+                    source.namespace
+                ))
+            })?;
+            let (source_meta, _) = config_manager.get_config(&source.config_id).await?;
+            enforcer
+                .check_config_access(&user.0, &source_meta, "read")
+                .await?;
+        }
+        content.content = expanded.content;
+        // Referenced configs change without touching this one
+        modified = None;
+    }
+
+    let (content_type, body) = if query.format.as_deref() == Some(ENV_FORMAT) {
+        let env = config_core::format::render_env(&content)?;
+        ("text/plain; charset=utf-8", env.into_bytes())
+    } else {
+        let content = match format {
+            Some(format) => config_core::format::convert(&content, format)?,
+            None => content,
+        };
+        ("application/json", serde_json::to_vec(&(meta, content))?)
+    };
+    Ok(caching::respond(
+        &http_req,
+        &cache_policy,
+        modified,
+        HttpResponse::Ok(),
+        content_type,
+        body,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn get_raw_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    query: web::Query<RawConfigRequest>,
+    user: Option<CurrentUser>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+    metrics: web::Data<ConfigMetrics>,
+    cache_policy: web::Data<CachePolicy>,
+) -> config_common::Result<HttpResponse> {
+    let timer = metrics.start("get");
+    let result = config_manager.get_config(&id).await;
+    let (namespace, environment) = match &result {
+        Ok((meta, _)) => (
+            Some(meta.namespace.as_str()),
+            Some(meta.environment.as_str()),
+        ),
+        Err(_) => (None, None),
+    };
+    timer.finish(namespace, environment, &result);
+
+    let (meta, content) = result?;
+    // Releases change what is served without touching the config
+    let modified = (!has_release(releases.get_ref(), &meta.id).await?).then_some(meta.updated_at);
+    let (meta, content) = config_core::releases::served(
+        releases.get_ref(),
+        version_control.get_ref(),
+        meta,
+        content,
+        &rollout_client(
+            &http_req,
+            query.client_id.as_deref(),
+            query.client_labels.as_deref(),
+        ),
+    )
+    .await?;
+    // Served as stored, so secrets need the same authorization as the JSON form
+    let content = if query.decrypt {
+        let user = user.as_ref().ok_or_else(|| {
+            config_common::Error::Auth("decrypting requires a caller identity".to_string())
+        })?;
+        config_manager
+            .decrypt_content(&meta, content, &user.0)
+            .await?
+    } else {
+        config_manager.redact_content(content)?
+    };
+    let content_type = match content.format {
+        _ if content.is_encrypted => "application/octet-stream",
+        config_common::ConfigFormat::Json => "application/json",
+        config_common::ConfigFormat::Yaml => "application/yaml",
+        config_common::ConfigFormat::Toml => "application/toml",
+        config_common::ConfigFormat::Properties => "text/plain; charset=utf-8",
+    };
+    let mut response = HttpResponse::Ok();
+    response.insert_header((CONFIG_VERSION_HEADER, meta.version));
+    Ok(caching::respond(
+        &http_req,
+        &cache_policy,
+        modified,
+        response,
+        content_type,
+        content.content.into_bytes(),
+    ))
+}
+
+pub async fn get_namespace_at(
+    namespace: web::Path<String>,
+    query: web::Query<NamespaceAtRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+) -> config_common::Result<HttpResponse> {
+    let snapshots = version_control
+        .get_namespace_at(&namespace, query.at.timestamp())
+        .await?
+        .into_iter()
+        .map(|mut snapshot| {
+            snapshot.content = config_manager.redact_content(snapshot.content)?;
+            Ok(snapshot)
+        })
+        .collect::<config_common::Result<Vec<_>>>()?;
+    Ok(HttpResponse::Ok().json(snapshots))
+}
+
+/// State of a namespace as served to the caller, in a canonical form; the hash doubles as ETag
+/// so pollers can ask with `If-None-Match`
+pub async fn get_namespace_state(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    query: web::Query<NamespaceStateRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let filter = ConfigFilter {
+        namespace: Some(namespace.to_string()),
+        ..Default::default()
+    };
+    let client = rollout_client(
+        &http_req,
+        query.client_id.as_deref(),
+        query.client_labels.as_deref(),
+    );
+    let mut configs = BTreeMap::new();
+    for meta in config_core::list_all_configs(config_manager.get_ref(), filter).await? {
+        let (meta, content) = config_manager.get_config(&meta.id).await?;
+        let (meta, content) = config_core::releases::served(
+            releases.get_ref(),
+            version_control.get_ref(),
+            meta,
+            content,
+            &client,
+        )
+        .await?;
+        let content = config_manager.redact_content(content)?;
+        let key = format!("{}/{}/{}", meta.application, meta.environment, meta.name);
+        configs.insert(
+            key,
+            ConfigState {
+                id: meta.id,
+                version: meta.version,
+                format: content.format,
+                content_hash: format!("{:x}", Sha256::digest(content.content.as_bytes())),
+                content: content.content,
+                is_encrypted: content.is_encrypted,
+            },
+        );
+    }
+    let hash = format!("{:x}", Sha256::digest(serde_json::to_vec(&configs)?));
+
+    let etag = EntityTag::new_strong(hash.clone());
+    let unchanged = http_req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<EntityTag>().ok())
+        .is_some_and(|tag| tag.strong_eq(&etag));
+    if unchanged {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .finish());
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ETag(etag))
+        .json(NamespaceState {
+            namespace: namespace.into_inner(),
+            hash,
+            configs,
+        }))
+}
+
+/// Prometheus `http_sd_configs` endpoint. Configs labelled `scrape-target` hold a target group,
+/// `{targets: [...], labels: {...}}`, or a list of them; each group also gets
+/// `__meta_config_*` labels naming the config.
+pub async fn prometheus_discovery(
+    query: web::Query<DiscoveryRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+) -> config_common::Result<HttpResponse> {
+    let filter = ConfigFilter {
+        namespace: query.into_inner().namespace,
+        ..Default::default()
+    };
+    let mut groups = Vec::new();
+    for meta in config_core::list_all_configs(config_manager.get_ref(), filter).await? {
+        if !meta.labels.iter().any(|label| label == SCRAPE_TARGET_LABEL) {
+            continue;
+        }
+        let (meta, content) = config_manager.get_config(&meta.id).await?;
+        let document = if content.is_encrypted {
+            Err("content is encrypted".to_string())
+        } else {
+            config_core::format::parse(content.format, &content.content).map_err(|e| e.message)
+        };
+        let parsed = document.and_then(|document| {
+            let document = match document {
+                serde_json::Value::Array(_) => document,
+                group => serde_json::Value::Array(vec![group]),
+            };
+            serde_json::from_value::<Vec<TargetGroup>>(document).map_err(|e| e.to_string())
+        });
+        let config_groups = match parsed {
+            Ok(config_groups) => config_groups,
+            Err(e) => {
+                tracing::warn!(config = %meta.id, error = %e, "Invalid scrape target config");
+                continue;
+            }
+        };
+
+        for mut group in config_groups {
+            // Prometheus only takes string label values
+            for value in group.labels.values_mut() {
+                if !value.is_string() {
+                    *value = serde_json::Value::String(value.to_string());
+                }
+            }
+            for (name, value) in [
+                ("__meta_config_namespace", &meta.namespace),
+                ("__meta_config_application", &meta.application),
+                ("__meta_config_environment", &meta.environment),
+                ("__meta_config_name", &meta.name),
+            ] {
+                group
+                    .labels
+                    .insert(name.to_string(), serde_json::Value::String(value.clone()));
+            }
+            groups.push(group);
+        }
+    }
+    Ok(HttpResponse::Ok().json(groups))
+}
+
+pub async fn create_namespace(
+    http_req: HttpRequest,
+    req: web::Json<CreateNamespaceRequest>,
+    user: CurrentUser,
+    namespaces: web::Data<dyn NamespaceManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    if req.name.trim().is_empty() || req.name.contains('/') {
+        return Err(config_common::Error::Validation(format!(
+            "invalid namespace name: {:?}",
+            req.name
+        )));
+    }
+
+    let namespace = namespaces
+        .create_namespace(
+            &req.name,
+            req.parent.as_deref(),
+            req.description.as_deref(),
+            &user.0,
+        )
+        .await?;
+    set_audit_summary(
+        &http_req,
+        match &namespace.parent {
+            Some(parent) => format!("namespace {} below {}", namespace.name, parent),
+            None => format!("namespace {}", namespace.name),
+        },
+    );
+    Ok(HttpResponse::Created().json(namespace))
+}
+
+pub async fn list_namespaces(
+    query: web::Query<ListNamespacesRequest>,
+    namespaces: web::Data<dyn NamespaceManager>,
+) -> config_common::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(namespaces.list_namespaces(query.include_archived).await?))
+}
+
+pub async fn get_namespace(
+    namespace: web::Path<String>,
+    namespaces: web::Data<dyn NamespaceManager>,
+) -> config_common::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(namespaces.get_namespace(&namespace).await?))
+}
+
+pub async fn update_namespace(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    req: web::Json<UpdateNamespaceRequest>,
+    user: CurrentUser,
+    namespaces: web::Data<dyn NamespaceManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let namespace = namespaces
+        .update_namespace(
+            &namespace,
+            req.parent.as_deref(),
+            req.description.as_deref(),
+            &user.0,
+        )
+        .await?;
+    set_audit_summary(
+        &http_req,
+        match &namespace.parent {
+            Some(parent) => format!("namespace {} below {}", namespace.name, parent),
+            None => format!("namespace {} at the top level", namespace.name),
+        },
+    );
+    Ok(HttpResponse::Ok().json(namespace))
+}
+
+pub async fn set_namespace_status(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    req: web::Json<SetNamespaceStatusRequest>,
+    user: CurrentUser,
+    namespaces: web::Data<dyn NamespaceManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let previous = namespaces.get_namespace(&namespace).await?;
+    let namespace = namespaces
+        .set_namespace_status(&namespace, req.status, &user.0)
+        .await?;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "namespace {} {} -> {}",
+            namespace.name,
+            previous.status.as_str(),
+            namespace.status.as_str()
+        ),
+    );
+    Ok(HttpResponse::Ok().json(namespace))
+}
+
+/// Size and recent activity of a namespace, recomputed at most once a minute
+pub async fn get_namespace_stats(
+    namespace: web::Path<String>,
+    user: CurrentUser,
+    stats: web::Data<dyn NamespaceStatsProvider>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let stats = stats.namespace_stats(&namespace).await?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+pub async fn delete_namespace(
+    namespace: web::Path<String>,
+    user: CurrentUser,
+    namespaces: web::Data<dyn NamespaceManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    if namespaces.delete_namespace(&namespace).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(config_common::Error::NotFound(format!(
+            "namespace {}",
+            namespace
+        )))
+    }
+}
+
+/// Configs visible in a namespace, including those inherited from its ancestors; each keeps
+/// the namespace it is defined in
+pub async fn get_inherited_configs(
+    namespace: web::Path<String>,
+    query: web::Query<ListInheritedConfigsRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    namespaces: web::Data<dyn NamespaceManager>,
+) -> config_common::Result<HttpResponse> {
+    let query = query.into_inner();
+    let filter = ConfigFilter {
+        application: query.application,
+        environment: query.environment,
+        ..Default::default()
+    };
+    let configs = config_core::namespaces::inherited_configs(
+        namespaces.get_ref(),
+        config_manager.get_ref(),
+        &namespace,
+        filter,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(configs))
+}
+
+pub async fn resolve(
+    http_req: HttpRequest,
+    query: web::Query<ResolveRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let client = rollout_client(
+        &http_req,
+        query.client_id.as_deref(),
+        query.client_labels.as_deref(),
+    );
+    let serving = config_core::resolve::Serving {
+        releases: releases.get_ref(),
+        version_control: version_control.get_ref(),
+        client: &client,
+    };
+    let resolved = config_core::resolve::resolve(
+        namespaces.get_ref(),
+        config_manager.get_ref(),
+        &query.namespace,
+        &query.application,
+        &query.environment,
+        query.name.as_deref(),
+        (!query.draft).then_some(&serving),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(resolved))
+}
+
+pub async fn get_recipients(
+    namespace: web::Path<String>,
+    recipients: web::Data<dyn RecipientKeyManager>,
+) -> config_common::Result<HttpResponse> {
+    let keys = recipients.get_recipients(&namespace).await?;
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+pub async fn set_recipients(
+    http_req: HttpRequest,
+    namespace: web::Path<String>,
+    req: web::Json<SetRecipientsRequest>,
+    user: CurrentUser,
+    recipients: web::Data<dyn RecipientKeyManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let req = req.into_inner();
+    for (i, key) in req.recipients.iter().enumerate() {
+        key.check()?;
+        if req.recipients[..i].iter().any(|other| other.id == key.id) {
+            return Err(config_common::Error::Validation(format!(
+                "duplicate recipient {}",
+                key.id
+            )));
+        }
+    }
+    // One envelope has to reach every recipient, so they must share a scheme
+    if req
+        .recipients
+        .windows(2)
+        .any(|pair| pair[0].scheme != pair[1].scheme)
+    {
+        return Err(config_common::Error::Validation(
+            "recipients must all use the same scheme".to_string(),
+        ));
+    }
+
+    // Stored content stays encrypted to the previous recipients until clients save it again
+    let previous = recipients.get_recipients(&namespace).await?;
+    recipients
+        .set_recipients(&namespace, req.recipients.clone(), &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "recipients of {} {} -> {}",
+            namespace,
+            previous.len(),
+            req.recipients.len()
+        ),
+    );
+    Ok(HttpResponse::Ok().json(req.recipients))
 }
 
 pub async fn create_config(
+    http_req: HttpRequest,
     req: web::Json<CreateConfigRequest>,
-    user: String,
+    user: CurrentUser,
     config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    metrics: web::Data<ConfigMetrics>,
 ) -> config_common::Result<HttpResponse> {
-    let meta = config_manager
+    let timer = metrics.start("create");
+    let result = config_manager
         .create_config(
             &req.name,
             &req.namespace,
@@ -27,49 +638,3084 @@ pub async fn create_config(
             &req.environment,
             req.description.as_deref(),
             req.content.clone(),
-            &user,
+            &user.0,
         )
-        .await?;
+        .await;
+    timer.finish(Some(&req.namespace), Some(&req.environment), &result);
+    let meta = result?;
+    // Later updates of release-managed configs stay drafts until released
+    if release_policy.is_managed(&meta.namespace) {
+        releases
+            .create_release(NewRelease {
+                config_id: meta.id.clone(),
+                version: meta.version.clone(),
+                requested_by: user.0.clone(),
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "created {}/{}/{}/{} ({:?}, {} bytes)",
+            meta.namespace,
+            meta.application,
+            meta.environment,
+            meta.name,
+            req.content.format,
+            req.content.content.len()
+        ),
+    );
     Ok(HttpResponse::Created().json(meta))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_config(
+    http_req: HttpRequest,
     id: web::Path<String>,
     req: web::Json<UpdateConfigRequest>,
-    user: String,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+    approval_policy: web::Data<ApprovalPolicy>,
+    approvals: web::Data<dyn ApprovalManager>,
+    events: web::Data<EventBus>,
+    metrics: web::Data<ConfigMetrics>,
+) -> config_common::Result<HttpResponse> {
+    let (current, current_content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    reason_policy.check(&current.namespace, req.change_reason.as_deref())?;
+    if approval_policy.protection(&current.namespace).is_some() {
+        let approval = approvals
+            .request_change(
+                &id,
+                req.description.as_deref(),
+                req.content.clone(),
+                req.change_reason.as_deref(),
+                &user.0,
+            )
+            .await?;
+        announce_approval(&http_req, &events, &current, &approval).await;
+        return Ok(HttpResponse::Accepted().json(approval));
+    }
+
+    let timer = metrics.start("update");
+    let result = config_manager
+        .update_config(
+            &id,
+            req.description.as_deref(),
+            req.content.clone(),
+            req.change_reason.as_deref(),
+            &user.0,
+        )
+        .await;
+    timer.finish(
+        Some(&current.namespace),
+        Some(&current.environment),
+        &result,
+    );
+    let meta = result?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "version {} -> {}, {} -> {} bytes",
+                current.version,
+                meta.version,
+                current_content.content.len(),
+                req.content.content.len()
+            ),
+            req.change_reason.as_deref(),
+        ),
+    );
+    let secrets = secret_paths.get_secret_paths(&id).await?;
+    set_audit_diff(
+        &http_req,
+        ConfigDiff::compute_with_secrets(
+            &config_manager.redact_content(current_content)?,
+            &req.content,
+            &secrets,
+        ),
+    );
+    Ok(HttpResponse::Ok().json(meta))
+}
+
+/// Content type of an RFC 6902 JSON Patch body
+const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+#[allow(clippy::too_many_arguments)]
+pub async fn patch_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    query: web::Query<PatchConfigRequest>,
+    body: web::Bytes,
+    user: CurrentUser,
     config_manager: web::Data<dyn ConfigManager>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+    approval_policy: web::Data<ApprovalPolicy>,
+    approvals: web::Data<dyn ApprovalManager>,
+    events: web::Data<EventBus>,
+    metrics: web::Data<ConfigMetrics>,
 ) -> config_common::Result<HttpResponse> {
+    // JSON Patch when declared, merge patch for `application/merge-patch+json` or plain JSON
+    let patch = if http_req.content_type() == JSON_PATCH_CONTENT_TYPE {
+        ContentPatch::Json(serde_json::from_slice(&body)?)
+    } else {
+        ContentPatch::Merge(serde_json::from_slice(&body)?)
+    };
+
+    let (current, current_content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    reason_policy.check(&current.namespace, query.change_reason.as_deref())?;
+    let content = config_core::format::apply_patch(&current_content, &patch)?;
+    if approval_policy.protection(&current.namespace).is_some() {
+        let approval = approvals
+            .request_change(
+                &id,
+                current.description.as_deref(),
+                content,
+                query.change_reason.as_deref(),
+                &user.0,
+            )
+            .await?;
+        announce_approval(&http_req, &events, &current, &approval).await;
+        return Ok(HttpResponse::Accepted().json(approval));
+    }
+
+    let timer = metrics.start("patch");
+    let result = config_manager
+        .update_config(
+            &id,
+            current.description.as_deref(),
+            content.clone(),
+            query.change_reason.as_deref(),
+            &user.0,
+        )
+        .await;
+    timer.finish(
+        Some(&current.namespace),
+        Some(&current.environment),
+        &result,
+    );
+    let meta = result?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "patched version {} -> {}, {} -> {} bytes",
+                current.version,
+                meta.version,
+                current_content.content.len(),
+                content.content.len()
+            ),
+            query.change_reason.as_deref(),
+        ),
+    );
+    let secrets = secret_paths.get_secret_paths(&id).await?;
+    set_audit_diff(
+        &http_req,
+        ConfigDiff::compute_with_secrets(
+            &config_manager.redact_content(current_content)?,
+            &content,
+            &secrets,
+        ),
+    );
+    Ok(HttpResponse::Ok().json(meta))
+}
+
+pub async fn update_owners(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<UpdateOwnersRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    if req.owners.is_empty() {
+        return Err(config_common::Error::Validation(
+            "a config must have at least one owner".to_string(),
+        ));
+    }
+
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer.check_config_owner(&user.0, &current).await?;
+
     let meta = config_manager
-        .update_config(&id, req.description.as_deref(), req.content.clone(), &user)
+        .update_owners(&id, req.owners.clone(), &user.0)
         .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "owners [{}] -> [{}]",
+            current.owners.join(", "),
+            meta.owners.join(", ")
+        ),
+    );
     Ok(HttpResponse::Ok().json(meta))
 }
 
-pub async fn delete_config(
+pub async fn update_labels(
+    http_req: HttpRequest,
     id: web::Path<String>,
+    req: web::Json<UpdateLabelsRequest>,
+    user: CurrentUser,
     config_manager: web::Data<dyn ConfigManager>,
+    enforcer: web::Data<PolicyEnforcer>,
 ) -> config_common::Result<HttpResponse> {
-    config_manager.delete_config(&id).await?;
-    Ok(HttpResponse::NoContent().finish())
+    if let Some(label) = req.labels.iter().find(|label| {
+        label.is_empty() || label.contains(char::is_whitespace) || label.contains('"')
+    }) {
+        return Err(config_common::Error::Validation(format!(
+            "invalid label: {:?}",
+            label
+        )));
+    }
+
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    let mut labels = req.into_inner().labels;
+    labels.sort();
+    labels.dedup();
+    let meta = config_manager.update_labels(&id, labels, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "labels [{}] -> [{}]",
+            current.labels.join(", "),
+            meta.labels.join(", ")
+        ),
+    );
+    Ok(HttpResponse::Ok().json(meta))
 }
 
-pub async fn list_configs(
-    req: web::Query<ListConfigsRequest>,
+pub async fn get_rules(
+    id: web::Path<String>,
     config_manager: web::Data<dyn ConfigManager>,
+    rules: web::Data<dyn ValidationRuleManager>,
 ) -> config_common::Result<HttpResponse> {
-    let filter = ConfigFilter {
-        namespace: req.namespace.clone(),
-        department: req.department.clone(),
-        application: req.application.clone(),
-        environment: req.environment.clone(),
+    config_manager.get_config(&id).await?;
+    let rules = rules.get_rules(&id).await?;
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+pub async fn set_rules(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<SetRulesRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    rules: web::Data<dyn ValidationRuleManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    config_core::rules::check_rules(&req.rules)?;
+
+    let previous = rules.get_rules(&id).await?;
+    let req = req.into_inner();
+    rules.set_rules(&id, req.rules.clone(), &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!("validation rules {} -> {}", previous.len(), req.rules.len()),
+    );
+    Ok(HttpResponse::Ok().json(req.rules))
+}
+
+pub async fn get_secret_paths(
+    id: web::Path<String>,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+) -> config_common::Result<HttpResponse> {
+    config_manager.get_config(&id).await?;
+    let paths = secret_paths.get_secret_paths(&id).await?;
+    Ok(HttpResponse::Ok().json(paths))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn set_secret_paths(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<SetSecretPathsRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+) -> config_common::Result<HttpResponse> {
+    let (current, content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    if req.paths.iter().any(|path| path.is_empty()) {
+        return Err(config_common::Error::Validation(
+            "secret path is empty".to_string(),
+        ));
+    }
+    if content.is_encrypted && !req.paths.is_empty() {
+        return Err(config_common::Error::Validation(
+            "content is encrypted as a whole".to_string(),
+        ));
+    }
+    reason_policy.check(&current.namespace, req.change_reason.as_deref())?;
+
+    let previous = secret_paths.get_secret_paths(&id).await?;
+    let req = req.into_inner();
+    secret_paths
+        .set_secret_paths(&id, req.paths.clone(), &user.0)
+        .await?;
+
+    // Save the current content again so newly secret values are encrypted now; values
+    // already encrypted are kept as they are. The content itself doesn't change, so it
+    // needs no approval in protected namespaces.
+    let mut summary = format!("secret paths {} -> {}", previous.len(), req.paths.len());
+    if req.paths.iter().any(|path| !previous.contains(path)) {
+        let meta = with_approval(config_manager.update_config(
+            &id,
+            None,
+            content,
+            req.change_reason.as_deref(),
+            &user.0,
+        ))
+        .await?;
+        summary = format!(
+            "{}, version {} -> {}",
+            summary, current.version, meta.version
+        );
+    }
+
+    set_audit_summary(
+        &http_req,
+        with_reason(summary, req.change_reason.as_deref()),
+    );
+    Ok(HttpResponse::Ok().json(req.paths))
+}
+
+pub async fn get_secret_expiry(
+    id: web::Path<String>,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_expiry: web::Data<dyn SecretExpiryManager>,
+) -> config_common::Result<HttpResponse> {
+    config_manager.get_config(&id).await?;
+    let expiry = secret_expiry
+        .get_expiry(&id)
+        .await?
+        .ok_or_else(|| config_common::Error::NotFound(format!("expiry date of config {}", id)))?;
+    Ok(HttpResponse::Ok().json(expiry))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn set_secret_expiry(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<SetSecretExpiryRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_expiry: web::Data<dyn SecretExpiryManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+) -> config_common::Result<HttpResponse> {
+    let (current, content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    if req.expires_at.is_some()
+        && !content.is_encrypted
+        && !config_core::secrets::has_secret_fields(&content)
+    {
+        return Err(config_common::Error::Validation(
+            "config holds no encrypted content".to_string(),
+        ));
+    }
+    reason_policy.check(&current.namespace, req.change_reason.as_deref())?;
+
+    let expires_at = req.expires_at.map(|at| at.timestamp());
+    secret_expiry.set_expiry(&id, expires_at, &user.0).await?;
+
+    let summary = match req.expires_at {
+        Some(at) => format!("secret expiry set to {}", at.to_rfc3339()),
+        None => "secret expiry cleared".to_string(),
     };
+    set_audit_summary(
+        &http_req,
+        with_reason(summary, req.change_reason.as_deref()),
+    );
+    match secret_expiry.get_expiry(&id).await? {
+        Some(expiry) => Ok(HttpResponse::Ok().json(expiry)),
+        None => Ok(HttpResponse::NoContent().finish()),
+    }
+}
 
-    let page_size = req.page_size.unwrap_or(10);
-    let page_number = req.page_number.unwrap_or(1);
+pub async fn create_secret_share(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<CreateSecretShareRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_shares: web::Data<dyn SecretShareManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (meta, content) = config_manager.get_config(&id).await?;
+    // Nobody may hand out secrets they can't read themselves
+    crate::secrets::check_secret_read(&enforcer, &user.0, &meta).await?;
+    if !content.is_encrypted && !config_core::secrets::has_secret_fields(&content) {
+        return Err(config_common::Error::Validation(
+            "config holds no encrypted content".to_string(),
+        ));
+    }
+    if config_core::recipients::is_client_sealed(&content) {
+        return Err(config_common::Error::Validation(
+            "content is encrypted client-side; clients read it directly".to_string(),
+        ));
+    }
+    let ttl_secs = req.ttl_secs.unwrap_or(DEFAULT_SHARE_TTL_SECS);
+    if !(1..=MAX_SHARE_TTL_SECS).contains(&ttl_secs) {
+        return Err(config_common::Error::Validation(format!(
+            "share ttl must be between 1 and {} seconds",
+            MAX_SHARE_TTL_SECS
+        )));
+    }
 
-    let (configs, total) = config_manager
-        .list_configs(filter, page_size, page_number)
+    let expires_at = chrono::Utc::now().timestamp() + ttl_secs;
+    let (share, token) = secret_shares.create_share(&id, expires_at, &user.0).await?;
+    set_audit_summary(
+        &http_req,
+        format!("secret share {} valid for {}s", share.id, ttl_secs),
+    );
+    Ok(HttpResponse::Created().json(SecretShareResponse { share, token }))
+}
+
+pub async fn redeem_secret_share(
+    http_req: HttpRequest,
+    req: web::Json<RedeemSecretShareRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    secret_shares: web::Data<dyn SecretShareManager>,
+) -> config_common::Result<HttpResponse> {
+    let share = secret_shares.get_share(&req.token).await?;
+    let (meta, content) = config_manager.get_config(&share.config_id).await?;
+    // Decrypted on behalf of the creator, whose access is checked again and audited
+    let content = config_manager
+        .decrypt_content(&meta, content, &share.created_by)
+        .await?;
+
+    // Used only once the content can be delivered; of concurrent redemptions only the one
+    // consuming the share gets it
+    let share = secret_shares.consume_share(&req.token).await?;
+    set_audit_summary(
+        &http_req,
+        format!(
+            "redeemed secret share {} of config {} created by {}",
+            share.id, share.config_id, share.created_by
+        ),
+    );
+    Ok(HttpResponse::Ok().json((meta, content)))
+}
+
+pub async fn list_versions(
+    id: web::Path<String>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+) -> config_common::Result<HttpResponse> {
+    let versions = version_control.get_version_history(&id).await?;
+    Ok(HttpResponse::Ok().json(versions))
+}
+
+pub async fn get_version(
+    path: web::Path<(String, String)>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+) -> config_common::Result<HttpResponse> {
+    let (id, version) = path.into_inner();
+    let (version, content) = version_control.get_version(&id, &version).await?;
+    let content = config_manager.redact_content(content)?;
+    Ok(HttpResponse::Ok().json(ConfigVersionResponse { version, content }))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn rollback_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<RollbackRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    enforcer: web::Data<PolicyEnforcer>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+    metrics: web::Data<ConfigMetrics>,
+) -> config_common::Result<HttpResponse> {
+    let (current, current_content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+    reason_policy.check(&current.namespace, req.change_reason.as_deref())?;
+    let (_, target_content) = version_control.get_version(&id, &req.version).await?;
+
+    let timer = metrics.start("rollback");
+    let result = version_control
+        .rollback(&id, &req.version, req.change_reason.as_deref(), &user.0)
+        .await;
+    timer.finish(
+        Some(&current.namespace),
+        Some(&current.environment),
+        &result,
+    );
+    let meta = result?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "rolled back from version {} to {}",
+                current.version, req.version
+            ),
+            req.change_reason.as_deref(),
+        ),
+    );
+    set_audit_diff(
+        &http_req,
+        ConfigDiff::compute(
+            &config_manager.redact_content(current_content)?,
+            &config_manager.redact_content(target_content)?,
+        ),
+    );
+    Ok(HttpResponse::Ok().json(meta))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn report_release_health(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    req: web::Json<HealthReport>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+    canary: web::Data<CanaryMonitor>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, version) = path.into_inner();
+    let (current, content) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    // Reports are about the released version, which may be behind the latest draft
+    let (served, _) = config_core::releases::served(
+        releases.get_ref(),
+        version_control.get_ref(),
+        current,
+        content,
+        &RolloutClient::default(),
+    )
+    .await?;
+    let status = canary.report(&served, &version, &req).await?;
+
+    let mut summary = format!(
+        "version {} reported {}, {}/{} failures",
+        version,
+        if req.healthy { "healthy" } else { "unhealthy" },
+        status.failures,
+        status.failure_threshold
+    );
+    if let Some(previous) = &status.rolled_back_to {
+        summary.push_str(&format!(", rolled back to {}", previous));
+    }
+    set_audit_summary(&http_req, summary);
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_version(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    req: web::Json<CloneVersionRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    metrics: web::Data<ConfigMetrics>,
+) -> config_common::Result<HttpResponse> {
+    let (id, version) = path.into_inner();
+    let (source, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &source, "read")
+        .await?;
+    let (source_version, content) = version_control.get_version(&id, &version).await?;
+    let content = copy_content(config_manager.get_ref(), &source, content, &user.0).await?;
+
+    let req = req.into_inner();
+    let namespace = req.namespace.unwrap_or(source.namespace.clone());
+    let department = req.department.unwrap_or(source.department.clone());
+    let application = req.application.unwrap_or(source.application.clone());
+    let environment = req.environment.unwrap_or(source.environment.clone());
+    let description = req.description.or(source_version.description);
+
+    let timer = metrics.start("clone");
+    let result = config_manager
+        .create_config(
+            &req.name,
+            &namespace,
+            &department,
+            &application,
+            &environment,
+            description.as_deref(),
+            content,
+            &user.0,
+        )
+        .await;
+    timer.finish(Some(&namespace), Some(&environment), &result);
+    let meta = result?;
+    copy_secret_paths(secret_paths.get_ref(), &source.id, &meta.id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "cloned {}/{}/{}/{} version {} into {}/{}/{}/{}",
+            source.namespace,
+            source.application,
+            source.environment,
+            source.name,
+            version,
+            meta.namespace,
+            meta.application,
+            meta.environment,
+            meta.name
+        ),
+    );
+    Ok(HttpResponse::Created().json(meta))
+}
+
+/// Stored content of a config made ready to be saved into another one, which takes the
+/// caller being allowed to read its secrets. Content encrypted as a whole is decrypted and
+/// stays marked as encrypted, so saving it encrypts it again under the target's key;
+/// encrypted values and content encrypted client-side are carried over as they are.
+async fn copy_content(
+    config_manager: &dyn ConfigManager,
+    source: &ConfigMeta,
+    content: ConfigContent,
+    reader: &str,
+) -> config_common::Result<ConfigContent> {
+    if config_core::recipients::is_client_sealed(&content)
+        || (!content.is_encrypted && !config_core::secrets::has_secret_fields(&content))
+    {
+        return Ok(content);
+    }
+    let plaintext = config_manager
+        .decrypt_content(source, content.clone(), reader)
+        .await?;
+    if !content.is_encrypted {
+        return Ok(content);
+    }
+    Ok(ConfigContent {
+        is_encrypted: true,
+        ..plaintext
+    })
+}
+
+/// Add the secret paths of one config to those of another, so values carried over stay
+/// encrypted when the target is saved again
+async fn copy_secret_paths(
+    secret_paths: &dyn SecretPathManager,
+    from: &str,
+    to: &str,
+    updated_by: &str,
+) -> config_common::Result<()> {
+    let mut paths = secret_paths.get_secret_paths(to).await?;
+    let missing: Vec<String> = secret_paths
+        .get_secret_paths(from)
+        .await?
+        .into_iter()
+        .filter(|path| !paths.contains(path))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    paths.extend(missing);
+    secret_paths.set_secret_paths(to, paths, updated_by).await
+}
+
+pub async fn pin_version(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    set_pinned(
+        &http_req,
+        path.into_inner(),
+        true,
+        &user,
+        config_manager.get_ref(),
+        version_control.get_ref(),
+        &enforcer,
+    )
+    .await
+}
+
+pub async fn unpin_version(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    set_pinned(
+        &http_req,
+        path.into_inner(),
+        false,
+        &user,
+        config_manager.get_ref(),
+        version_control.get_ref(),
+        &enforcer,
+    )
+    .await
+}
+
+async fn set_pinned(
+    http_req: &HttpRequest,
+    (id, version): (String, String),
+    pinned: bool,
+    user: &CurrentUser,
+    config_manager: &dyn ConfigManager,
+    version_control: &dyn ConfigVersionControl,
+    enforcer: &PolicyEnforcer,
+) -> config_common::Result<HttpResponse> {
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    version_control
+        .pin_version(&id, &version, pinned, &user.0)
         .await?;
 
-    Ok(HttpResponse::Ok().json(ListConfigsResponse { configs, total }))
+    let verb = if pinned { "pinned" } else { "unpinned" };
+    set_audit_summary(http_req, format!("{} version {}", verb, version));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn get_tagged_version(
+    path: web::Path<(String, String)>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+) -> config_common::Result<HttpResponse> {
+    let (id, tag) = path.into_inner();
+    let (version, content) = version_control.get_tagged_version(&id, &tag).await?;
+    let content = config_manager.redact_content(content)?;
+    Ok(HttpResponse::Ok().json(ConfigVersionResponse { version, content }))
+}
+
+pub async fn tag_version(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    req: web::Json<TagVersionRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, tag) = path.into_inner();
+    if tag.trim().is_empty() {
+        return Err(config_common::Error::Validation(
+            "tag must not be empty".to_string(),
+        ));
+    }
+
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    version_control
+        .tag_version(&id, &req.version, &tag, &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!("tagged version {} as {}", req.version, tag),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn untag_version(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, tag) = path.into_inner();
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    if !version_control.untag_version(&id, &tag, &user.0).await? {
+        return Err(config_common::Error::NotFound(format!("tag {}", tag)));
+    }
+
+    set_audit_summary(&http_req, format!("removed tag {}", tag));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn delete_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    metrics: web::Data<ConfigMetrics>,
+) -> config_common::Result<HttpResponse> {
+    let (current, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "delete")
+        .await?;
+
+    let timer = metrics.start("delete");
+    let result = config_manager.delete_config(&id).await;
+    timer.finish(
+        Some(&current.namespace),
+        Some(&current.environment),
+        &result,
+    );
+    result?;
+
+    set_audit_summary(
+        &http_req,
+        format!("deleted {} at version {}", current.name, current.version),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn list_configs(
+    req: web::Query<ListConfigsRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    secret_expiry: web::Data<dyn SecretExpiryManager>,
+    expiry_policy: web::Data<SecretExpiryConfig>,
+    metrics: web::Data<ConfigMetrics>,
+) -> config_common::Result<HttpResponse> {
+    let filter = list_filter(&req, namespaces.get_ref()).await?;
+    let page_size = req.page_size.unwrap_or(10);
+    let page_number = req.page_number.unwrap_or(1);
+
+    let timer = metrics.start("list");
+    let result = config_manager
+        .list_configs(filter, page_size, page_number)
+        .await;
+    timer.finish(
+        req.namespace.as_deref(),
+        req.environment.as_deref(),
+        &result,
+    );
+    let (configs, total) = result?;
+
+    let now = chrono::Utc::now().timestamp();
+    let warnings = secret_expiry
+        .list_expiring(expiry_policy.warn_until(now))
+        .await?
+        .into_iter()
+        .filter(|expiry| configs.iter().any(|meta| meta.id == expiry.config_id))
+        .map(|expiry| SecretExpiryWarning {
+            config_id: expiry.config_id,
+            expires_at: expiry.expires_at,
+            expired: expiry.expires_at <= now,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ListConfigsResponse {
+        configs,
+        total,
+        warnings,
+    }))
+}
+
+/// `GET /configs/stream`: every config matching the filters of `GET /configs` as NDJSON, one
+/// per line, read from a database cursor as the client takes them. Paging is ignored.
+pub async fn stream_configs(
+    req: web::Query<ListConfigsRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+    namespaces: web::Data<dyn NamespaceManager>,
+) -> config_common::Result<HttpResponse> {
+    let filter = list_filter(&req, namespaces.get_ref()).await?;
+    let configs = config_manager.stream_configs(filter).await?.map(|meta| {
+        let mut line = serde_json::to_vec(&meta?)?;
+        line.push(b'\n');
+        Ok::<_, config_common::Error>(web::Bytes::from(line))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(configs))
+}
+
+/// Filter of a config listing
+async fn list_filter(
+    req: &ListConfigsRequest,
+    namespaces: &dyn NamespaceManager,
+) -> config_common::Result<ConfigFilter> {
+    let mut filter = ConfigFilter {
+        namespace: req.namespace.clone(),
+        department: req.department.clone(),
+        application: req.application.clone(),
+        environment: req.environment.clone(),
+        exclude_namespaces: Vec::new(),
+    };
+    if req.namespace.is_none() && !req.include_archived {
+        filter.exclude_namespaces = archived_namespaces(namespaces).await?;
+    }
+    Ok(filter)
+}
+
+/// Namespaces left out of listings unless asked for, by name or explicitly
+pub(crate) async fn archived_namespaces(
+    namespaces: &dyn NamespaceManager,
+) -> config_common::Result<Vec<String>> {
+    Ok(namespaces
+        .list_namespaces(true)
+        .await?
+        .into_iter()
+        .filter(|namespace| namespace.status == NamespaceStatus::Archived)
+        .map(|namespace| namespace.name)
+        .collect())
+}
+
+pub async fn create_changeset(
+    req: web::Json<CreateChangeSetRequest>,
+    user: CurrentUser,
+    changesets: web::Data<dyn ChangeSetManager>,
+) -> config_common::Result<HttpResponse> {
+    if req.title.trim().is_empty() {
+        return Err(config_common::Error::Validation(
+            "change set title must not be empty".to_string(),
+        ));
+    }
+
+    let changeset = changesets
+        .create_changeset(&req.title, req.description.as_deref(), &user.0)
+        .await?;
+    Ok(HttpResponse::Created().json(changeset))
+}
+
+pub async fn list_changesets(
+    req: web::Query<ListChangeSetsRequest>,
+    changesets: web::Data<dyn ChangeSetManager>,
+) -> config_common::Result<HttpResponse> {
+    let changesets = changesets.list_changesets(req.status).await?;
+    Ok(HttpResponse::Ok().json(changesets))
+}
+
+pub async fn get_changeset(
+    id: web::Path<String>,
+    changesets: web::Data<dyn ChangeSetManager>,
+) -> config_common::Result<HttpResponse> {
+    let changeset = changesets.get_changeset(&id).await?;
+    Ok(HttpResponse::Ok().json(changeset))
+}
+
+pub async fn discard_changeset(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    changesets: web::Data<dyn ChangeSetManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let changeset = changesets.get_changeset(&id).await?;
+    if changeset.created_by != user.0 {
+        enforcer.check_admin(&user.0).await?;
+    }
+
+    let changeset = changesets.discard_changeset(&id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!("discarded change set {}", changeset.title),
+    );
+    Ok(HttpResponse::Ok().json(changeset))
+}
+
+pub async fn stage_change(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    req: web::Json<StageChangeRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    changesets: web::Data<dyn ChangeSetManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, config_id) = path.into_inner();
+    let (current, _) = config_manager.get_config(&config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    let req = req.into_inner();
+    let change = StagedChange {
+        config_id,
+        base_version: current.version.clone(),
+        description: req.description,
+        content: req.content,
+    };
+    let changeset = changesets.stage_change(&id, change, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "staged {} at version {} in change set {}",
+            current.name, current.version, changeset.title
+        ),
+    );
+    Ok(HttpResponse::Ok().json(changeset))
+}
+
+pub async fn unstage_change(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    changesets: web::Data<dyn ChangeSetManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, config_id) = path.into_inner();
+    let (current, _) = config_manager.get_config(&config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &current, "update")
+        .await?;
+
+    let changeset = changesets.unstage_change(&id, &config_id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "unstaged {} from change set {}",
+            current.name, changeset.title
+        ),
+    );
+    Ok(HttpResponse::Ok().json(changeset))
+}
+
+pub async fn changeset_diff(
+    id: web::Path<String>,
+    config_manager: web::Data<dyn ConfigManager>,
+    changesets: web::Data<dyn ChangeSetManager>,
+) -> config_common::Result<HttpResponse> {
+    let changeset = changesets.get_changeset(&id).await?;
+
+    let mut entries = Vec::with_capacity(changeset.changes.len());
+    for change in changeset.changes {
+        let (current, current_content) = config_manager.get_config(&change.config_id).await?;
+        entries.push(ChangeSetDiffEntry {
+            config_id: change.config_id,
+            name: current.name,
+            namespace: current.namespace,
+            environment: current.environment,
+            conflict: current.version != change.base_version,
+            base_version: change.base_version,
+            current_version: current.version,
+            diff: ConfigDiff::compute(
+                &config_manager.redact_content(current_content)?,
+                &config_manager.redact_content(change.content)?,
+            ),
+        });
+    }
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+pub async fn apply_changeset(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    changesets: web::Data<dyn ChangeSetManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let changeset = changesets.get_changeset(&id).await?;
+    for change in &changeset.changes {
+        let (current, _) = config_manager.get_config(&change.config_id).await?;
+        enforcer
+            .check_config_access(&user.0, &current, "update")
+            .await?;
+    }
+
+    let applied = changesets.apply_changeset(&id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "applied change set {}: {}",
+            changeset.title,
+            applied
+                .iter()
+                .map(|meta| format!("{} -> {}", meta.name, meta.version))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    );
+    Ok(HttpResponse::Ok().json(applied))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn promote_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<PromoteConfigRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    promotions: web::Data<dyn PromotionManager>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+) -> config_common::Result<HttpResponse> {
+    let (source, source_content) = config_manager.get_config(&id).await?;
+    if req.target_environment.trim().is_empty() || req.target_environment == source.environment {
+        return Err(config_common::Error::Validation(format!(
+            "cannot promote a {} config to environment {:?}",
+            source.environment, req.target_environment
+        )));
+    }
+
+    let target = promotion_target(&config_manager, &source, &req.target_environment).await?;
+    let target_content = match &target {
+        Some(target) => config_manager.get_config(&target.id).await?.1,
+        None => empty_content(&source_content),
+    };
+    let diff = ConfigDiff::compute(
+        &config_manager.redact_content(target_content)?,
+        &config_manager.redact_content(source_content)?,
+    );
+    let mut preview = PromotionPreview {
+        promotion: None,
+        source_version: source.version.clone(),
+        target_id: target.as_ref().map(|t| t.id.clone()),
+        target_version: target.as_ref().map(|t| t.version.clone()),
+        diff,
+    };
+    if req.dry_run {
+        return Ok(HttpResponse::Ok().json(preview));
+    }
+
+    reason_policy.check(&source.namespace, req.change_reason.as_deref())?;
+    let promotion = promotions
+        .create_promotion(
+            &source,
+            &req.target_environment,
+            target.as_ref(),
+            req.change_reason.as_deref(),
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "requested promotion of version {} from {} to {}",
+                source.version, source.environment, req.target_environment
+            ),
+            req.change_reason.as_deref(),
+        ),
+    );
+    set_audit_diff(&http_req, preview.diff.clone());
+    preview.promotion = Some(promotion);
+    Ok(HttpResponse::Created().json(preview))
+}
+
+pub async fn compare_environments(
+    query: web::Query<CompareRequest>,
+    config_manager: web::Data<dyn ConfigManager>,
+) -> config_common::Result<HttpResponse> {
+    if query.from_env == query.to_env {
+        return Err(config_common::Error::Validation(
+            "from_env and to_env must differ".to_string(),
+        ));
+    }
+    let configs_in = |environment: &str| {
+        let filter = ConfigFilter {
+            namespace: query.namespace.clone(),
+            application: Some(query.application.clone()),
+            environment: Some(environment.to_string()),
+            ..Default::default()
+        };
+        config_core::list_all_configs(config_manager.get_ref(), filter)
+    };
+    let from = configs_in(&query.from_env).await?;
+    let mut to = configs_in(&query.to_env).await?;
+
+    let mut pairs: Vec<(Option<ConfigMeta>, Option<ConfigMeta>)> = Vec::new();
+    for meta in from {
+        let matching = to
+            .iter()
+            .position(|t| t.namespace == meta.namespace && t.name == meta.name)
+            .map(|i| to.swap_remove(i));
+        pairs.push((Some(meta), matching));
+    }
+    pairs.extend(to.into_iter().map(|meta| (None, Some(meta))));
+
+    let mut configs = Vec::new();
+    for (from, to) in pairs {
+        let from_content = match &from {
+            Some(meta) => Some(config_manager.get_config(&meta.id).await?.1),
+            None => None,
+        };
+        let to_content = match &to {
+            Some(meta) => Some(config_manager.get_config(&meta.id).await?.1),
+            None => None,
+        };
+        let (from_content, to_content) = match (from_content, to_content) {
+            (Some(from), Some(to)) => (from, to),
+            (Some(from), None) => (from.clone(), empty_content(&from)),
+            (None, Some(to)) => (empty_content(&to), to),
+            (None, None) => continue,
+        };
+        let diff = ConfigDiff::compute(
+            &config_manager.redact_content(from_content)?,
+            &config_manager.redact_content(to_content)?,
+        );
+        if from.is_some() && to.is_some() && diff.old_hash == diff.new_hash {
+            continue;
+        }
+
+        let (namespace, name) = match from.as_ref().or(to.as_ref()) {
+            Some(meta) => (meta.namespace.clone(), meta.name.clone()),
+            None => continue,
+        };
+        configs.push(ConfigComparison {
+            namespace,
+            name,
+            from_id: from.as_ref().map(|m| m.id.clone()),
+            from_version: from.as_ref().map(|m| m.version.clone()),
+            to_id: to.as_ref().map(|m| m.id.clone()),
+            to_version: to.as_ref().map(|m| m.version.clone()),
+            diff,
+        });
+    }
+    configs.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+    let query = query.into_inner();
+    Ok(HttpResponse::Ok().json(EnvironmentComparison {
+        application: query.application,
+        from_env: query.from_env,
+        to_env: query.to_env,
+        configs,
+    }))
+}
+
+pub async fn list_promotions(
+    req: web::Query<ListPromotionsRequest>,
+    promotions: web::Data<dyn PromotionManager>,
+) -> config_common::Result<HttpResponse> {
+    let promotions = promotions.list_promotions(req.status).await?;
+    Ok(HttpResponse::Ok().json(promotions))
+}
+
+pub async fn get_promotion(
+    id: web::Path<String>,
+    promotions: web::Data<dyn PromotionManager>,
+) -> config_common::Result<HttpResponse> {
+    let promotion = promotions.get_promotion(&id).await?;
+    Ok(HttpResponse::Ok().json(promotion))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn approve_promotion(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    promotions: web::Data<dyn PromotionManager>,
+    secret_paths: web::Data<dyn SecretPathManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let promotion = promotions.get_promotion(&id).await?;
+    if promotion.status != PromotionStatus::Pending {
+        return Err(config_common::Error::Validation(format!(
+            "promotion {} is already {}",
+            promotion.id,
+            promotion.status.as_str()
+        )));
+    }
+    if promotion.requested_by == user.0 {
+        return Err(config_common::Error::Authorization(
+            "a promotion must be approved by someone other than its requester".to_string(),
+        ));
+    }
+
+    let (source, _) = config_manager.get_config(&promotion.source_id).await?;
+    let target = promotion_target(&config_manager, &source, &promotion.target_environment).await?;
+    if target.as_ref().map(|t| (&t.id, &t.version))
+        != promotion
+            .target_id
+            .as_ref()
+            .zip(promotion.target_version.as_ref())
+    {
+        return Err(config_common::Error::Validation(format!(
+            "the {} config changed since promotion {} was requested",
+            promotion.target_environment, promotion.id
+        )));
+    }
+    // A missing target is checked as the source config in the target environment
+    let target_meta = target.clone().unwrap_or_else(|| ConfigMeta {
+        environment: promotion.target_environment.clone(),
+        ..source.clone()
+    });
+    enforcer
+        .check_config_access(&user.0, &target_meta, "update")
+        .await?;
+
+    let (_, content) = version_control
+        .get_version(&promotion.source_id, &promotion.source_version)
+        .await?;
+    let copy = copy_content(config_manager.get_ref(), &source, content.clone(), &user.0).await?;
+    let reason = promotion.change_reason.clone().unwrap_or_else(|| {
+        format!(
+            "promoted from {} version {}",
+            source.environment, promotion.source_version
+        )
+    });
+    let (previous, meta) = match target {
+        Some(target) => {
+            let (_, previous) = config_manager.get_config(&target.id).await?;
+            let meta = config_manager
+                .update_config(&target.id, None, copy, Some(&reason), &user.0)
+                .await?;
+            (previous, meta)
+        }
+        None => {
+            let meta = config_manager
+                .create_config(
+                    &source.name,
+                    &source.namespace,
+                    &source.department,
+                    &source.application,
+                    &promotion.target_environment,
+                    source.description.as_deref(),
+                    copy,
+                    &user.0,
+                )
+                .await?;
+            (empty_content(&content), meta)
+        }
+    };
+    copy_secret_paths(secret_paths.get_ref(), &source.id, &meta.id, &user.0).await?;
+    let promotion = promotions
+        .review_promotion(
+            &promotion.id,
+            PromotionStatus::Approved,
+            &user.0,
+            Some(&meta.version),
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "promoted {} version {} from {} to {} as version {}, requested by {}",
+                source.name,
+                promotion.source_version,
+                source.environment,
+                promotion.target_environment,
+                meta.version,
+                promotion.requested_by
+            ),
+            promotion.change_reason.as_deref(),
+        ),
+    );
+    set_audit_diff(
+        &http_req,
+        ConfigDiff::compute(
+            &config_manager.redact_content(previous)?,
+            &config_manager.redact_content(content)?,
+        ),
+    );
+    Ok(HttpResponse::Ok().json(promotion))
+}
+
+pub async fn reject_promotion(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    promotions: web::Data<dyn PromotionManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let promotion = promotions.get_promotion(&id).await?;
+    // Requesters may withdraw their own promotions
+    if promotion.requested_by != user.0 {
+        let (source, _) = config_manager.get_config(&promotion.source_id).await?;
+        let target_meta = ConfigMeta {
+            environment: promotion.target_environment.clone(),
+            ..source
+        };
+        enforcer
+            .check_config_access(&user.0, &target_meta, "update")
+            .await?;
+    }
+
+    let promotion = promotions
+        .review_promotion(&promotion.id, PromotionStatus::Rejected, &user.0, None)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "rejected promotion of version {} to {}",
+            promotion.source_version, promotion.target_environment
+        ),
+    );
+    Ok(HttpResponse::Ok().json(promotion))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn release_config(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<ReleaseConfigRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    gates: web::Data<ReleaseGates>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let (meta, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+    reason_policy.check(&meta.namespace, req.change_reason.as_deref())?;
+    if let Some(rollout) = &req.rollout {
+        rollout.validate()?;
+        if let Some(rolling) = releases.rolling_release(&meta.id).await? {
+            return Err(config_common::Error::Validation(format!(
+                "release {} of {} is still rolling out",
+                rolling.id, meta.id
+            )));
+        }
+    }
+
+    let scheduled_at = match &req.publish_at {
+        Some(publish_at) => {
+            let at = release_policy.schedule_time(publish_at)?;
+            if at <= chrono::Utc::now().timestamp() {
+                return Err(config_common::Error::Validation(format!(
+                    "publish time {} is in the past",
+                    publish_at
+                )));
+            }
+            Some(at)
+        }
+        None => None,
+    };
+
+    let version = req.version.clone().unwrap_or_else(|| meta.version.clone());
+    version_control.get_version(&meta.id, &version).await?;
+    if releases.released_version(&meta.id).await?.as_deref() == Some(version.as_str()) {
+        return Err(config_common::Error::Validation(format!(
+            "version {} of {} is already released",
+            version, meta.id
+        )));
+    }
+
+    let release = request_release(
+        &meta,
+        NewRelease {
+            config_id: meta.id.clone(),
+            version,
+            change_reason: req.change_reason.clone(),
+            requested_by: user.0.clone(),
+            required_approvals: release_policy.required_approvals,
+            rollout: req.rollout.clone(),
+            scheduled_at,
+            ..Default::default()
+        },
+        releases.get_ref(),
+        &gates,
+    )
+    .await?;
+    if matches!(
+        release.status,
+        ReleaseStatus::Published | ReleaseStatus::Rolling
+    ) {
+        publish_event(
+            &events,
+            &meta,
+            ConfigEventType::Released,
+            &release.version,
+            &user.0,
+        )
+        .await;
+    }
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "{} version {} of {}/{}/{}/{}",
+                match release.status {
+                    ReleaseStatus::Published => "released".to_string(),
+                    ReleaseStatus::Rolling => "started rollout of".to_string(),
+                    ReleaseStatus::Blocked => "release gates blocked".to_string(),
+                    ReleaseStatus::Scheduled => format!(
+                        "scheduled for {} release of",
+                        chrono::DateTime::from_timestamp(scheduled_at.unwrap_or_default(), 0)
+                            .map(|at| at.to_rfc3339())
+                            .unwrap_or_default()
+                    ),
+                    _ => "requested release of".to_string(),
+                },
+                release.version,
+                meta.namespace,
+                meta.application,
+                meta.environment,
+                meta.name
+            ),
+            req.change_reason.as_deref(),
+        ),
+    );
+    Ok(HttpResponse::Created().json(release))
+}
+
+pub async fn list_releases(
+    query: web::Query<ListReleasesRequest>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let releases = releases
+        .list_releases(query.config_id.as_deref(), query.status)
+        .await?;
+    Ok(HttpResponse::Ok().json(releases))
+}
+
+/// Releases of a config, newest first
+pub async fn config_releases(
+    id: web::Path<String>,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let (meta, _) = config_manager.get_config(&id).await?;
+    let releases = releases.list_releases(Some(&meta.id), None).await?;
+    Ok(HttpResponse::Ok().json(releases))
+}
+
+pub async fn get_release(
+    id: web::Path<String>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    Ok(HttpResponse::Ok().json(release))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn approve_release(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    gates: web::Data<ReleaseGates>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    if release.requested_by == user.0 {
+        return Err(config_common::Error::Authorization(
+            "a release must be approved by someone other than its requester".to_string(),
+        ));
+    }
+    let (meta, _) = config_manager.get_config(&release.config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    // The last approval publishes the release unless it is scheduled for later
+    let completes = release.status == ReleaseStatus::Pending
+        && release.approvals.len() + 1 >= release.required_approvals
+        && release
+            .scheduled_at
+            .is_none_or(|at| at <= chrono::Utc::now().timestamp());
+    if completes {
+        check_release_order(releases.get_ref(), &release.config_id).await?;
+    }
+    let gate_results = if completes {
+        gates
+            .check(GateSubject {
+                release_id: Some(&release.id),
+                meta: &meta,
+                version: &release.version,
+                requested_by: &release.requested_by,
+                change_reason: release.change_reason.as_deref(),
+            })
+            .await
+    } else {
+        Vec::new()
+    };
+    if !config_core::gates::all_passed(&gate_results) {
+        let release = releases.block_release(&release.id, gate_results).await?;
+        set_audit_summary(
+            &http_req,
+            format!(
+                "approved release of version {} of {}, blocked by release gates",
+                release.version, release.config_id
+            ),
+        );
+        return Ok(HttpResponse::Ok().json(release));
+    }
+
+    let mut release = releases.approve_release(&release.id, &user.0).await?;
+    if !gate_results.is_empty() {
+        release = releases.record_gates(&release.id, gate_results).await?;
+    }
+    if matches!(
+        release.status,
+        ReleaseStatus::Published | ReleaseStatus::Rolling
+    ) {
+        publish_event(
+            &events,
+            &meta,
+            ConfigEventType::Released,
+            &release.version,
+            &user.0,
+        )
+        .await;
+    }
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "approved release of version {} of {} ({}/{} approvals{})",
+            release.version,
+            release.config_id,
+            release.approvals.len(),
+            release.required_approvals,
+            match release.status {
+                ReleaseStatus::Published => ", published",
+                ReleaseStatus::Scheduled => ", scheduled",
+                ReleaseStatus::Rolling => ", rolling out",
+                _ => "",
+            }
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+pub async fn reject_release(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    // Requesters may withdraw their own releases
+    if release.requested_by != user.0 {
+        let (meta, _) = config_manager.get_config(&release.config_id).await?;
+        enforcer
+            .check_config_access(&user.0, &meta, "update")
+            .await?;
+    }
+
+    let release = releases.reject_release(&release.id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "rejected release of version {} of {}",
+            release.version, release.config_id
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+pub async fn cancel_release(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    let (meta, _) = config_manager.get_config(&release.config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    let release = releases.cancel_release(&release.id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "cancelled scheduled release of version {} of {}",
+            release.version, release.config_id
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+/// Record a release, first checking dependency order and the release gates when it is
+/// published right away; later publishes are checked when their time comes
+async fn request_release(
+    meta: &ConfigMeta,
+    mut release: NewRelease,
+    releases: &dyn ReleaseManager,
+    gates: &ReleaseGates,
+) -> config_common::Result<Release> {
+    if release.required_approvals == 0 && release.scheduled_at.is_none() {
+        check_release_order(releases, &meta.id).await?;
+        release.gate_results = gates
+            .check(GateSubject {
+                release_id: None,
+                meta,
+                version: &release.version,
+                requested_by: &release.requested_by,
+                change_reason: release.change_reason.as_deref(),
+            })
+            .await;
+    }
+    releases.create_release(release).await
+}
+
+/// Release the latest versions of several configs, each after the configs it depends on
+#[allow(clippy::too_many_arguments)]
+pub async fn batch_release(
+    http_req: HttpRequest,
+    req: web::Json<BatchReleaseRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    gates: web::Data<ReleaseGates>,
+    reason_policy: web::Data<ChangeReasonPolicy>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    if req.config_ids.is_empty() {
+        return Err(config_common::Error::Validation(
+            "a batch release needs at least one config".to_string(),
+        ));
+    }
+    let order = dependency_order(&req.config_ids, &releases.list_dependencies(None).await?)?;
+
+    // Check every config before releasing any of them
+    let mut metas = Vec::with_capacity(order.len());
+    for id in &order {
+        let (meta, _) = config_manager.get_config(id).await?;
+        enforcer
+            .check_config_access(&user.0, &meta, "update")
+            .await?;
+        reason_policy.check(&meta.namespace, req.change_reason.as_deref())?;
+        let outside: Vec<String> = releases
+            .unreleased_dependencies(&meta.id)
+            .await?
+            .into_iter()
+            .filter(|dep| !order.contains(dep))
+            .collect();
+        if !outside.is_empty() {
+            return Err(config_common::Error::Validation(format!(
+                "{} must be released before {}",
+                outside.join(", "),
+                meta.id
+            )));
+        }
+        metas.push(meta);
+    }
+
+    let mut batch = BatchReleaseResponse {
+        releases: Vec::new(),
+        unreleased: Vec::new(),
+    };
+    for meta in metas {
+        // A blocked release holds back every config after it
+        if !batch.unreleased.is_empty() {
+            batch.unreleased.push(meta.id);
+            continue;
+        }
+        if releases.released_version(&meta.id).await?.as_deref() == Some(meta.version.as_str()) {
+            continue;
+        }
+
+        let release = request_release(
+            &meta,
+            NewRelease {
+                config_id: meta.id.clone(),
+                version: meta.version.clone(),
+                change_reason: req.change_reason.clone(),
+                requested_by: user.0.clone(),
+                required_approvals: release_policy.required_approvals,
+                ..Default::default()
+            },
+            releases.get_ref(),
+            &gates,
+        )
+        .await?;
+        match release.status {
+            ReleaseStatus::Published => {
+                publish_event(
+                    &events,
+                    &meta,
+                    ConfigEventType::Released,
+                    &release.version,
+                    &user.0,
+                )
+                .await
+            }
+            ReleaseStatus::Blocked => batch.unreleased.push(meta.id.clone()),
+            _ => {}
+        }
+        batch.releases.push(release);
+    }
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "batch release of {}{}",
+                order.join(", "),
+                if batch.unreleased.is_empty() {
+                    String::new()
+                } else {
+                    format!(", blocked at {}", batch.unreleased.join(", "))
+                }
+            ),
+            req.change_reason.as_deref(),
+        ),
+    );
+    Ok(HttpResponse::Created().json(batch))
+}
+
+/// Configs a config must be released after
+pub async fn list_dependencies(
+    id: web::Path<String>,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let (meta, _) = config_manager.get_config(&id).await?;
+    let dependencies = releases.list_dependencies(Some(&meta.id)).await?;
+    Ok(HttpResponse::Ok().json(dependencies))
+}
+
+pub async fn add_dependency(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<AddDependencyRequest>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (meta, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+    let (depends_on, _) = config_manager.get_config(&req.depends_on).await?;
+
+    let dependency = releases
+        .add_dependency(&meta.id, &depends_on.id, &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "declared {} must be released before {}",
+            depends_on.id, meta.id
+        ),
+    );
+    Ok(HttpResponse::Created().json(dependency))
+}
+
+pub async fn remove_dependency(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let (id, depends_on) = path.into_inner();
+    let (meta, _) = config_manager.get_config(&id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    if !releases.remove_dependency(&meta.id, &depends_on).await? {
+        return Err(config_common::Error::NotFound(format!(
+            "dependency of {} on {}",
+            meta.id, depends_on
+        )));
+    }
+
+    set_audit_summary(
+        &http_req,
+        format!("removed dependency of {} on {}", meta.id, depends_on),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Whether a release decides which version of a config is served
+async fn has_release(
+    releases: &dyn ReleaseManager,
+    config_id: &str,
+) -> config_common::Result<bool> {
+    Ok(releases.released_version(config_id).await?.is_some()
+        || releases.rolling_release(config_id).await?.is_some())
+}
+
+/// Client as described by its query parameters and the address it connects from
+pub(crate) fn rollout_client(
+    http_req: &HttpRequest,
+    client_id: Option<&str>,
+    client_labels: Option<&str>,
+) -> RolloutClient {
+    let address = http_req
+        .connection_info()
+        .realip_remote_addr()
+        .and_then(|addr| {
+            addr.parse::<std::net::SocketAddr>()
+                .map(|addr| addr.ip())
+                .or_else(|_| addr.parse())
+                .ok()
+        });
+    RolloutClient {
+        id: client_id.map(String::from),
+        labels: client_labels
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(String::from)
+            .collect(),
+        address,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn set_rollout(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<RolloutRule>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    req.validate()?;
+    let release = releases.get_release(&id).await?;
+    let (meta, _) = config_manager.get_config(&release.config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    let release = releases.set_rollout(&release.id, req.into_inner()).await?;
+    publish_event(
+        &events,
+        &meta,
+        ConfigEventType::Released,
+        &release.version,
+        &user.0,
+    )
+    .await;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "changed rollout of version {} of {}",
+            release.version, release.config_id
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+pub async fn finalize_release(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    let (meta, _) = config_manager.get_config(&release.config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    let release = releases.finalize_release(&release.id).await?;
+    publish_event(
+        &events,
+        &meta,
+        ConfigEventType::Released,
+        &release.version,
+        &user.0,
+    )
+    .await;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "finalized rollout of version {} of {}",
+            release.version, release.config_id
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+pub async fn abort_release(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let release = releases.get_release(&id).await?;
+    let (meta, _) = config_manager.get_config(&release.config_id).await?;
+    enforcer
+        .check_config_access(&user.0, &meta, "update")
+        .await?;
+
+    let release = releases.abort_release(&release.id, &user.0).await?;
+    // Clients go back to the release before it
+    let served = releases
+        .released_version(&meta.id)
+        .await?
+        .unwrap_or_else(|| meta.version.clone());
+    publish_event(&events, &meta, ConfigEventType::Released, &served, &user.0).await;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "aborted release of version {} of {}, serving version {}",
+            release.version, release.config_id, served
+        ),
+    );
+    Ok(HttpResponse::Ok().json(release))
+}
+
+/// Publish an event about a config; a failure leaves the change that caused it in place
+async fn publish_event(
+    events: &EventBus,
+    meta: &ConfigMeta,
+    event_type: ConfigEventType,
+    version: &str,
+    user: &str,
+) {
+    let event = ConfigEvent {
+        config_id: meta.id.clone(),
+        namespace: meta.namespace.clone(),
+        environment: meta.environment.clone(),
+        labels: meta.labels.clone(),
+        event_type,
+        version: version.to_string(),
+        timestamp: chrono::Utc::now().timestamp(),
+        user: user.to_string(),
+        message: None,
+    };
+    if let Err(e) = events.publish(event).await {
+        tracing::error!(
+            error = %e,
+            config_id = %meta.id,
+            event = event_type.as_str(),
+            "Failed to publish event"
+        );
+    }
+}
+
+/// Audit a change held for approval and let notification subscribers know about it
+async fn announce_approval(
+    http_req: &HttpRequest,
+    events: &EventBus,
+    meta: &ConfigMeta,
+    approval: &ApprovalRequest,
+) {
+    set_audit_summary(
+        http_req,
+        with_reason(
+            format!(
+                "requested approval {} for version {} of {}/{}/{}/{}",
+                approval.id,
+                meta.version,
+                meta.namespace,
+                meta.application,
+                meta.environment,
+                meta.name
+            ),
+            approval.change_reason.as_deref(),
+        ),
+    );
+    publish_event(
+        events,
+        meta,
+        ConfigEventType::ApprovalRequested,
+        &meta.version,
+        &approval.requested_by,
+    )
+    .await;
+}
+
+pub async fn list_approvals(
+    query: web::Query<ListApprovalsRequest>,
+    approvals: web::Data<dyn ApprovalManager>,
+) -> config_common::Result<HttpResponse> {
+    let approvals = approvals.list_approvals(query.status).await?;
+    Ok(HttpResponse::Ok().json(approvals))
+}
+
+pub async fn get_approval(
+    id: web::Path<String>,
+    approvals: web::Data<dyn ApprovalManager>,
+) -> config_common::Result<HttpResponse> {
+    let approval = approvals.get_approval(&id).await?;
+    Ok(HttpResponse::Ok().json(approval))
+}
+
+pub async fn approve_change(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    approvals: web::Data<dyn ApprovalManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let approval = approvals.get_approval(&id).await?;
+    check_reviewer(&enforcer, &user.0, &approval).await?;
+
+    let approval = approvals.approve(&approval.id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "approved change {} of {} ({}/{} approvals{})",
+            approval.id,
+            approval.change.config_id,
+            approval.approvals.len(),
+            approval.required_approvals,
+            if approval.status == ApprovalStatus::Applied {
+                ", applied"
+            } else {
+                ""
+            }
+        ),
+    );
+    Ok(HttpResponse::Ok().json(approval))
+}
+
+pub async fn reject_change(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    user: CurrentUser,
+    approvals: web::Data<dyn ApprovalManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let approval = approvals.get_approval(&id).await?;
+    check_reviewer(&enforcer, &user.0, &approval).await?;
+
+    let approval = approvals.reject(&approval.id, &user.0).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "rejected change {} of {}",
+            approval.id, approval.change.config_id
+        ),
+    );
+    Ok(HttpResponse::Ok().json(approval))
+}
+
+/// Check that a user is one of the reviewers of a request, directly or through a role
+async fn check_reviewer(
+    enforcer: &PolicyEnforcer,
+    user: &str,
+    approval: &ApprovalRequest,
+) -> config_common::Result<()> {
+    for reviewer in &approval.reviewers {
+        if reviewer == user || enforcer.has_role(user, reviewer).await {
+            return Ok(());
+        }
+    }
+    Err(config_common::Error::Authorization(format!(
+        "{} is not a reviewer of namespace {}",
+        user, approval.namespace
+    )))
+}
+
+/// Config with the name and application of `source` in another environment
+async fn promotion_target(
+    config_manager: &web::Data<dyn ConfigManager>,
+    source: &ConfigMeta,
+    environment: &str,
+) -> config_common::Result<Option<ConfigMeta>> {
+    let filter = ConfigFilter {
+        namespace: Some(source.namespace.clone()),
+        application: Some(source.application.clone()),
+        environment: Some(environment.to_string()),
+        ..Default::default()
+    };
+    Ok(
+        config_core::list_all_configs(config_manager.get_ref(), filter)
+            .await?
+            .into_iter()
+            .find(|meta| meta.name == source.name),
+    )
+}
+
+/// Content standing in for a config that doesn't exist yet when diffing
+fn empty_content(like: &ConfigContent) -> ConfigContent {
+    ConfigContent {
+        format: like.format,
+        content: String::new(),
+        is_encrypted: false,
+    }
+}
+
+pub async fn create_schema(
+    http_req: HttpRequest,
+    req: web::Json<CreateSchemaRequest>,
+    user: CurrentUser,
+    schemas: web::Data<dyn SchemaManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let document = parse_schema(req.format, &req.schema)?;
+
+    let schema = schemas
+        .create_schema(
+            &req.namespace,
+            req.application.as_deref(),
+            req.description.as_deref(),
+            document,
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "schema {} for {}/{}",
+            schema.id,
+            schema.namespace,
+            schema.application.as_deref().unwrap_or("*")
+        ),
+    );
+    Ok(HttpResponse::Created().json(schema))
+}
+
+pub async fn list_schemas(
+    query: web::Query<ListSchemasRequest>,
+    schemas: web::Data<dyn SchemaManager>,
+) -> config_common::Result<HttpResponse> {
+    let schemas = schemas.list_schemas(query.namespace.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(schemas))
+}
+
+pub async fn get_schema(
+    id: web::Path<String>,
+    schemas: web::Data<dyn SchemaManager>,
+) -> config_common::Result<HttpResponse> {
+    let schema = schemas.get_schema(&id).await?;
+    Ok(HttpResponse::Ok().json(schema))
+}
+
+pub async fn update_schema(
+    http_req: HttpRequest,
+    id: web::Path<String>,
+    req: web::Json<UpdateSchemaRequest>,
+    user: CurrentUser,
+    schemas: web::Data<dyn SchemaManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let document = parse_schema(req.format, &req.schema)?;
+
+    let schema = schemas
+        .update_schema(&id, req.description.as_deref(), document, &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "schema {} for {}/{}",
+            schema.id,
+            schema.namespace,
+            schema.application.as_deref().unwrap_or("*")
+        ),
+    );
+    Ok(HttpResponse::Ok().json(schema))
+}
+
+pub async fn delete_schema(
+    id: web::Path<String>,
+    user: CurrentUser,
+    schemas: web::Data<dyn SchemaManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    if schemas.delete_schema(&id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(config_common::Error::NotFound(format!("schema {}", id)))
+    }
+}
+
+/// Parse a schema document and make sure it is a valid JSON Schema
+fn parse_schema(
+    format: config_common::ConfigFormat,
+    text: &str,
+) -> config_common::Result<serde_json::Value> {
+    let document =
+        config_core::format::parse(format, text).map_err(config_common::Error::InvalidContent)?;
+    config_core::validation::compile_schema(&document)?;
+    Ok(document)
+}
+
+pub async fn create_validation_hook(
+    http_req: HttpRequest,
+    req: web::Json<CreateValidationHookRequest>,
+    user: CurrentUser,
+    hooks: web::Data<dyn ValidationHookManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    if !req.url.starts_with("http://") && !req.url.starts_with("https://") {
+        return Err(config_common::Error::Validation(format!(
+            "hook url must be http or https: {}",
+            req.url
+        )));
+    }
+
+    let hook = hooks
+        .create_hook(
+            &req.namespace,
+            &req.url,
+            req.timeout_ms,
+            req.fail_open,
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "validation hook {} for {} -> {}",
+            hook.id, hook.namespace, hook.url
+        ),
+    );
+    Ok(HttpResponse::Created().json(hook))
+}
+
+pub async fn list_validation_hooks(
+    query: web::Query<ListValidationHooksRequest>,
+    user: CurrentUser,
+    hooks: web::Data<dyn ValidationHookManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    let hooks = hooks.list_hooks(query.namespace.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(hooks))
+}
+
+pub async fn delete_validation_hook(
+    id: web::Path<String>,
+    user: CurrentUser,
+    hooks: web::Data<dyn ValidationHookManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    if hooks.delete_hook(&id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(config_common::Error::NotFound(format!(
+            "validation hook {}",
+            id
+        )))
+    }
+}
+
+pub async fn create_notification(
+    http_req: HttpRequest,
+    req: web::Json<CreateNotificationRequest>,
+    user: CurrentUser,
+    notifications: web::Data<dyn NotificationManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    req.channel.check()?;
+
+    let req = req.into_inner();
+    let subscription = notifications
+        .create_subscription(
+            &req.name,
+            req.channel,
+            req.filter,
+            req.template,
+            req.debounce_secs,
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "notification subscription {} ({})",
+            subscription.id, subscription.name
+        ),
+    );
+    Ok(HttpResponse::Created().json(subscription))
+}
+
+pub async fn list_notifications(
+    user: CurrentUser,
+    notifications: web::Data<dyn NotificationManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    let subscriptions = notifications.list_subscriptions().await?;
+    Ok(HttpResponse::Ok().json(subscriptions))
+}
+
+pub async fn delete_notification(
+    id: web::Path<String>,
+    user: CurrentUser,
+    notifications: web::Data<dyn NotificationManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    if notifications.delete_subscription(&id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(config_common::Error::NotFound(format!(
+            "notification subscription {}",
+            id
+        )))
+    }
+}
+
+pub async fn create_grant(
+    req: web::Json<CreateGrantRequest>,
+    user: CurrentUser,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    let grant = policy_service
+        .grant_temporary(
+            &req.user,
+            &req.role,
+            req.duration_secs,
+            &req.reason,
+            &user.0,
+        )
+        .await?;
+    Ok(HttpResponse::Created().json(grant))
+}
+
+pub async fn list_grants(
+    user: CurrentUser,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    let grants = policy_service.list_grants().await?;
+    Ok(HttpResponse::Ok().json(grants))
+}
+
+pub async fn revoke_grant(
+    id: web::Path<String>,
+    user: CurrentUser,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    policy_service.revoke_grant(&id, &user.0).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn list_audit_logs(
+    req: web::Query<ListAuditLogsRequest>,
+    user: CurrentUser,
+    audit_service: web::Data<dyn AuditService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_auditor(&user.0).await?;
+
+    let filter = AuditFilter {
+        user: req.user.clone(),
+        action: req.action.clone(),
+        resource: req.resource.clone(),
+        start_time: req.start_time,
+        end_time: req.end_time,
+    };
+
+    let page_size = req.page_size.unwrap_or(10).clamp(1, 1000);
+    let page_number = req.page_number.unwrap_or(1).max(1);
+
+    let (logs, total) = audit_service
+        .get_logs(filter, page_size, page_number)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(ListAuditLogsResponse { logs, total }))
+}
+
+/// Past change events for consumers catching up after downtime
+pub async fn list_events(
+    req: web::Query<ListEventsRequest>,
+    _user: CurrentUser,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let outbox = events.outbox().ok_or_else(|| {
+        config_common::Error::Validation("events are not stored for replay".to_string())
+    })?;
+
+    let mut filter = EventFilter {
+        namespace: req.namespace.clone(),
+        ..Default::default()
+    };
+    if let Some(since) = req.since.as_deref() {
+        match since.parse::<i64>() {
+            Ok(seq) => filter.after = seq,
+            Err(_) => {
+                let time = chrono::DateTime::parse_from_rfc3339(since).map_err(|_| {
+                    config_common::Error::Validation(format!(
+                        "since must be an event id or RFC 3339 time, got {}",
+                        since
+                    ))
+                })?;
+                filter.since = Some(time.timestamp());
+            }
+        }
+    }
+
+    let limit = req.limit.unwrap_or(100).clamp(1, 1000);
+    let events = outbox.list_events(&filter, limit).await?;
+    let next = match events.last() {
+        Some(last) if events.len() as i64 == limit => Some(last.seq),
+        _ => None,
+    };
+
+    Ok(HttpResponse::Ok().json(ListEventsResponse { events, next }))
+}
+
+pub async fn export_audit_logs(
+    req: web::Query<ExportAuditLogsRequest>,
+    user: CurrentUser,
+    audit_service: web::Data<dyn AuditService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_auditor(&user.0).await?;
+
+    let filter = AuditFilter {
+        user: req.user.clone(),
+        action: req.action.clone(),
+        resource: req.resource.clone(),
+        start_time: req.start,
+        end_time: req.end,
+    };
+    let format = req.format;
+
+    let header = stream::iter(format.header().map(Ok));
+    let records = audit_service
+        .export_logs(filter)
+        .map(move |log| log.and_then(|log| format.encode(&log)));
+
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!(
+            "audit-logs.{}",
+            format.extension()
+        ))],
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(disposition)
+        .streaming(header.chain(records)))
+}
+
+pub async fn list_alerts(
+    _user: CurrentUser,
+    alert_engine: web::Data<AlertEngine>,
+) -> config_common::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(alert_engine.alerts().await))
+}
+
+pub async fn client_stats(
+    req: web::Query<ClientStatsRequest>,
+    user: CurrentUser,
+    client_metrics: web::Data<ClientMetrics>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    Ok(HttpResponse::Ok().json(client_metrics.top_clients(req.limit)))
+}
+
+pub async fn get_log_level(
+    user: CurrentUser,
+    log_level: web::Data<LogLevel>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    Ok(HttpResponse::Ok().json(LogLevelRequest {
+        filter: log_level.current(),
+    }))
+}
+
+pub async fn set_log_level(
+    http_req: HttpRequest,
+    req: web::Json<LogLevelRequest>,
+    user: CurrentUser,
+    log_level: web::Data<LogLevel>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    let previous = log_level.current();
+    log_level.set(&req.filter)?;
+    tracing::warn!(user = %user.0, from = %previous, to = %req.filter, "Log level changed");
+    set_audit_summary(
+        &http_req,
+        format!("log filter changed from {} to {}", previous, req.filter),
+    );
+
+    Ok(HttpResponse::Ok().json(LogLevelRequest {
+        filter: req.filter.clone(),
+    }))
+}
+
+pub async fn get_key_rotation(
+    user: CurrentUser,
+    key_rotation: Option<web::Data<dyn KeyRotationManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let rotation = key_rotation_manager(key_rotation)?
+        .rotation_status()
+        .await?
+        .ok_or_else(|| config_common::Error::NotFound("no key rotation".to_string()))?;
+    Ok(HttpResponse::Ok().json(rotation))
+}
+
+pub async fn start_key_rotation(
+    http_req: HttpRequest,
+    req: web::Json<StartKeyRotationRequest>,
+    user: CurrentUser,
+    key_rotation: Option<web::Data<dyn KeyRotationManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let rotation = key_rotation_manager(key_rotation)?
+        .start_rotation(req.key_id.as_deref(), &user.0)
+        .await?;
+
+    tracing::warn!(user = %user.0, key = %rotation.key_id, "Encryption key rotated");
+    set_audit_summary(
+        &http_req,
+        format!(
+            "rotated encryption key to {}, re-encrypting {} contents",
+            rotation.key_id, rotation.total
+        ),
+    );
+    Ok(HttpResponse::Accepted().json(rotation))
+}
+
+fn key_rotation_manager(
+    key_rotation: Option<web::Data<dyn KeyRotationManager>>,
+) -> config_common::Result<web::Data<dyn KeyRotationManager>> {
+    key_rotation
+        .ok_or_else(|| config_common::Error::Validation("encryption is not configured".to_string()))
+}
+
+pub async fn provision_tenant(
+    http_req: HttpRequest,
+    req: web::Json<ProvisionTenantRequest>,
+    user: CurrentUser,
+    tenants: Option<web::Data<dyn TenantManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let tenant = tenant_manager(tenants)?
+        .provision_tenant(&req.name, req.key_id.as_deref(), &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "provisioned tenant {} in schema {}{}",
+            tenant.name,
+            tenant.schema,
+            tenant
+                .key_id
+                .as_deref()
+                .map(|key| format!(" with master key {}", key))
+                .unwrap_or_default()
+        ),
+    );
+    Ok(HttpResponse::Created().json(tenant))
+}
+
+pub async fn list_tenants(
+    user: CurrentUser,
+    tenants: Option<web::Data<dyn TenantManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let tenants = tenant_manager(tenants)?.list_tenants().await?;
+    Ok(HttpResponse::Ok().json(tenants))
+}
+
+pub async fn get_tenant(
+    name: web::Path<String>,
+    user: CurrentUser,
+    tenants: Option<web::Data<dyn TenantManager>>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let tenant = tenant_manager(tenants)?.get_tenant(&name).await?;
+    Ok(HttpResponse::Ok().json(tenant))
+}
+
+pub async fn create_freeze_window(
+    http_req: HttpRequest,
+    req: web::Json<CreateFreezeWindowRequest>,
+    user: CurrentUser,
+    windows: web::Data<dyn FreezeWindowManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let window = windows
+        .create_freeze_window(
+            &req.environment,
+            &req.starts,
+            &req.ends,
+            req.reason.as_deref(),
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        with_reason(
+            format!(
+                "froze {} from {} to {}",
+                window.environment, window.starts, window.ends
+            ),
+            window.reason.as_deref(),
+        ),
+    );
+    Ok(HttpResponse::Created().json(window))
+}
+
+pub async fn list_freeze_windows(
+    query: web::Query<ListFreezeWindowsRequest>,
+    windows: web::Data<dyn FreezeWindowManager>,
+) -> config_common::Result<HttpResponse> {
+    let windows = windows
+        .list_freeze_windows(query.environment.as_deref())
+        .await?;
+    Ok(HttpResponse::Ok().json(windows))
+}
+
+pub async fn delete_freeze_window(
+    id: web::Path<String>,
+    user: CurrentUser,
+    windows: web::Data<dyn FreezeWindowManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+
+    if windows.delete_freeze_window(&id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(config_common::Error::NotFound(format!(
+            "freeze window {}",
+            id
+        )))
+    }
+}
+
+pub async fn create_team(
+    http_req: HttpRequest,
+    req: web::Json<CreateTeamRequest>,
+    user: CurrentUser,
+    teams: web::Data<dyn TeamManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let team = teams
+        .create_team(&req.name, req.description.as_deref(), &user.0)
+        .await?;
+    set_audit_summary(&http_req, format!("created team {}", team.name));
+    Ok(HttpResponse::Created().json(team))
+}
+
+pub async fn list_teams(teams: web::Data<dyn TeamManager>) -> config_common::Result<HttpResponse> {
+    let teams = teams.list_teams().await?;
+    Ok(HttpResponse::Ok().json(teams))
+}
+
+pub async fn get_team(
+    name: web::Path<String>,
+    teams: web::Data<dyn TeamManager>,
+) -> config_common::Result<HttpResponse> {
+    let team = teams.get_team(&name).await?;
+    Ok(HttpResponse::Ok().json(team))
+}
+
+pub async fn delete_team(
+    http_req: HttpRequest,
+    name: web::Path<String>,
+    user: CurrentUser,
+    teams: web::Data<dyn TeamManager>,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let team = teams.get_team(&name).await?;
+
+    // Members leave the team's policy group along with the team
+    let role = config_core::teams::team_role(&team.name);
+    for member in &team.members {
+        policy_service
+            .revoke_role(&RoleAssignment {
+                user: member.clone(),
+                role: role.clone(),
+            })
+            .await?;
+    }
+    teams.delete_team(&team.name).await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "deleted team {} with {} members",
+            team.name,
+            team.members.len()
+        ),
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn add_team_member(
+    http_req: HttpRequest,
+    name: web::Path<String>,
+    req: web::Json<AddTeamMemberRequest>,
+    user: CurrentUser,
+    teams: web::Data<dyn TeamManager>,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let team = teams.add_member(&name, &req.user, &user.0).await?;
+    policy_service
+        .assign_role(&RoleAssignment {
+            user: req.user.clone(),
+            role: config_core::teams::team_role(&team.name),
+        })
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!("added {} to team {}", req.user, team.name),
+    );
+    Ok(HttpResponse::Ok().json(team))
+}
+
+pub async fn remove_team_member(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    user: CurrentUser,
+    teams: web::Data<dyn TeamManager>,
+    policy_service: web::Data<PolicyService>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let (name, member) = path.into_inner();
+
+    if !teams.remove_member(&name, &member).await? {
+        return Err(config_common::Error::NotFound(format!(
+            "member {} of team {}",
+            member, name
+        )));
+    }
+    policy_service
+        .revoke_role(&RoleAssignment {
+            user: member.clone(),
+            role: config_core::teams::team_role(&name),
+        })
+        .await?;
+
+    set_audit_summary(&http_req, format!("removed {} from team {}", member, name));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Check a caller may change a namespace's flags; administrators always may
+async fn check_flag_access(
+    enforcer: &PolicyEnforcer,
+    user: &str,
+    flag: &FeatureFlag,
+    action: &str,
+) -> config_common::Result<()> {
+    if enforcer.is_admin(user).await {
+        return Ok(());
+    }
+    enforcer
+        .check(
+            user,
+            &format!("flags/{}/{}", flag.namespace, flag.key),
+            action,
+        )
+        .await
+}
+
+pub async fn create_flag(
+    http_req: HttpRequest,
+    req: web::Json<CreateFlagRequest>,
+    user: CurrentUser,
+    flags: web::Data<dyn FlagManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let req = req.into_inner();
+    let now = chrono::Utc::now().timestamp();
+    let draft = FeatureFlag {
+        key: req.key,
+        namespace: req.namespace,
+        description: req.description,
+        settings: req.settings,
+        created_at: now,
+        created_by: user.0.clone(),
+        updated_at: now,
+        updated_by: user.0.clone(),
+    };
+    check_flag_access(&enforcer, &user.0, &draft, "create").await?;
+    let flag = flags
+        .create_flag(
+            &draft.key,
+            &draft.namespace,
+            draft.description.as_deref(),
+            draft.settings,
+            &user.0,
+        )
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!("created flag {} in {}", flag.key, flag.namespace),
+    );
+    Ok(HttpResponse::Created().json(flag))
+}
+
+pub async fn list_flags(
+    query: web::Query<ListFlagsRequest>,
+    flags: web::Data<dyn FlagManager>,
+) -> config_common::Result<HttpResponse> {
+    let flags = flags.list_flags(query.namespace.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(flags))
+}
+
+pub async fn get_flag(
+    key: web::Path<String>,
+    flags: web::Data<dyn FlagManager>,
+) -> config_common::Result<HttpResponse> {
+    let flag = flags.get_flag(&key).await?;
+    Ok(HttpResponse::Ok().json(flag))
+}
+
+pub async fn update_flag(
+    http_req: HttpRequest,
+    key: web::Path<String>,
+    req: web::Json<UpdateFlagRequest>,
+    user: CurrentUser,
+    flags: web::Data<dyn FlagManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let flag = flags.get_flag(&key).await?;
+    check_flag_access(&enforcer, &user.0, &flag, "update").await?;
+    let req = req.into_inner();
+    let flag = flags
+        .update_flag(&flag.key, req.description.as_deref(), req.settings, &user.0)
+        .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "updated flag {}: {}",
+            flag.key,
+            serde_json::to_string(&flag.settings).unwrap_or_default()
+        ),
+    );
+    Ok(HttpResponse::Ok().json(flag))
+}
+
+pub async fn delete_flag(
+    http_req: HttpRequest,
+    key: web::Path<String>,
+    user: CurrentUser,
+    flags: web::Data<dyn FlagManager>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    let flag = flags.get_flag(&key).await?;
+    check_flag_access(&enforcer, &user.0, &flag, "delete").await?;
+    flags.delete_flag(&flag.key).await?;
+
+    set_audit_summary(&http_req, format!("deleted flag {}", flag.key));
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn evaluate_flag(
+    key: web::Path<String>,
+    client: web::Json<FlagClient>,
+    flags: web::Data<dyn FlagManager>,
+) -> config_common::Result<HttpResponse> {
+    let flag = flags.get_flag(&key).await?;
+    Ok(HttpResponse::Ok().json(flag.settings.evaluate(&flag.key, &client)))
+}
+
+fn tenant_manager(
+    tenants: Option<web::Data<dyn TenantManager>>,
+) -> config_common::Result<web::Data<dyn TenantManager>> {
+    tenants.ok_or_else(|| {
+        config_common::Error::Validation("tenant isolation is not enabled".to_string())
+    })
+}
+
+/// Append the change reason, if any, to an audit summary
+fn with_reason(summary: String, reason: Option<&str>) -> String {
+    match reason {
+        Some(reason) => format!("{} (reason: {})", summary, reason),
+        None => summary,
+    }
 }
@@ -0,0 +1,428 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_auth::PolicyEnforcer;
+use config_common::{ConfigContent, ConfigFormat};
+use config_core::{ConfigManager, NamespaceManager, ReleaseManager, ReleasePolicy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use crate::archive::{import_configs, summarize, ArchivedConfig, ImportEntry, MAX_UNPACKED_BYTES};
+use crate::audit::set_audit_summary;
+use crate::auth::CurrentUser;
+use crate::model::{ExternalImportRequest, ImportNamespaceRequest};
+
+/// Group of Nacos configs not placed in a group of their own
+const NACOS_DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+
+/// Metadata of a Nacos 2.x export, listing every config
+const NACOS_METADATA_PATH: &str = ".metadata.yml";
+
+/// Metadata of a Nacos 1.x export, holding the app name of configs that have one
+const NACOS_LEGACY_METADATA_PATH: &str = ".meta.yml";
+
+/// Cluster of Apollo configs not placed in a cluster of their own
+const APOLLO_DEFAULT_CLUSTER: &str = "default";
+
+/// Configuration system an export comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalSource {
+    /// Zip of `<group>/<dataId>` files from the Nacos console
+    Nacos,
+    /// Zip of `<appId>+<cluster>+<namespace>.<format>` files, in a directory per
+    /// environment, from the Apollo portal
+    Apollo,
+}
+
+impl ExternalSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExternalSource::Nacos => "nacos",
+            ExternalSource::Apollo => "apollo",
+        }
+    }
+}
+
+impl std::str::FromStr for ExternalSource {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> config_common::Result<Self> {
+        match s {
+            "nacos" => Ok(ExternalSource::Nacos),
+            "apollo" => Ok(ExternalSource::Apollo),
+            _ => Err(config_common::Error::Validation(format!(
+                "unknown import source {}, expected nacos or apollo",
+                s
+            ))),
+        }
+    }
+}
+
+/// What the group of a Nacos config maps to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupMapping {
+    /// The group names the application; configs in `DEFAULT_GROUP` take their app name,
+    /// or else their data ID
+    #[default]
+    Application,
+    /// The group names the environment; the application is the app name, or else the
+    /// data ID
+    Environment,
+}
+
+/// What an import did, or would do, with one config of an export
+#[derive(Debug, Serialize)]
+pub struct ExternalImportEntry {
+    /// Path of the config in the export
+    pub source: String,
+    #[serde(flatten)]
+    pub entry: ImportEntry,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExternalImportReport {
+    pub namespace: String,
+    pub source: ExternalSource,
+    pub dry_run: bool,
+    pub entries: Vec<ExternalImportEntry>,
+    /// Files of the export that aren't configs, or whose format isn't supported
+    pub ignored: Vec<String>,
+}
+
+/// Config of an export mapped onto an application, environment and name
+struct MappedConfig {
+    source: String,
+    config: ArchivedConfig,
+}
+
+/// `.metadata.yml` of a Nacos 2.x export
+#[derive(Debug, Default, Deserialize)]
+struct NacosMetadata {
+    #[serde(default)]
+    metadata: Vec<NacosConfigMetadata>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NacosConfigMetadata {
+    group: String,
+    data_id: String,
+    #[serde(rename = "type")]
+    config_type: Option<String>,
+    app_name: Option<String>,
+    desc: Option<String>,
+}
+
+/// `POST /namespaces/{namespace}/import/{source}`: create or update configs from a Nacos or
+/// Apollo export. A dry run previews how each file maps onto a config without changing
+/// anything.
+#[allow(clippy::too_many_arguments)]
+pub async fn import_external(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<ExternalImportRequest>,
+    body: web::Bytes,
+    user: CurrentUser,
+    config_manager: web::Data<dyn ConfigManager>,
+    namespaces: web::Data<dyn NamespaceManager>,
+    releases: web::Data<dyn ReleaseManager>,
+    release_policy: web::Data<ReleasePolicy>,
+    enforcer: web::Data<PolicyEnforcer>,
+) -> config_common::Result<HttpResponse> {
+    enforcer.check_admin(&user.0).await?;
+    let (namespace, source) = path.into_inner();
+    let source: ExternalSource = source.parse()?;
+    let namespace = namespaces.get_namespace(&namespace).await?.name;
+
+    let files = unzip(&body)?;
+    let (mapped, ignored) = match source {
+        ExternalSource::Nacos => map_nacos(files, &query),
+        ExternalSource::Apollo => map_apollo(files, &query),
+    };
+    let (sources, configs): (Vec<String>, Vec<ArchivedConfig>) = mapped
+        .into_iter()
+        .map(|mapped| (mapped.source, mapped.config))
+        .unzip();
+
+    let options = ImportNamespaceRequest {
+        dry_run: query.dry_run,
+        conflict: query.conflict,
+        change_reason: query.change_reason.clone(),
+    };
+    let entries = import_configs(
+        config_manager.get_ref(),
+        releases.get_ref(),
+        &release_policy,
+        &namespace,
+        &configs,
+        &options,
+        &user.0,
+    )
+    .await?;
+
+    set_audit_summary(
+        &http_req,
+        format!(
+            "{}imported {} export into {}: {}, {} files ignored",
+            if query.dry_run { "dry run: " } else { "" },
+            source.as_str(),
+            namespace,
+            summarize(&entries),
+            ignored.len()
+        ),
+    );
+    Ok(HttpResponse::Ok().json(ExternalImportReport {
+        namespace,
+        source,
+        dry_run: query.dry_run,
+        entries: sources
+            .into_iter()
+            .zip(entries)
+            .map(|(source, entry)| ExternalImportEntry { source, entry })
+            .collect(),
+        ignored,
+    }))
+}
+
+/// Map `<group>/<dataId>` files onto configs, using the export's metadata for formats,
+/// app names and descriptions
+fn map_nacos(
+    files: Vec<(String, Vec<u8>)>,
+    options: &ExternalImportRequest,
+) -> (Vec<MappedConfig>, Vec<String>) {
+    let mut metadata: HashMap<(String, String), NacosConfigMetadata> = HashMap::new();
+    for (path, data) in &files {
+        let data = String::from_utf8_lossy(data);
+        match path.as_str() {
+            NACOS_METADATA_PATH => {
+                let parsed: NacosMetadata = serde_yaml::from_str(&data).unwrap_or_default();
+                for item in parsed.metadata {
+                    metadata.insert((item.group.clone(), item.data_id.clone()), item);
+                }
+            }
+            // Lines of `<group>.<dataId with '.' as '~'>.app=<app name>`
+            NACOS_LEGACY_METADATA_PATH => {
+                for line in data.lines() {
+                    let Some((key, app_name)) = line.split_once('=') else {
+                        continue;
+                    };
+                    let Some((group, data_id)) = key
+                        .trim()
+                        .strip_suffix(".app")
+                        .and_then(|key| key.split_once('.'))
+                    else {
+                        continue;
+                    };
+                    let data_id = data_id.replace('~', ".");
+                    metadata.insert(
+                        (group.to_string(), data_id.clone()),
+                        NacosConfigMetadata {
+                            group: group.to_string(),
+                            data_id,
+                            app_name: Some(app_name.trim().to_string()),
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut mapped = Vec::new();
+    let mut ignored = Vec::new();
+    for (path, data) in files {
+        if path == NACOS_METADATA_PATH || path == NACOS_LEGACY_METADATA_PATH {
+            continue;
+        }
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        let [.., group, data_id] = components[..] else {
+            ignored.push(path);
+            continue;
+        };
+        let meta = metadata
+            .get(&(group.to_string(), data_id.to_string()))
+            .cloned()
+            .unwrap_or_default();
+        let (stem, extension) = split_extension(data_id);
+        let Some(format) = meta
+            .config_type
+            .as_deref()
+            .and_then(parse_format)
+            .or_else(|| extension.and_then(parse_format))
+        else {
+            ignored.push(path);
+            continue;
+        };
+        let Ok(content) = String::from_utf8(data) else {
+            ignored.push(path);
+            continue;
+        };
+
+        let app_name = meta.app_name.filter(|name| !name.is_empty());
+        let (application, environment) = match options.group_as {
+            GroupMapping::Application if group != NACOS_DEFAULT_GROUP => {
+                (group.to_string(), options.environment.clone())
+            }
+            GroupMapping::Application => (
+                app_name.unwrap_or_else(|| stem.to_string()),
+                options.environment.clone(),
+            ),
+            GroupMapping::Environment => (
+                app_name.unwrap_or_else(|| stem.to_string()),
+                group.to_string(),
+            ),
+        };
+        mapped.push(MappedConfig {
+            source: path.clone(),
+            config: external_config(
+                stem,
+                application,
+                environment,
+                meta.desc.filter(|desc| !desc.is_empty()),
+                format!("nacos:{}", group),
+                format,
+                content,
+                options,
+            ),
+        });
+    }
+    (mapped, ignored)
+}
+
+/// Map `<appId>+<cluster>+<namespace>.<format>` files onto configs, taking the environment
+/// from the directory holding the file
+fn map_apollo(
+    files: Vec<(String, Vec<u8>)>,
+    options: &ExternalImportRequest,
+) -> (Vec<MappedConfig>, Vec<String>) {
+    let mut mapped = Vec::new();
+    let mut ignored = Vec::new();
+    for (path, data) in files {
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        let (env, file_name) = match components[..] {
+            [.., env, file_name] => (Some(env), file_name),
+            [file_name] => (None, file_name),
+            [] => {
+                ignored.push(path);
+                continue;
+            }
+        };
+        let (stem, extension) = split_extension(file_name);
+        let parts: Vec<&str> = stem.splitn(3, '+').collect();
+        let (Some(format), &[app_id, cluster, namespace]) =
+            (extension.and_then(parse_format), parts.as_slice())
+        else {
+            ignored.push(path);
+            continue;
+        };
+        let Ok(content) = String::from_utf8(data) else {
+            ignored.push(path);
+            continue;
+        };
+
+        let env = env
+            .map(str::to_lowercase)
+            .unwrap_or_else(|| options.environment.clone());
+        let environment = match cluster {
+            APOLLO_DEFAULT_CLUSTER => env,
+            cluster => format!("{}-{}", env, cluster),
+        };
+        mapped.push(MappedConfig {
+            source: path.clone(),
+            config: external_config(
+                namespace,
+                app_id.to_string(),
+                environment,
+                None,
+                format!("apollo:{}", cluster),
+                format,
+                content,
+                options,
+            ),
+        });
+    }
+    (mapped, ignored)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn external_config(
+    name: &str,
+    application: String,
+    environment: String,
+    description: Option<String>,
+    label: String,
+    format: ConfigFormat,
+    content: String,
+    options: &ExternalImportRequest,
+) -> ArchivedConfig {
+    ArchivedConfig {
+        name: name.to_string(),
+        department: options.department.clone(),
+        application,
+        environment,
+        description,
+        owners: Vec::new(),
+        labels: vec![label],
+        version: String::new(),
+        content: ConfigContent {
+            format,
+            content,
+            is_encrypted: false,
+        },
+        versions: Vec::new(),
+    }
+}
+
+/// File name without its extension, and the extension
+fn split_extension(file_name: &str) -> (&str, Option<&str>) {
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (file_name, None),
+    }
+}
+
+/// Format of a Nacos config type or file extension; text, XML and HTML aren't supported
+pub(crate) fn parse_format(name: &str) -> Option<ConfigFormat> {
+    match name.to_lowercase().as_str() {
+        "yaml" | "yml" => Some(ConfigFormat::Yaml),
+        "json" => Some(ConfigFormat::Json),
+        "toml" => Some(ConfigFormat::Toml),
+        "properties" => Some(ConfigFormat::Properties),
+        _ => None,
+    }
+}
+
+/// Files of a zip export by path, leaving out directories
+fn unzip(data: &[u8]) -> config_common::Result<Vec<(String, Vec<u8>)>> {
+    let invalid = |e: String| config_common::Error::Validation(format!("invalid export: {}", e));
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| invalid(e.to_string()))?;
+
+    let mut files = Vec::new();
+    let mut unpacked = 0;
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index(index)
+            .map_err(|e| invalid(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+        let path = file.name().to_string();
+        let mut data = Vec::new();
+        file.take(MAX_UNPACKED_BYTES - unpacked + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| invalid(format!("{}: {}", path, e)))?;
+        unpacked += data.len() as u64;
+        if unpacked > MAX_UNPACKED_BYTES {
+            return Err(invalid(format!(
+                "unpacks to more than {} bytes",
+                MAX_UNPACKED_BYTES
+            )));
+        }
+        files.push((path, data));
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
@@ -1,25 +1,599 @@
+pub mod archive;
+pub mod audit;
+pub mod auth;
+pub mod backup;
+pub mod bulk;
+pub mod caching;
+pub mod consul;
+pub mod etcd;
+pub mod export;
+pub mod freeze;
+pub mod git;
+pub mod grpc;
 mod handlers;
+pub mod importer;
 pub mod model;
+pub mod ratelimit;
+pub mod secrets;
+pub mod spring;
+pub mod usage;
+pub mod ws;
 
-use actix_web::web;
-use config_core::ConfigManager;
+use actix_web::{middleware, web};
+use config_audit::AuditService;
+use config_auth::PolicyService;
+use config_core::{
+    ApprovalManager, ApprovalPolicy, BackupManager, CanaryMonitor, ChangeReasonPolicy,
+    ChangeSetManager, ConfigManager, ConfigVersionControl, EventBus, FlagManager,
+    FreezeWindowManager, GitSync, KeyRotationManager, NamespaceManager, NamespaceStatsProvider,
+    NotificationManager, PromotionManager, RecipientKeyManager, ReleaseGates, ReleaseManager,
+    ReleasePolicy, SchemaManager, SecretExpiryConfig, SecretExpiryManager, SecretPathManager,
+    SecretShareManager, TeamManager, TenantManager, ValidationHookManager, ValidationRuleManager,
+};
+use config_monitor::{AlertEngine, LogLevel, MonitoringService};
 use std::sync::Arc;
 
+pub use crate::auth::CurrentUser;
+pub use crate::caching::CachePolicy;
+pub use crate::etcd::{EtcdConfig, EtcdGateway};
+pub use crate::grpc::{GrpcApi, GrpcConfig};
+pub use crate::model::AddDependencyRequest;
+pub use crate::model::AddTeamMemberRequest;
+pub use crate::model::BatchReleaseRequest;
+pub use crate::model::BatchReleaseResponse;
+pub use crate::model::BulkImportRequest;
+pub use crate::model::ChangeSetDiffEntry;
+pub use crate::model::ClientStatsRequest;
+pub use crate::model::CloneVersionRequest;
+pub use crate::model::CompareRequest;
+pub use crate::model::ConfigComparison;
+pub use crate::model::CreateChangeSetRequest;
 pub use crate::model::CreateConfigRequest;
+pub use crate::model::CreateFlagRequest;
+pub use crate::model::CreateFreezeWindowRequest;
+pub use crate::model::CreateGrantRequest;
+pub use crate::model::CreateNamespaceRequest;
+pub use crate::model::CreateNotificationRequest;
+pub use crate::model::CreateSchemaRequest;
+pub use crate::model::CreateSecretShareRequest;
+pub use crate::model::CreateTeamRequest;
+pub use crate::model::CreateValidationHookRequest;
+pub use crate::model::DiscoveryRequest;
+pub use crate::model::EnvironmentComparison;
+pub use crate::model::ExportAuditLogsRequest;
+pub use crate::model::ExportNamespaceRequest;
+pub use crate::model::ExternalImportRequest;
+pub use crate::model::ImportNamespaceRequest;
+pub use crate::model::ListApprovalsRequest;
+pub use crate::model::ListAuditLogsRequest;
+pub use crate::model::ListAuditLogsResponse;
+pub use crate::model::ListChangeSetsRequest;
 pub use crate::model::ListConfigsRequest;
 pub use crate::model::ListConfigsResponse;
+pub use crate::model::ListEventsRequest;
+pub use crate::model::ListEventsResponse;
+pub use crate::model::ListFlagsRequest;
+pub use crate::model::ListFreezeWindowsRequest;
+pub use crate::model::ListInheritedConfigsRequest;
+pub use crate::model::ListNamespacesRequest;
+pub use crate::model::ListPromotionsRequest;
+pub use crate::model::ListReleasesRequest;
+pub use crate::model::ListSchemasRequest;
+pub use crate::model::ListValidationHooksRequest;
+pub use crate::model::LogLevelRequest;
+pub use crate::model::NamespaceAtRequest;
+pub use crate::model::NamespaceState;
+pub use crate::model::PatchConfigRequest;
+pub use crate::model::PointInTimeRequest;
+pub use crate::model::PromoteConfigRequest;
+pub use crate::model::PromotionPreview;
+pub use crate::model::ProvisionTenantRequest;
+pub use crate::model::RawConfigRequest;
+pub use crate::model::RedeemSecretShareRequest;
+pub use crate::model::ReleaseConfigRequest;
+pub use crate::model::ResolveRequest;
+pub use crate::model::RestoreBackupRequest;
+pub use crate::model::RollbackRequest;
+pub use crate::model::SecretExpiryWarning;
+pub use crate::model::SecretShareResponse;
+pub use crate::model::SetNamespaceStatusRequest;
+pub use crate::model::SetRecipientsRequest;
+pub use crate::model::SetRulesRequest;
+pub use crate::model::SetSecretExpiryRequest;
+pub use crate::model::SetSecretPathsRequest;
+pub use crate::model::StageChangeRequest;
+pub use crate::model::StartKeyRotationRequest;
+pub use crate::model::TagVersionRequest;
+pub use crate::model::TargetGroup;
 pub use crate::model::UpdateConfigRequest;
+pub use crate::model::UpdateFlagRequest;
+pub use crate::model::UpdateLabelsRequest;
+pub use crate::model::UpdateNamespaceRequest;
+pub use crate::model::UpdateOwnersRequest;
+pub use crate::model::UpdateSchemaRequest;
+pub use crate::ratelimit::{RateLimitConfig, RateLimiter};
+pub use crate::secrets::PolicySecretAccess;
+
+/// Room in request bodies for fields other than the content
+const BODY_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Services the REST handlers depend on
+#[derive(Clone)]
+pub struct ApiServices {
+    pub config_manager: Arc<dyn ConfigManager>,
+    pub version_control: Arc<dyn ConfigVersionControl>,
+    pub changesets: Arc<dyn ChangeSetManager>,
+    pub approvals: Arc<dyn ApprovalManager>,
+    pub schemas: Arc<dyn SchemaManager>,
+    pub rules: Arc<dyn ValidationRuleManager>,
+    pub secret_paths: Arc<dyn SecretPathManager>,
+    pub secret_expiry: Arc<dyn SecretExpiryManager>,
+    pub secret_shares: Arc<dyn SecretShareManager>,
+    pub recipients: Arc<dyn RecipientKeyManager>,
+    /// When list responses warn of secrets near expiry
+    pub secret_expiry_policy: SecretExpiryConfig,
+    pub validation_hooks: Arc<dyn ValidationHookManager>,
+    pub notifications: Arc<dyn NotificationManager>,
+    pub namespaces: Arc<dyn NamespaceManager>,
+    pub promotions: Arc<dyn PromotionManager>,
+    pub freeze_windows: Arc<dyn FreezeWindowManager>,
+    pub teams: Arc<dyn TeamManager>,
+    pub flags: Arc<dyn FlagManager>,
+    pub namespace_stats: Arc<dyn NamespaceStatsProvider>,
+    pub releases: Arc<dyn ReleaseManager>,
+    pub policy_service: Arc<PolicyService>,
+    pub audit_service: Arc<dyn AuditService>,
+    pub monitoring: Arc<MonitoringService>,
+    pub alert_engine: Arc<AlertEngine>,
+    pub log_level: Arc<LogLevel>,
+    pub change_reason: ChangeReasonPolicy,
+    pub release_policy: ReleasePolicy,
+    pub release_gates: Arc<ReleaseGates>,
+    pub approval_policy: ApprovalPolicy,
+    pub canary: Arc<CanaryMonitor>,
+    pub events: Arc<EventBus>,
+    /// Unset when encryption is not configured
+    pub key_rotation: Option<Arc<dyn KeyRotationManager>>,
+    /// Unset when no Git repositories are imported
+    pub git_sync: Option<Arc<dyn GitSync>>,
+    /// Unset when tenant isolation is disabled
+    pub tenants: Option<Arc<dyn TenantManager>>,
+    /// Unset when no backup location is configured
+    pub backups: Option<Arc<dyn BackupManager>>,
+    /// Unset when requests aren't rate limited
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Largest accepted config content; request bodies are sized to fit it
+    pub max_content_bytes: usize,
+    /// How long clients may reuse config reads
+    pub cache_policy: CachePolicy,
+}
 
 /// Configure REST API routes
-pub fn configure_routes(config: &mut web::ServiceConfig, config_manager: Arc<dyn ConfigManager>) {
-    config.app_data(web::Data::new(config_manager));
+pub fn configure_routes(config: &mut web::ServiceConfig, services: ApiServices) {
+    let monitoring = services.monitoring;
+    // Escaping can double content inside a JSON body
+    let body_limit = services.max_content_bytes * 2 + BODY_OVERHEAD_BYTES;
+    config.app_data(web::JsonConfig::default().limit(body_limit));
+    config.app_data(web::PayloadConfig::new(body_limit));
+    config.app_data(web::Data::from(services.log_level));
+    config.app_data(web::Data::new(services.cache_policy));
+    config.app_data(web::Data::new(services.change_reason));
+    config.app_data(web::Data::new(services.release_policy));
+    config.app_data(web::Data::from(services.release_gates));
+    config.app_data(web::Data::new(services.approval_policy));
+    config.app_data(web::Data::from(services.canary));
+    config.app_data(web::Data::from(services.events));
+    if let Some(key_rotation) = services.key_rotation {
+        config.app_data(web::Data::from(key_rotation));
+    }
+    if let Some(git_sync) = services.git_sync {
+        config.app_data(web::Data::from(git_sync));
+    }
+    if let Some(tenants) = services.tenants {
+        config.app_data(web::Data::from(tenants));
+    }
+    if let Some(backups) = services.backups {
+        config.app_data(web::Data::from(backups));
+    }
+    if let Some(rate_limiter) = services.rate_limiter {
+        config.app_data(web::Data::from(rate_limiter));
+    }
+    config.app_data(web::Data::new(monitoring.config_metrics().clone()));
+    config.app_data(web::Data::new(monitoring.client_metrics().clone()));
+    config.app_data(web::Data::from(services.alert_engine));
+    config.app_data(web::Data::from(services.audit_service));
+    config.app_data(web::Data::from(services.config_manager));
+    config.app_data(web::Data::from(services.version_control));
+    config.app_data(web::Data::from(services.changesets));
+    config.app_data(web::Data::from(services.approvals));
+    config.app_data(web::Data::from(services.schemas));
+    config.app_data(web::Data::from(services.rules));
+    config.app_data(web::Data::from(services.secret_paths));
+    config.app_data(web::Data::from(services.secret_expiry));
+    config.app_data(web::Data::new(services.secret_expiry_policy));
+    config.app_data(web::Data::from(services.secret_shares));
+    config.app_data(web::Data::from(services.recipients));
+    config.app_data(web::Data::from(services.validation_hooks));
+    config.app_data(web::Data::from(services.notifications));
+    config.app_data(web::Data::from(services.namespaces));
+    config.app_data(web::Data::from(services.promotions));
+    config.app_data(web::Data::from(services.freeze_windows));
+    config.app_data(web::Data::from(services.teams));
+    config.app_data(web::Data::from(services.flags));
+    config.app_data(web::Data::from(services.namespace_stats));
+    config.app_data(web::Data::from(services.releases));
+    config.app_data(web::Data::from(services.policy_service.enforcer()));
+    config.app_data(web::Data::from(services.policy_service));
 
     config.service(
         web::scope("/api/v1")
+            .wrap(middleware::from_fn(freeze::overriding))
+            .wrap(middleware::from_fn(audit::capture))
+            .wrap(middleware::from_fn(ratelimit::limit))
+            .wrap(middleware::from_fn(usage::track))
+            .route("/ws", web::get().to(ws::subscribe))
+            .route("/events", web::get().to(handlers::list_events))
+            .route(
+                "/discovery/prometheus",
+                web::get().to(handlers::prometheus_discovery),
+            )
+            .route("/compare", web::get().to(handlers::compare_environments))
             .route("/configs", web::post().to(handlers::create_config))
             .route("/configs", web::get().to(handlers::list_configs))
+            .route("/configs/stream", web::get().to(handlers::stream_configs))
             .route("/configs/{id}", web::get().to(handlers::get_config))
+            .route("/configs/{id}/raw", web::get().to(handlers::get_raw_config))
             .route("/configs/{id}", web::put().to(handlers::update_config))
-            .route("/configs/{id}", web::delete().to(handlers::delete_config)),
+            .route("/configs/{id}", web::patch().to(handlers::patch_config))
+            .route("/configs/{id}", web::delete().to(handlers::delete_config))
+            .route(
+                "/configs/{id}/owners",
+                web::put().to(handlers::update_owners),
+            )
+            .route(
+                "/configs/{id}/labels",
+                web::put().to(handlers::update_labels),
+            )
+            .route("/configs/{id}/rules", web::get().to(handlers::get_rules))
+            .route("/configs/{id}/rules", web::put().to(handlers::set_rules))
+            .route(
+                "/configs/{id}/secrets",
+                web::get().to(handlers::get_secret_paths),
+            )
+            .route(
+                "/configs/{id}/secrets",
+                web::put().to(handlers::set_secret_paths),
+            )
+            .route(
+                "/configs/{id}/expiry",
+                web::get().to(handlers::get_secret_expiry),
+            )
+            .route(
+                "/configs/{id}/expiry",
+                web::put().to(handlers::set_secret_expiry),
+            )
+            .route(
+                "/configs/{id}/shares",
+                web::post().to(handlers::create_secret_share),
+            )
+            .route(
+                "/shares/redeem",
+                web::post().to(handlers::redeem_secret_share),
+            )
+            .route(
+                "/configs/{id}/versions",
+                web::get().to(handlers::list_versions),
+            )
+            .route(
+                "/configs/{id}/versions/{version}",
+                web::get().to(handlers::get_version),
+            )
+            .route(
+                "/configs/{id}/versions/{version}/clone",
+                web::post().to(handlers::clone_version),
+            )
+            .route(
+                "/configs/{id}/versions/{version}/pin",
+                web::put().to(handlers::pin_version),
+            )
+            .route(
+                "/configs/{id}/versions/{version}/pin",
+                web::delete().to(handlers::unpin_version),
+            )
+            .route(
+                "/configs/{id}/tags/{tag}",
+                web::get().to(handlers::get_tagged_version),
+            )
+            .route(
+                "/configs/{id}/tags/{tag}",
+                web::put().to(handlers::tag_version),
+            )
+            .route(
+                "/configs/{id}/tags/{tag}",
+                web::delete().to(handlers::untag_version),
+            )
+            .route(
+                "/configs/{id}/rollback",
+                web::post().to(handlers::rollback_config),
+            )
+            .route(
+                "/configs/{id}/releases/{version}/health",
+                web::post().to(handlers::report_release_health),
+            )
+            .route("/flags", web::post().to(handlers::create_flag))
+            .route("/flags", web::get().to(handlers::list_flags))
+            .route("/flags/{key}", web::get().to(handlers::get_flag))
+            .route("/flags/{key}", web::put().to(handlers::update_flag))
+            .route("/flags/{key}", web::delete().to(handlers::delete_flag))
+            .route(
+                "/flags/{key}/evaluate",
+                web::post().to(handlers::evaluate_flag),
+            )
+            .route("/teams", web::post().to(handlers::create_team))
+            .route("/teams", web::get().to(handlers::list_teams))
+            .route("/teams/{name}", web::get().to(handlers::get_team))
+            .route("/teams/{name}", web::delete().to(handlers::delete_team))
+            .route(
+                "/teams/{name}/members",
+                web::post().to(handlers::add_team_member),
+            )
+            .route(
+                "/teams/{name}/members/{user}",
+                web::delete().to(handlers::remove_team_member),
+            )
+            .route("/namespaces", web::post().to(handlers::create_namespace))
+            .route("/namespaces", web::get().to(handlers::list_namespaces))
+            .route(
+                "/namespaces/{namespace}",
+                web::get().to(handlers::get_namespace),
+            )
+            .route(
+                "/namespaces/{namespace}",
+                web::put().to(handlers::update_namespace),
+            )
+            .route(
+                "/namespaces/{namespace}",
+                web::delete().to(handlers::delete_namespace),
+            )
+            .route("/resolve", web::get().to(handlers::resolve))
+            .route(
+                "/namespaces/{namespace}/stats",
+                web::get().to(handlers::get_namespace_stats),
+            )
+            .route(
+                "/namespaces/{namespace}/status",
+                web::put().to(handlers::set_namespace_status),
+            )
+            .route(
+                "/namespaces/{namespace}/inherited",
+                web::get().to(handlers::get_inherited_configs),
+            )
+            .route(
+                "/namespaces/{namespace}/configs",
+                web::get().to(handlers::get_namespace_at),
+            )
+            .route(
+                "/namespaces/{namespace}/state",
+                web::get().to(handlers::get_namespace_state),
+            )
+            .route(
+                "/namespaces/{namespace}/export",
+                web::get().to(archive::export_namespace),
+            )
+            .service(
+                web::resource("/namespaces/{namespace}/import")
+                    .app_data(web::PayloadConfig::new(archive::MAX_ARCHIVE_BYTES))
+                    .route(web::post().to(archive::import_namespace)),
+            )
+            .service(
+                web::resource("/namespaces/{namespace}/import/{source}")
+                    .app_data(web::PayloadConfig::new(archive::MAX_ARCHIVE_BYTES))
+                    .route(web::post().to(importer::import_external)),
+            )
+            .route(
+                "/namespaces/{namespace}/recipients",
+                web::get().to(handlers::get_recipients),
+            )
+            .route(
+                "/namespaces/{namespace}/recipients",
+                web::put().to(handlers::set_recipients),
+            )
+            .route("/changesets", web::post().to(handlers::create_changeset))
+            .route("/changesets", web::get().to(handlers::list_changesets))
+            .route("/changesets/{id}", web::get().to(handlers::get_changeset))
+            .route(
+                "/changesets/{id}",
+                web::delete().to(handlers::discard_changeset),
+            )
+            .route(
+                "/changesets/{id}/changes/{config_id}",
+                web::put().to(handlers::stage_change),
+            )
+            .route(
+                "/changesets/{id}/changes/{config_id}",
+                web::delete().to(handlers::unstage_change),
+            )
+            .route(
+                "/changesets/{id}/diff",
+                web::get().to(handlers::changeset_diff),
+            )
+            .route(
+                "/changesets/{id}/apply",
+                web::post().to(handlers::apply_changeset),
+            )
+            .route(
+                "/configs/{id}/promote",
+                web::post().to(handlers::promote_config),
+            )
+            .route("/promotions", web::get().to(handlers::list_promotions))
+            .route("/promotions/{id}", web::get().to(handlers::get_promotion))
+            .route(
+                "/promotions/{id}/approve",
+                web::post().to(handlers::approve_promotion),
+            )
+            .route(
+                "/promotions/{id}/reject",
+                web::post().to(handlers::reject_promotion),
+            )
+            .route("/approvals", web::get().to(handlers::list_approvals))
+            .route("/approvals/{id}", web::get().to(handlers::get_approval))
+            .route(
+                "/approvals/{id}/approve",
+                web::post().to(handlers::approve_change),
+            )
+            .route(
+                "/approvals/{id}/reject",
+                web::post().to(handlers::reject_change),
+            )
+            .route(
+                "/configs/{id}/release",
+                web::post().to(handlers::release_config),
+            )
+            .route(
+                "/configs/{id}/releases",
+                web::get().to(handlers::config_releases),
+            )
+            .route(
+                "/configs/{id}/dependencies",
+                web::get().to(handlers::list_dependencies),
+            )
+            .route(
+                "/configs/{id}/dependencies",
+                web::post().to(handlers::add_dependency),
+            )
+            .route(
+                "/configs/{id}/dependencies/{depends_on}",
+                web::delete().to(handlers::remove_dependency),
+            )
+            .route("/releases", web::get().to(handlers::list_releases))
+            .route("/releases/batch", web::post().to(handlers::batch_release))
+            .route("/releases/{id}", web::get().to(handlers::get_release))
+            .route(
+                "/releases/{id}/approve",
+                web::post().to(handlers::approve_release),
+            )
+            .route(
+                "/releases/{id}/reject",
+                web::post().to(handlers::reject_release),
+            )
+            .route(
+                "/releases/{id}/cancel",
+                web::post().to(handlers::cancel_release),
+            )
+            .route(
+                "/releases/{id}/rollout",
+                web::put().to(handlers::set_rollout),
+            )
+            .route(
+                "/releases/{id}/finalize",
+                web::post().to(handlers::finalize_release),
+            )
+            .route(
+                "/releases/{id}/abort",
+                web::post().to(handlers::abort_release),
+            )
+            .route("/schemas", web::post().to(handlers::create_schema))
+            .route("/schemas", web::get().to(handlers::list_schemas))
+            .route("/schemas/{id}", web::get().to(handlers::get_schema))
+            .route("/schemas/{id}", web::put().to(handlers::update_schema))
+            .route("/schemas/{id}", web::delete().to(handlers::delete_schema))
+            .route(
+                "/validation-hooks",
+                web::post().to(handlers::create_validation_hook),
+            )
+            .route(
+                "/validation-hooks",
+                web::get().to(handlers::list_validation_hooks),
+            )
+            .route(
+                "/validation-hooks/{id}",
+                web::delete().to(handlers::delete_validation_hook),
+            )
+            .route(
+                "/notifications",
+                web::post().to(handlers::create_notification),
+            )
+            .route(
+                "/notifications",
+                web::get().to(handlers::list_notifications),
+            )
+            .route(
+                "/notifications/{id}",
+                web::delete().to(handlers::delete_notification),
+            )
+            .route("/grants", web::post().to(handlers::create_grant))
+            .route("/grants", web::get().to(handlers::list_grants))
+            .route("/grants/{id}", web::delete().to(handlers::revoke_grant))
+            .route("/audit/logs", web::get().to(handlers::list_audit_logs))
+            .route("/audit/export", web::get().to(handlers::export_audit_logs))
+            .route("/alerts", web::get().to(handlers::list_alerts))
+            .route("/stats/clients", web::get().to(handlers::client_stats))
+            .route("/admin/loglevel", web::get().to(handlers::get_log_level))
+            .route("/admin/loglevel", web::put().to(handlers::set_log_level))
+            .route(
+                "/admin/keyrotation",
+                web::get().to(handlers::get_key_rotation),
+            )
+            .route(
+                "/admin/keyrotation",
+                web::post().to(handlers::start_key_rotation),
+            )
+            .route("/admin/tenants", web::post().to(handlers::provision_tenant))
+            .route("/admin/tenants", web::get().to(handlers::list_tenants))
+            .route("/admin/tenants/{name}", web::get().to(handlers::get_tenant))
+            .route(
+                "/admin/freezewindows",
+                web::post().to(handlers::create_freeze_window),
+            )
+            .route(
+                "/admin/freezewindows",
+                web::get().to(handlers::list_freeze_windows),
+            )
+            .route(
+                "/admin/freezewindows/{id}",
+                web::delete().to(handlers::delete_freeze_window),
+            )
+            .route("/admin/backup", web::post().to(backup::start_backup))
+            .route("/admin/backups", web::get().to(backup::list_backups))
+            .route("/admin/restore", web::post().to(backup::restore_backup))
+            .route(
+                "/admin/backups/{name}/verify",
+                web::post().to(backup::verify_backup),
+            )
+            .route("/admin/import", web::post().to(bulk::bulk_import))
+            .route(
+                "/admin/backup/jobs",
+                web::get().to(backup::list_backup_jobs),
+            )
+            .route(
+                "/admin/backup/jobs/{id}",
+                web::get().to(backup::get_backup_job),
+            )
+            .route(
+                "/git/{repository}/sync",
+                web::post().to(git::sync_repository),
+            )
+            .route("/git/{repository}/webhook", web::post().to(git::webhook)),
+    );
+
+    // consul-template and envconsul read through Consul's KV API at the server root
+    config.service(
+        web::resource("/v1/kv/{key:.*}")
+            .wrap(middleware::from_fn(ratelimit::limit))
+            .route(web::get().to(consul::get_kv)),
+    );
+
+    // Spring Cloud Config clients use `http://<host>/spring` as their config server URI
+    config.service(
+        web::scope("/spring")
+            .wrap(middleware::from_fn(ratelimit::limit))
+            .route(
+                "/{application}/{profile}",
+                web::get().to(spring::environment),
+            )
+            .route(
+                "/{application}/{profile}/{label}",
+                web::get().to(spring::labelled_environment),
+            ),
     );
 }
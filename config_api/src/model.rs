@@ -1,6 +1,17 @@
-use config_common::{ConfigContent, ConfigMeta};
+use chrono::{DateTime, Utc};
+use config_audit::ConfigDiff;
+use config_common::{AuditLog, ConfigContent, ConfigFormat, ConfigMeta};
+use config_core::{
+    ApprovalStatus, ChangeSetStatus, ConfigVersion, FlagSettings, NamespaceStatus,
+    NotificationChannel, NotificationFilter, Promotion, PromotionStatus, PublishedEvent,
+    RecipientKey, Release, ReleaseStatus, RolloutRule, SecretShare, ValidationRule,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::archive::ConflictStrategy;
+use crate::export::ExportFormat;
+use crate::importer::GroupMapping;
+
 /// REST API request and response types
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateConfigRequest {
@@ -13,10 +24,91 @@ pub struct CreateConfigRequest {
     pub content: ConfigContent,
 }
 
+/// Optional instant to read a configuration as of
+#[derive(Debug, Deserialize)]
+pub struct PointInTimeRequest {
+    pub at: Option<DateTime<Utc>>,
+    /// Convert the content to `json`, `yaml`, `toml` or `properties`, or render it as `env`
+    pub format: Option<String>,
+    /// Return encrypted content decrypted
+    #[serde(default)]
+    pub decrypt: bool,
+    /// Return the latest version even when it isn't released
+    #[serde(default)]
+    pub draft: bool,
+    /// Leave `${ref:...}` placeholders unresolved
+    #[serde(default)]
+    pub raw_refs: bool,
+    /// Stable client identity placing the caller in or out of a gray rollout
+    pub client_id: Option<String>,
+    /// Comma-separated labels rollout rules select clients by, e.g. `region=eu,cluster=blue`
+    pub client_labels: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawConfigRequest {
+    /// Return encrypted content decrypted
+    #[serde(default)]
+    pub decrypt: bool,
+    /// Stable client identity placing the caller in or out of a gray rollout
+    pub client_id: Option<String>,
+    /// Comma-separated labels rollout rules select clients by, e.g. `region=eu,cluster=blue`
+    pub client_labels: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NamespaceStateRequest {
+    /// Stable client identity placing the caller in or out of a gray rollout
+    pub client_id: Option<String>,
+    /// Comma-separated labels rollout rules select clients by, e.g. `region=eu,cluster=blue`
+    pub client_labels: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NamespaceAtRequest {
+    pub at: DateTime<Utc>,
+}
+
+/// Every config of a namespace keyed by `application/environment/name`, for infrastructure
+/// tools detecting drift
+#[derive(Debug, Serialize)]
+pub struct NamespaceState {
+    pub namespace: String,
+    /// SHA-256 of the serialized `configs`; changes whenever any config does
+    pub hash: String,
+    pub configs: std::collections::BTreeMap<String, ConfigState>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigState {
+    pub id: String,
+    pub version: String,
+    pub format: ConfigFormat,
+    /// Redacted where values are encrypted
+    pub content: String,
+    pub is_encrypted: bool,
+    /// SHA-256 of `content`
+    pub content_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscoveryRequest {
+    pub namespace: Option<String>,
+}
+
+/// Target group of Prometheus HTTP service discovery
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TargetGroup {
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub labels: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateConfigRequest {
     pub description: Option<String>,
     pub content: ConfigContent,
+    pub change_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,10 +119,555 @@ pub struct ListConfigsRequest {
     pub environment: Option<String>,
     pub page_size: Option<i32>,
     pub page_number: Option<i32>,
+    /// Also list configs of archived namespaces when no namespace is given
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ListConfigsResponse {
     pub configs: Vec<ConfigMeta>,
     pub total: i32,
+    /// Listed configs whose secrets are near or past their expiry date
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<SecretExpiryWarning>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecretExpiryWarning {
+    pub config_id: String,
+    pub expires_at: i64,
+    pub expired: bool,
+}
+
+/// Query of a PATCH; the body is the patch document
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatchConfigRequest {
+    pub change_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateOwnersRequest {
+    pub owners: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLabelsRequest {
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    pub version: String,
+    pub change_reason: Option<String>,
+}
+
+/// Target of a clone; unset fields are copied from the source configuration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloneVersionRequest {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub department: Option<String>,
+    pub application: Option<String>,
+    pub environment: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagVersionRequest {
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigVersionResponse {
+    #[serde(flatten)]
+    pub version: ConfigVersion,
+    pub content: ConfigContent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateGrantRequest {
+    pub user: String,
+    pub role: String,
+    pub duration_secs: u64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListAuditLogsRequest {
+    pub user: Option<String>,
+    pub action: Option<String>,
+    pub resource: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub page_size: Option<i32>,
+    pub page_number: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAuditLogsResponse {
+    pub logs: Vec<AuditLog>,
+    pub total: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportAuditLogsRequest {
+    pub format: ExportFormat,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub user: Option<String>,
+    pub action: Option<String>,
+    pub resource: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientStatsRequest {
+    #[serde(default = "default_client_limit")]
+    pub limit: usize,
+}
+
+fn default_client_limit() -> usize {
+    10
+}
+
+/// Tracing filter directives, e.g. `info,raft=debug`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogLevelRequest {
+    pub filter: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartKeyRotationRequest {
+    /// Master key to activate; a new version of the active key is created when unset
+    pub key_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateFreezeWindowRequest {
+    pub environment: String,
+    /// Day and UTC time the window opens, e.g. `fri 18:00`
+    pub starts: String,
+    /// Day and UTC time the window closes, e.g. `mon 08:00`
+    pub ends: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListFreezeWindowsRequest {
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTeamRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateFlagRequest {
+    pub key: String,
+    pub namespace: String,
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub settings: FlagSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateFlagRequest {
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub settings: FlagSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListFlagsRequest {
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddTeamMemberRequest {
+    pub user: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProvisionTenantRequest {
+    /// Department to isolate
+    pub name: String,
+    /// Master key encrypting the tenant's content; the active key when unset
+    pub key_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateChangeSetRequest {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// Schema document in JSON or YAML
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSchemaRequest {
+    pub namespace: String,
+    /// Unset to cover every application of the namespace
+    pub application: Option<String>,
+    pub description: Option<String>,
+    #[serde(default = "default_schema_format")]
+    pub format: ConfigFormat,
+    pub schema: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSchemaRequest {
+    pub description: Option<String>,
+    #[serde(default = "default_schema_format")]
+    pub format: ConfigFormat,
+    pub schema: String,
+}
+
+fn default_schema_format() -> ConfigFormat {
+    ConfigFormat::Json
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSchemasRequest {
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateValidationHookRequest {
+    pub namespace: String,
+    pub url: String,
+    #[serde(default = "default_hook_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Accept changes when the hook is unreachable instead of rejecting them
+    #[serde(default)]
+    pub fail_open: bool,
+}
+
+fn default_hook_timeout_ms() -> u64 {
+    3000
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListValidationHooksRequest {
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateNamespaceRequest {
+    pub name: String,
+    /// Namespace whose configs this one inherits
+    pub parent: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateNamespaceRequest {
+    pub parent: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListNamespacesRequest {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetNamespaceStatusRequest {
+    pub status: NamespaceStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListInheritedConfigsRequest {
+    pub application: Option<String>,
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateNotificationRequest {
+    pub name: String,
+    pub channel: NotificationChannel,
+    #[serde(default)]
+    pub filter: NotificationFilter,
+    /// Message body with `{placeholder}` event fields; a one-line summary when absent
+    pub template: Option<String>,
+    /// Seconds to coalesce events per config before sending them as one message
+    #[serde(default)]
+    pub debounce_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetRulesRequest {
+    pub rules: Vec<ValidationRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetSecretPathsRequest {
+    /// Dotted paths whose values are encrypted, e.g. `database.password`
+    pub paths: Vec<String>,
+    pub change_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetSecretExpiryRequest {
+    /// Expiry or rotation-due date; clears the date when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    pub change_reason: Option<String>,
+}
+
+/// Recipient keys of a namespace; an empty list leaves client-side mode
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetRecipientsRequest {
+    pub recipients: Vec<RecipientKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSecretShareRequest {
+    /// Seconds until the link expires, an hour when absent
+    pub ttl_secs: Option<i64>,
+}
+
+/// Created share; the token is shown only once
+#[derive(Debug, Serialize)]
+pub struct SecretShareResponse {
+    #[serde(flatten)]
+    pub share: SecretShare,
+    pub token: String,
+}
+
+/// Token in the body, so it never shows up in request paths or audit records
+#[derive(Debug, Deserialize)]
+pub struct RedeemSecretShareRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListEventsRequest {
+    /// Event id to continue after, or RFC 3339 time to start from
+    pub since: Option<String>,
+    pub namespace: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListEventsResponse {
+    pub events: Vec<PublishedEvent>,
+    /// Pass as `since` for the next page; absent once caught up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListChangeSetsRequest {
+    pub status: Option<ChangeSetStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StageChangeRequest {
+    pub description: Option<String>,
+    pub content: ConfigContent,
+}
+
+/// Preview of one staged edit against the current configuration
+#[derive(Debug, Serialize)]
+pub struct ChangeSetDiffEntry {
+    pub config_id: String,
+    pub name: String,
+    pub namespace: String,
+    pub environment: String,
+    pub base_version: String,
+    pub current_version: String,
+    /// The configuration was updated after the edit was staged
+    pub conflict: bool,
+    pub diff: ConfigDiff,
+}
+
+/// Application and environment to resolve the layered document of
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveRequest {
+    pub namespace: String,
+    pub application: String,
+    pub environment: String,
+    /// Only merge configs of this name
+    pub name: Option<String>,
+    /// Merge the latest versions even when they aren't released
+    #[serde(default)]
+    pub draft: bool,
+    /// Stable client identity placing the caller in or out of a gray rollout
+    pub client_id: Option<String>,
+    /// Comma-separated labels rollout rules select clients by, e.g. `region=eu,cluster=blue`
+    pub client_labels: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromoteConfigRequest {
+    /// Environment of the config receiving the content, e.g. `production`
+    pub target_environment: String,
+    pub change_reason: Option<String>,
+    /// Only preview the diff without requesting the promotion
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompareRequest {
+    pub application: String,
+    pub from_env: String,
+    pub to_env: String,
+    /// Only compare configs of this namespace
+    pub namespace: Option<String>,
+}
+
+/// Configs of an application that differ between two environments
+#[derive(Debug, Serialize)]
+pub struct EnvironmentComparison {
+    pub application: String,
+    pub from_env: String,
+    pub to_env: String,
+    pub configs: Vec<ConfigComparison>,
+}
+
+/// Difference of a config, matched by namespace and name, between two environments
+#[derive(Debug, Serialize)]
+pub struct ConfigComparison {
+    pub namespace: String,
+    pub name: String,
+    /// Absent when the config only exists in `to_env`
+    pub from_id: Option<String>,
+    pub from_version: Option<String>,
+    /// Absent when the config only exists in `from_env`
+    pub to_id: Option<String>,
+    pub to_version: Option<String>,
+    pub diff: ConfigDiff,
+}
+
+/// Changes a promotion makes to the target config
+#[derive(Debug, Serialize)]
+pub struct PromotionPreview {
+    /// The pending promotion; absent for a dry run
+    pub promotion: Option<Promotion>,
+    pub source_version: String,
+    /// Absent when approving creates the target config
+    pub target_id: Option<String>,
+    pub target_version: Option<String>,
+    pub diff: ConfigDiff,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListPromotionsRequest {
+    pub status: Option<PromotionStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListApprovalsRequest {
+    pub status: Option<ApprovalStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReleaseConfigRequest {
+    /// Version to serve; the latest when unset
+    pub version: Option<String>,
+    pub change_reason: Option<String>,
+    /// Serve the version only to the clients the rule selects, until finalized
+    pub rollout: Option<RolloutRule>,
+    /// Publish at this time rather than once approved, in RFC 3339 or as a local
+    /// `YYYY-MM-DDTHH:MM` time in the configured release timezone
+    pub publish_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListReleasesRequest {
+    pub config_id: Option<String>,
+    pub status: Option<ReleaseStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportNamespaceRequest {
+    /// Include every config's version history
+    #[serde(default)]
+    pub versions: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportNamespaceRequest {
+    /// Report what the import would do without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// What to do with configs already in the namespace
+    #[serde(default)]
+    pub conflict: ConflictStrategy,
+    /// Reason recorded on overwritten configs
+    pub change_reason: Option<String>,
+}
+
+/// Query of `POST /admin/import`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkImportRequest {
+    /// Report what the import would do without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Department of created configs
+    #[serde(default = "default_import_department")]
+    pub department: String,
+    /// Reason recorded on updated configs
+    pub change_reason: Option<String>,
+}
+
+/// Query of `POST /namespaces/{namespace}/import/{source}`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalImportRequest {
+    /// Report how the export maps onto configs without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// What to do with configs already in the namespace
+    #[serde(default)]
+    pub conflict: ConflictStrategy,
+    /// Reason recorded on overwritten configs
+    pub change_reason: Option<String>,
+    /// Department of created configs
+    #[serde(default = "default_import_department")]
+    pub department: String,
+    /// Environment of configs whose export doesn't name one
+    #[serde(default = "default_import_environment")]
+    pub environment: String,
+    /// What Nacos groups map to
+    #[serde(default)]
+    pub group_as: GroupMapping,
+}
+
+fn default_import_department() -> String {
+    "default".to_string()
+}
+
+fn default_import_environment() -> String {
+    "default".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreBackupRequest {
+    /// Name of the backup, as listed by `GET /admin/backups`
+    pub backup: String,
+    /// RFC 3339 time to restore configs to; the backup's time when unset
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddDependencyRequest {
+    /// Config that must be released first
+    pub depends_on: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchReleaseRequest {
+    /// Configs whose latest versions are released, in dependency order
+    pub config_ids: Vec<String>,
+    pub change_reason: Option<String>,
+}
+
+/// Releases of a batch, in the order they were requested
+#[derive(Debug, Serialize)]
+pub struct BatchReleaseResponse {
+    pub releases: Vec<Release>,
+    /// Configs held back because a release before them, or their own, was blocked
+    pub unreleased: Vec<String>,
 }
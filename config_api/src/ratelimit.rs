@@ -0,0 +1,384 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpResponse};
+use config_core::ConfigManager;
+use redis::aio::MultiplexedConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::auth::caller_identity;
+
+/// Prefix of the Redis keys holding cluster-wide buckets
+const REDIS_KEY_PREFIX: &str = "config-server:rate-limit:";
+
+/// Node-local buckets kept before full ones are dropped
+const MAX_LOCAL_BUCKETS: usize = 10_000;
+
+/// Namespaces of configs addressed by ID kept before the cache is cleared
+const MAX_CACHED_NAMESPACES: usize = 10_000;
+
+/// Takes a token from the bucket in `KEYS[1]`, refilled at `ARGV[1]` tokens a second up to
+/// `ARGV[2]`, on the Redis clock so every node sees the same time. Returns the seconds to
+/// wait as a string, since Redis truncates fractional numbers; 0 when a token was taken.
+const TAKE_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+local tokens = tonumber(bucket[1]) or burst
+local at = tonumber(bucket[2]) or now
+tokens = math.min(burst, tokens + math.max(0, now - at) * rate)
+local wait = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+else
+    wait = (1 - tokens) / rate
+end
+redis.call('HSET', KEYS[1], 'tokens', tokens, 'at', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(burst / rate * 1000) + 1000)
+return tostring(wait)
+"#;
+
+/// Puts back the token taken from the bucket in `KEYS[1]` by a request refused by another
+/// bucket, up to `ARGV[1]`
+const REFUND_SCRIPT: &str = r#"
+local tokens = tonumber(redis.call('HGET', KEYS[1], 'tokens'))
+if tokens then
+    redis.call('HSET', KEYS[1], 'tokens', math.min(tonumber(ARGV[1]), tokens + 1))
+end
+return 0
+"#;
+
+/// Token bucket: `burst` requests at once, refilled at `per_second`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: u32,
+}
+
+/// Request rate limits. Clients are told apart by their identity header; namespaces by the
+/// `/namespaces/{namespace}` path or `namespace` query of the request, the first segment of
+/// a Consul key, or the config a `/configs/{id}` path addresses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Limit of each client without an override; unlimited when unset
+    #[serde(default)]
+    pub client: Option<RateLimit>,
+    /// Limits of particular clients
+    #[serde(default)]
+    pub clients: HashMap<String, RateLimit>,
+    /// Limit of each namespace without an override; unlimited when unset
+    #[serde(default)]
+    pub namespace: Option<RateLimit>,
+    /// Limits of particular namespaces
+    #[serde(default)]
+    pub namespaces: HashMap<String, RateLimit>,
+    /// Share buckets between nodes through Redis; otherwise each node limits on its own
+    #[serde(default)]
+    pub cluster_wide: bool,
+}
+
+impl RateLimitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.client.is_some()
+            || !self.clients.is_empty()
+            || self.namespace.is_some()
+            || !self.namespaces.is_empty()
+    }
+
+    fn limits_namespaces(&self) -> bool {
+        self.namespace.is_some() || !self.namespaces.is_empty()
+    }
+
+    fn client_limit(&self, client: &str) -> Option<&RateLimit> {
+        self.clients.get(client).or(self.client.as_ref())
+    }
+
+    fn namespace_limit(&self, namespace: &str) -> Option<&RateLimit> {
+        self.namespaces.get(namespace).or(self.namespace.as_ref())
+    }
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    at: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            limit: limit.clone(),
+            tokens: limit.burst as f64,
+            at: now,
+        }
+    }
+
+    fn refund(&mut self) {
+        self.tokens = (self.tokens + 1.0).min(self.limit.burst as f64);
+    }
+
+    /// Take a token, or return how long until one is available
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        self.tokens = self.tokens_at(now);
+        self.at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.limit.per_second,
+            ))
+        }
+    }
+
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let refill = now.duration_since(self.at).as_secs_f64() * self.limit.per_second;
+        (self.tokens + refill).min(self.limit.burst as f64)
+    }
+}
+
+/// Token buckets per client and namespace, in Redis when limits are cluster-wide. When Redis
+/// can't be reached each node falls back to its own buckets rather than failing requests.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    redis: Option<redis::Client>,
+    connection: tokio::sync::Mutex<Option<MultiplexedConnection>>,
+    script: redis::Script,
+    refund_script: redis::Script,
+    local: Mutex<HashMap<String, Bucket>>,
+    /// Namespace of each config addressed by ID, which never changes
+    config_namespaces: Mutex<HashMap<String, String>>,
+}
+
+impl RateLimiter {
+    /// `redis` is only used when limits are cluster-wide
+    pub fn new(
+        config: RateLimitConfig,
+        redis: Option<redis::Client>,
+    ) -> config_common::Result<Self> {
+        let limits = config
+            .client
+            .iter()
+            .chain(config.clients.values())
+            .chain(config.namespace.iter())
+            .chain(config.namespaces.values());
+        for limit in limits {
+            if limit.per_second <= 0.0 || limit.burst == 0 {
+                return Err(config_common::Error::Config(
+                    "rate limits need a positive rate and burst".to_string(),
+                ));
+            }
+        }
+        let redis = redis.filter(|_| config.cluster_wide);
+        Ok(Self {
+            config,
+            redis,
+            connection: tokio::sync::Mutex::new(None),
+            script: redis::Script::new(TAKE_SCRIPT),
+            refund_script: redis::Script::new(REFUND_SCRIPT),
+            local: Mutex::new(HashMap::new()),
+            config_namespaces: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Wait before a request of the client may go through, `None` when it may go now
+    async fn check_client(&self, client: &str) -> Option<Duration> {
+        let limit = self.config.client_limit(client)?;
+        self.take(&format!("client:{}", client), limit).await
+    }
+
+    /// Wait before a request the client was let through for may go to the namespace, `None`
+    /// when it may go now. A refused request gets the client's token back.
+    async fn check_namespace(&self, client: &str, namespace: &str) -> Option<Duration> {
+        let limit = self.config.namespace_limit(namespace)?;
+        let wait = self
+            .take(&format!("namespace:{}", namespace), limit)
+            .await?;
+        if let Some(limit) = self.config.client_limit(client) {
+            self.refund(&format!("client:{}", client), limit).await;
+        }
+        Some(wait)
+    }
+
+    /// Namespace of a config addressed by ID, read once and cached
+    async fn config_namespace(&self, req: &ServiceRequest, id: &str) -> Option<String> {
+        if let Some(namespace) = self.lock_config_namespaces().get(id) {
+            return Some(namespace.clone());
+        }
+        let config_manager = req.app_data::<web::Data<dyn ConfigManager>>()?;
+        // Unknown configs are left to the handler to refuse
+        let (meta, _) = config_manager.get_config(id).await.ok()?;
+        let mut namespaces = self.lock_config_namespaces();
+        if namespaces.len() >= MAX_CACHED_NAMESPACES {
+            namespaces.clear();
+        }
+        namespaces.insert(id.to_string(), meta.namespace.clone());
+        Some(meta.namespace)
+    }
+
+    fn lock_config_namespaces(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.config_namespaces
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn take(&self, key: &str, limit: &RateLimit) -> Option<Duration> {
+        if let Some(client) = &self.redis {
+            match self.take_shared(client, key, limit).await {
+                Ok(wait) => return wait,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Redis rate limit check failed, limiting locally");
+                    *self.connection.lock().await = None;
+                }
+            }
+        }
+        self.take_local(key, limit)
+    }
+
+    async fn refund(&self, key: &str, limit: &RateLimit) {
+        if let Some(client) = &self.redis {
+            match self.refund_shared(client, key, limit).await {
+                Ok(()) => return,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Redis rate limit refund failed, refunding locally");
+                    *self.connection.lock().await = None;
+                }
+            }
+        }
+        let mut buckets = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.refund();
+        }
+    }
+
+    async fn refund_shared(
+        &self,
+        client: &redis::Client,
+        key: &str,
+        limit: &RateLimit,
+    ) -> redis::RedisResult<()> {
+        let mut conn = self.connection(client).await?;
+        self.refund_script
+            .key(format!("{}{}", REDIS_KEY_PREFIX, key))
+            .arg(limit.burst)
+            .invoke_async::<i64>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn take_shared(
+        &self,
+        client: &redis::Client,
+        key: &str,
+        limit: &RateLimit,
+    ) -> redis::RedisResult<Option<Duration>> {
+        let mut conn = self.connection(client).await?;
+        let wait: String = self
+            .script
+            .key(format!("{}{}", REDIS_KEY_PREFIX, key))
+            .arg(limit.per_second)
+            .arg(limit.burst)
+            .invoke_async(&mut conn)
+            .await?;
+        let wait = wait.parse::<f64>().unwrap_or_default();
+        Ok((wait > 0.0).then(|| Duration::from_secs_f64(wait)))
+    }
+
+    /// Connection shared by all checks, opened on first use and after failures
+    async fn connection(
+        &self,
+        client: &redis::Client,
+    ) -> redis::RedisResult<MultiplexedConnection> {
+        let mut connection = self.connection.lock().await;
+        if let Some(conn) = connection.as_ref() {
+            return Ok(conn.clone());
+        }
+        let conn = client.get_multiplexed_async_connection().await?;
+        *connection = Some(conn.clone());
+        Ok(conn)
+    }
+
+    fn take_local(&self, key: &str, limit: &RateLimit) -> Option<Duration> {
+        let now = Instant::now();
+        let mut buckets = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_LOCAL_BUCKETS && !buckets.contains_key(key) {
+            // A full bucket is the same as none
+            buckets.retain(|_, bucket| bucket.tokens_at(now) < bucket.limit.burst as f64);
+        }
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket::new(limit, now))
+            .take(now)
+    }
+}
+
+/// Middleware answering `429 Too Many Requests` with `Retry-After` to clients over their
+/// limit or the limit of the namespace they address
+pub async fn limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let Some(limiter) = req.app_data::<web::Data<RateLimiter>>().cloned() else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    let client = caller_identity(req.request()).unwrap_or_else(|| "anonymous".to_string());
+
+    // The client is checked first so clients over their limit cost no namespace lookup
+    let mut wait = limiter.check_client(&client).await;
+    if wait.is_none() && limiter.config.limits_namespaces() {
+        if let Some(namespace) = request_namespace(&limiter, &req).await {
+            wait = limiter.check_namespace(&client, &namespace).await;
+        }
+    }
+
+    match wait {
+        None => Ok(next.call(req).await?.map_into_left_body()),
+        Some(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+                .body("rate limit exceeded");
+            Ok(req.into_response(response).map_into_right_body())
+        }
+    }
+}
+
+/// Namespace a request addresses; routes aren't matched yet, so the path is read directly
+/// and a config addressed by its ID is looked up
+async fn request_namespace(limiter: &RateLimiter, req: &ServiceRequest) -> Option<String> {
+    let path = req.path();
+    let from_path = path
+        .split_once("/namespaces/")
+        .and_then(|(_, rest)| rest.split('/').next())
+        .filter(|namespace| !namespace.is_empty())
+        .map(String::from);
+    let from_query = || {
+        web::Query::<HashMap<String, String>>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().remove("namespace"))
+            .filter(|namespace| !namespace.is_empty())
+    };
+    if let Some(namespace) = from_path.or_else(from_query) {
+        return Some(namespace);
+    }
+
+    // Consul keys are `namespace/application/environment/name`
+    if let Some(key) = path.strip_prefix("/v1/kv/") {
+        return key
+            .split_once('/')
+            .map(|(namespace, _)| namespace)
+            .filter(|namespace| !namespace.is_empty())
+            .map(String::from);
+    }
+    let id = path
+        .split_once("/configs/")
+        .and_then(|(_, rest)| rest.split('/').next())
+        .filter(|id| !id.is_empty())?;
+    limiter.config_namespace(req, id).await
+}
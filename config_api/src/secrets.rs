@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use config_audit::AuditService;
+use config_auth::PolicyEnforcer;
+use config_common::{AuditLog, ConfigMeta, Result};
+use config_core::SecretAccessControl;
+use std::sync::Arc;
+
+/// Policy action required, on top of read access, to see decrypted content
+pub const SECRET_READ_ACTION: &str = "secret:read";
+
+/// Audit action of a read of decrypted content
+const SECRET_READ_AUDIT_ACTION: &str = "configs.secret_read";
+
+/// Fail unless the reader is an admin or holds `secret:read` on the configuration
+pub async fn check_secret_read(
+    enforcer: &PolicyEnforcer,
+    reader: &str,
+    meta: &ConfigMeta,
+) -> Result<()> {
+    // Owning a configuration is not enough to read its secrets
+    if enforcer.is_admin(reader).await {
+        return Ok(());
+    }
+    enforcer
+        .check(
+            reader,
+            &config_auth::enforcer::config_resource(meta),
+            SECRET_READ_ACTION,
+        )
+        .await
+}
+
+/// Requires the `secret:read` permission for decrypted reads and audits each of them
+pub struct PolicySecretAccess {
+    enforcer: Arc<PolicyEnforcer>,
+    audit: Arc<dyn AuditService>,
+}
+
+impl PolicySecretAccess {
+    pub fn new(enforcer: Arc<PolicyEnforcer>, audit: Arc<dyn AuditService>) -> Self {
+        Self { enforcer, audit }
+    }
+}
+
+#[async_trait]
+impl SecretAccessControl for PolicySecretAccess {
+    async fn authorize_read(&self, reader: &str, meta: &ConfigMeta) -> Result<()> {
+        check_secret_read(&self.enforcer, reader, meta).await
+    }
+
+    async fn record_read(&self, reader: &str, meta: &ConfigMeta, key_ids: &[String]) -> Result<()> {
+        let details = serde_json::json!({
+            "namespace": meta.namespace,
+            "environment": meta.environment,
+            "version": meta.version,
+            "key_ids": key_ids,
+        });
+        self.audit
+            .record(AuditLog {
+                id: uuid::Uuid::new_v4().to_string(),
+                user: reader.to_string(),
+                action: SECRET_READ_AUDIT_ACTION.to_string(),
+                resource: format!("/api/v1/configs/{}", meta.id),
+                details: details.to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+            })
+            .await
+    }
+}
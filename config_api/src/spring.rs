@@ -0,0 +1,211 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use config_common::{ConfigContent, ConfigMeta};
+use config_core::resolve::Serving;
+use config_core::{ConfigFilter, ConfigManager, ConfigVersionControl, ReleaseManager};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Application whose configs every application shares, as in Spring Cloud Config
+const SHARED_APPLICATION: &str = "application";
+
+/// Profile whose configs apply whatever profiles are active
+const DEFAULT_PROFILE: &str = "default";
+
+/// Environment document of the Spring Cloud Config server protocol
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpringEnvironment {
+    pub name: String,
+    pub profiles: Vec<String>,
+    pub label: Option<String>,
+    pub version: Option<String>,
+    pub state: Option<String>,
+    /// Highest precedence first
+    pub property_sources: Vec<PropertySource>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PropertySource {
+    pub name: String,
+    pub source: Map<String, Value>,
+}
+
+/// `GET /spring/{application}/{profile}`: content of matching configs as served to the caller
+pub async fn environment(
+    http_req: HttpRequest,
+    path: web::Path<(String, String)>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let (application, profile) = path.into_inner();
+    let client = crate::handlers::rollout_client(&http_req, None, None);
+    let serving = Serving {
+        releases: releases.get_ref(),
+        version_control: version_control.get_ref(),
+        client: &client,
+    };
+    let environment = assemble(
+        &application,
+        &profile,
+        None,
+        config_manager.get_ref(),
+        &serving,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(environment))
+}
+
+/// `GET /spring/{application}/{profile}/{label}`: content of the versions tagged with the
+/// label; configs without the tag are left out
+pub async fn labelled_environment(
+    http_req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    config_manager: web::Data<dyn ConfigManager>,
+    version_control: web::Data<dyn ConfigVersionControl>,
+    releases: web::Data<dyn ReleaseManager>,
+) -> config_common::Result<HttpResponse> {
+    let (application, profile, label) = path.into_inner();
+    let client = crate::handlers::rollout_client(&http_req, None, None);
+    let serving = Serving {
+        releases: releases.get_ref(),
+        version_control: version_control.get_ref(),
+        client: &client,
+    };
+    let environment = assemble(
+        &application,
+        &profile,
+        Some(&label),
+        config_manager.get_ref(),
+        &serving,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(environment))
+}
+
+/// Property sources of an application in the order Spring applies them: the active profiles,
+/// last one first, then the default profile, each with the application's own configs before
+/// the shared ones. Without a label, each config is taken at the version the client is served.
+async fn assemble(
+    application: &str,
+    profile: &str,
+    label: Option<&str>,
+    config_manager: &dyn ConfigManager,
+    serving: &Serving<'_>,
+) -> config_common::Result<SpringEnvironment> {
+    let profiles: Vec<String> = profile
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect();
+
+    let mut environments: Vec<&str> = profiles.iter().rev().map(String::as_str).collect();
+    if !environments.contains(&DEFAULT_PROFILE) {
+        environments.push(DEFAULT_PROFILE);
+    }
+    let mut applications = vec![application];
+    if application != SHARED_APPLICATION {
+        applications.push(SHARED_APPLICATION);
+    }
+
+    let mut property_sources = Vec::new();
+    for environment in environments {
+        for application in &applications {
+            let filter = ConfigFilter {
+                application: Some(application.to_string()),
+                environment: Some(environment.to_string()),
+                ..Default::default()
+            };
+            let mut configs = config_core::list_all_configs(config_manager, filter).await?;
+            configs.sort_by(|a, b| a.name.cmp(&b.name));
+            for meta in configs {
+                let content = match label {
+                    Some(label) => {
+                        match serving
+                            .version_control
+                            .get_tagged_version(&meta.id, label)
+                            .await
+                        {
+                            Ok((_, content)) => content,
+                            Err(config_common::Error::NotFound(_)) => continue,
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    None => {
+                        let (meta, content) = config_manager.get_config(&meta.id).await?;
+                        let (_, content) = config_core::releases::served(
+                            serving.releases,
+                            serving.version_control,
+                            meta,
+                            content,
+                            serving.client,
+                        )
+                        .await?;
+                        content
+                    }
+                };
+                let content = config_manager.redact_content(content)?;
+                if let Some(source) = property_source(&meta, &content)? {
+                    property_sources.push(source);
+                }
+            }
+        }
+    }
+
+    Ok(SpringEnvironment {
+        name: application.to_string(),
+        profiles,
+        label: label.map(String::from),
+        version: None,
+        state: None,
+        property_sources,
+    })
+}
+
+/// Flattened properties of a config; encrypted content has none the server can show
+fn property_source(
+    meta: &ConfigMeta,
+    content: &ConfigContent,
+) -> config_common::Result<Option<PropertySource>> {
+    if content.is_encrypted {
+        return Ok(None);
+    }
+    let document = config_core::format::parse(content.format, &content.content)
+        .map_err(config_common::Error::InvalidContent)?;
+    let mut source = Map::new();
+    flatten("", document, &mut source);
+    Ok(Some(PropertySource {
+        name: format!(
+            "{}/{}/{}/{}",
+            meta.namespace, meta.application, meta.environment, meta.name
+        ),
+        source,
+    }))
+}
+
+/// Spring property names: dotted keys and `[i]` list indices
+fn flatten(prefix: &str, value: Value, out: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.into_iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, index), value, out);
+            }
+        }
+        value if !prefix.is_empty() => {
+            out.insert(prefix.to_string(), value);
+        }
+        // A bare scalar document has no property name
+        _ => {}
+    }
+}
@@ -0,0 +1,30 @@
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::web;
+use config_monitor::ClientMetrics;
+
+use crate::auth::caller_identity;
+
+/// Middleware counting requests and response bytes per client
+pub async fn track(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let client = caller_identity(req.request()).unwrap_or_else(|| "anonymous".to_string());
+    let metrics = req.app_data::<web::Data<ClientMetrics>>().cloned();
+
+    let res = next.call(req).await?;
+
+    if let Some(metrics) = metrics {
+        // Streamed bodies such as exports have no known size and count as zero
+        let bytes = match res.response().body().size() {
+            BodySize::Sized(n) => n,
+            _ => 0,
+        };
+        let success = !(res.status().is_client_error() || res.status().is_server_error());
+        metrics.record(&client, success, bytes);
+    }
+
+    Ok(res)
+}
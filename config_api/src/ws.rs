@@ -0,0 +1,365 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_ws::{CloseReason, Message, MessageStream, Session};
+use config_common::ConfigEvent;
+use config_core::{EventBus, LabelSelector, PublishedEvent};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Interval between pings sent to each client
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Clients silent for this long are disconnected
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Events read from the outbox at a time while replaying
+const REPLAY_BATCH_SIZE: i64 = 100;
+
+/// Message sent by a client to change what it receives
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        #[serde(default)]
+        namespaces: Vec<String>,
+        #[serde(default)]
+        config_ids: Vec<String>,
+        /// Configs whose labels match any of these selectors
+        #[serde(default)]
+        selectors: Vec<LabelSelector>,
+        /// Stores the position of the stream so a later connection can resume from it
+        #[serde(default)]
+        subscription_id: Option<String>,
+        /// Replay events after this sequence number instead of the stored position
+        #[serde(default)]
+        resume_from: Option<i64>,
+        /// Collect events this long and send them as one batch, only the latest per config
+        #[serde(default)]
+        debounce_ms: Option<u64>,
+    },
+    Unsubscribe {
+        #[serde(default)]
+        namespaces: Vec<String>,
+        #[serde(default)]
+        config_ids: Vec<String>,
+        #[serde(default)]
+        selectors: Vec<LabelSelector>,
+    },
+}
+
+/// Message sent to a client
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    Event(&'a PublishedEvent),
+    /// Debounced events, oldest first
+    Batch {
+        events: &'a [PublishedEvent],
+    },
+    /// Current subscription, sent after every change to it
+    Subscribed {
+        namespaces: &'a HashSet<String>,
+        config_ids: &'a HashSet<String>,
+        selectors: &'a [LabelSelector],
+        /// Sequence number of the last event delivered
+        seq: i64,
+    },
+    /// Events were dropped because the client read too slowly and can't be replayed
+    Lagged {
+        skipped: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Namespaces, configs and label selectors a connection receives events for
+#[derive(Debug, Default)]
+struct Subscription {
+    namespaces: HashSet<String>,
+    config_ids: HashSet<String>,
+    selectors: Vec<LabelSelector>,
+}
+
+impl Subscription {
+    fn matches(&self, event: &ConfigEvent) -> bool {
+        self.namespaces.contains(&event.namespace)
+            || self.config_ids.contains(&event.config_id)
+            || self
+                .selectors
+                .iter()
+                .any(|selector| selector.matches(&event.labels))
+    }
+}
+
+/// Upgrade to a WebSocket streaming change events of subscribed namespaces and configs
+pub async fn subscribe(
+    req: HttpRequest,
+    body: web::Payload,
+    events: web::Data<EventBus>,
+) -> config_common::Result<HttpResponse> {
+    let (response, session, stream) = actix_ws::handle(&req, body)
+        .map_err(|e| config_common::Error::Validation(format!("websocket upgrade: {}", e)))?;
+    let connection = Connection {
+        live: events.subscribe(),
+        events: events.into_inner(),
+        session,
+        subscription: Subscription::default(),
+        cursor: None,
+        seq: 0,
+        debounce: None,
+        pending: Vec::new(),
+        flush_at: None,
+    };
+    actix_web::rt::spawn(connection.serve(stream));
+    Ok(response)
+}
+
+struct Connection {
+    events: Arc<EventBus>,
+    live: broadcast::Receiver<PublishedEvent>,
+    session: Session,
+    subscription: Subscription,
+    /// Outbox consumer storing the position of a named subscription
+    cursor: Option<String>,
+    /// Sequence number of the last event delivered
+    seq: i64,
+    debounce: Option<Duration>,
+    /// Debounced events not sent yet
+    pending: Vec<PublishedEvent>,
+    /// When the pending events are sent
+    flush_at: Option<tokio::time::Instant>,
+}
+
+impl Connection {
+    async fn serve(mut self, mut stream: MessageStream) {
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        let mut last_seen = Instant::now();
+
+        let reason: Option<CloseReason> = loop {
+            let flush_at = self.flush_at.unwrap_or_else(tokio::time::Instant::now);
+            let open = tokio::select! {
+                message = stream.next() => {
+                    last_seen = Instant::now();
+                    match message {
+                        Some(Ok(Message::Text(text))) => self.receive(&text).await,
+                        Some(Ok(Message::Ping(bytes))) => self.session.pong(&bytes).await.is_ok(),
+                        Some(Ok(Message::Close(reason))) => break reason,
+                        Some(Ok(_)) => true,
+                        Some(Err(_)) | None => break None,
+                    }
+                }
+                event = self.live.recv() => match event {
+                    Ok(event) => self.deliver(&event).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        self.catch_up(skipped).await
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break None,
+                },
+                _ = tokio::time::sleep_until(flush_at), if self.flush_at.is_some() => {
+                    self.flush().await
+                }
+                _ = ping.tick() => {
+                    last_seen.elapsed() <= CLIENT_TIMEOUT && self.session.ping(b"").await.is_ok()
+                }
+            };
+            if !open {
+                break None;
+            }
+        };
+        let _ = self.session.close(reason).await;
+    }
+
+    /// Apply a client message; false once the connection is gone
+    async fn receive(&mut self, text: &str) -> bool {
+        let message = match serde_json::from_str::<ClientMessage>(text) {
+            Ok(message) => message,
+            Err(e) => {
+                return self
+                    .send(&ServerMessage::Error {
+                        message: e.to_string(),
+                    })
+                    .await
+            }
+        };
+
+        match message {
+            ClientMessage::Subscribe {
+                namespaces,
+                config_ids,
+                selectors,
+                subscription_id,
+                resume_from,
+                debounce_ms,
+            } => {
+                self.subscription.namespaces.extend(namespaces);
+                for selector in selectors {
+                    if !self.subscription.selectors.contains(&selector) {
+                        self.subscription.selectors.push(selector);
+                    }
+                }
+                if let Some(debounce_ms) = debounce_ms {
+                    self.debounce = (debounce_ms > 0).then(|| Duration::from_millis(debounce_ms));
+                }
+                self.subscription.config_ids.extend(config_ids);
+                if let Err(e) = self.resume(subscription_id, resume_from).await {
+                    return self
+                        .send(&ServerMessage::Error {
+                            message: e.to_string(),
+                        })
+                        .await;
+                }
+            }
+            ClientMessage::Unsubscribe {
+                namespaces,
+                config_ids,
+                selectors,
+            } => {
+                self.subscription
+                    .selectors
+                    .retain(|selector| !selectors.contains(selector));
+                for namespace in &namespaces {
+                    self.subscription.namespaces.remove(namespace);
+                }
+                for id in &config_ids {
+                    self.subscription.config_ids.remove(id);
+                }
+            }
+        }
+
+        let subscribed = ServerMessage::Subscribed {
+            namespaces: &self.subscription.namespaces,
+            config_ids: &self.subscription.config_ids,
+            selectors: &self.subscription.selectors,
+            seq: self.seq,
+        };
+        let Ok(text) = serde_json::to_string(&subscribed) else {
+            return true;
+        };
+        self.session.text(text).await.is_ok()
+    }
+
+    /// Name the subscription and replay what it missed since its stored or given position
+    async fn resume(
+        &mut self,
+        subscription_id: Option<String>,
+        resume_from: Option<i64>,
+    ) -> config_common::Result<()> {
+        if subscription_id.is_none() && resume_from.is_none() {
+            return Ok(());
+        }
+        let outbox = self.events.outbox().cloned().ok_or_else(|| {
+            config_common::Error::Validation("events are not stored for resuming".to_string())
+        })?;
+        if let Some(id) = subscription_id {
+            let cursor = format!("ws:{}", id);
+            self.seq = outbox.get_offset(&cursor).await?;
+            self.cursor = Some(cursor);
+            // A new subscription starts from now rather than the oldest stored event
+            if self.seq == 0 && resume_from.is_none() {
+                self.seq = outbox.latest_seq().await?;
+                self.save_cursor().await;
+            }
+        }
+        if let Some(seq) = resume_from {
+            self.seq = seq;
+        }
+
+        loop {
+            let batch = outbox.read_after(self.seq, REPLAY_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                return Ok(());
+            }
+            for event in &batch {
+                if !self.deliver(event).await {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Replay events dropped from the live channel, or report them lost without an outbox
+    async fn catch_up(&mut self, skipped: u64) -> bool {
+        let Some(outbox) = self.events.outbox().cloned() else {
+            return self.send(&ServerMessage::Lagged { skipped }).await;
+        };
+        loop {
+            let batch = match outbox.read_after(self.seq, REPLAY_BATCH_SIZE).await {
+                Ok(batch) if !batch.is_empty() => batch,
+                Ok(_) => return true,
+                Err(_) => return self.send(&ServerMessage::Lagged { skipped }).await,
+            };
+            for event in &batch {
+                if !self.deliver(event).await {
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Send a matching event not delivered yet and store the new position
+    async fn deliver(&mut self, event: &PublishedEvent) -> bool {
+        // Replayed events come around again on the live channel
+        if event.seq != 0 && event.seq <= self.seq {
+            return true;
+        }
+        if event.seq != 0 {
+            self.seq = event.seq;
+        }
+        if !self.subscription.matches(&event.event) {
+            // Move the stored position past it, unless debounced events still wait to be sent
+            if event.seq != 0 && self.pending.is_empty() {
+                self.save_cursor().await;
+            }
+            return true;
+        }
+        if let Some(debounce) = self.debounce {
+            let config_id = &event.event.config_id;
+            self.pending
+                .retain(|pending| &pending.event.config_id != config_id);
+            self.pending.push(event.clone());
+            self.flush_at
+                .get_or_insert_with(|| tokio::time::Instant::now() + debounce);
+            return true;
+        }
+        if !self.send(&ServerMessage::Event(event)).await {
+            return false;
+        }
+        self.save_cursor().await;
+        true
+    }
+
+    /// Send the debounced events as one batch
+    async fn flush(&mut self) -> bool {
+        self.flush_at = None;
+        let events = std::mem::take(&mut self.pending);
+        if events.is_empty() {
+            return true;
+        }
+        if !self.send(&ServerMessage::Batch { events: &events }).await {
+            return false;
+        }
+        self.save_cursor().await;
+        true
+    }
+
+    /// Store the position of a named subscription
+    async fn save_cursor(&self) {
+        if let (Some(cursor), Some(outbox)) = (&self.cursor, self.events.outbox()) {
+            if let Err(e) = outbox.set_offset(cursor, self.seq).await {
+                tracing::warn!(cursor = %cursor, error = %e, "Saving subscription cursor failed");
+            }
+        }
+    }
+
+    /// Send a message; false once the connection is gone
+    async fn send(&mut self, message: &ServerMessage<'_>) -> bool {
+        match serde_json::to_string(message) {
+            Ok(text) => self.session.text(text).await.is_ok(),
+            Err(_) => true,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use chrono::{Datelike, FixedOffset, Timelike, Weekday};
+use config_common::{AuditLog, ConfigEvent, ConfigEventType, Result};
+use prometheus::{IntCounterVec, Opts, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use crate::model::{AnomalyRule, BusinessHours};
+use crate::sink::AuditSink;
+
+/// Alerts kept for slow subscribers before they start missing some
+const ALERT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Alert raised by an anomaly rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyAlert {
+    pub rule: String,
+    pub user: Option<String>,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+impl AnomalyAlert {
+    /// Event carrying the alert to notification subscriptions; it names no config
+    pub fn to_event(&self) -> ConfigEvent {
+        ConfigEvent {
+            config_id: String::new(),
+            namespace: String::new(),
+            environment: String::new(),
+            labels: Vec::new(),
+            event_type: ConfigEventType::AuditAnomaly,
+            version: String::new(),
+            timestamp: self.timestamp,
+            user: self.user.clone().unwrap_or_default(),
+            message: Some(format!("{}: {}", self.rule, self.message)),
+        }
+    }
+}
+
+/// Audit sink evaluating anomaly rules over every record
+pub struct AnomalyDetector {
+    rules: Vec<AnomalyRule>,
+    /// Recent matching timestamps per rule and user key
+    windows: Mutex<HashMap<(usize, String), VecDeque<i64>>>,
+    alerts: broadcast::Sender<ConfigEvent>,
+    counter: IntCounterVec,
+}
+
+impl AnomalyDetector {
+    pub fn new(rules: Vec<AnomalyRule>, registry: &Registry) -> Result<Self> {
+        let counter = IntCounterVec::new(
+            Opts::new(
+                "audit_anomalies_total",
+                "Alerts raised by audit anomaly rules",
+            ),
+            &["rule"],
+        )?;
+        registry.register(Box::new(counter.clone()))?;
+
+        let (alerts, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Ok(Self {
+            rules,
+            windows: Mutex::new(HashMap::new()),
+            alerts,
+            counter,
+        })
+    }
+
+    /// Receive an event for every raised alert
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigEvent> {
+        self.alerts.subscribe()
+    }
+
+    /// Evaluate every rule against a record, returning the alerts raised
+    pub fn evaluate(&self, log: &AuditLog) -> Vec<AnomalyAlert> {
+        let mut raised = Vec::new();
+
+        for (idx, rule) in self.rules.iter().enumerate() {
+            let alert = match rule {
+                AnomalyRule::Rate {
+                    name,
+                    action,
+                    threshold,
+                    window_secs,
+                    per_user,
+                } if action_matches(action, &log.action) => {
+                    let key = if *per_user {
+                        log.user.clone()
+                    } else {
+                        String::new()
+                    };
+                    let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+                    let window = windows.entry((idx, key)).or_default();
+
+                    window.push_back(log.timestamp);
+                    while window
+                        .front()
+                        .is_some_and(|ts| *ts <= log.timestamp - *window_secs as i64)
+                    {
+                        window.pop_front();
+                    }
+
+                    if window.len() > *threshold {
+                        let count = window.len();
+                        // Start a fresh window so one burst raises one alert
+                        window.clear();
+                        Some(AnomalyAlert {
+                            rule: name.clone(),
+                            user: per_user.then(|| log.user.clone()),
+                            message: format!(
+                                "{} {} actions within {} seconds",
+                                count, action, window_secs
+                            ),
+                            timestamp: log.timestamp,
+                        })
+                    } else {
+                        None
+                    }
+                }
+                AnomalyRule::OffHours {
+                    name,
+                    action,
+                    contains,
+                    business_hours,
+                } if action_matches(action, &log.action)
+                    && contains.as_ref().is_none_or(|text| {
+                        log.resource.contains(text.as_str()) || log.details.contains(text.as_str())
+                    })
+                    && !within_business_hours(business_hours, log.timestamp) =>
+                {
+                    Some(AnomalyAlert {
+                        rule: name.clone(),
+                        user: Some(log.user.clone()),
+                        message: format!(
+                            "{} performed {} on {} outside business hours",
+                            log.user, log.action, log.resource
+                        ),
+                        timestamp: log.timestamp,
+                    })
+                }
+                _ => None,
+            };
+
+            if let Some(alert) = alert {
+                raised.push(alert);
+            }
+        }
+
+        raised
+    }
+}
+
+#[async_trait]
+impl AuditSink for AnomalyDetector {
+    fn name(&self) -> &str {
+        "anomaly"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        for log in logs {
+            for alert in self.evaluate(log) {
+                tracing::warn!(rule = %alert.rule, message = %alert.message, "Audit anomaly detected");
+                self.counter.with_label_values(&[&alert.rule]).inc();
+                // No subscribers is not an error
+                let _ = self.alerts.send(alert.to_event());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Match an action against a pattern with an optional trailing `*`
+fn action_matches(pattern: &str, action: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => action.starts_with(prefix),
+        None => pattern == action,
+    }
+}
+
+fn within_business_hours(hours: &BusinessHours, timestamp: i64) -> bool {
+    let Some(offset) = FixedOffset::east_opt(hours.utc_offset_minutes * 60) else {
+        return true;
+    };
+    let Some(time) = chrono::DateTime::from_timestamp(timestamp, 0) else {
+        return true;
+    };
+    let local = time.with_timezone(&offset);
+
+    let weekend = matches!(local.weekday(), Weekday::Sat | Weekday::Sun);
+    if weekend && !hours.include_weekends {
+        return false;
+    }
+    (hours.start_hour..hours.end_hour).contains(&local.hour())
+}
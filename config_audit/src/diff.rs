@@ -0,0 +1,183 @@
+use config_common::{ConfigContent, ConfigFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Placeholder replacing secret values in diffs
+pub const REDACTED: &str = "***";
+
+/// Key name fragments that mark a value as secret
+const SECRET_KEY_MARKERS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "credential",
+    "private_key",
+    "privatekey",
+    "api_key",
+    "apikey",
+];
+
+/// Structured difference between two versions of a configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDiff {
+    pub old_hash: String,
+    pub new_hash: String,
+    pub format_changed: bool,
+    /// Key-level changes, only available when both versions are structured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<KeyChange>>,
+}
+
+/// Change of a single flattened key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyChange {
+    pub key: String,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Kind of key change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ConfigDiff {
+    /// Compute the difference between two contents. Values of secret keys and
+    /// of encrypted contents are never included.
+    pub fn compute(old: &ConfigContent, new: &ConfigContent) -> Self {
+        Self::compute_with_secrets(old, new, &[])
+    }
+
+    /// Compute the difference, also redacting values at or below the given secret paths
+    pub fn compute_with_secrets(
+        old: &ConfigContent,
+        new: &ConfigContent,
+        secret_paths: &[String],
+    ) -> Self {
+        let changes = match (flatten_content(old), flatten_content(new)) {
+            (Some(before), Some(after)) => {
+                let redact_all = old.is_encrypted || new.is_encrypted;
+                Some(diff_keys(&before, &after, redact_all, secret_paths))
+            }
+            _ => None,
+        };
+
+        Self {
+            old_hash: content_hash(&old.content),
+            new_hash: content_hash(&new.content),
+            format_changed: std::mem::discriminant(&old.format)
+                != std::mem::discriminant(&new.format),
+            changes,
+        }
+    }
+}
+
+/// Hex encoded SHA-256 of the content
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Whether a key name looks like it holds a secret
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+/// Parse structured content into dotted key paths
+fn flatten_content(content: &ConfigContent) -> Option<BTreeMap<String, Value>> {
+    if content.is_encrypted {
+        return None;
+    }
+
+    let value: Value = match content.format {
+        ConfigFormat::Json => serde_json::from_str(&content.content).ok()?,
+        ConfigFormat::Yaml => serde_yaml::from_str(&content.content).ok()?,
+        _ => return None,
+    };
+
+    let mut flat = BTreeMap::new();
+    flatten_value("", value, &mut flat);
+    Some(flat)
+}
+
+fn flatten_value(prefix: &str, value: Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_value(&path, value, out);
+            }
+        }
+        other => {
+            out.insert(prefix.to_string(), other);
+        }
+    }
+}
+
+fn diff_keys(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+    redact_all: bool,
+    secret_paths: &[String],
+) -> Vec<KeyChange> {
+    let under_secret_path = |key: &str| {
+        secret_paths.iter().any(|path| {
+            key == path
+                || key
+                    .strip_prefix(path.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    };
+    let redact = |key: &str, value: &Value| -> Value {
+        if redact_all || is_secret_key(key) || under_secret_path(key) {
+            Value::String(REDACTED.to_string())
+        } else {
+            value.clone()
+        }
+    };
+
+    let mut changes = Vec::new();
+    for (key, old) in before {
+        match after.get(key) {
+            None => changes.push(KeyChange {
+                key: key.clone(),
+                kind: ChangeKind::Removed,
+                old: Some(redact(key, old)),
+                new: None,
+            }),
+            Some(new) if new != old => changes.push(KeyChange {
+                key: key.clone(),
+                kind: ChangeKind::Changed,
+                old: Some(redact(key, old)),
+                new: Some(redact(key, new)),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, new) in after {
+        if !before.contains_key(key) {
+            changes.push(KeyChange {
+                key: key.clone(),
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some(redact(key, new)),
+            });
+        }
+    }
+
+    changes
+}
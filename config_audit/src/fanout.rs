@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use config_common::{AuditLog, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::model::DeliveryConfig;
+use crate::sink::AuditSink;
+use crate::{AuditFilter, AuditLogStream, AuditService};
+
+/// Audit service copying every record to additional sinks.
+///
+/// Records are written synchronously to the primary service, which also answers
+/// queries. Each sink gets its own bounded buffer and delivery task, so a slow
+/// or unavailable sink never blocks API requests.
+pub struct FanoutAuditService {
+    primary: Arc<dyn AuditService>,
+    sinks: Vec<(String, mpsc::Sender<AuditLog>)>,
+}
+
+impl FanoutAuditService {
+    pub fn new(
+        primary: Arc<dyn AuditService>,
+        sinks: Vec<Arc<dyn AuditSink>>,
+        delivery: &DeliveryConfig,
+    ) -> Self {
+        let sinks = sinks
+            .into_iter()
+            .map(|sink| {
+                let (tx, rx) = mpsc::channel(delivery.buffer_size.max(1));
+                let name = sink.name().to_string();
+                tokio::spawn(deliver(sink, rx, delivery.clone()));
+                (name, tx)
+            })
+            .collect();
+
+        Self { primary, sinks }
+    }
+}
+
+#[async_trait]
+impl AuditService for FanoutAuditService {
+    async fn record(&self, log: AuditLog) -> Result<()> {
+        for (name, tx) in &self.sinks {
+            if tx.try_send(log.clone()).is_err() {
+                tracing::warn!(sink = %name, id = %log.id, "Audit sink buffer full, dropping record");
+            }
+        }
+        self.primary.record(log).await
+    }
+
+    async fn get_logs(
+        &self,
+        filter: AuditFilter,
+        page_size: i32,
+        page_number: i32,
+    ) -> Result<(Vec<AuditLog>, i32)> {
+        self.primary.get_logs(filter, page_size, page_number).await
+    }
+
+    fn export_logs(&self, filter: AuditFilter) -> AuditLogStream {
+        self.primary.export_logs(filter)
+    }
+}
+
+/// Drain a sink buffer in batches, retrying failed writes with exponential backoff
+async fn deliver(
+    sink: Arc<dyn AuditSink>,
+    mut rx: mpsc::Receiver<AuditLog>,
+    delivery: DeliveryConfig,
+) {
+    let batch_size = delivery.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+
+    while rx.recv_many(&mut batch, batch_size).await > 0 {
+        let mut backoff = Duration::from_millis(delivery.retry_backoff_ms);
+        let mut attempt = 0;
+
+        loop {
+            match sink.write(&batch).await {
+                Ok(()) => break,
+                Err(e) if attempt < delivery.max_retries => {
+                    attempt += 1;
+                    tracing::warn!(sink = %sink.name(), attempt, error = %e, "Audit sink write failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        sink = %sink.name(),
+                        dropped = batch.len(),
+                        error = %e,
+                        "Audit sink write failed, dropping batch"
+                    );
+                    break;
+                }
+            }
+        }
+
+        batch.clear();
+    }
+}
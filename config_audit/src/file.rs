@@ -0,0 +1,362 @@
+use async_trait::async_trait;
+use config_common::{AuditLog, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::{receiver_stream, AuditFilter, AuditLogStream, AuditService, EXPORT_BUFFER};
+
+const FILE_PREFIX: &str = "audit-";
+const LOG_SUFFIX: &str = ".log";
+const INDEX_SUFFIX: &str = ".idx";
+
+/// Daily audit file holding records of the given timestamp
+pub(crate) fn daily_file(directory: &Path, timestamp: i64) -> PathBuf {
+    let day = chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d");
+    directory.join(format!("{}{}{}", FILE_PREFIX, day, LOG_SUFFIX))
+}
+
+/// Fixed-size bloom filter over user ids
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UserBloom {
+    bits: Vec<u64>,
+}
+
+impl UserBloom {
+    const WORDS: usize = 32;
+    const HASHES: u64 = 3;
+
+    fn new() -> Self {
+        Self {
+            bits: vec![0; Self::WORDS],
+        }
+    }
+
+    fn positions(user: &str) -> impl Iterator<Item = usize> + '_ {
+        (0..Self::HASHES).map(move |seed| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            user.hash(&mut hasher);
+            (hasher.finish() % (Self::WORDS as u64 * 64)) as usize
+        })
+    }
+
+    fn insert(&mut self, user: &str) {
+        for pos in Self::positions(user) {
+            self.bits[pos / 64] |= 1 << (pos % 64);
+        }
+    }
+
+    fn may_contain(&self, user: &str) -> bool {
+        Self::positions(user).all(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+}
+
+/// Summary of a single audit file used to skip it without reading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileIndex {
+    /// File size the index was built for
+    size: u64,
+    count: u64,
+    min_timestamp: i64,
+    max_timestamp: i64,
+    users: UserBloom,
+}
+
+impl FileIndex {
+    fn new() -> Self {
+        Self {
+            size: 0,
+            count: 0,
+            min_timestamp: i64::MAX,
+            max_timestamp: i64::MIN,
+            users: UserBloom::new(),
+        }
+    }
+
+    fn add(&mut self, log: &AuditLog, line_len: u64) {
+        self.size += line_len;
+        self.count += 1;
+        self.min_timestamp = self.min_timestamp.min(log.timestamp);
+        self.max_timestamp = self.max_timestamp.max(log.timestamp);
+        self.users.insert(&log.user);
+    }
+
+    /// Whether the file may contain records matching the filter
+    fn may_match(&self, filter: &AuditFilter) -> bool {
+        if self.count == 0 {
+            return false;
+        }
+        if filter
+            .start_time
+            .is_some_and(|start| self.max_timestamp < start)
+        {
+            return false;
+        }
+        if filter.end_time.is_some_and(|end| self.min_timestamp > end) {
+            return false;
+        }
+        match &filter.user {
+            Some(user) => self.users.may_contain(user),
+            None => true,
+        }
+    }
+
+    /// Whether every record of the file matches the filter
+    fn fully_matches(&self, filter: &AuditFilter) -> bool {
+        filter.user.is_none()
+            && filter.action.is_none()
+            && filter.resource.is_none()
+            && filter
+                .start_time
+                .is_none_or(|start| self.min_timestamp >= start)
+            && filter.end_time.is_none_or(|end| self.max_timestamp <= end)
+    }
+}
+
+/// Audit service storing records as JSON lines in daily files
+pub struct FileAuditService {
+    directory: PathBuf,
+    /// Index per file, keyed by file path; file names sort chronologically
+    index: Arc<RwLock<BTreeMap<PathBuf, FileIndex>>>,
+}
+
+impl FileAuditService {
+    /// Open the audit directory, loading or rebuilding file indexes
+    pub async fn open(directory: impl Into<PathBuf>) -> Result<Self> {
+        let directory = directory.into();
+        tokio::fs::create_dir_all(&directory)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        let mut index = BTreeMap::new();
+        let mut entries = tokio::fs::read_dir(&directory)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?
+        {
+            let path = entry.path();
+            let is_log = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(FILE_PREFIX) && n.ends_with(LOG_SUFFIX));
+            if is_log {
+                let file_index = load_or_build_index(&path).await?;
+                index.insert(path, file_index);
+            }
+        }
+
+        Ok(Self {
+            directory,
+            index: Arc::new(RwLock::new(index)),
+        })
+    }
+
+    /// Newest-first matching records, reading files only until `limit` records are found
+    pub async fn query_events(&self, filter: &AuditFilter, limit: usize) -> Result<Vec<AuditLog>> {
+        let mut results = Vec::new();
+        if limit == 0 {
+            return Ok(results);
+        }
+
+        let paths = candidate_files(&*self.index.read().await, filter);
+        for path in paths.into_iter().rev() {
+            let mut logs = read_file(&path).await?;
+            logs.retain(|log| matches(filter, log));
+            logs.sort_by_key(|log| std::cmp::Reverse(log.timestamp));
+
+            for log in logs {
+                results.push(log);
+                if results.len() >= limit {
+                    return Ok(results);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Count matching records, using index counts for files that match entirely
+    pub async fn count_events(&self, filter: &AuditFilter) -> Result<u64> {
+        let mut total = 0;
+        let index = self.index.read().await.clone();
+
+        for (path, file_index) in index.iter().filter(|(_, i)| i.may_match(filter)) {
+            if file_index.fully_matches(filter) {
+                total += file_index.count;
+            } else {
+                total += read_file(path)
+                    .await?
+                    .iter()
+                    .filter(|log| matches(filter, log))
+                    .count() as u64;
+            }
+        }
+
+        Ok(total)
+    }
+}
+
+#[async_trait]
+impl AuditService for FileAuditService {
+    async fn record(&self, log: AuditLog) -> Result<()> {
+        let path = daily_file(&self.directory, log.timestamp);
+        let mut line = serde_json::to_vec(&log)?;
+        line.push(b'\n');
+
+        // Holding the index lock serializes appends and keeps the index exact
+        let mut index = self.index.write().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        file.write_all(&line)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        let file_index = index.entry(path.clone()).or_insert_with(FileIndex::new);
+        file_index.add(&log, line.len() as u64);
+        save_index(&path, file_index).await;
+
+        Ok(())
+    }
+
+    async fn get_logs(
+        &self,
+        filter: AuditFilter,
+        page_size: i32,
+        page_number: i32,
+    ) -> Result<(Vec<AuditLog>, i32)> {
+        let offset = ((page_number.max(1) - 1) * page_size.max(0)) as usize;
+        let limit = offset + page_size.max(0) as usize;
+
+        let logs = self
+            .query_events(&filter, limit)
+            .await?
+            .into_iter()
+            .skip(offset)
+            .collect();
+        let total = self.count_events(&filter).await? as i32;
+
+        Ok((logs, total))
+    }
+
+    fn export_logs(&self, filter: AuditFilter) -> AuditLogStream {
+        let index = self.index.clone();
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+
+        tokio::spawn(async move {
+            let paths = candidate_files(&*index.read().await, &filter);
+            for path in paths {
+                let logs = match read_file(&path).await {
+                    Ok(mut logs) => {
+                        logs.retain(|log| matches(&filter, log));
+                        logs.sort_by_key(|log| log.timestamp);
+                        logs.into_iter().map(Ok).collect()
+                    }
+                    Err(e) => vec![Err(e)],
+                };
+                for log in logs {
+                    if tx.send(log).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        receiver_stream(rx)
+    }
+}
+
+/// Files that may contain matching records, oldest first
+fn candidate_files(index: &BTreeMap<PathBuf, FileIndex>, filter: &AuditFilter) -> Vec<PathBuf> {
+    index
+        .iter()
+        .filter(|(_, i)| i.may_match(filter))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+fn matches(filter: &AuditFilter, log: &AuditLog) -> bool {
+    filter.user.as_ref().is_none_or(|u| &log.user == u)
+        && filter.action.as_ref().is_none_or(|a| &log.action == a)
+        && filter.resource.as_ref().is_none_or(|r| &log.resource == r)
+        && filter.start_time.is_none_or(|start| log.timestamp >= start)
+        && filter.end_time.is_none_or(|end| log.timestamp <= end)
+}
+
+async fn read_file(path: &Path) -> Result<Vec<AuditLog>> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                tracing::warn!(file = %path.display(), error = %e, "Skipping malformed audit line");
+                None
+            }
+        })
+        .collect())
+}
+
+fn index_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension(&INDEX_SUFFIX[1..])
+}
+
+/// Load the sidecar index, rebuilding it when missing or stale
+async fn load_or_build_index(path: &Path) -> Result<FileIndex> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?
+        .len();
+
+    if let Ok(raw) = tokio::fs::read(index_path(path)).await {
+        if let Ok(index) = serde_json::from_slice::<FileIndex>(&raw) {
+            if index.size == size {
+                return Ok(index);
+            }
+        }
+    }
+
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+    let mut index = FileIndex::new();
+    for line in content.lines() {
+        if let Ok(log) = serde_json::from_str::<AuditLog>(line) {
+            index.add(&log, 0);
+        }
+    }
+    index.size = size;
+
+    save_index(path, &index).await;
+    Ok(index)
+}
+
+/// Persist the sidecar index; failures only cost a rebuild on next startup
+async fn save_index(path: &Path, index: &FileIndex) {
+    let result = match serde_json::to_vec(index) {
+        Ok(raw) => tokio::fs::write(index_path(path), raw)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+        tracing::warn!(file = %path.display(), error = %e, "Failed to save audit index");
+    }
+}
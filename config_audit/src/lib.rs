@@ -1,8 +1,43 @@
+pub mod anomaly;
+pub mod diff;
+pub mod fanout;
+pub mod file;
+pub mod model;
+pub mod retention;
+pub mod sink;
+
+pub use anomaly::{AnomalyAlert, AnomalyDetector};
+pub use diff::ConfigDiff;
+pub use fanout::FanoutAuditService;
+pub use file::FileAuditService;
+pub use model::{
+    AnomalyRule, AuditConfig, BusinessHours, DeliveryConfig, RetentionConfig, SinkConfig,
+};
+pub use retention::{RetentionJob, RetentionMetrics};
+pub use sink::{build_sinks, AuditSink, FileSink, SyslogSink, WebhookSink};
+
 use async_trait::async_trait;
 use config_common::{AuditLog, Result};
+use futures_util::stream::{self, BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
-use sqlx::{Execute, PgPool};
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Stream of audit log entries
+pub type AuditLogStream = BoxStream<'static, Result<AuditLog>>;
+
+/// Number of rows buffered between the database cursor and the consumer
+pub(crate) const EXPORT_BUFFER: usize = 256;
+
+/// Columns selected for an audit log row
+const LOG_COLUMNS: &str = "id, user_id, action, resource, details, timestamp";
+
+/// `id, user_id, action, resource, details, timestamp` of an audit log
+type LogRow = (String, String, String, String, String, i64);
+
+/// Audit log row with the number of logs matching the query
+type CountedLogRow = (String, String, String, String, String, i64, i64);
 
 /// Audit service trait
 #[async_trait]
@@ -17,6 +52,9 @@ pub trait AuditService: Send + Sync {
         page_size: i32,
         page_number: i32,
     ) -> Result<(Vec<AuditLog>, i32)>;
+
+    /// Stream all audit logs matching the filter, oldest first
+    fn export_logs(&self, filter: AuditFilter) -> AuditLogStream;
 }
 
 /// Audit log filter
@@ -69,83 +107,116 @@ impl AuditService for DbAuditService {
         page_size: i32,
         page_number: i32,
     ) -> Result<(Vec<AuditLog>, i32)> {
-        let offset = (page_number - 1) * page_size;
-
-        let mut base_query = sqlx::QueryBuilder::new(
-            "SELECT id, user_id, action, resource, details, timestamp FROM audit_logs WHERE 1=1",
-        );
-
-        // 构建主查询条件
-        if let Some(user) = filter.user {
-            base_query.push(" AND user_id = ").push_bind(user);
-        }
-        if let Some(action) = filter.action {
-            base_query.push(" AND action = ").push_bind(action);
-        }
-        if let Some(resource) = filter.resource {
-            base_query.push(" AND resource = ").push_bind(resource);
-        }
-        if let Some(start_time) = filter.start_time {
-            base_query.push(" AND timestamp >= ").push_bind(start_time);
-        }
-        if let Some(end_time) = filter.end_time {
-            base_query.push(" AND timestamp <= ").push_bind(end_time);
-        }
-
-        // 构造计数查询
-        let count_query = {
-            let mut count_query_builder = base_query.duplicate(); // 复制基础查询
-            count_query_builder.push(" ORDER BY timestamp DESC"); // 需要排序以确保一致性？
-            count_query_builder.build() // 返回 Query<Postgres, PgArguments>
-        };
-        let (count_sql, count_params) = count_query.into_parts();
-
-        let total =
-            sqlx::query_as::<_, (i64,)>(&format!("SELECT COUNT(*) FROM ({}) AS t", count_sql))
-                .bind_all(count_params)
-                .fetch_one(&*self.pool)
-                .await
-                .map_err(|e| config_common::Error::Database(e.to_string()))?
-                .0 as i32;
-
-        // 构造分页查询
-        let mut page_query = base_query.duplicate();
-        page_query.push(" ORDER BY timestamp DESC LIMIT ");
-        page_query.push_bind(page_size);
-        page_query.push(" OFFSET ");
-        page_query.push_bind(offset);
-
-        let logs = page_query
-            .build_query_as::<AuditLog>()
+        let offset = (page_number.max(1) - 1) * page_size;
+
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {}, COUNT(*) OVER() FROM audit_logs",
+            LOG_COLUMNS
+        ));
+        push_filter(&mut query, &filter);
+        query
+            .push(" ORDER BY timestamp DESC LIMIT ")
+            .push_bind(page_size as i64)
+            .push(" OFFSET ")
+            .push_bind(offset as i64);
+        let rows = query
+            .build_query_as::<CountedLogRow>()
             .fetch_all(&*self.pool)
             .await
             .map_err(|e| config_common::Error::Database(e.to_string()))?;
 
-        Ok((logs, total))
+        let total = match rows.first() {
+            Some(row) => row.6,
+            None if offset == 0 => 0,
+            // Past the last page, where no row carries the total
+            None => {
+                let mut count = QueryBuilder::new("SELECT COUNT(*) FROM audit_logs");
+                push_filter(&mut count, &filter);
+                count
+                    .build_query_scalar::<i64>()
+                    .fetch_one(&*self.pool)
+                    .await
+                    .map_err(|e| config_common::Error::Database(e.to_string()))?
+            }
+        };
+        let logs = rows
+            .into_iter()
+            .map(
+                |(id, user, action, resource, details, timestamp, _)| AuditLog {
+                    id,
+                    user,
+                    action,
+                    resource,
+                    details,
+                    timestamp,
+                },
+            )
+            .collect();
+
+        Ok((logs, total as i32))
+    }
+
+    fn export_logs(&self, filter: AuditFilter) -> AuditLogStream {
+        let pool = self.pool.clone();
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+
+        // The cursor lives in its own task; the bounded channel applies backpressure
+        tokio::spawn(async move {
+            let mut query = QueryBuilder::new(format!("SELECT {} FROM audit_logs", LOG_COLUMNS));
+            push_filter(&mut query, &filter);
+            query.push(" ORDER BY timestamp ASC");
+            let mut rows = query.build_query_as::<LogRow>().fetch(&*pool);
+            while let Some(row) = rows.next().await {
+                let log = row
+                    .map(
+                        |(id, user, action, resource, details, timestamp)| AuditLog {
+                            id,
+                            user,
+                            action,
+                            resource,
+                            details,
+                            timestamp,
+                        },
+                    )
+                    .map_err(|e| config_common::Error::Database(e.to_string()));
+                let failed = log.is_err();
+                if tx.send(log).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        receiver_stream(rx)
     }
 }
 
-/// Initialize audit database schema
-pub async fn init_schema(pool: &PgPool) -> Result<()> {
-    sqlx::query!(
-        r#"
-        CREATE TABLE IF NOT EXISTS audit_logs (
-            id TEXT PRIMARY KEY,
-            user_id TEXT NOT NULL,
-            action TEXT NOT NULL,
-            resource TEXT NOT NULL,
-            details TEXT NOT NULL,
-            timestamp BIGINT NOT NULL
-        );
-        CREATE INDEX IF NOT EXISTS audit_logs_user_id_idx ON audit_logs (user_id);
-        CREATE INDEX IF NOT EXISTS audit_logs_action_idx ON audit_logs (action);
-        CREATE INDEX IF NOT EXISTS audit_logs_resource_idx ON audit_logs (resource);
-        CREATE INDEX IF NOT EXISTS audit_logs_timestamp_idx ON audit_logs (timestamp);
-        "#
-    )
-    .execute(pool)
-    .await
-    .map_err(|e| config_common::Error::Database(e.to_string()))?;
+/// Conditions on the filter fields that are set. Each combination of fields yields the same
+/// SQL, which each connection prepares once; unlike conditions that skip NULL parameters,
+/// its plan can use the indexes of the fields it names.
+fn push_filter<'a>(query: &mut QueryBuilder<'a, Postgres>, filter: &'a AuditFilter) {
+    query.push(" WHERE TRUE");
+    if let Some(user) = &filter.user {
+        query.push(" AND user_id = ").push_bind(user);
+    }
+    if let Some(action) = &filter.action {
+        query.push(" AND action = ").push_bind(action);
+    }
+    if let Some(resource) = &filter.resource {
+        query.push(" AND resource = ").push_bind(resource);
+    }
+    if let Some(start_time) = filter.start_time {
+        query.push(" AND timestamp >= ").push_bind(start_time);
+    }
+    if let Some(end_time) = filter.end_time {
+        query.push(" AND timestamp <= ").push_bind(end_time);
+    }
+}
 
-    Ok(())
+/// Adapt a channel fed by a producer task into a log stream
+pub(crate) fn receiver_stream(rx: mpsc::Receiver<Result<AuditLog>>) -> AuditLogStream {
+    stream::unfold(
+        rx,
+        |mut rx| async move { rx.recv().await.map(|log| (log, rx)) },
+    )
+    .boxed()
 }
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Audit configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Additional sinks receiving every audit record
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+    /// Delivery settings shared by all sinks
+    #[serde(default)]
+    pub delivery: DeliveryConfig,
+    /// Purge old records; records are kept forever when unset
+    #[serde(default)]
+    pub retention: Option<RetentionConfig>,
+    /// Rules raising alerts on suspicious activity
+    #[serde(default)]
+    pub anomaly_rules: Vec<AnomalyRule>,
+}
+
+/// Audit retention settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Records older than this many days are purged
+    pub days: u32,
+    /// Directory receiving gzip archives of purged records; records are
+    /// deleted without archiving when unset
+    #[serde(default)]
+    pub archive_directory: Option<String>,
+    /// Seconds between retention runs
+    #[serde(default = "default_retention_interval")]
+    pub interval_secs: u64,
+    /// Records archived and deleted per statement
+    #[serde(default = "default_retention_batch")]
+    pub batch_size: i64,
+}
+
+fn default_retention_interval() -> u64 {
+    60 * 60
+}
+
+fn default_retention_batch() -> i64 {
+    1_000
+}
+
+/// Buffering and retry settings for sink delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryConfig {
+    /// Records buffered per sink before new ones are dropped
+    pub buffer_size: usize,
+    /// Maximum records written to a sink in one call
+    pub batch_size: usize,
+    /// Attempts per batch before it is dropped
+    pub max_retries: u32,
+    /// Initial retry delay, doubled on every attempt
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 10_000,
+            batch_size: 100,
+            max_retries: 5,
+            retry_backoff_ms: 200,
+        }
+    }
+}
+
+/// Audit sink configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    /// Append JSON lines to daily files in a directory
+    File { directory: String },
+    /// Publish to a Kafka topic, keyed by audit log id
+    Kafka { brokers: String, topic: String },
+    /// Send RFC 5424 messages to a syslog server over UDP
+    Syslog {
+        address: String,
+        #[serde(default = "default_app_name")]
+        app_name: String,
+    },
+    /// POST batches as a JSON array to an HTTP endpoint
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default = "default_webhook_timeout")]
+        timeout_ms: u64,
+    },
+}
+
+fn default_app_name() -> String {
+    "config-server".to_string()
+}
+
+fn default_webhook_timeout() -> u64 {
+    5_000
+}
+
+/// Rule evaluated over incoming audit records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnomalyRule {
+    /// More than `threshold` matching records within `window_secs`
+    Rate {
+        name: String,
+        /// Action pattern, e.g. `configs.delete` or `configs.*`
+        action: String,
+        threshold: usize,
+        window_secs: u64,
+        /// Count per user instead of globally
+        #[serde(default)]
+        per_user: bool,
+    },
+    /// Matching records outside business hours
+    OffHours {
+        name: String,
+        action: String,
+        /// Only records whose resource or details contain this text
+        #[serde(default)]
+        contains: Option<String>,
+        #[serde(default)]
+        business_hours: BusinessHours,
+    },
+}
+
+impl AnomalyRule {
+    pub fn name(&self) -> &str {
+        match self {
+            AnomalyRule::Rate { name, .. } | AnomalyRule::OffHours { name, .. } => name,
+        }
+    }
+}
+
+/// Business hours window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessHours {
+    /// First hour of the working day, inclusive
+    pub start_hour: u32,
+    /// Last hour of the working day, exclusive
+    pub end_hour: u32,
+    /// Whether Saturday and Sunday are working days
+    #[serde(default)]
+    pub include_weekends: bool,
+    /// Offset of the local timezone from UTC
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+impl Default for BusinessHours {
+    fn default() -> Self {
+        Self {
+            start_hour: 9,
+            end_hour: 18,
+            include_weekends: false,
+            utc_offset_minutes: 0,
+        }
+    }
+}
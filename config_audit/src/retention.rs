@@ -0,0 +1,172 @@
+use config_common::{AuditLog, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use prometheus::{IntCounter, Registry};
+use sqlx::PgPool;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+
+use crate::model::RetentionConfig;
+
+/// Retention job metrics
+#[derive(Clone)]
+pub struct RetentionMetrics {
+    pub purged: IntCounter,
+    pub archived: IntCounter,
+}
+
+impl RetentionMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let purged = IntCounter::new(
+            "audit_logs_purged_total",
+            "Audit records deleted by the retention job",
+        )?;
+        let archived = IntCounter::new(
+            "audit_logs_archived_total",
+            "Audit records archived by the retention job",
+        )?;
+
+        registry.register(Box::new(purged.clone()))?;
+        registry.register(Box::new(archived.clone()))?;
+
+        Ok(Self { purged, archived })
+    }
+}
+
+/// Background job purging audit records past the retention period
+pub struct RetentionJob {
+    pool: Arc<PgPool>,
+    config: RetentionConfig,
+    metrics: RetentionMetrics,
+}
+
+impl RetentionJob {
+    pub fn new(pool: Arc<PgPool>, config: RetentionConfig, metrics: RetentionMetrics) -> Self {
+        Self {
+            pool,
+            config,
+            metrics,
+        }
+    }
+
+    /// Run the job periodically until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+            loop {
+                ticker.tick().await;
+                match self.run_once().await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!(purged, "Purged expired audit records"),
+                    Err(e) => tracing::error!(error = %e, "Audit retention run failed"),
+                }
+            }
+        })
+    }
+
+    /// Archive and delete every record older than the retention period
+    pub async fn run_once(&self) -> Result<u64> {
+        let now = chrono::Utc::now();
+        let cutoff = (now - chrono::Duration::days(self.config.days as i64)).timestamp();
+        let archive = self.config.archive_directory.as_ref().map(|dir| {
+            Path::new(dir).join(format!("audit-{}.jsonl.gz", now.format("%Y%m%dT%H%M%S")))
+        });
+
+        let mut purged = 0;
+        loop {
+            let batch = self.fetch_expired(cutoff).await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            if let Some(path) = &archive {
+                self.archive(path, &batch).await?;
+                self.metrics.archived.inc_by(batch.len() as u64);
+            }
+
+            let ids: Vec<String> = batch.into_iter().map(|log| log.id).collect();
+            let deleted = sqlx::query("DELETE FROM audit_logs WHERE id = ANY($1)")
+                .bind(&ids)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?
+                .rows_affected();
+
+            self.metrics.purged.inc_by(deleted);
+            purged += deleted;
+        }
+
+        Ok(purged)
+    }
+
+    async fn fetch_expired(&self, cutoff: i64) -> Result<Vec<AuditLog>> {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, i64)>(
+            r#"
+            SELECT id, user_id, action, resource, details, timestamp
+            FROM audit_logs
+            WHERE timestamp < $1
+            ORDER BY timestamp ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(cutoff)
+        .bind(self.config.batch_size)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, user, action, resource, details, timestamp)| AuditLog {
+                    id,
+                    user,
+                    action,
+                    resource,
+                    details,
+                    timestamp,
+                },
+            )
+            .collect())
+    }
+
+    /// Append a batch to the archive as its own gzip member
+    async fn archive(&self, path: &Path, logs: &[AuditLog]) -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for log in logs {
+            serde_json::to_writer(&mut encoder, log)?;
+            encoder
+                .write_all(b"\n")
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        }
+        let compressed = encoder
+            .finish()
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        file.write_all(&compressed)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        // The records are deleted right after, so make sure the archive is durable
+        file.sync_all()
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use async_trait::async_trait;
+use config_common::{AuditLog, Result, Webhook};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+use crate::file::daily_file;
+use crate::model::SinkConfig;
+use crate::{AuditService, DbAuditService};
+
+/// Destination receiving copies of audit records
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Sink name used in logs
+    fn name(&self) -> &str;
+
+    /// Write a batch of audit records
+    async fn write(&self, logs: &[AuditLog]) -> Result<()>;
+}
+
+/// Create the sinks described by the configuration
+pub fn build_sinks(configs: &[SinkConfig]) -> Result<Vec<Arc<dyn AuditSink>>> {
+    configs
+        .iter()
+        .map(|config| -> Result<Arc<dyn AuditSink>> {
+            match config {
+                SinkConfig::File { directory } => Ok(Arc::new(FileSink::new(directory))),
+                SinkConfig::Kafka { brokers, topic } => kafka_sink(brokers, topic),
+                SinkConfig::Syslog { address, app_name } => {
+                    Ok(Arc::new(SyslogSink::new(address, app_name)))
+                }
+                SinkConfig::Webhook {
+                    url,
+                    headers,
+                    timeout_ms,
+                } => Ok(Arc::new(WebhookSink::new(
+                    url,
+                    headers.clone(),
+                    Duration::from_millis(*timeout_ms),
+                )?)),
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl AuditSink for DbAuditService {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        for log in logs {
+            self.record(log.clone()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Sink appending JSON lines to one file per day
+pub struct FileSink {
+    directory: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileSink {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        tokio::fs::create_dir_all(&self.directory)
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        for log in logs {
+            let mut line = serde_json::to_vec(log)?;
+            line.push(b'\n');
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(daily_file(&self.directory, log.timestamp))
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+            file.write_all(&line)
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sink sending RFC 5424 messages over UDP
+pub struct SyslogSink {
+    address: String,
+    app_name: String,
+    hostname: String,
+}
+
+impl SyslogSink {
+    /// Facility `log audit` (13), severity `informational` (6)
+    const PRIORITY: u8 = 13 * 8 + 6;
+
+    pub fn new(address: &str, app_name: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            app_name: app_name.to_string(),
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+        }
+    }
+
+    fn format(&self, log: &AuditLog) -> Result<String> {
+        let timestamp = chrono::DateTime::from_timestamp(log.timestamp, 0)
+            .unwrap_or_default()
+            .to_rfc3339();
+        Ok(format!(
+            "<{}>1 {} {} {} {} {} - {}",
+            Self::PRIORITY,
+            timestamp,
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            log.action,
+            serde_json::to_string(log)?
+        ))
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogSink {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        for log in logs {
+            socket
+                .send_to(self.format(log)?.as_bytes(), &self.address)
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sink posting batches to an HTTP endpoint
+pub struct WebhookSink {
+    webhook: Webhook,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str, headers: HashMap<String, String>, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            webhook: Webhook::new(url, headers, timeout)?,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        self.webhook.post(logs).await
+    }
+}
+
+#[cfg(feature = "kafka")]
+fn kafka_sink(brokers: &str, topic: &str) -> Result<Arc<dyn AuditSink>> {
+    Ok(Arc::new(KafkaSink::new(brokers, topic)?))
+}
+
+#[cfg(not(feature = "kafka"))]
+fn kafka_sink(_brokers: &str, _topic: &str) -> Result<Arc<dyn AuditSink>> {
+    Err(config_common::Error::Config(
+        "kafka audit sink requires the `kafka` feature".to_string(),
+    ))
+}
+
+/// Sink publishing records to a Kafka topic
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(brokers: &str, topic: &str) -> Result<Self> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "5000")
+            .create()
+            .map_err(|e| config_common::Error::Config(e.to_string()))?;
+
+        Ok(Self {
+            producer,
+            topic: topic.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl AuditSink for KafkaSink {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    async fn write(&self, logs: &[AuditLog]) -> Result<()> {
+        for log in logs {
+            let payload = serde_json::to_vec(log)?;
+            self.producer
+                .send(
+                    rdkafka::producer::FutureRecord::to(&self.topic)
+                        .key(&log.id)
+                        .payload(&payload),
+                    Self::SEND_TIMEOUT,
+                )
+                .await
+                .map_err(|(e, _)| config_common::Error::Internal(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use casbin::prelude::*;
+use config_common::{ConfigMeta, RbacPolicy, Result};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use crate::model::{AuthConfig, RoleAssignment, TemporaryGrant};
+
+/// Casbin model with an explicit effect column on every policy. `g2` links users to the roles
+/// of their active temporary grants, so grants are weighed in the same check as the user's
+/// own policies and denies still override them.
+const MODEL_TEMPLATE: &str = r#"
+[request_definition]
+r = sub, obj, act
+
+[policy_definition]
+p = sub, obj, act, eft
+
+[role_definition]
+g = _, _
+g2 = _, _
+
+[policy_effect]
+e = {effect}
+
+[matchers]
+m = (g(r.sub, p.sub) || g2(r.sub, p.sub)) && keyMatch(r.obj, p.obj) && (r.act == p.act || p.act == "*")
+"#;
+
+/// Deny-overrides: at least one allow and no deny
+const EFFECT_DEFAULT_DENY: &str = "some(where (p.eft == allow)) && !some(where (p.eft == deny))";
+
+/// Deny-overrides without requiring a matching allow
+const EFFECT_DEFAULT_ALLOW: &str = "!some(where (p.eft == deny))";
+
+/// Policy enforcer evaluating RBAC policies with allow/deny effects
+pub struct PolicyEnforcer {
+    config: AuthConfig,
+    enforcer: RwLock<Enforcer>,
+    grants: RwLock<Vec<TemporaryGrant>>,
+    /// Grant roles each user is currently linked to through `g2`; only changed while holding
+    /// the enforcer's write lock
+    grant_links: RwLock<HashMap<String, Vec<String>>>,
+}
+
+impl PolicyEnforcer {
+    /// Create an enforcer loaded with the given policies and role assignments
+    pub async fn new(
+        config: &AuthConfig,
+        policies: &[RbacPolicy],
+        assignments: &[RoleAssignment],
+    ) -> Result<Self> {
+        let enforcer = build_enforcer(config, policies, assignments).await?;
+
+        Ok(Self {
+            config: config.clone(),
+            enforcer: RwLock::new(enforcer),
+            grants: RwLock::new(Vec::new()),
+            grant_links: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn config(&self) -> &AuthConfig {
+        &self.config
+    }
+
+    /// Replace all loaded policies and role assignments
+    pub async fn reload(
+        &self,
+        policies: &[RbacPolicy],
+        assignments: &[RoleAssignment],
+    ) -> Result<()> {
+        // Build outside the lock so readers are never blocked on policy loading
+        let enforcer = build_enforcer(&self.config, policies, assignments).await?;
+        let mut current = self.enforcer.write().await;
+        *current = enforcer;
+        self.grant_links.write().await.clear();
+        drop(current);
+
+        tracing::info!(
+            policies = policies.len(),
+            assignments = assignments.len(),
+            "Reloaded policy enforcer"
+        );
+        Ok(())
+    }
+
+    /// Replace the loaded temporary grants
+    pub async fn set_grants(&self, grants: Vec<TemporaryGrant>) {
+        *self.grants.write().await = grants;
+    }
+
+    /// Add a temporary grant
+    pub async fn add_grant(&self, grant: TemporaryGrant) {
+        self.grants.write().await.push(grant);
+    }
+
+    /// Remove a temporary grant
+    pub async fn remove_grant(&self, id: &str) {
+        self.grants.write().await.retain(|g| g.id != id);
+    }
+
+    /// Roles temporarily granted to the user at the current time
+    pub async fn active_grant_roles(&self, user: &str) -> Vec<String> {
+        let now = chrono::Utc::now().timestamp();
+        self.grants
+            .read()
+            .await
+            .iter()
+            .filter(|g| g.user == user && g.is_active(now))
+            .map(|g| g.role.clone())
+            .collect()
+    }
+
+    /// Check whether the subject may perform the action on the resource, with the roles of
+    /// any temporary grants the subject currently holds
+    pub async fn enforce(&self, subject: &str, resource: &str, action: &str) -> Result<bool> {
+        let roles = self.active_grant_roles(subject).await;
+        let allowed = {
+            let enforcer = self.enforcer.read().await;
+            if self.grants_linked(subject, &roles).await {
+                Some(enforce_request(&enforcer, subject, resource, action)?)
+            } else {
+                None
+            }
+        };
+        let allowed = match allowed {
+            Some(allowed) => allowed,
+            None => {
+                let mut enforcer = self.enforcer.write().await;
+                self.link_grants(&mut enforcer, subject, &roles).await?;
+                enforce_request(&enforcer, subject, resource, action)?
+            }
+        };
+
+        if allowed && !roles.is_empty() {
+            tracing::info!(
+                user = %subject,
+                roles = ?roles,
+                resource = %resource,
+                action = %action,
+                "Access allowed with temporary grants in effect"
+            );
+        }
+        Ok(allowed)
+    }
+
+    async fn grants_linked(&self, user: &str, roles: &[String]) -> bool {
+        let links = self.grant_links.read().await;
+        links.get(user).map(Vec::as_slice).unwrap_or_default() == roles
+    }
+
+    /// Point the user's `g2` links at the given grant roles and the roles those inherit
+    async fn link_grants(
+        &self,
+        enforcer: &mut Enforcer,
+        user: &str,
+        roles: &[String],
+    ) -> Result<()> {
+        enforcer
+            .remove_filtered_named_grouping_policy("g2", 0, vec![user.to_string()])
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+
+        let mut links = Vec::new();
+        for role in roles {
+            links.push(vec![user.to_string(), role.clone()]);
+            for inherited in enforcer.get_implicit_roles_for_user(role, None) {
+                links.push(vec![user.to_string(), inherited]);
+            }
+        }
+        links.sort();
+        links.dedup();
+        if !links.is_empty() {
+            enforcer
+                .add_named_grouping_policies("g2", links)
+                .await
+                .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+        }
+
+        let mut grant_links = self.grant_links.write().await;
+        if roles.is_empty() {
+            grant_links.remove(user);
+        } else {
+            grant_links.insert(user.to_string(), roles.to_vec());
+        }
+        Ok(())
+    }
+
+    /// Drop every `g2` link, so they are rebuilt on the next check; needed once the roles a
+    /// grant role inherits may have changed
+    async fn unlink_grants(&self, enforcer: &mut Enforcer) -> Result<()> {
+        let mut grant_links = self.grant_links.write().await;
+        for user in grant_links.keys() {
+            enforcer
+                .remove_filtered_named_grouping_policy("g2", 0, vec![user.clone()])
+                .await
+                .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+        }
+        grant_links.clear();
+        Ok(())
+    }
+
+    /// Same as `enforce`, but returns an authorization error when denied
+    pub async fn check(&self, subject: &str, resource: &str, action: &str) -> Result<()> {
+        if self.enforce(subject, resource, action).await? {
+            Ok(())
+        } else {
+            Err(config_common::Error::Authorization(format!(
+                "{} is not allowed to {} {}",
+                subject, action, resource
+            )))
+        }
+    }
+
+    /// Check whether the user is assigned the given role or team membership; temporary grants
+    /// only count through policies
+    pub async fn has_role(&self, user: &str, role: &str) -> bool {
+        self.enforcer
+            .write()
+            .await
+            .has_role_for_user(user, role, None)
+    }
+
+    /// Check whether the user is an administrator
+    pub async fn is_admin(&self, user: &str) -> bool {
+        self.has_role(user, &self.config.admin_role).await
+    }
+
+    /// Require the user to be an administrator
+    pub async fn check_admin(&self, user: &str) -> Result<()> {
+        if self.is_admin(user).await {
+            Ok(())
+        } else {
+            Err(config_common::Error::Authorization(format!(
+                "{} is not an administrator",
+                user
+            )))
+        }
+    }
+
+    /// Require the user to be an auditor or an administrator
+    pub async fn check_auditor(&self, user: &str) -> Result<()> {
+        if self.has_role(user, &self.config.auditor_role).await || self.is_admin(user).await {
+            Ok(())
+        } else {
+            Err(config_common::Error::Authorization(format!(
+                "{} is not allowed to read audit logs",
+                user
+            )))
+        }
+    }
+
+    /// Check whether the user owns the configuration, directly or through a team
+    pub async fn is_owner(&self, user: &str, meta: &ConfigMeta) -> bool {
+        for owner in &meta.owners {
+            if owner == user || self.has_role(user, owner).await {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Require the user to be an owner of the configuration or an administrator
+    pub async fn check_config_owner(&self, user: &str, meta: &ConfigMeta) -> Result<()> {
+        if self.is_owner(user, meta).await || self.is_admin(user).await {
+            Ok(())
+        } else {
+            Err(config_common::Error::Authorization(format!(
+                "{} is not an owner of config {}",
+                user, meta.id
+            )))
+        }
+    }
+
+    /// Check access to an existing configuration. Owners and administrators are
+    /// always allowed, everyone else goes through the regular policies.
+    pub async fn check_config_access(
+        &self,
+        user: &str,
+        meta: &ConfigMeta,
+        action: &str,
+    ) -> Result<()> {
+        if self.check_config_owner(user, meta).await.is_ok() {
+            return Ok(());
+        }
+        self.check(user, &config_resource(meta), action).await
+    }
+
+    /// Add a policy
+    pub async fn add_policy(&self, policy: &RbacPolicy) -> Result<bool> {
+        self.enforcer
+            .write()
+            .await
+            .add_policy(policy_rule(policy))
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))
+    }
+
+    /// Remove a policy
+    pub async fn remove_policy(&self, policy: &RbacPolicy) -> Result<bool> {
+        self.enforcer
+            .write()
+            .await
+            .remove_policy(policy_rule(policy))
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))
+    }
+
+    /// Assign a role to a user
+    pub async fn assign_role(&self, user: &str, role: &str) -> Result<bool> {
+        let mut enforcer = self.enforcer.write().await;
+        self.unlink_grants(&mut enforcer).await?;
+        enforcer
+            .add_grouping_policy(vec![user.to_string(), role.to_string()])
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))
+    }
+
+    /// Revoke a role from a user
+    pub async fn revoke_role(&self, user: &str, role: &str) -> Result<bool> {
+        let mut enforcer = self.enforcer.write().await;
+        self.unlink_grants(&mut enforcer).await?;
+        enforcer
+            .remove_grouping_policy(vec![user.to_string(), role.to_string()])
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))
+    }
+}
+
+/// Resource path of a configuration as matched by policies
+pub fn config_resource(meta: &ConfigMeta) -> String {
+    format!(
+        "configs/{}/{}/{}/{}",
+        meta.namespace, meta.application, meta.environment, meta.id
+    )
+}
+
+fn enforce_request(
+    enforcer: &Enforcer,
+    subject: &str,
+    resource: &str,
+    action: &str,
+) -> Result<bool> {
+    enforcer
+        .enforce((subject, resource, action))
+        .map_err(|e| config_common::Error::Auth(e.to_string()))
+}
+
+/// Build a Casbin enforcer from the configuration and loaded rules
+async fn build_enforcer(
+    config: &AuthConfig,
+    policies: &[RbacPolicy],
+    assignments: &[RoleAssignment],
+) -> Result<Enforcer> {
+    let effect = if config.default_deny {
+        EFFECT_DEFAULT_DENY
+    } else {
+        EFFECT_DEFAULT_ALLOW
+    };
+    let model = DefaultModel::from_str(&MODEL_TEMPLATE.replace("{effect}", effect))
+        .await
+        .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+
+    let mut enforcer = Enforcer::new(model, MemoryAdapter::default())
+        .await
+        .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+
+    if !policies.is_empty() {
+        enforcer
+            .add_policies(policies.iter().map(policy_rule).collect())
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+    }
+    if !assignments.is_empty() {
+        enforcer
+            .add_grouping_policies(
+                assignments
+                    .iter()
+                    .map(|a| vec![a.user.clone(), a.role.clone()])
+                    .collect(),
+            )
+            .await
+            .map_err(|e| config_common::Error::Auth(e.to_string()))?;
+    }
+
+    Ok(enforcer)
+}
+
+/// Convert a policy into a Casbin rule
+fn policy_rule(policy: &RbacPolicy) -> Vec<String> {
+    vec![
+        policy.role.clone(),
+        policy.resource.clone(),
+        policy.action.clone(),
+        policy.effect.as_str().to_string(),
+    ]
+}
@@ -0,0 +1,11 @@
+pub mod enforcer;
+pub mod model;
+pub mod service;
+pub mod store;
+pub mod sync;
+
+pub use enforcer::PolicyEnforcer;
+pub use model::{AuthConfig, RoleAssignment, TemporaryGrant};
+pub use service::PolicyService;
+pub use store::{DbPolicyStore, PolicyStore};
+pub use sync::PolicySync;
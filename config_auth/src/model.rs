@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// Authorization configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Deny requests that no policy matches. When disabled, only explicit
+    /// deny policies reject a request.
+    #[serde(default = "default_deny")]
+    pub default_deny: bool,
+    /// Redis channel used to broadcast policy changes between nodes
+    #[serde(default = "default_policy_channel")]
+    pub policy_channel: String,
+    /// Role that bypasses ownership checks
+    #[serde(default = "default_admin_role")]
+    pub admin_role: String,
+    /// Role allowed to read audit logs
+    #[serde(default = "default_auditor_role")]
+    pub auditor_role: String,
+    /// Longest window a temporary grant may be issued for, in seconds
+    #[serde(default = "default_max_grant_duration")]
+    pub max_grant_duration: u64,
+    /// Secret the authenticating gateway sends along with the caller identity; identities
+    /// of requests without it are ignored
+    #[serde(default)]
+    pub gateway_token: Option<String>,
+    /// Take caller identities on trust, for servers that only a trusted gateway can reach
+    #[serde(default)]
+    pub trust_identity_header: bool,
+}
+
+fn default_deny() -> bool {
+    true
+}
+
+fn default_policy_channel() -> String {
+    "config:policy:changes".to_string()
+}
+
+fn default_admin_role() -> String {
+    "admin".to_string()
+}
+
+fn default_auditor_role() -> String {
+    "auditor".to_string()
+}
+
+fn default_max_grant_duration() -> u64 {
+    8 * 60 * 60
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            default_deny: default_deny(),
+            policy_channel: default_policy_channel(),
+            admin_role: default_admin_role(),
+            auditor_role: default_auditor_role(),
+            max_grant_duration: default_max_grant_duration(),
+            gateway_token: None,
+            trust_identity_header: false,
+        }
+    }
+}
+
+impl AuthConfig {
+    /// Whether a request presenting this gateway token may state its caller's identity
+    pub fn accepts_identity(&self, gateway_token: Option<&str>) -> bool {
+        if self.trust_identity_header {
+            return true;
+        }
+        match (&self.gateway_token, gateway_token) {
+            (Some(expected), Some(presented)) => {
+                // Compared in constant time so the token can't be guessed byte by byte
+                expected.len() == presented.len()
+                    && expected
+                        .bytes()
+                        .zip(presented.bytes())
+                        .fold(0, |diff, (a, b)| diff | (a ^ b))
+                        == 0
+            }
+            _ => false,
+        }
+    }
+}
+
+/// User to role assignment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleAssignment {
+    pub user: String,
+    pub role: String,
+}
+
+/// Temporary role grant that expires on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemporaryGrant {
+    pub id: String,
+    pub user: String,
+    pub role: String,
+    pub reason: String,
+    pub granted_by: String,
+    pub granted_at: i64,
+    pub expires_at: i64,
+}
+
+impl TemporaryGrant {
+    /// Whether the grant is in effect at the given timestamp
+    pub fn is_active(&self, now: i64) -> bool {
+        self.granted_at <= now && now < self.expires_at
+    }
+}
@@ -0,0 +1,222 @@
+use config_audit::AuditService;
+use config_common::{AuditLog, RbacPolicy, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::enforcer::PolicyEnforcer;
+use crate::model::{RoleAssignment, TemporaryGrant};
+use crate::store::PolicyStore;
+use crate::sync::{self, PolicySync};
+
+/// Policy management keeping the store, local enforcer and cluster peers in sync
+pub struct PolicyService {
+    enforcer: Arc<PolicyEnforcer>,
+    store: Arc<dyn PolicyStore>,
+    sync: Option<Arc<PolicySync>>,
+    audit: Option<Arc<dyn AuditService>>,
+}
+
+impl PolicyService {
+    pub fn new(enforcer: Arc<PolicyEnforcer>, store: Arc<dyn PolicyStore>) -> Self {
+        Self {
+            enforcer,
+            store,
+            sync: None,
+            audit: None,
+        }
+    }
+
+    /// Broadcast changes to other nodes through the given sync channel
+    pub fn with_sync(mut self, sync: Arc<PolicySync>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Record grant lifecycle events through the given audit service
+    pub fn with_audit(mut self, audit: Arc<dyn AuditService>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn enforcer(&self) -> Arc<PolicyEnforcer> {
+        self.enforcer.clone()
+    }
+
+    /// List all policies
+    pub async fn list_policies(&self) -> Result<Vec<RbacPolicy>> {
+        self.store.load_policies().await
+    }
+
+    /// Add a policy
+    pub async fn add_policy(&self, policy: &RbacPolicy) -> Result<()> {
+        self.store.save_policy(policy).await?;
+        self.enforcer.add_policy(policy).await?;
+        self.notify().await
+    }
+
+    /// Remove a policy
+    pub async fn remove_policy(&self, policy: &RbacPolicy) -> Result<bool> {
+        let removed = self.store.delete_policy(policy).await?;
+        self.enforcer.remove_policy(policy).await?;
+        self.notify().await?;
+        Ok(removed)
+    }
+
+    /// Assign a role to a user
+    pub async fn assign_role(&self, assignment: &RoleAssignment) -> Result<()> {
+        self.store.save_role_assignment(assignment).await?;
+        self.enforcer
+            .assign_role(&assignment.user, &assignment.role)
+            .await?;
+        self.notify().await
+    }
+
+    /// Revoke a role from a user
+    pub async fn revoke_role(&self, assignment: &RoleAssignment) -> Result<bool> {
+        let removed = self.store.delete_role_assignment(assignment).await?;
+        self.enforcer
+            .revoke_role(&assignment.user, &assignment.role)
+            .await?;
+        self.notify().await?;
+        Ok(removed)
+    }
+
+    /// Grant a role to a user for a bounded time window
+    pub async fn grant_temporary(
+        &self,
+        user: &str,
+        role: &str,
+        duration_secs: u64,
+        reason: &str,
+        granted_by: &str,
+    ) -> Result<TemporaryGrant> {
+        let max = self.enforcer.config().max_grant_duration;
+        if duration_secs == 0 || duration_secs > max {
+            return Err(config_common::Error::Validation(format!(
+                "grant duration must be between 1 and {} seconds",
+                max
+            )));
+        }
+        if reason.trim().is_empty() {
+            return Err(config_common::Error::Validation(
+                "a reason is required for temporary grants".to_string(),
+            ));
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let grant = TemporaryGrant {
+            id: uuid::Uuid::new_v4().to_string(),
+            user: user.to_string(),
+            role: role.to_string(),
+            reason: reason.to_string(),
+            granted_by: granted_by.to_string(),
+            granted_at: now,
+            expires_at: now + duration_secs as i64,
+        };
+
+        self.store.save_grant(&grant).await?;
+        self.enforcer.add_grant(grant.clone()).await;
+        self.notify().await?;
+        self.audit_grant(granted_by, "grant.create", &grant).await;
+
+        Ok(grant)
+    }
+
+    /// List temporary grants that are still in effect
+    pub async fn list_grants(&self) -> Result<Vec<TemporaryGrant>> {
+        let now = chrono::Utc::now().timestamp();
+        Ok(self
+            .store
+            .load_grants()
+            .await?
+            .into_iter()
+            .filter(|g| g.is_active(now))
+            .collect())
+    }
+
+    /// Revoke a temporary grant before it expires
+    pub async fn revoke_grant(&self, id: &str, revoked_by: &str) -> Result<bool> {
+        let grant = self
+            .store
+            .load_grants()
+            .await?
+            .into_iter()
+            .find(|g| g.id == id)
+            .ok_or_else(|| config_common::Error::NotFound(format!("grant {}", id)))?;
+
+        let removed = self.store.delete_grant(id).await?;
+        self.enforcer.remove_grant(id).await;
+        self.notify().await?;
+        self.audit_grant(revoked_by, "grant.revoke", &grant).await;
+
+        Ok(removed)
+    }
+
+    /// Remove grants whose window has passed
+    pub async fn purge_expired_grants(&self) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let mut purged = 0;
+
+        for grant in self.store.load_grants().await? {
+            if grant.expires_at > now {
+                continue;
+            }
+            if self.store.delete_grant(&grant.id).await? {
+                self.enforcer.remove_grant(&grant.id).await;
+                self.audit_grant("system", "grant.expire", &grant).await;
+                purged += 1;
+            }
+        }
+
+        if purged > 0 {
+            self.notify().await?;
+        }
+        Ok(purged)
+    }
+
+    /// Reload the local enforcer from the store
+    pub async fn reload(&self) -> Result<()> {
+        sync::reload(&self.enforcer, &*self.store).await
+    }
+
+    async fn audit_grant(&self, user: &str, action: &str, grant: &TemporaryGrant) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+
+        let log = AuditLog {
+            id: uuid::Uuid::new_v4().to_string(),
+            user: user.to_string(),
+            action: action.to_string(),
+            resource: format!("grants/{}", grant.id),
+            details: serde_json::to_string(grant).unwrap_or_default(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        if let Err(e) = audit.record(log).await {
+            tracing::error!(error = %e, grant = %grant.id, "Failed to audit temporary grant");
+        }
+    }
+
+    async fn notify(&self) -> Result<()> {
+        match &self.sync {
+            Some(sync) => sync.publish().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Spawn a task periodically purging expired temporary grants
+pub fn spawn_grant_expiry(service: Arc<PolicyService>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match service.purge_expired_grants().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!(purged, "Purged expired temporary grants"),
+                Err(e) => tracing::error!(error = %e, "Failed to purge expired grants"),
+            }
+        }
+    })
+}
@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use config_common::{PolicyEffect, RbacPolicy, Result};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::model::{RoleAssignment, TemporaryGrant};
+
+/// Persistent storage for authorization policies
+#[async_trait]
+pub trait PolicyStore: Send + Sync {
+    /// Load all policies
+    async fn load_policies(&self) -> Result<Vec<RbacPolicy>>;
+
+    /// Load all user to role assignments
+    async fn load_role_assignments(&self) -> Result<Vec<RoleAssignment>>;
+
+    /// Persist a policy
+    async fn save_policy(&self, policy: &RbacPolicy) -> Result<()>;
+
+    /// Delete a policy
+    async fn delete_policy(&self, policy: &RbacPolicy) -> Result<bool>;
+
+    /// Persist a role assignment
+    async fn save_role_assignment(&self, assignment: &RoleAssignment) -> Result<()>;
+
+    /// Delete a role assignment
+    async fn delete_role_assignment(&self, assignment: &RoleAssignment) -> Result<bool>;
+
+    /// Load all temporary grants, including expired ones not yet purged
+    async fn load_grants(&self) -> Result<Vec<TemporaryGrant>>;
+
+    /// Persist a temporary grant
+    async fn save_grant(&self, grant: &TemporaryGrant) -> Result<()>;
+
+    /// Delete a temporary grant
+    async fn delete_grant(&self, id: &str) -> Result<bool>;
+}
+
+/// Database-backed policy store using the `casbin_rule` table
+pub struct DbPolicyStore {
+    pool: Arc<PgPool>,
+}
+
+impl DbPolicyStore {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PolicyStore for DbPolicyStore {
+    async fn load_policies(&self) -> Result<Vec<RbacPolicy>> {
+        let rows = sqlx::query_as::<_, (String, String, String, String)>(
+            "SELECT v0, v1, v2, v3 FROM casbin_rule WHERE ptype = 'p'",
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(role, resource, action, effect)| {
+                Ok(RbacPolicy {
+                    role,
+                    resource,
+                    action,
+                    effect: effect.parse::<PolicyEffect>()?,
+                })
+            })
+            .collect()
+    }
+
+    async fn load_role_assignments(&self) -> Result<Vec<RoleAssignment>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT v0, v1 FROM casbin_rule WHERE ptype = 'g'",
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user, role)| RoleAssignment { user, role })
+            .collect())
+    }
+
+    async fn save_policy(&self, policy: &RbacPolicy) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO casbin_rule (ptype, v0, v1, v2, v3)
+            VALUES ('p', $1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&policy.role)
+        .bind(&policy.resource)
+        .bind(&policy.action)
+        .bind(policy.effect.as_str())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_policy(&self, policy: &RbacPolicy) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM casbin_rule
+            WHERE ptype = 'p' AND v0 = $1 AND v1 = $2 AND v2 = $3 AND v3 = $4
+            "#,
+        )
+        .bind(&policy.role)
+        .bind(&policy.resource)
+        .bind(&policy.action)
+        .bind(policy.effect.as_str())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn save_role_assignment(&self, assignment: &RoleAssignment) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO casbin_rule (ptype, v0, v1)
+            VALUES ('g', $1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&assignment.user)
+        .bind(&assignment.role)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_role_assignment(&self, assignment: &RoleAssignment) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM casbin_rule WHERE ptype = 'g' AND v0 = $1 AND v1 = $2")
+                .bind(&assignment.user)
+                .bind(&assignment.role)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn load_grants(&self) -> Result<Vec<TemporaryGrant>> {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, i64, i64)>(
+            r#"
+            SELECT id, user_id, role, reason, granted_by, granted_at, expires_at
+            FROM temporary_grants
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, user, role, reason, granted_by, granted_at, expires_at)| TemporaryGrant {
+                    id,
+                    user,
+                    role,
+                    reason,
+                    granted_by,
+                    granted_at,
+                    expires_at,
+                },
+            )
+            .collect())
+    }
+
+    async fn save_grant(&self, grant: &TemporaryGrant) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO temporary_grants (id, user_id, role, reason, granted_by, granted_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&grant.id)
+        .bind(&grant.user)
+        .bind(&grant.role)
+        .bind(&grant.reason)
+        .bind(&grant.granted_by)
+        .bind(grant.granted_at)
+        .bind(grant.expires_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_grant(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM temporary_grants WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
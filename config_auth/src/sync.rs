@@ -0,0 +1,108 @@
+use config_common::Result;
+use futures_util::StreamExt;
+use redis::AsyncCommands;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::enforcer::PolicyEnforcer;
+use crate::store::PolicyStore;
+
+/// Delay before resubscribing after the pub/sub connection drops
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// Broadcasts policy changes between nodes over Redis pub/sub
+pub struct PolicySync {
+    client: redis::Client,
+    channel: String,
+    node_id: String,
+}
+
+impl PolicySync {
+    pub fn new(client: redis::Client, channel: &str, node_id: &str) -> Self {
+        Self {
+            client,
+            channel: channel.to_string(),
+            node_id: node_id.to_string(),
+        }
+    }
+
+    /// Notify other nodes that policies have changed
+    pub async fn publish(&self) -> Result<()> {
+        let mut conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| config_common::Error::Cache(e.to_string()))?;
+
+        conn.publish::<_, _, ()>(&self.channel, &self.node_id)
+            .await
+            .map_err(|e| config_common::Error::Cache(e.to_string()))
+    }
+
+    /// Spawn a task reloading the enforcer whenever another node publishes a change
+    pub fn spawn_listener(
+        &self,
+        enforcer: Arc<PolicyEnforcer>,
+        store: Arc<dyn PolicyStore>,
+    ) -> JoinHandle<()> {
+        let client = self.client.clone();
+        let channel = self.channel.clone();
+        let node_id = self.node_id.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = listen(&client, &channel, &node_id, &enforcer, &*store).await {
+                    tracing::warn!(error = %e, "Policy change subscription lost");
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        })
+    }
+}
+
+/// Subscribe to the policy channel until the connection fails
+async fn listen(
+    client: &redis::Client,
+    channel: &str,
+    node_id: &str,
+    enforcer: &PolicyEnforcer,
+    store: &dyn PolicyStore,
+) -> Result<()> {
+    let mut pubsub = client
+        .get_async_pubsub()
+        .await
+        .map_err(|e| config_common::Error::Cache(e.to_string()))?;
+    pubsub
+        .subscribe(channel)
+        .await
+        .map_err(|e| config_common::Error::Cache(e.to_string()))?;
+
+    // Changes may have been published while we were disconnected
+    reload(enforcer, store).await?;
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let origin: String = msg
+            .get_payload()
+            .map_err(|e| config_common::Error::Cache(e.to_string()))?;
+        if origin == node_id {
+            continue;
+        }
+        if let Err(e) = reload(enforcer, store).await {
+            tracing::error!(error = %e, origin = %origin, "Failed to reload policies");
+        }
+    }
+
+    Ok(())
+}
+
+/// Reload the enforcer from the policy store
+pub async fn reload(enforcer: &PolicyEnforcer, store: &dyn PolicyStore) -> Result<()> {
+    let policies = store.load_policies().await?;
+    let assignments = store.load_role_assignments().await?;
+    let grants = store.load_grants().await?;
+    enforcer.reload(&policies, &assignments).await?;
+    enforcer.set_grants(grants).await;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use config_common::Result;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Source of the bearer token sent with each request
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    /// Current token, fetching one when none is held yet
+    async fn token(&self) -> Result<String>;
+
+    /// Replace the token after the server rejected it
+    async fn refresh(&self) -> Result<String>;
+}
+
+/// Token that never changes, such as a long-lived service token
+pub struct StaticToken(String);
+
+impl StaticToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticToken {
+    async fn token(&self) -> Result<String> {
+        Ok(self.0.clone())
+    }
+
+    async fn refresh(&self) -> Result<String> {
+        Err(config_common::Error::Auth(
+            "static token was rejected".to_string(),
+        ))
+    }
+}
+
+/// Function fetching a new token, e.g. from an OAuth token endpoint
+pub type FetchToken = Arc<dyn Fn() -> BoxFuture<'static, Result<String>> + Send + Sync>;
+
+/// Holds a fetched token until the server rejects it
+pub struct RefreshingToken {
+    fetch: FetchToken,
+    current: Mutex<Option<String>>,
+}
+
+impl RefreshingToken {
+    pub fn new(fetch: FetchToken) -> Self {
+        Self {
+            fetch,
+            current: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for RefreshingToken {
+    async fn token(&self) -> Result<String> {
+        let mut current = self.current.lock().await;
+        if let Some(token) = current.as_ref() {
+            return Ok(token.clone());
+        }
+        let token = (self.fetch)().await?;
+        *current = Some(token.clone());
+        Ok(token)
+    }
+
+    async fn refresh(&self) -> Result<String> {
+        // Holding the lock makes concurrent rejections share one fetch
+        let mut current = self.current.lock().await;
+        let token = (self.fetch)().await?;
+        *current = Some(token.clone());
+        Ok(token)
+    }
+}
@@ -0,0 +1,248 @@
+use config_common::{ConfigContent, ConfigMeta, Result};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::auth::TokenProvider;
+use crate::watch::{Subscription, Watch};
+
+/// Header the server reads the caller identity from when called without a gateway
+const USER_HEADER: &str = "X-User-Id";
+
+/// Header vouching for the caller identity, checked against the server's `auth.gateway_token`
+const GATEWAY_TOKEN_HEADER: &str = "X-Gateway-Token";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Configuration held locally with the time it was fetched
+#[derive(Clone)]
+struct CachedConfig {
+    meta: ConfigMeta,
+    content: ConfigContent,
+    fetched_at: Instant,
+}
+
+/// Configs fetched by the client, by ID
+type ConfigCache = Arc<RwLock<HashMap<String, CachedConfig>>>;
+
+/// Async client of the configuration server REST API
+#[derive(Clone)]
+pub struct ConfigClient {
+    http: reqwest::Client,
+    base_url: String,
+    tokens: Option<Arc<dyn TokenProvider>>,
+    user: Option<String>,
+    gateway_token: Option<String>,
+    client_id: Option<String>,
+    client_labels: Vec<String>,
+    cache: ConfigCache,
+    cache_ttl: Duration,
+}
+
+impl ConfigClient {
+    /// Client of the server at `base_url`, e.g. `https://config.example.com`
+    pub fn new(base_url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .build()
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            tokens: None,
+            user: None,
+            gateway_token: None,
+            client_id: None,
+            client_labels: Vec::new(),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_ttl: DEFAULT_CACHE_TTL,
+        })
+    }
+
+    /// Send a bearer token with each request, refreshed when the server rejects it
+    pub fn with_token_provider(mut self, tokens: Arc<dyn TokenProvider>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// Identify as a user directly, for servers reached without an authenticating gateway.
+    /// The server only accepts the identity along with its gateway token, see
+    /// [`with_gateway_token`](Self::with_gateway_token), unless it trusts the header outright
+    pub fn with_user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    /// Vouch for the identity set with [`with_user`](Self::with_user) using the server's
+    /// gateway token
+    pub fn with_gateway_token(mut self, token: &str) -> Self {
+        self.gateway_token = Some(token.to_string());
+        self
+    }
+
+    /// Identify this client instance so gray rollouts consistently include or exclude it
+    pub fn with_client_id(mut self, client_id: &str) -> Self {
+        self.client_id = Some(client_id.to_string());
+        self
+    }
+
+    /// Report labels, such as `region=eu`, that targeted rollouts select clients by
+    pub fn with_client_labels(mut self, labels: &[&str]) -> Self {
+        self.client_labels = labels.iter().map(|label| label.to_string()).collect();
+        self
+    }
+
+    /// How long fetched configs are served from the cache; zero disables caching
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Fetch a config, from the cache while fresh; a cached copy is also returned when the
+    /// server can't be reached
+    pub async fn get(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        let cached = self.cache.read().unwrap().get(id).cloned();
+        if let Some(cached) = &cached {
+            if cached.fetched_at.elapsed() < self.cache_ttl {
+                return Ok((cached.meta.clone(), cached.content.clone()));
+            }
+        }
+
+        let url = format!("{}/api/v1/configs/{}", self.base_url, id);
+        let fetched: Result<(ConfigMeta, ConfigContent)> = match self
+            .send(|| {
+                let mut request = self.http.get(&url);
+                if let Some(client_id) = &self.client_id {
+                    request = request.query(&[("client_id", client_id)]);
+                }
+                if !self.client_labels.is_empty() {
+                    request = request.query(&[("client_labels", self.client_labels.join(","))]);
+                }
+                request
+            })
+            .await
+        {
+            Ok(response) => response
+                .json()
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string())),
+            Err(e) => Err(e),
+        };
+        match fetched {
+            Ok((meta, content)) => {
+                if !self.cache_ttl.is_zero() {
+                    self.cache.write().unwrap().insert(
+                        id.to_string(),
+                        CachedConfig {
+                            meta: meta.clone(),
+                            content: content.clone(),
+                            fetched_at: Instant::now(),
+                        },
+                    );
+                }
+                Ok((meta, content))
+            }
+            Err(config_common::Error::Internal(e)) => match cached {
+                Some(cached) => {
+                    tracing::warn!(
+                        config = %id,
+                        error = %e,
+                        "Serving cached config, server unavailable"
+                    );
+                    Ok((cached.meta, cached.content))
+                }
+                None => Err(config_common::Error::Internal(e)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetch a config and deserialize its content, whatever its format, into `T`
+    pub async fn get_as<T: DeserializeOwned>(&self, id: &str) -> Result<T> {
+        let (_, content) = self.get(id).await?;
+        if content.is_encrypted {
+            return Err(config_common::Error::Validation(format!(
+                "config {} is encrypted",
+                id
+            )));
+        }
+        let document = config_core::format::parse(content.format, &content.content)
+            .map_err(config_common::Error::InvalidContent)?;
+        serde_json::from_value(document).map_err(|e| {
+            config_common::Error::Validation(format!("config {} doesn't fit: {}", id, e))
+        })
+    }
+
+    /// Drop a config from the cache so the next `get` fetches it
+    pub fn invalidate(&self, id: &str) {
+        self.cache.write().unwrap().remove(id);
+    }
+
+    /// Stream change events of a subscription, reconnecting and resuming after failures;
+    /// changed configs are dropped from the cache as their events arrive
+    pub fn watch(&self, subscription: Subscription) -> Watch {
+        Watch::spawn(self.clone(), subscription)
+    }
+
+    pub(crate) fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Headers identifying the caller
+    pub(crate) async fn auth_headers(&self, refresh: bool) -> Result<Vec<(&'static str, String)>> {
+        let mut headers = Vec::new();
+        if let Some(tokens) = &self.tokens {
+            let token = if refresh {
+                tokens.refresh().await?
+            } else {
+                tokens.token().await?
+            };
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+        if let Some(user) = &self.user {
+            headers.push((USER_HEADER, user.clone()));
+        }
+        if let Some(token) = &self.gateway_token {
+            headers.push((GATEWAY_TOKEN_HEADER, token.clone()));
+        }
+        Ok(headers)
+    }
+
+    /// Send a request, retrying once with a refreshed token when it is rejected
+    async fn send<F>(&self, request: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut refresh = false;
+        loop {
+            let mut builder = request();
+            for (name, value) in self.auth_headers(refresh).await? {
+                builder = builder.header(name, value);
+            }
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+            let status = response.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED && self.tokens.is_some() && !refresh {
+                refresh = true;
+                continue;
+            }
+            if status.is_success() {
+                return Ok(response);
+            }
+            let message = response.text().await.unwrap_or_default();
+            return Err(match status {
+                reqwest::StatusCode::UNAUTHORIZED => config_common::Error::Auth(message),
+                reqwest::StatusCode::FORBIDDEN => config_common::Error::Authorization(message),
+                reqwest::StatusCode::NOT_FOUND => config_common::Error::NotFound(message),
+                reqwest::StatusCode::CONFLICT => config_common::Error::AlreadyExists(message),
+                status if status.is_client_error() => config_common::Error::Validation(message),
+                _ => config_common::Error::Internal(message),
+            });
+        }
+    }
+}
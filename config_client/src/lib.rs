@@ -0,0 +1,7 @@
+pub mod auth;
+pub mod client;
+pub mod watch;
+
+pub use auth::{FetchToken, RefreshingToken, StaticToken, TokenProvider};
+pub use client::ConfigClient;
+pub use watch::{Subscription, Watch};
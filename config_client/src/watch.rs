@@ -0,0 +1,171 @@
+use config_common::Result;
+use config_core::PublishedEvent;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::client::ConfigClient;
+
+/// Events buffered for a slow reader before the connection stops reading
+const WATCH_BUFFER: usize = 256;
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// What a watch receives events for; a config matching any criterion is included
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Subscription {
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub config_ids: Vec<String>,
+    /// Label selectors such as `labels contains "feature-flag"`
+    #[serde(default)]
+    pub selectors: Vec<String>,
+    /// Name under which the server stores the position, so restarts of the application
+    /// resume where they stopped as well
+    #[serde(default)]
+    pub subscription_id: Option<String>,
+}
+
+/// Subscribe message of the WebSocket protocol
+#[derive(Serialize)]
+struct SubscribeMessage<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(flatten)]
+    subscription: &'a Subscription,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resume_from: Option<i64>,
+}
+
+/// Change events of a subscription; the connection closes when dropped
+pub struct Watch {
+    events: mpsc::Receiver<PublishedEvent>,
+    task: JoinHandle<()>,
+}
+
+impl Watch {
+    pub(crate) fn spawn(client: ConfigClient, subscription: Subscription) -> Self {
+        let (sender, events) = mpsc::channel(WATCH_BUFFER);
+        let task = tokio::spawn(async move {
+            let mut last_seq = 0;
+            let mut delay = MIN_RECONNECT_DELAY;
+            loop {
+                match run(&client, &subscription, &mut last_seq, &mut delay, &sender).await {
+                    Ok(()) => return,
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %e,
+                            ?delay,
+                            "Config watch disconnected, reconnecting"
+                        );
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    }
+                }
+            }
+        });
+        Self { events, task }
+    }
+
+    /// Next change event; `None` once the watch has stopped
+    pub async fn next(&mut self) -> Option<PublishedEvent> {
+        self.events.recv().await
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Forward events of one connection; `Ok` once nobody reads the watch anymore
+async fn run(
+    client: &ConfigClient,
+    subscription: &Subscription,
+    last_seq: &mut i64,
+    delay: &mut Duration,
+    sender: &mpsc::Sender<PublishedEvent>,
+) -> Result<()> {
+    let url = format!("{}/api/v1/ws", client.base_url()).replacen("http", "ws", 1);
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| config_common::Error::Config(format!("watch url: {}", e)))?;
+    for (name, value) in client.auth_headers(false).await? {
+        let value = HeaderValue::from_str(&value)
+            .map_err(|e| config_common::Error::Config(e.to_string()))?;
+        request.headers_mut().insert(name, value);
+    }
+    let (mut socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| config_common::Error::Internal(format!("watch connect: {}", e)))?;
+
+    // Without a stored position, pick up after the last event seen
+    let resume_from =
+        (subscription.subscription_id.is_none() && *last_seq > 0).then_some(*last_seq);
+    let subscribe = SubscribeMessage {
+        kind: "subscribe",
+        subscription,
+        resume_from,
+    };
+    socket
+        .send(Message::text(serde_json::to_string(&subscribe)?))
+        .await
+        .map_err(|e| config_common::Error::Internal(format!("watch subscribe: {}", e)))?;
+    *delay = MIN_RECONNECT_DELAY;
+
+    while let Some(message) = socket.next().await {
+        let message =
+            message.map_err(|e| config_common::Error::Internal(format!("watch read: {}", e)))?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let message: ServerMessage = match serde_json::from_str(text.as_str()) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!(error = %e, "Ignoring unknown watch message");
+                continue;
+            }
+        };
+        let events = match message {
+            ServerMessage::Event(event) => vec![event],
+            ServerMessage::Batch { events } => events,
+            ServerMessage::Lagged { skipped } => {
+                tracing::warn!(skipped, "Config watch missed events");
+                continue;
+            }
+            ServerMessage::Error { message } => {
+                return Err(config_common::Error::Validation(message));
+            }
+            ServerMessage::Subscribed {} => continue,
+        };
+        for event in events {
+            client.invalidate(&event.event.config_id);
+            *last_seq = (*last_seq).max(event.seq);
+            if sender.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Err(config_common::Error::Internal(
+        "watch connection closed".to_string(),
+    ))
+}
+
+/// Messages the server sends on the watch connection
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Event(PublishedEvent),
+    Batch { events: Vec<PublishedEvent> },
+    Lagged { skipped: u64 },
+    Error { message: String },
+    Subscribed {},
+}
@@ -1,7 +1,12 @@
 use actix_web::ResponseError;
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod webhook;
+
+pub use webhook::Webhook;
+
 /// Common result type used throughout the project
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -26,6 +31,9 @@ pub enum Error {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Invalid content: {0}")]
+    InvalidContent(ContentError),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
@@ -48,12 +56,66 @@ impl ResponseError for Error {
             Error::Auth(_) => actix_web::http::StatusCode::UNAUTHORIZED,
             Error::Authorization(_) => actix_web::http::StatusCode::FORBIDDEN,
             Error::Validation(_) => actix_web::http::StatusCode::BAD_REQUEST,
+            Error::InvalidContent(_) => actix_web::http::StatusCode::UNPROCESSABLE_ENTITY,
             Error::NotFound(_) => actix_web::http::StatusCode::NOT_FOUND,
             Error::AlreadyExists(_) => actix_web::http::StatusCode::CONFLICT,
             Error::Internal(_) => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
             Error::PrometheusError(_) => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        match self {
+            Error::InvalidContent(err) => {
+                actix_web::HttpResponse::build(self.status_code()).json(err)
+            }
+            _ => actix_web::HttpResponse::build(self.status_code()).body(self.to_string()),
+        }
+    }
+}
+
+/// Where and why configuration content failed to parse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentError {
+    pub format: ConfigFormat,
+    pub message: String,
+    /// 1-based line of the error, when the parser reports one
+    pub line: Option<usize>,
+    /// 1-based column of the error, when the parser reports one
+    pub column: Option<usize>,
+    /// Schema violations as `<instance path>: <message>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<String>,
+}
+
+impl ContentError {
+    pub fn new(format: ConfigFormat, message: String) -> Self {
+        Self {
+            format,
+            message,
+            line: None,
+            column: None,
+            violations: Vec::new(),
+        }
+    }
+
+    /// Attach the position of the error
+    pub fn at(mut self, line: Option<usize>, column: Option<usize>) -> Self {
+        self.line = line;
+        self.column = column;
+        self
+    }
+}
+
+impl std::fmt::Display for ContentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "malformed {}: {}", self.format.as_str(), self.message)?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column),
+            (Some(line), None) => write!(f, " at line {}", line),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl From<serde_json::Error> for Error {
@@ -89,16 +151,43 @@ pub struct ConfigMeta {
     pub updated_at: i64,
     pub created_by: String,
     pub updated_by: String,
+    /// Users or teams that may always update and delete this configuration
+    #[serde(default)]
+    pub owners: Vec<String>,
+    /// Free-form labels watchers and subscriptions can select configurations by
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 /// Configuration content with type information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigContent {
     pub format: ConfigFormat,
+    /// Kept as text since it is parsed, validated, rendered and encrypted as such; the
+    /// protobuf `bytes` fields share its buffer through `from_bytes` and `into_bytes`
     pub content: String,
     pub is_encrypted: bool,
 }
 
+impl ConfigContent {
+    /// Content received as bytes, such as a protobuf `bytes` field. The buffer becomes the
+    /// string in place when nothing else shares it; only the UTF-8 check is unavoidable.
+    pub fn from_bytes(format: ConfigFormat, content: Bytes, is_encrypted: bool) -> Result<Self> {
+        let content = String::from_utf8(Vec::from(content))
+            .map_err(|e| Error::Validation(format!("content is not UTF-8: {}", e)))?;
+        Ok(Self {
+            format,
+            content,
+            is_encrypted,
+        })
+    }
+
+    /// Content as bytes for the wire, handing over the string's buffer without copying
+    pub fn into_bytes(self) -> Bytes {
+        Bytes::from(self.content)
+    }
+}
+
 /// Supported configuration formats
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ConfigFormat {
@@ -108,6 +197,35 @@ pub enum ConfigFormat {
     Toml,
 }
 
+impl ConfigFormat {
+    /// Format name as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Properties => "properties",
+            ConfigFormat::Json => "json",
+            ConfigFormat::Toml => "toml",
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "yaml" => Ok(ConfigFormat::Yaml),
+            "properties" => Ok(ConfigFormat::Properties),
+            "json" => Ok(ConfigFormat::Json),
+            "toml" => Ok(ConfigFormat::Toml),
+            other => Err(Error::Validation(format!(
+                "unknown config format: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// Audit log entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLog {
@@ -135,22 +253,82 @@ pub enum PolicyEffect {
     Deny,
 }
 
+impl PolicyEffect {
+    /// Effect name as used by the policy engine
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyEffect::Allow => "allow",
+            PolicyEffect::Deny => "deny",
+        }
+    }
+}
+
+impl std::str::FromStr for PolicyEffect {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "allow" => Ok(PolicyEffect::Allow),
+            "deny" => Ok(PolicyEffect::Deny),
+            other => Err(Error::Validation(format!(
+                "unknown policy effect: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// Configuration change event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigEvent {
     pub config_id: String,
+    pub namespace: String,
+    /// Empty for events stored before environments were recorded
+    #[serde(default)]
+    pub environment: String,
+    /// Labels of the configuration when the event happened
+    #[serde(default)]
+    pub labels: Vec<String>,
     pub event_type: ConfigEventType,
     pub version: String,
     pub timestamp: i64,
     pub user: String,
+    /// What happened, for events not about a change to one config such as audit anomalies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 /// Configuration event types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConfigEventType {
     Created,
     Updated,
     Deleted,
     Released,
     Rolled,
+    /// A secret is due for rotation soon
+    SecretExpiring,
+    /// A secret is past its expiry date
+    SecretExpired,
+    /// A change to a protected namespace awaits its reviewers
+    ApprovalRequested,
+    /// An audit anomaly rule raised an alert
+    AuditAnomaly,
+}
+
+impl ConfigEventType {
+    /// Event type name used in subjects and topics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigEventType::Created => "created",
+            ConfigEventType::Updated => "updated",
+            ConfigEventType::Deleted => "deleted",
+            ConfigEventType::Released => "released",
+            ConfigEventType::Rolled => "rolled",
+            ConfigEventType::SecretExpiring => "secret_expiring",
+            ConfigEventType::SecretExpired => "secret_expired",
+            ConfigEventType::ApprovalRequested => "approval_requested",
+            ConfigEventType::AuditAnomaly => "audit_anomaly",
+        }
+    }
 }
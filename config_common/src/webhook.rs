@@ -0,0 +1,52 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// HTTP client for webhooks, giving up on requests that outlast the timeout
+pub fn client(timeout: Duration) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| Error::Internal(e.to_string()))
+}
+
+/// Send a request with a JSON body, failing unless the endpoint answers with a success status
+pub async fn post_json<T: Serialize + ?Sized>(
+    request: reqwest::RequestBuilder,
+    body: &T,
+) -> Result<()> {
+    request
+        .json(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| Error::Internal(e.to_string()))?;
+    Ok(())
+}
+
+/// Endpoint receiving JSON posts with a fixed set of headers
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    headers: HashMap<String, String>,
+}
+
+impl Webhook {
+    pub fn new(url: &str, headers: HashMap<String, String>, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            client: client(timeout)?,
+            url: url.to_string(),
+            headers,
+        })
+    }
+
+    pub async fn post<T: Serialize + ?Sized>(&self, body: &T) -> Result<()> {
+        let mut request = self.client.post(&self.url);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        post_json(request, body).await
+    }
+}
@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+use crate::{StagedChange, ValidationContext, WriteGuard};
+
+/// Namespaces whose content updates wait for reviewers before being applied
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    #[serde(default)]
+    pub namespaces: Vec<ProtectedNamespace>,
+}
+
+impl ApprovalPolicy {
+    pub fn protection(&self, namespace: &str) -> Option<&ProtectedNamespace> {
+        self.namespaces.iter().find(|p| p.namespace == namespace)
+    }
+}
+
+/// Reviewers of a protected namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtectedNamespace {
+    pub namespace: String,
+    /// Users or roles, such as `team:platform`, allowed to review changes
+    pub reviewers: Vec<String>,
+    #[serde(default = "default_required_approvals")]
+    pub required_approvals: usize,
+}
+
+fn default_required_approvals() -> usize {
+    1
+}
+
+tokio::task_local! {
+    static APPROVED: ();
+}
+
+/// Run `f` as the application of an approved request, letting its writes through
+/// [`ApprovalGuard`]; only the approval manager applying a change should call this
+pub async fn with_approval<F: Future>(f: F) -> F::Output {
+    APPROVED.scope((), f).await
+}
+
+/// Rejects content updates of existing configs of protected namespaces, other than those
+/// applied under [`with_approval`]. Creating a config isn't held, as approval requests carry
+/// updates of existing configs, nor are metadata edits, rollbacks and deletes, which they
+/// can't carry.
+pub struct ApprovalGuard {
+    policy: ApprovalPolicy,
+}
+
+impl ApprovalGuard {
+    pub fn new(policy: ApprovalPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+#[async_trait]
+impl WriteGuard for ApprovalGuard {
+    async fn check_write(&self, ctx: &ValidationContext) -> Result<()> {
+        if ctx.config_id.is_none()
+            || !ctx.changes_content
+            || self.policy.protection(&ctx.namespace).is_none()
+            || APPROVED.try_with(|_| ()).is_ok()
+        {
+            return Ok(());
+        }
+        Err(config_common::Error::Validation(format!(
+            "changes to configs of namespace {} need an approval request",
+            ctx.namespace
+        )))
+    }
+}
+
+/// Update of a config in a protected namespace waiting for approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub namespace: String,
+    /// Sealed edit, applied as is once approved
+    pub change: StagedChange,
+    pub change_reason: Option<String>,
+    pub status: ApprovalStatus,
+    pub requested_by: String,
+    pub requested_at: i64,
+    pub reviewers: Vec<String>,
+    pub required_approvals: usize,
+    pub approvals: Vec<String>,
+    pub reviewed_at: Option<i64>,
+    pub rejected_by: Option<String>,
+}
+
+/// Approval request lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalStatus {
+    Pending,
+    Applied,
+    Rejected,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Applied => "applied",
+            ApprovalStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for ApprovalStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(ApprovalStatus::Pending),
+            "applied" => Ok(ApprovalStatus::Applied),
+            "rejected" => Ok(ApprovalStatus::Rejected),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown approval status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Manager for approval requests
+#[async_trait]
+pub trait ApprovalManager: Send + Sync {
+    /// Hold an update of a config in a protected namespace until it is approved
+    async fn request_change(
+        &self,
+        config_id: &str,
+        description: Option<&str>,
+        content: ConfigContent,
+        change_reason: Option<&str>,
+        requested_by: &str,
+    ) -> Result<ApprovalRequest>;
+
+    async fn get_approval(&self, id: &str) -> Result<ApprovalRequest>;
+
+    /// List approval requests, newest first, optionally only those in the given status
+    async fn list_approvals(&self, status: Option<ApprovalStatus>) -> Result<Vec<ApprovalRequest>>;
+
+    /// Record an approval, applying the change once it has enough
+    async fn approve(&self, id: &str, reviewer: &str) -> Result<ApprovalRequest>;
+
+    async fn reject(&self, id: &str, reviewer: &str) -> Result<ApprovalRequest>;
+}
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+
+/// Layout version of backups written by this server
+pub const BACKUP_FORMAT: u32 = 1;
+
+/// Path of the manifest inside a backup archive
+pub const BACKUP_MANIFEST_PATH: &str = "manifest.json";
+
+/// `manifest.json` of a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: u32,
+    pub created_at: i64,
+    pub created_by: String,
+    /// Tables in the snapshot, each stored as `tables/<name>.jsonl`
+    pub tables: Vec<BackupTable>,
+}
+
+/// Table of a backup, one JSON object per row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTable {
+    pub name: String,
+    pub columns: Vec<BackupColumn>,
+    pub rows: u64,
+    /// Hex SHA-256 of the table's JSONL file
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupColumn {
+    pub name: String,
+    /// Postgres type, e.g. `text` or `jsonb`
+    pub data_type: String,
+}
+
+impl BackupTable {
+    /// Path of the table's rows inside a backup archive
+    pub fn path(&self) -> String {
+        format!("tables/{}.jsonl", self.name)
+    }
+}
+
+/// Backup job lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupJobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of a backup taken by this server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupJob {
+    pub id: String,
+    pub status: BackupJobStatus,
+    /// File path or `s3://` URL the backup is written to
+    pub location: String,
+    pub started_by: String,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    /// Tables in the snapshot, once listed
+    pub tables_total: usize,
+    pub tables_done: usize,
+    pub rows: u64,
+    /// Uncompressed bytes written so far
+    pub bytes: u64,
+    pub error: Option<String>,
+}
+
+/// Backup found at the configured location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub location: String,
+    pub size: u64,
+    pub created_at: i64,
+}
+
+/// Outcome of restoring a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub backup: String,
+    pub backup_created_at: i64,
+    /// Time configs, their versions and the event outbox were restored to
+    pub restored_to: i64,
+    pub restored_by: String,
+    pub tables: usize,
+    pub rows: u64,
+    /// Versions and events newer than the backup replayed from the database being replaced
+    pub replayed_versions: u64,
+    pub replayed_events: u64,
+    /// Versions and events of the backup newer than the restore time
+    pub discarded_versions: u64,
+    pub discarded_events: u64,
+    /// Configs set to the version they had at the restore time
+    pub configs_reset: u64,
+}
+
+/// One check of a backup verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationCheck {
+    /// `format`, `schema`, `secrets`, or `checksum:<table>`
+    pub name: String,
+    pub passed: bool,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Whether a backup could be restored and read by this server, checked without restoring it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub backup: String,
+    pub backup_created_at: i64,
+    pub format: u32,
+    /// Latest schema migration recorded in the backup
+    pub schema_version: Option<i64>,
+    pub verified_at: i64,
+    pub verified_by: String,
+    /// Whether every check passed
+    pub passed: bool,
+    pub checks: Vec<VerificationCheck>,
+    /// Encrypted contents sampled, and how many of them the current keys decrypted
+    pub secrets_sampled: usize,
+    pub secrets_decrypted: usize,
+}
+
+/// Verification result with a signature over the JSON encoding of `verification`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedVerification {
+    pub verification: BackupVerification,
+    /// Always `hmac-sha256`, keyed by the configured signing key
+    pub algorithm: String,
+    /// Hex signature
+    pub signature: String,
+}
+
+/// Takes and lists consistent snapshots of the database
+#[async_trait]
+pub trait BackupManager: Send + Sync {
+    /// Start a backup in the background; its job reports progress
+    async fn start_backup(&self, started_by: &str) -> Result<BackupJob>;
+
+    async fn get_backup_job(&self, id: &str) -> Result<BackupJob>;
+
+    /// Backup jobs started since this server started, newest first
+    async fn list_backup_jobs(&self) -> Result<Vec<BackupJob>>;
+
+    /// Backups at the configured location, newest first
+    async fn list_backups(&self) -> Result<Vec<BackupInfo>>;
+
+    /// Replace the tables of a backup with its contents, then replay or discard config
+    /// versions and events so configs are as they were at `until`, the backup's time when
+    /// unset. Other tables are restored as of the backup.
+    async fn restore_backup(
+        &self,
+        name: &str,
+        until: Option<i64>,
+        restored_by: &str,
+    ) -> Result<RestoreReport>;
+
+    /// Check a backup's format, schema version, table checksums and that a sample of its
+    /// encrypted content decrypts with the current keys, without restoring it
+    async fn verify_backup(&self, name: &str, verified_by: &str) -> Result<SignedVerification>;
+}
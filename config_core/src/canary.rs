@@ -0,0 +1,253 @@
+use config_common::{ConfigEvent, ConfigEventType, ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{ConfigVersionControl, EventBus, Release, ReleaseManager, ReleaseStatus};
+
+/// User recorded on rollbacks triggered by failed health reports
+pub const CANARY_USER: &str = "canary";
+
+/// Release identified by config ID and version
+type ReleaseKey = (String, String);
+
+/// When failed health reports roll a released version back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryConfig {
+    /// Automatic rollback is disabled when false; reports are still counted
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Failed reports within the window that trigger a rollback
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: usize,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: i64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_failure_threshold() -> usize {
+    3
+}
+
+fn default_window_secs() -> i64 {
+    300
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            failure_threshold: default_failure_threshold(),
+            window_secs: default_window_secs(),
+        }
+    }
+}
+
+/// Health report from a deployment tool for a released version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    /// Instance or deployment that observed the result
+    pub source: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Outcome of recording a health report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryStatus {
+    pub config_id: String,
+    pub version: String,
+    /// Failed reports within the window
+    pub failures: usize,
+    pub failure_threshold: usize,
+    /// Version restored when this report triggered a rollback
+    pub rolled_back_to: Option<String>,
+}
+
+/// Tracks health reports per released version and rolls back failing releases
+pub struct CanaryMonitor {
+    version_control: Arc<dyn ConfigVersionControl>,
+    releases: Arc<dyn ReleaseManager>,
+    events: Arc<EventBus>,
+    config: CanaryConfig,
+    failures: Mutex<HashMap<ReleaseKey, VecDeque<i64>>>,
+    rolled_back: Mutex<HashSet<ReleaseKey>>,
+}
+
+impl CanaryMonitor {
+    pub fn new(
+        version_control: Arc<dyn ConfigVersionControl>,
+        releases: Arc<dyn ReleaseManager>,
+        events: Arc<EventBus>,
+        config: CanaryConfig,
+    ) -> Self {
+        Self {
+            version_control,
+            releases,
+            events,
+            config,
+            failures: Mutex::new(HashMap::new()),
+            rolled_back: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Record a report for `version`, which must be the version clients of the config are
+    /// served, given by `served`
+    pub async fn report(
+        &self,
+        served: &ConfigMeta,
+        version: &str,
+        report: &HealthReport,
+    ) -> Result<CanaryStatus> {
+        let id = served.id.as_str();
+        if version != served.version {
+            return Err(config_common::Error::Validation(format!(
+                "version {} is not the released version of {} (current {})",
+                version, id, served.version
+            )));
+        }
+
+        let key = (id.to_string(), version.to_string());
+        let failures = self.record_failure(&key, report.healthy);
+        let mut status = CanaryStatus {
+            config_id: id.to_string(),
+            version: version.to_string(),
+            failures,
+            failure_threshold: self.config.failure_threshold,
+            rolled_back_to: None,
+        };
+
+        if !self.config.enabled || failures < self.config.failure_threshold {
+            return Ok(status);
+        }
+        // Only the first report crossing the threshold rolls back
+        if !self.lock_rolled_back().insert(key.clone()) {
+            return Ok(status);
+        }
+
+        let reason = format!(
+            "automatic rollback: {} failed health reports within {}s",
+            failures, self.config.window_secs
+        );
+        let restored = match self.roll_back(served, &reason).await {
+            Ok(Some(restored)) => restored,
+            Ok(None) => {
+                tracing::warn!(
+                    "Release {} of {} is failing but has no previous release to roll back to",
+                    version,
+                    id
+                );
+                return Ok(status);
+            }
+            Err(e) => {
+                self.lock_rolled_back().remove(&key);
+                return Err(e);
+            }
+        };
+
+        tracing::warn!(
+            "Rolled back {} from {} to {}: {}",
+            id,
+            version,
+            restored,
+            reason
+        );
+
+        self.lock_failures().remove(&key);
+        status.rolled_back_to = Some(restored);
+        Ok(status)
+    }
+
+    /// Go back to the release before the served one, returning the version now served. A
+    /// released config has its release aborted, as the abort endpoint does; a config never
+    /// released serves its latest version, so it is rolled back to the version before.
+    async fn roll_back(&self, served: &ConfigMeta, reason: &str) -> Result<Option<String>> {
+        let id = served.id.as_str();
+        let Some(release) = self.served_release(id).await? else {
+            let Some(previous) = self.previous_version(id, &served.version).await? else {
+                return Ok(None);
+            };
+            // Subscribers hear of it from the applied rollback
+            self.version_control
+                .rollback(id, &previous, Some(reason), CANARY_USER)
+                .await?;
+            return Ok(Some(previous));
+        };
+        let Some(previous) = release.previous_version else {
+            return Ok(None);
+        };
+
+        self.releases
+            .abort_release(&release.id, CANARY_USER)
+            .await?;
+        let restored = self
+            .releases
+            .released_version(id)
+            .await?
+            .unwrap_or(previous);
+        let event = ConfigEvent {
+            config_id: id.to_string(),
+            namespace: served.namespace.clone(),
+            environment: served.environment.clone(),
+            labels: served.labels.clone(),
+            event_type: ConfigEventType::Released,
+            version: restored.clone(),
+            timestamp: chrono::Utc::now().timestamp(),
+            user: CANARY_USER.to_string(),
+            message: None,
+        };
+        // The abort already happened; a lost event only delays clients noticing it
+        if let Err(e) = self.events.publish(event).await {
+            tracing::error!(error = %e, config_id = %id, "Failed to publish rollback event");
+        }
+        Ok(Some(restored))
+    }
+
+    /// Published release clients of a config are served, if it was ever released
+    async fn served_release(&self, id: &str) -> Result<Option<Release>> {
+        let published = self
+            .releases
+            .list_releases(Some(id), Some(ReleaseStatus::Published))
+            .await?;
+        Ok(published.into_iter().max_by_key(|r| r.published_at))
+    }
+
+    /// Failed reports for a release within the window, counting this one
+    fn record_failure(&self, key: &ReleaseKey, healthy: bool) -> usize {
+        let now = chrono::Utc::now().timestamp();
+        let mut failures = self.lock_failures();
+        let reports = failures.entry(key.clone()).or_default();
+        while reports
+            .front()
+            .is_some_and(|at| now - at > self.config.window_secs)
+        {
+            reports.pop_front();
+        }
+        if !healthy {
+            reports.push_back(now);
+        }
+        reports.len()
+    }
+
+    /// Version saved before `version`, if any
+    async fn previous_version(&self, id: &str, version: &str) -> Result<Option<String>> {
+        // History is newest first
+        let history = self.version_control.get_version_history(id).await?;
+        Ok(history
+            .iter()
+            .skip_while(|v| v.version != version)
+            .nth(1)
+            .map(|v| v.version.clone()))
+    }
+
+    fn lock_failures(&self) -> MutexGuard<'_, HashMap<ReleaseKey, VecDeque<i64>>> {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_rolled_back(&self) -> MutexGuard<'_, HashSet<ReleaseKey>> {
+        self.rolled_back.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
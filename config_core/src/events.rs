@@ -0,0 +1,264 @@
+use async_trait::async_trait;
+use config_common::{ConfigEvent, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Events read from the outbox at a time by catching-up consumers
+const OUTBOX_BATCH_SIZE: i64 = 100;
+
+/// Delay before a consumer retries an event it failed to handle
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Sizing and retention of the event bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventBusConfig {
+    /// Events buffered per live subscriber before it starts lagging
+    #[serde(default = "default_channel_capacity")]
+    pub channel_capacity: usize,
+    /// Hours events stay in the outbox for consumers to catch up
+    #[serde(default = "default_retention_hours")]
+    pub retention_hours: u64,
+}
+
+fn default_channel_capacity() -> usize {
+    1024
+}
+
+fn default_retention_hours() -> u64 {
+    72
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: default_channel_capacity(),
+            retention_hours: default_retention_hours(),
+        }
+    }
+}
+
+/// Event with its position in the outbox; zero when published without one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedEvent {
+    pub seq: i64,
+    #[serde(flatten)]
+    pub event: ConfigEvent,
+}
+
+/// Selects past events from the outbox
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Only events after this sequence number
+    pub after: i64,
+    /// Only events published at or after this time
+    pub since: Option<i64>,
+    pub namespace: Option<String>,
+}
+
+/// Durable log of published events with the position of each consumer
+#[async_trait]
+pub trait EventOutbox: Send + Sync {
+    /// Store an event and return its sequence number
+    async fn append(&self, event: &ConfigEvent) -> Result<i64>;
+
+    /// Events after a sequence number, oldest first
+    async fn read_after(&self, seq: i64, limit: i64) -> Result<Vec<PublishedEvent>>;
+
+    /// Events matching a filter, oldest first
+    async fn list_events(&self, filter: &EventFilter, limit: i64) -> Result<Vec<PublishedEvent>>;
+
+    /// Sequence number of the newest event, zero when there is none
+    async fn latest_seq(&self) -> Result<i64>;
+
+    /// Last sequence number a consumer handled, zero when it never ran
+    async fn get_offset(&self, consumer: &str) -> Result<i64>;
+
+    async fn set_offset(&self, consumer: &str, seq: i64) -> Result<()>;
+
+    /// Delete events published before the given time; returns how many were deleted
+    async fn purge(&self, before: i64) -> Result<u64>;
+}
+
+/// Receiver of every event on the bus, delivered at least once when the bus has an outbox
+#[async_trait]
+pub trait EventConsumer: Send + Sync {
+    /// Stable name under which the consumer's position is stored
+    fn name(&self) -> &str;
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()>;
+}
+
+/// Fans configuration events out to live subscribers and durable consumers
+pub struct EventBus {
+    config: EventBusConfig,
+    outbox: Option<Arc<dyn EventOutbox>>,
+    sender: broadcast::Sender<PublishedEvent>,
+}
+
+impl EventBus {
+    pub fn new(config: EventBusConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity.max(1));
+        Self {
+            config,
+            outbox: None,
+            sender,
+        }
+    }
+
+    /// Store every event before it is broadcast so consumers can catch up after restarts
+    pub fn with_outbox(mut self, outbox: Arc<dyn EventOutbox>) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
+    /// Store the event in the outbox, then send it to live subscribers
+    pub async fn publish(&self, event: ConfigEvent) -> Result<()> {
+        let seq = match &self.outbox {
+            Some(outbox) => outbox.append(&event).await?,
+            None => 0,
+        };
+        // Nobody may be listening; durable consumers read the outbox anyway
+        let _ = self.sender.send(PublishedEvent { seq, event });
+        Ok(())
+    }
+
+    /// Receive events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<PublishedEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Outbox of past events, unset when events are not stored
+    pub fn outbox(&self) -> Option<&Arc<dyn EventOutbox>> {
+        self.outbox.as_ref()
+    }
+
+    /// Publish every event of another channel, such as canary rollbacks
+    pub fn forward(
+        self: &Arc<Self>,
+        mut events: broadcast::Receiver<ConfigEvent>,
+    ) -> JoinHandle<()> {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = bus.publish(event).await {
+                            tracing::error!(error = %e, "Failed to publish forwarded event");
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Event forwarder lagged behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Deliver events to a consumer until the task is aborted; with an outbox the consumer
+    /// resumes after the last event it handled, retrying failures until they succeed
+    pub fn spawn_consumer(self: &Arc<Self>, consumer: Arc<dyn EventConsumer>) -> JoinHandle<()> {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            match bus.outbox.clone() {
+                Some(outbox) => bus.consume_outbox(outbox, consumer).await,
+                None => bus.consume_live(consumer).await,
+            }
+        })
+    }
+
+    async fn consume_outbox(&self, outbox: Arc<dyn EventOutbox>, consumer: Arc<dyn EventConsumer>) {
+        // Subscribe first so nothing published while catching up goes unnoticed
+        let mut live = self.subscribe();
+        let mut offset = loop {
+            match outbox.get_offset(consumer.name()).await {
+                Ok(offset) => break offset,
+                Err(e) => {
+                    tracing::error!(
+                        consumer = consumer.name(),
+                        error = %e,
+                        "Reading offset failed"
+                    );
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+        };
+
+        loop {
+            let batch = match outbox.read_after(offset, OUTBOX_BATCH_SIZE).await {
+                Ok(batch) => batch,
+                Err(e) => {
+                    tracing::error!(
+                        consumer = consumer.name(),
+                        error = %e,
+                        "Reading outbox failed"
+                    );
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            if batch.is_empty() {
+                // Any wake-up, lagging included, means there is more in the outbox
+                if let Err(broadcast::error::RecvError::Closed) = live.recv().await {
+                    return;
+                }
+                continue;
+            }
+
+            for event in batch {
+                deliver(consumer.as_ref(), &event).await;
+                offset = event.seq;
+                if let Err(e) = outbox.set_offset(consumer.name(), offset).await {
+                    tracing::error!(consumer = consumer.name(), error = %e, "Saving offset failed");
+                }
+            }
+        }
+    }
+
+    async fn consume_live(&self, consumer: Arc<dyn EventConsumer>) {
+        let mut live = self.subscribe();
+        loop {
+            match live.recv().await {
+                Ok(event) => deliver(consumer.as_ref(), &event).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(consumer = consumer.name(), skipped, "Event consumer lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Purge outbox events past the retention period periodically
+    pub fn spawn_purge(self: &Arc<Self>, interval: Duration) -> Option<JoinHandle<()>> {
+        let outbox = self.outbox.clone()?;
+        let retention = (self.config.retention_hours * 60 * 60) as i64;
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let before = chrono::Utc::now().timestamp() - retention;
+                match outbox.purge(before).await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!(purged, "Purged old events from the outbox"),
+                    Err(e) => tracing::error!(error = %e, "Event outbox purge failed"),
+                }
+            }
+        }))
+    }
+}
+
+/// Hand an event to a consumer, retrying until it succeeds
+async fn deliver(consumer: &dyn EventConsumer, event: &PublishedEvent) {
+    while let Err(e) = consumer.handle(event).await {
+        tracing::warn!(
+            consumer = consumer.name(),
+            seq = event.seq,
+            error = %e,
+            "Event delivery failed, retrying"
+        );
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use config_common::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::releases::in_rollout;
+
+/// Boolean switch evaluated per client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub key: String,
+    /// Namespace owning the flag, whose policies govern changes to it
+    pub namespace: String,
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub settings: FlagSettings,
+    pub created_at: i64,
+    pub created_by: String,
+    pub updated_at: i64,
+    pub updated_by: String,
+}
+
+/// Typed rule of a flag; deny entries win over allow entries, which win over the percentage
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagSettings {
+    /// Off for every client when false
+    pub enabled: bool,
+    /// Share of clients, from 0 to 100, the flag is on for; every client when unset
+    #[serde(default)]
+    pub percentage: Option<u8>,
+    /// Client IDs or `attribute=value` pairs the flag is always on for
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Client IDs or `attribute=value` pairs the flag is always off for
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl FlagSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.percentage.is_some_and(|percentage| percentage > 100) {
+            return Err(Error::Validation(
+                "flag percentage must be between 0 and 100".to_string(),
+            ));
+        }
+        if let Some(entry) = self
+            .allow
+            .iter()
+            .chain(&self.deny)
+            .find(|e| e.trim().is_empty())
+        {
+            return Err(Error::Validation(format!(
+                "empty allow or deny entry '{}'",
+                entry
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the flag is on for a client of flag `key`, and why
+    pub fn evaluate(&self, key: &str, client: &FlagClient) -> FlagEvaluation {
+        let (enabled, reason) = if !self.enabled {
+            (false, FlagReason::Disabled)
+        } else if self.deny.iter().any(|entry| client.matches(entry)) {
+            (false, FlagReason::Denied)
+        } else if self.allow.iter().any(|entry| client.matches(entry)) {
+            (true, FlagReason::Allowed)
+        } else {
+            match self.percentage {
+                Some(percentage) => (
+                    client
+                        .id
+                        .as_deref()
+                        .is_some_and(|id| in_rollout(key, id, percentage)),
+                    FlagReason::Percentage,
+                ),
+                None => (true, FlagReason::Enabled),
+            }
+        };
+        FlagEvaluation {
+            key: key.to_string(),
+            enabled,
+            reason,
+        }
+    }
+}
+
+/// Client a flag is evaluated for
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlagClient {
+    /// Stable identity placing the client in or out of the flag's percentage
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Attributes such as `region` or `plan`
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl FlagClient {
+    /// Whether an allow or deny entry names the client's ID or one of its attributes
+    fn matches(&self, entry: &str) -> bool {
+        match entry.split_once('=') {
+            Some((name, value)) => {
+                self.attributes.get(name.trim()).map(String::as_str) == Some(value.trim())
+            }
+            None => self.id.as_deref() == Some(entry.trim()),
+        }
+    }
+}
+
+/// Outcome of evaluating a flag for a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagEvaluation {
+    pub key: String,
+    pub enabled: bool,
+    pub reason: FlagReason,
+}
+
+/// Part of a flag's settings deciding an evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlagReason {
+    Disabled,
+    Denied,
+    Allowed,
+    Percentage,
+    /// On for every client
+    Enabled,
+}
+
+/// Check a flag key is made of letters, digits, `.`, `_` and `-`
+pub fn check_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !valid {
+        return Err(Error::Validation(format!(
+            "flag key '{}' may only contain letters, digits, '.', '_' and '-'",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Manager for feature flags
+#[async_trait]
+pub trait FlagManager: Send + Sync {
+    async fn create_flag(
+        &self,
+        key: &str,
+        namespace: &str,
+        description: Option<&str>,
+        settings: FlagSettings,
+        created_by: &str,
+    ) -> Result<FeatureFlag>;
+
+    async fn get_flag(&self, key: &str) -> Result<FeatureFlag>;
+
+    /// Flags of a namespace, or of every namespace
+    async fn list_flags(&self, namespace: Option<&str>) -> Result<Vec<FeatureFlag>>;
+
+    async fn update_flag(
+        &self,
+        key: &str,
+        description: Option<&str>,
+        settings: FlagSettings,
+        updated_by: &str,
+    ) -> Result<FeatureFlag>;
+
+    async fn delete_flag(&self, key: &str) -> Result<bool>;
+}
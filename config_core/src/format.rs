@@ -0,0 +1,384 @@
+use config_common::{ConfigContent, ConfigFormat, ContentError};
+
+/// Parse `text` as `format` into a JSON value, reporting where parsing failed
+pub fn parse(format: ConfigFormat, text: &str) -> Result<serde_json::Value, ContentError> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(text).map_err(|e| {
+            ContentError::new(format, without_position(&e.to_string()))
+                .at(Some(e.line()), Some(e.column()))
+        }),
+        ConfigFormat::Yaml => {
+            let value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| {
+                let location = e.location();
+                ContentError::new(format, without_position(&e.to_string())).at(
+                    location.as_ref().map(|l| l.line()),
+                    location.as_ref().map(|l| l.column()),
+                )
+            })?;
+            serde_json::to_value(value)
+                .map_err(|e| ContentError::new(format, format!("unsupported value: {}", e)))
+        }
+        ConfigFormat::Toml => {
+            let table = text.parse::<toml::Table>().map_err(|e| {
+                let (line, column) = match e.span() {
+                    Some(span) => {
+                        let (line, column) = line_column(text, span.start);
+                        (Some(line), Some(column))
+                    }
+                    None => (None, None),
+                };
+                ContentError::new(format, e.message().to_string()).at(line, column)
+            })?;
+            serde_json::to_value(table)
+                .map_err(|e| ContentError::new(format, format!("unsupported value: {}", e)))
+        }
+        ConfigFormat::Properties => {
+            let entries = parse_properties(text)?;
+            Ok(serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, serde_json::Value::String(value)))
+                    .collect(),
+            ))
+        }
+    }
+}
+
+/// Re-encode content in another format, failing where the target can't represent it
+pub fn convert(content: &ConfigContent, to: ConfigFormat) -> config_common::Result<ConfigContent> {
+    if content.is_encrypted {
+        return Err(config_common::Error::Validation(
+            "encrypted content can't be converted".to_string(),
+        ));
+    }
+    if content.format.as_str() == to.as_str() {
+        return Ok(content.clone());
+    }
+
+    let value =
+        parse(content.format, &content.content).map_err(config_common::Error::InvalidContent)?;
+    let text = render(to, &value).map_err(|e| {
+        config_common::Error::Validation(format!(
+            "{} content can't be represented as {}: {}",
+            content.format.as_str(),
+            to.as_str(),
+            e
+        ))
+    })?;
+
+    Ok(ConfigContent {
+        format: to,
+        content: text,
+        is_encrypted: false,
+    })
+}
+
+/// Encode a document as `format`
+pub fn render(format: ConfigFormat, value: &serde_json::Value) -> Result<String, String> {
+    match format {
+        ConfigFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        ConfigFormat::Toml => {
+            if !value.is_object() {
+                return Err("the document is not a table".to_string());
+            }
+            toml::to_string_pretty(value).map_err(|e| e.to_string())
+        }
+        ConfigFormat::Properties => render_properties(value),
+    }
+}
+
+/// How a PATCH body modifies a document
+#[derive(Debug, Clone)]
+pub enum ContentPatch {
+    /// RFC 6902 list of operations
+    Json(json_patch::Patch),
+    /// RFC 7396 partial document; `null` removes a key
+    Merge(serde_json::Value),
+}
+
+/// Apply a patch to structured content, keeping its format
+pub fn apply_patch(
+    content: &ConfigContent,
+    patch: &ContentPatch,
+) -> config_common::Result<ConfigContent> {
+    if content.is_encrypted {
+        return Err(config_common::Error::Validation(
+            "encrypted content can't be patched".to_string(),
+        ));
+    }
+    let mut value =
+        parse(content.format, &content.content).map_err(config_common::Error::InvalidContent)?;
+    match patch {
+        ContentPatch::Json(patch) => json_patch::patch(&mut value, patch)
+            .map_err(|e| config_common::Error::Validation(format!("patch failed: {}", e)))?,
+        ContentPatch::Merge(patch) => json_patch::merge(&mut value, patch),
+    }
+
+    let text = render(content.format, &value).map_err(|e| {
+        config_common::Error::Validation(format!(
+            "patched content can't be represented as {}: {}",
+            content.format.as_str(),
+            e
+        ))
+    })?;
+    Ok(ConfigContent {
+        format: content.format,
+        content: text,
+        is_encrypted: false,
+    })
+}
+
+/// Render content as an env file, one `KEY=VALUE` line per flattened key
+pub fn render_env(content: &ConfigContent) -> config_common::Result<String> {
+    if content.is_encrypted {
+        return Err(config_common::Error::Validation(
+            "encrypted content can't be converted".to_string(),
+        ));
+    }
+    let value =
+        parse(content.format, &content.content).map_err(config_common::Error::InvalidContent)?;
+    let mut entries = Vec::new();
+    flatten("", &value, &mut entries).map_err(|e| {
+        config_common::Error::Validation(format!(
+            "{} content can't be rendered as env: {}",
+            content.format.as_str(),
+            e
+        ))
+    })?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(key, value)| format!("{}={}\n", env_key(&key), env_value(&value)))
+        .collect())
+}
+
+/// `server.http-port` becomes `SERVER_HTTP_PORT`
+fn env_key(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Quote values that a shell or env-file parser would otherwise split or expand
+fn env_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-.,/:@+%".contains(c));
+    if plain {
+        return value.to_string();
+    }
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' | '$' | '`' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Flatten a document into properties, joining nested keys and array indexes with dots
+fn render_properties(value: &serde_json::Value) -> Result<String, String> {
+    let mut entries = Vec::new();
+    flatten("", value, &mut entries)?;
+    Ok(entries
+        .into_iter()
+        .map(|(key, value)| format!("{}={}\n", escape(&key, true), escape(&value, false)))
+        .collect())
+}
+
+/// Dotted paths of every leaf value of a document
+pub fn flattened_keys(value: &serde_json::Value) -> Vec<String> {
+    let mut entries = Vec::new();
+    // A document that isn't a table has no keys
+    let _ = flatten("", value, &mut entries);
+    entries.into_iter().map(|(key, _)| key).collect()
+}
+
+fn flatten(
+    prefix: &str,
+    value: &serde_json::Value,
+    out: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, out)?;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                flatten(&join(&index.to_string()), value, out)?;
+            }
+        }
+        _ if prefix.is_empty() => return Err("the document is not a table".to_string()),
+        serde_json::Value::Null => out.push((prefix.to_string(), String::new())),
+        serde_json::Value::String(s) => out.push((prefix.to_string(), s.clone())),
+        other => out.push((prefix.to_string(), other.to_string())),
+    }
+    Ok(())
+}
+
+/// Escape a key or value for a properties file
+fn escape(text: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (pos, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '=' | ':' if is_key => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if is_key || pos == 0 => out.push_str("\\ "),
+            '#' | '!' if pos == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parser message without its trailing `at line L column C`, which is reported separately
+fn without_position(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// 1-based line and column of a byte offset
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// Entries of a Java-style properties file in order of appearance
+pub fn parse_properties(text: &str) -> Result<Vec<(String, String)>, ContentError> {
+    let format = ConfigFormat::Properties;
+    let mut entries = Vec::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((index, raw)) = lines.next() {
+        let line_no = index + 1;
+        let line = raw.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        // Join continuation lines, which end in an odd number of backslashes
+        let mut logical = line.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(next.trim_start()),
+                None => {
+                    return Err(ContentError::new(
+                        format,
+                        "line continuation at end of content".to_string(),
+                    )
+                    .at(Some(line_no), None))
+                }
+            }
+        }
+
+        let (key, value) = split_entry(&logical);
+        if key.is_empty() {
+            return Err(
+                ContentError::new(format, "entry has an empty key".to_string())
+                    .at(Some(line_no), Some(raw.len() - line.len() + 1)),
+            );
+        }
+        let key =
+            unescape(key).map_err(|m| ContentError::new(format, m).at(Some(line_no), None))?;
+        let value =
+            unescape(value).map_err(|m| ContentError::new(format, m).at(Some(line_no), None))?;
+        entries.push((key, value));
+    }
+
+    Ok(entries)
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// Split a logical line at the first unescaped `=`, `:` or whitespace
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (pos, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | ':' => return (&line[..pos], line[pos + 1..].trim_start()),
+            c if c.is_whitespace() => {
+                let rest = line[pos..].trim_start();
+                let rest = rest
+                    .strip_prefix('=')
+                    .or_else(|| rest.strip_prefix(':'))
+                    .unwrap_or(rest);
+                return (&line[..pos], rest.trim_start());
+            }
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let decoded = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 4)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("malformed \\u escape: \\u{}", hex))?;
+                out.push(decoded);
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    Ok(out)
+}
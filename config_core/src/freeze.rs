@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use config_common::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::{ValidationContext, WriteGuard};
+
+const MINUTES_PER_WEEK: u32 = 7 * 24 * 60;
+
+/// Weekly span during which an environment takes no writes, e.g. `fri 18:00` to `mon 08:00`,
+/// in UTC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreezeWindow {
+    pub id: String,
+    pub environment: String,
+    /// Day and time the window opens, e.g. `fri 18:00`
+    pub starts: String,
+    /// Day and time the window closes; before `starts` when it spans the week's end
+    pub ends: String,
+    pub reason: Option<String>,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+impl FreezeWindow {
+    /// Whether `at` falls inside the window
+    pub fn contains(&self, at: DateTime<Utc>) -> Result<bool> {
+        let starts = week_minute(&self.starts)?;
+        let ends = week_minute(&self.ends)?;
+        let now = at.weekday().num_days_from_monday() * 24 * 60 + at.hour() * 60 + at.minute();
+        Ok(if starts <= ends {
+            starts <= now && now < ends
+        } else {
+            now >= starts || now < ends
+        })
+    }
+}
+
+/// Minute of the week, counted from Monday 00:00, of a `<day> HH:MM` time
+pub fn week_minute(value: &str) -> Result<u32> {
+    let invalid = || Error::Validation(format!("'{}' is not a '<day> HH:MM' time", value));
+    let (day, time) = value.trim().split_once(' ').ok_or_else(invalid)?;
+    let day: Weekday = day.parse().map_err(|_| invalid())?;
+    let (hour, minute) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+    Ok((day.num_days_from_monday() * 24 * 60 + hour * 60 + minute) % MINUTES_PER_WEEK)
+}
+
+/// Manager for environment freeze windows
+#[async_trait]
+pub trait FreezeWindowManager: Send + Sync {
+    async fn create_freeze_window(
+        &self,
+        environment: &str,
+        starts: &str,
+        ends: &str,
+        reason: Option<&str>,
+        created_by: &str,
+    ) -> Result<FreezeWindow>;
+
+    /// Windows of an environment, or of every environment
+    async fn list_freeze_windows(&self, environment: Option<&str>) -> Result<Vec<FreezeWindow>>;
+
+    async fn delete_freeze_window(&self, id: &str) -> Result<bool>;
+}
+
+tokio::task_local! {
+    static OVERRIDE: FreezeOverride;
+}
+
+/// Caller allowed to write through freeze windows for the duration of a request
+#[derive(Debug, Clone)]
+struct FreezeOverride {
+    user: String,
+    reason: String,
+    used: Arc<AtomicBool>,
+}
+
+/// Run `f` with freeze windows waived for `user`; also tells whether a write actually
+/// went through an open window
+pub async fn with_override<F: Future>(user: &str, reason: &str, f: F) -> (F::Output, bool) {
+    let used = Arc::new(AtomicBool::new(false));
+    let scope = FreezeOverride {
+        user: user.to_string(),
+        reason: reason.to_string(),
+        used: used.clone(),
+    };
+    let output = OVERRIDE.scope(scope, f).await;
+    (output, used.load(Ordering::SeqCst))
+}
+
+/// Rejects changes to the configs of an environment while one of its freeze windows is open,
+/// unless the write runs under [`with_override`]
+pub struct FreezeWindowGuard {
+    windows: Arc<dyn FreezeWindowManager>,
+}
+
+impl FreezeWindowGuard {
+    pub fn new(windows: Arc<dyn FreezeWindowManager>) -> Self {
+        Self { windows }
+    }
+}
+
+#[async_trait]
+impl WriteGuard for FreezeWindowGuard {
+    async fn check_write(&self, ctx: &ValidationContext) -> Result<()> {
+        let now = Utc::now();
+        let windows = self
+            .windows
+            .list_freeze_windows(Some(&ctx.environment))
+            .await?;
+        for window in windows {
+            if !window.contains(now)? {
+                continue;
+            }
+            let overridden = OVERRIDE
+                .try_with(|o| {
+                    o.used.store(true, Ordering::SeqCst);
+                    tracing::warn!(
+                        user = %o.user,
+                        reason = %o.reason,
+                        environment = %ctx.environment,
+                        namespace = %ctx.namespace,
+                        name = %ctx.name,
+                        window = %window.id,
+                        "Write let through a freeze window"
+                    );
+                })
+                .is_ok();
+            if overridden {
+                return Ok(());
+            }
+            return Err(Error::Validation(format!(
+                "environment {} is frozen from {} to {}{}",
+                ctx.environment,
+                window.starts,
+                window.ends,
+                window
+                    .reason
+                    .map(|reason| format!(": {}", reason))
+                    .unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+}
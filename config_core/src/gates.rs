@@ -0,0 +1,162 @@
+use config_common::ConfigMeta;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// External check a release must pass before it is published, such as CI status or a
+/// change-management ticket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseGate {
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Namespaces whose releases the gate checks; every namespace when empty
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+impl ReleaseGate {
+    fn applies_to(&self, namespace: &str) -> bool {
+        self.namespaces.is_empty() || self.namespaces.iter().any(|ns| ns == namespace)
+    }
+}
+
+/// Outcome of one gate for a release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateResult {
+    pub gate: String,
+    pub passed: bool,
+    pub message: Option<String>,
+    pub checked_at: i64,
+}
+
+/// Whether every gate passed
+pub fn all_passed(results: &[GateResult]) -> bool {
+    results.iter().all(|result| result.passed)
+}
+
+/// Body POSTed to a release gate
+#[derive(Debug, Serialize)]
+struct GateRequest<'a> {
+    release_id: Option<&'a str>,
+    config_id: &'a str,
+    namespace: &'a str,
+    application: &'a str,
+    environment: &'a str,
+    version: &'a str,
+    requested_by: &'a str,
+    change_reason: Option<&'a str>,
+}
+
+/// Optional body of a gate response; a non-2xx status also fails the gate
+#[derive(Debug, Default, Deserialize)]
+struct GateResponse {
+    #[serde(default = "default_passed")]
+    passed: bool,
+    message: Option<String>,
+}
+
+fn default_passed() -> bool {
+    true
+}
+
+/// Release being checked by the gates
+#[derive(Debug, Clone, Copy)]
+pub struct GateSubject<'a> {
+    /// Unset for a release not recorded yet
+    pub release_id: Option<&'a str>,
+    pub meta: &'a ConfigMeta,
+    pub version: &'a str,
+    pub requested_by: &'a str,
+    pub change_reason: Option<&'a str>,
+}
+
+/// Calls the configured gates of a release's namespace
+pub struct ReleaseGates {
+    gates: Vec<ReleaseGate>,
+    client: reqwest::Client,
+}
+
+impl ReleaseGates {
+    pub fn new(gates: Vec<ReleaseGate>) -> Self {
+        Self {
+            gates,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Call every gate of the release's namespace; an unreachable gate fails
+    pub async fn check(&self, subject: GateSubject<'_>) -> Vec<GateResult> {
+        let body = GateRequest {
+            release_id: subject.release_id,
+            config_id: &subject.meta.id,
+            namespace: &subject.meta.namespace,
+            application: &subject.meta.application,
+            environment: &subject.meta.environment,
+            version: subject.version,
+            requested_by: subject.requested_by,
+            change_reason: subject.change_reason,
+        };
+
+        let mut results = Vec::new();
+        for gate in self
+            .gates
+            .iter()
+            .filter(|gate| gate.applies_to(&subject.meta.namespace))
+        {
+            let (passed, message) = match self.call(gate, &body).await {
+                Ok(verdict) => verdict,
+                Err(e) => (false, Some(format!("gate unreachable: {}", e))),
+            };
+            if !passed {
+                tracing::warn!(
+                    gate = %gate.name,
+                    config_id = %subject.meta.id,
+                    version = %subject.version,
+                    message = ?message,
+                    "Release gate failed"
+                );
+            }
+            results.push(GateResult {
+                gate: gate.name.clone(),
+                passed,
+                message,
+                checked_at: chrono::Utc::now().timestamp(),
+            });
+        }
+        results
+    }
+
+    async fn call(
+        &self,
+        gate: &ReleaseGate,
+        body: &GateRequest<'_>,
+    ) -> reqwest::Result<(bool, Option<String>)> {
+        let response = self
+            .client
+            .post(&gate.url)
+            .timeout(Duration::from_millis(gate.timeout_ms))
+            .json(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        let verdict: GateResponse = serde_json::from_str(&text).unwrap_or_default();
+        if status.is_success() {
+            return Ok((verdict.passed, verdict.message));
+        }
+        Ok((
+            false,
+            Some(
+                verdict
+                    .message
+                    .unwrap_or_else(|| format!("responded {}", status)),
+            ),
+        ))
+    }
+}
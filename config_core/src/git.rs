@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+
+/// Changes applied by one sync of a Git repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitSyncReport {
+    pub repository: String,
+    /// Commit the configs now match
+    pub commit: String,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+/// Imports configs from Git repositories
+#[async_trait]
+pub trait GitSync: Send + Sync {
+    /// Pull a repository now and apply its changes
+    async fn sync(&self, repository: &str) -> Result<GitSyncReport>;
+
+    /// Secret webhooks of a repository sign their requests with, if any
+    fn webhook_secret(&self, repository: &str) -> Result<Option<String>>;
+}
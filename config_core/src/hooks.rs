@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{ConfigValidator, ValidationContext};
+
+/// Webhook consulted before content of a namespace is saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationHook {
+    pub id: String,
+    pub namespace: String,
+    pub url: String,
+    pub timeout_ms: u64,
+    /// Accept the change when the hook can't be reached or times out
+    pub fail_open: bool,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+/// Manager for pre-save validation webhooks
+#[async_trait]
+pub trait ValidationHookManager: Send + Sync {
+    /// Register a hook for a namespace
+    async fn create_hook(
+        &self,
+        namespace: &str,
+        url: &str,
+        timeout_ms: u64,
+        fail_open: bool,
+        created_by: &str,
+    ) -> Result<ValidationHook>;
+
+    /// List hooks, optionally only those of a namespace
+    async fn list_hooks(&self, namespace: Option<&str>) -> Result<Vec<ValidationHook>>;
+
+    /// Remove a hook
+    async fn delete_hook(&self, id: &str) -> Result<bool>;
+}
+
+/// Body POSTed to a validation hook
+#[derive(Debug, Serialize)]
+struct HookRequest<'a> {
+    config_id: Option<&'a str>,
+    namespace: &'a str,
+    application: &'a str,
+    content: &'a ConfigContent,
+}
+
+/// Optional body of a hook response; a non-2xx status also rejects the change
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default = "default_allowed")]
+    allowed: bool,
+    message: Option<String>,
+}
+
+fn default_allowed() -> bool {
+    true
+}
+
+/// Rejects content that a namespace's validation hooks refuse
+pub struct WebhookValidator {
+    hooks: Arc<dyn ValidationHookManager>,
+    client: reqwest::Client,
+}
+
+impl WebhookValidator {
+    pub fn new(hooks: Arc<dyn ValidationHookManager>) -> Self {
+        Self {
+            hooks,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// `Ok(None)` when the hook accepts, `Ok(Some(reason))` when it rejects
+    async fn call(
+        &self,
+        hook: &ValidationHook,
+        body: &HookRequest<'_>,
+    ) -> reqwest::Result<Option<String>> {
+        let response = self
+            .client
+            .post(&hook.url)
+            .timeout(Duration::from_millis(hook.timeout_ms))
+            .json(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        let verdict: HookResponse = serde_json::from_str(&text).unwrap_or_default();
+        if status.is_success() && verdict.allowed {
+            return Ok(None);
+        }
+        Ok(Some(verdict.message.unwrap_or_else(|| {
+            if status.is_success() {
+                "rejected".to_string()
+            } else {
+                format!("responded {}", status)
+            }
+        })))
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for WebhookValidator {
+    async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        let hooks = self.hooks.list_hooks(Some(&ctx.namespace)).await?;
+        let body = HookRequest {
+            config_id: ctx.config_id.as_deref(),
+            namespace: &ctx.namespace,
+            application: &ctx.application,
+            content,
+        };
+
+        for hook in &hooks {
+            match self.call(hook, &body).await {
+                Ok(None) => {}
+                Ok(Some(reason)) => {
+                    return Err(config_common::Error::Validation(format!(
+                        "rejected by validation hook {}: {}",
+                        hook.url, reason
+                    )));
+                }
+                Err(e) if hook.fail_open => {
+                    tracing::warn!("Validation hook {} failed, accepting: {}", hook.url, e);
+                }
+                Err(e) => {
+                    return Err(config_common::Error::Validation(format!(
+                        "validation hook {} failed: {}",
+                        hook.url, e
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
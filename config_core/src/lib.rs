@@ -1,13 +1,92 @@
+pub mod approvals;
+pub mod backup;
+pub mod canary;
+pub mod events;
+pub mod flags;
+pub mod format;
+pub mod freeze;
+pub mod gates;
+pub mod git;
+pub mod hooks;
+pub mod namespaces;
+pub mod naming;
+pub mod notifications;
+pub mod promotions;
+pub mod recipients;
+pub mod references;
+pub mod releases;
+pub mod resolve;
+pub mod rules;
+pub mod secrets;
+pub mod selector;
+pub mod stats;
+pub mod teams;
+pub mod tenants;
+pub mod validation;
+
 use async_trait::async_trait;
 use config_common::{ConfigContent, ConfigMeta, Result};
+use futures_util::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 
+pub use approvals::{
+    with_approval, ApprovalGuard, ApprovalManager, ApprovalPolicy, ApprovalRequest, ApprovalStatus,
+    ProtectedNamespace,
+};
+pub use backup::{
+    BackupColumn, BackupInfo, BackupJob, BackupJobStatus, BackupManager, BackupManifest,
+    BackupTable, BackupVerification, RestoreReport, SignedVerification, VerificationCheck,
+};
+pub use canary::{CanaryConfig, CanaryMonitor, CanaryStatus, HealthReport};
+pub use events::{
+    EventBus, EventBusConfig, EventConsumer, EventFilter, EventOutbox, PublishedEvent,
+};
+pub use flags::{FeatureFlag, FlagClient, FlagEvaluation, FlagManager, FlagReason, FlagSettings};
+pub use freeze::{FreezeWindow, FreezeWindowGuard, FreezeWindowManager};
+pub use gates::{GateResult, GateSubject, ReleaseGate, ReleaseGates};
+pub use git::{GitSync, GitSyncReport};
+pub use hooks::{ValidationHook, ValidationHookManager, WebhookValidator};
+pub use namespaces::{Namespace, NamespaceFreezeGuard, NamespaceManager, NamespaceStatus};
+pub use naming::{NamespaceNaming, NamingPolicy, NamingValidator};
+pub use notifications::{
+    NotificationChannel, NotificationFilter, NotificationManager, NotificationSubscription,
+};
+pub use promotions::{Promotion, PromotionManager, PromotionStatus};
+pub use recipients::{ClientEnvelope, ClientScheme, RecipientKey, RecipientKeyManager};
+pub use releases::{
+    check_release_order, dependency_order, ConfigDependency, NewRelease, Release, ReleaseManager,
+    ReleasePolicy, ReleaseStatus, RolloutClient, RolloutRule,
+};
+pub use resolve::{KeySource, Layer, Resolved};
+pub use rules::{CustomCheck, RuleValidator, ValidationRule, ValidationRuleManager, ValidatorType};
+pub use secrets::{
+    SecretAccessControl, SecretExpiry, SecretExpiryConfig, SecretExpiryManager, SecretPathManager,
+    SecretShare, SecretShareManager,
+};
+pub use selector::LabelSelector;
+pub use stats::{CachedNamespaceStats, EditorActivity, NamespaceStats, NamespaceStatsProvider};
+pub use teams::{DepartmentValidator, Team, TeamManager, TeamPolicy};
+pub use tenants::{KeyedEncryption, Tenant, TenantManager};
+pub use validation::{FormatValidator, SchemaValidator, SizeLimitValidator};
+
 /// Configuration manager trait defining core operations
 #[async_trait]
 pub trait ConfigManager: Send + Sync {
     /// Get configuration by ID
     async fn get_config(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)>;
 
+    /// Decrypt content or values that were stored encrypted for a reader allowed to see them;
+    /// plaintext is returned unchanged
+    async fn decrypt_content(
+        &self,
+        meta: &ConfigMeta,
+        content: ConfigContent,
+        reader: &str,
+    ) -> Result<ConfigContent>;
+
+    /// Replace encrypted content or values with a placeholder and checksum
+    fn redact_content(&self, content: ConfigContent) -> Result<ConfigContent>;
+
     /// Create new configuration
     async fn create_config(
         &self,
@@ -27,12 +106,38 @@ pub trait ConfigManager: Send + Sync {
         id: &str,
         description: Option<&str>,
         content: ConfigContent,
+        change_reason: Option<&str>,
+        updated_by: &str,
+    ) -> Result<ConfigMeta>;
+
+    /// Replace the owners of a configuration
+    async fn update_owners(
+        &self,
+        id: &str,
+        owners: Vec<String>,
+        updated_by: &str,
+    ) -> Result<ConfigMeta>;
+
+    /// Replace the labels of a configuration
+    async fn update_labels(
+        &self,
+        id: &str,
+        labels: Vec<String>,
         updated_by: &str,
     ) -> Result<ConfigMeta>;
 
     /// Delete configuration
     async fn delete_config(&self, id: &str) -> Result<bool>;
 
+    /// Create and update configurations atomically: every write is applied, or none.
+    /// Returns the configurations in the order of `writes`.
+    async fn apply_batch(
+        &self,
+        writes: Vec<BatchWrite>,
+        change_reason: Option<&str>,
+        user: &str,
+    ) -> Result<Vec<ConfigMeta>>;
+
     /// List configurations with filters
     async fn list_configs(
         &self,
@@ -40,22 +145,148 @@ pub trait ConfigManager: Send + Sync {
         page_size: i32,
         page_number: i32,
     ) -> Result<(Vec<ConfigMeta>, i32)>;
+
+    /// Stream every configuration matching a filter from a database cursor, for listings
+    /// too large to page through
+    async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream>;
+}
+
+/// Stream of configurations, read as the consumer takes them
+pub type ConfigMetaStream = BoxStream<'static, Result<ConfigMeta>>;
+
+/// Configurations fetched per page by [`list_all_configs`]
+const LIST_ALL_PAGE_SIZE: i32 = 500;
+
+/// Every configuration matching a filter, across all pages
+pub async fn list_all_configs(
+    manager: &dyn ConfigManager,
+    filter: ConfigFilter,
+) -> Result<Vec<ConfigMeta>> {
+    let mut configs = Vec::new();
+    for page_number in 1.. {
+        let (page, total) = manager
+            .list_configs(filter.clone(), LIST_ALL_PAGE_SIZE, page_number)
+            .await?;
+        let last = page.len() < LIST_ALL_PAGE_SIZE as usize;
+        configs.extend(page);
+        if last || configs.len() >= total as usize {
+            break;
+        }
+    }
+    Ok(configs)
 }
 
 /// Configuration filter for listing configurations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ConfigFilter {
     pub namespace: Option<String>,
     pub department: Option<String>,
     pub application: Option<String>,
     pub environment: Option<String>,
+    /// Namespaces left out, e.g. archived ones
+    #[serde(default)]
+    pub exclude_namespaces: Vec<String>,
 }
 
 /// Configuration validator trait for validating configuration content
 #[async_trait]
 pub trait ConfigValidator: Send + Sync {
     /// Validate configuration content
-    async fn validate(&self, content: &ConfigContent) -> Result<()>;
+    async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()>;
+}
+
+/// Check run before any change to a configuration, including deletes and metadata edits
+#[async_trait]
+pub trait WriteGuard: Send + Sync {
+    /// Reject the change with an error explaining why
+    async fn check_write(&self, ctx: &ValidationContext) -> Result<()>;
+}
+
+/// Configuration whose content is being validated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationContext {
+    /// Unset while the configuration is being created
+    pub config_id: Option<String>,
+    pub namespace: String,
+    pub department: String,
+    pub application: String,
+    pub environment: String,
+    pub name: String,
+    /// Whether the write saves new content, rather than editing metadata or versions
+    pub changes_content: bool,
+}
+
+impl ValidationContext {
+    /// Context of a content update of an existing configuration
+    pub fn of(meta: &ConfigMeta) -> Self {
+        Self {
+            config_id: Some(meta.id.clone()),
+            namespace: meta.namespace.clone(),
+            department: meta.department.clone(),
+            application: meta.application.clone(),
+            environment: meta.environment.clone(),
+            name: meta.name.clone(),
+            changes_content: true,
+        }
+    }
+
+    /// Context of a write to an existing configuration that leaves its content as is
+    pub fn metadata_of(meta: &ConfigMeta) -> Self {
+        Self {
+            changes_content: false,
+            ..Self::of(meta)
+        }
+    }
+}
+
+/// Manager for JSON Schemas attached to a namespace or one of its applications
+#[async_trait]
+pub trait SchemaManager: Send + Sync {
+    /// Attach a schema; without an application it covers the whole namespace
+    async fn create_schema(
+        &self,
+        namespace: &str,
+        application: Option<&str>,
+        description: Option<&str>,
+        schema: serde_json::Value,
+        created_by: &str,
+    ) -> Result<ConfigSchema>;
+
+    /// Get a schema by ID
+    async fn get_schema(&self, id: &str) -> Result<ConfigSchema>;
+
+    /// List schemas, optionally only those of a namespace
+    async fn list_schemas(&self, namespace: Option<&str>) -> Result<Vec<ConfigSchema>>;
+
+    /// Replace the document of a schema
+    async fn update_schema(
+        &self,
+        id: &str,
+        description: Option<&str>,
+        schema: serde_json::Value,
+        updated_by: &str,
+    ) -> Result<ConfigSchema>;
+
+    /// Delete a schema
+    async fn delete_schema(&self, id: &str) -> Result<bool>;
+
+    /// Schemas applying to configurations of an application
+    async fn schemas_for(&self, namespace: &str, application: &str) -> Result<Vec<ConfigSchema>>;
+}
+
+/// JSON Schema that configuration content must satisfy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSchema {
+    pub id: String,
+    pub namespace: String,
+    /// Unset when the schema covers every application of the namespace
+    pub application: Option<String>,
+    pub description: Option<String>,
+    pub schema: serde_json::Value,
+    pub created_at: i64,
+    pub created_by: String,
+    pub updated_at: i64,
+    pub updated_by: String,
 }
 
 /// Configuration encryption trait for encrypting/decrypting configuration content
@@ -66,6 +297,88 @@ pub trait ConfigEncryption: Send + Sync {
 
     /// Decrypt configuration content
     async fn decrypt(&self, content: &str) -> Result<String>;
+
+    /// Encrypt configuration content under the given master key instead of the active one
+    async fn encrypt_with_key(&self, _content: &str, key_id: &str) -> Result<String> {
+        Err(config_common::Error::Validation(format!(
+            "encryption can't use master key {}",
+            key_id
+        )))
+    }
+
+    /// Master key protecting a ciphertext, when it can be told
+    fn key_id(&self, _ciphertext: &str) -> Option<String> {
+        None
+    }
+
+    /// Short checksum of the plaintext recorded with a ciphertext, for telling values apart
+    /// without decrypting them
+    fn checksum(&self, _ciphertext: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Re-encryption of stored content under a new master key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotation {
+    pub id: String,
+    /// Master key activated by the rotation
+    pub key_id: String,
+    pub status: KeyRotationStatus,
+    /// Stored contents to re-encrypt, counted when the rotation started
+    pub total: i64,
+    pub processed: i64,
+    pub failed: i64,
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub started_by: String,
+    pub finished_at: Option<i64>,
+}
+
+/// Key rotation lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyRotationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl KeyRotationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyRotationStatus::Running => "running",
+            KeyRotationStatus::Completed => "completed",
+            KeyRotationStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for KeyRotationStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "running" => Ok(KeyRotationStatus::Running),
+            "completed" => Ok(KeyRotationStatus::Completed),
+            "failed" => Ok(KeyRotationStatus::Failed),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown key rotation status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Rotation of the master key wrapping data keys
+#[async_trait]
+pub trait KeyRotationManager: Send + Sync {
+    /// Activate `key_id`, or a new version of the active key when unset, then re-encrypt
+    /// stored content in the background
+    async fn start_rotation(&self, key_id: Option<&str>, started_by: &str) -> Result<KeyRotation>;
+
+    /// Latest rotation, if any
+    async fn rotation_status(&self) -> Result<Option<KeyRotation>>;
 }
 
 /// Configuration version control trait
@@ -74,8 +387,39 @@ pub trait ConfigVersionControl: Send + Sync {
     /// Get configuration version history
     async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>>;
 
+    /// Get a single version with its full content
+    async fn get_version(&self, id: &str, version: &str) -> Result<(ConfigVersion, ConfigContent)>;
+
+    /// Get the version that was current at a Unix timestamp
+    async fn get_version_at(&self, id: &str, at: i64) -> Result<ConfigSnapshot>;
+
+    /// Get every configuration of a namespace as it was at a Unix timestamp
+    async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>>;
+
     /// Roll back to specific version
-    async fn rollback(&self, id: &str, version: &str, user: &str) -> Result<ConfigMeta>;
+    async fn rollback(
+        &self,
+        id: &str,
+        version: &str,
+        change_reason: Option<&str>,
+        user: &str,
+    ) -> Result<ConfigMeta>;
+
+    /// Attach a tag to a version, moving it if another version carries it
+    async fn tag_version(&self, id: &str, version: &str, tag: &str, user: &str) -> Result<()>;
+
+    /// Remove a tag
+    async fn untag_version(&self, id: &str, tag: &str, user: &str) -> Result<bool>;
+
+    /// Pin or unpin a version; pinned versions survive retention compaction
+    async fn pin_version(&self, id: &str, version: &str, pinned: bool, user: &str) -> Result<()>;
+
+    /// Get the version carrying a tag with its full content
+    async fn get_tagged_version(
+        &self,
+        id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)>;
 }
 
 /// Configuration version information
@@ -85,4 +429,182 @@ pub struct ConfigVersion {
     pub created_at: i64,
     pub created_by: String,
     pub description: Option<String>,
+    /// Why the change producing this version was made
+    #[serde(default)]
+    pub change_reason: Option<String>,
+    /// Human tags such as `stable` pointing at this version
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Exempt from retention compaction
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Which namespaces require a reason on updates and rollbacks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeReasonPolicy {
+    /// Require a reason in every namespace
+    #[serde(default)]
+    pub required: bool,
+    /// Namespaces requiring a reason when not required everywhere
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+}
+
+impl ChangeReasonPolicy {
+    pub fn is_required(&self, namespace: &str) -> bool {
+        self.required || self.namespaces.iter().any(|ns| ns == namespace)
+    }
+
+    /// Reject a missing or blank reason where one is required
+    pub fn check(&self, namespace: &str, reason: Option<&str>) -> Result<()> {
+        let missing = reason.is_none_or(|r| r.trim().is_empty());
+        if missing && self.is_required(namespace) {
+            return Err(config_common::Error::Validation(format!(
+                "a change reason is required in namespace {}",
+                namespace
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Configuration as it was at some version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub meta: ConfigMeta,
+    pub version: ConfigVersion,
+    pub content: ConfigContent,
+}
+
+/// Version assigned to newly created configurations
+pub const INITIAL_VERSION: &str = "1.0.0";
+
+/// Version following `current`, bumping the patch component
+pub fn next_version(current: &str) -> Result<String> {
+    let parts: Vec<u64> = current
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| config_common::Error::Validation(format!("invalid version: {}", current)))?;
+
+    match parts.as_slice() {
+        [major, minor, patch] => Ok(format!("{}.{}.{}", major, minor, patch + 1)),
+        _ => Err(config_common::Error::Validation(format!(
+            "invalid version: {}",
+            current
+        ))),
+    }
+}
+
+/// Manager for change sets grouping edits to several configurations
+#[async_trait]
+pub trait ChangeSetManager: Send + Sync {
+    /// Open a new change set
+    async fn create_changeset(
+        &self,
+        title: &str,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<ChangeSet>;
+
+    /// Get a change set with its staged changes
+    async fn get_changeset(&self, id: &str) -> Result<ChangeSet>;
+
+    /// List change sets, optionally only those in the given status
+    async fn list_changesets(&self, status: Option<ChangeSetStatus>) -> Result<Vec<ChangeSet>>;
+
+    /// Stage new content for a configuration, replacing any earlier staged edit
+    async fn stage_change(&self, id: &str, change: StagedChange, user: &str) -> Result<ChangeSet>;
+
+    /// Remove the staged edit of a configuration
+    async fn unstage_change(&self, id: &str, config_id: &str, user: &str) -> Result<ChangeSet>;
+
+    /// Discard an open change set
+    async fn discard_changeset(&self, id: &str, user: &str) -> Result<ChangeSet>;
+
+    /// Apply all staged edits atomically
+    async fn apply_changeset(&self, id: &str, user: &str) -> Result<Vec<ConfigMeta>>;
+}
+
+/// Group of configuration edits reviewed and applied together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeSet {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: ChangeSetStatus,
+    pub changes: Vec<StagedChange>,
+    pub created_at: i64,
+    pub created_by: String,
+    pub updated_at: i64,
+    pub applied_at: Option<i64>,
+    pub applied_by: Option<String>,
+}
+
+/// Change set lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSetStatus {
+    Open,
+    Applied,
+    Discarded,
+}
+
+impl ChangeSetStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeSetStatus::Open => "open",
+            ChangeSetStatus::Applied => "applied",
+            ChangeSetStatus::Discarded => "discarded",
+        }
+    }
+}
+
+impl std::str::FromStr for ChangeSetStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "open" => Ok(ChangeSetStatus::Open),
+            "applied" => Ok(ChangeSetStatus::Applied),
+            "discarded" => Ok(ChangeSetStatus::Discarded),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown change set status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Write of a configuration applied in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchWrite {
+    Create {
+        name: String,
+        namespace: String,
+        department: String,
+        application: String,
+        environment: String,
+        description: Option<String>,
+        content: ConfigContent,
+    },
+    Update {
+        config_id: String,
+        /// Version the write was prepared against, used to detect conflicting updates
+        base_version: String,
+        description: Option<String>,
+        content: ConfigContent,
+    },
+}
+
+/// Edit of a single configuration staged in a change set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedChange {
+    pub config_id: String,
+    /// Version the edit was staged against, used to detect conflicting updates
+    pub base_version: String,
+    pub description: Option<String>,
+    pub content: ConfigContent,
 }
@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use config_common::{ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{ConfigFilter, ConfigManager, ValidationContext, WriteGuard};
+
+/// Registered namespace; configs of its ancestors are inherited unless overridden
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Namespace {
+    pub name: String,
+    pub parent: Option<String>,
+    pub description: Option<String>,
+    pub status: NamespaceStatus,
+    pub created_at: i64,
+    pub created_by: String,
+    pub updated_at: i64,
+    pub updated_by: String,
+}
+
+/// Whether the configs of a namespace can change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamespaceStatus {
+    Active,
+    /// Readable, but every write is rejected
+    Frozen,
+    /// Frozen and left out of listings unless asked for
+    Archived,
+}
+
+impl NamespaceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NamespaceStatus::Active => "active",
+            NamespaceStatus::Frozen => "frozen",
+            NamespaceStatus::Archived => "archived",
+        }
+    }
+}
+
+impl std::str::FromStr for NamespaceStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "active" => Ok(NamespaceStatus::Active),
+            "frozen" => Ok(NamespaceStatus::Frozen),
+            "archived" => Ok(NamespaceStatus::Archived),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown namespace status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Manager for namespaces and their hierarchy
+#[async_trait]
+pub trait NamespaceManager: Send + Sync {
+    /// Register a namespace; the parent must exist
+    async fn create_namespace(
+        &self,
+        name: &str,
+        parent: Option<&str>,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<Namespace>;
+
+    async fn get_namespace(&self, name: &str) -> Result<Namespace>;
+
+    /// List namespaces, leaving out archived ones unless `include_archived` is set
+    async fn list_namespaces(&self, include_archived: bool) -> Result<Vec<Namespace>>;
+
+    /// Move a namespace under another parent and replace its description; the new parent
+    /// must exist and not be below the namespace
+    async fn update_namespace(
+        &self,
+        name: &str,
+        parent: Option<&str>,
+        description: Option<&str>,
+        updated_by: &str,
+    ) -> Result<Namespace>;
+
+    /// Freeze, archive or restore a namespace
+    async fn set_namespace_status(
+        &self,
+        name: &str,
+        status: NamespaceStatus,
+        updated_by: &str,
+    ) -> Result<Namespace>;
+
+    /// Remove a namespace without child namespaces or configs
+    async fn delete_namespace(&self, name: &str) -> Result<bool>;
+}
+
+/// Rejects changes to the configs of frozen and archived namespaces
+pub struct NamespaceFreezeGuard {
+    namespaces: Arc<dyn NamespaceManager>,
+}
+
+impl NamespaceFreezeGuard {
+    pub fn new(namespaces: Arc<dyn NamespaceManager>) -> Self {
+        Self { namespaces }
+    }
+}
+
+#[async_trait]
+impl WriteGuard for NamespaceFreezeGuard {
+    async fn check_write(&self, ctx: &ValidationContext) -> Result<()> {
+        let namespace = match self.namespaces.get_namespace(&ctx.namespace).await {
+            Ok(namespace) => namespace,
+            // Unregistered namespaces can't be frozen
+            Err(config_common::Error::NotFound(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        match namespace.status {
+            NamespaceStatus::Active => Ok(()),
+            status => Err(config_common::Error::Validation(format!(
+                "namespace {} is {}; its configs are read-only",
+                namespace.name,
+                status.as_str()
+            ))),
+        }
+    }
+}
+
+/// A namespace followed by its ancestors, nearest first; just the namespace itself when it
+/// isn't registered
+pub async fn namespace_chain(manager: &dyn NamespaceManager, name: &str) -> Result<Vec<String>> {
+    let mut chain = vec![name.to_string()];
+    let mut current = name.to_string();
+    loop {
+        let parent = match manager.get_namespace(&current).await {
+            Ok(namespace) => namespace.parent,
+            Err(config_common::Error::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        match parent {
+            // Updates reject cycles; the check only guards against a corrupted table
+            Some(parent) if !chain.contains(&parent) => {
+                chain.push(parent.clone());
+                current = parent;
+            }
+            _ => return Ok(chain),
+        }
+    }
+}
+
+/// Configs visible in a namespace: its own, plus those of its ancestors with an application,
+/// environment and name no nearer namespace has
+pub async fn inherited_configs(
+    namespaces: &dyn NamespaceManager,
+    configs: &dyn ConfigManager,
+    namespace: &str,
+    filter: ConfigFilter,
+) -> Result<Vec<ConfigMeta>> {
+    let mut seen = HashSet::new();
+    let mut inherited = Vec::new();
+    for name in namespace_chain(namespaces, namespace).await? {
+        let filter = ConfigFilter {
+            namespace: Some(name),
+            ..filter.clone()
+        };
+        for meta in crate::list_all_configs(configs, filter).await? {
+            let key = (
+                meta.application.clone(),
+                meta.environment.clone(),
+                meta.name.clone(),
+            );
+            if seen.insert(key) {
+                inherited.push(meta);
+            }
+        }
+    }
+    Ok(inherited)
+}
@@ -0,0 +1,156 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{format, ConfigValidator, ValidationContext};
+
+/// Naming conventions for configuration names and content keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamingPolicy {
+    /// Pattern every configuration name must match
+    #[serde(default = "default_name_pattern")]
+    pub name_pattern: String,
+    /// Pattern every flattened content key must match, unchecked when unset
+    #[serde(default)]
+    pub key_pattern: Option<String>,
+    /// Prefixes no name or key may start with
+    #[serde(default = "default_reserved_prefixes")]
+    pub reserved_prefixes: Vec<String>,
+    /// Per-namespace patterns replacing the defaults above
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceNaming>,
+}
+
+/// Naming patterns of one namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceNaming {
+    pub name_pattern: Option<String>,
+    pub key_pattern: Option<String>,
+}
+
+fn default_name_pattern() -> String {
+    "^[A-Za-z0-9][A-Za-z0-9._-]*$".to_string()
+}
+
+fn default_reserved_prefixes() -> Vec<String> {
+    vec!["system.".to_string()]
+}
+
+impl Default for NamingPolicy {
+    fn default() -> Self {
+        Self {
+            name_pattern: default_name_pattern(),
+            key_pattern: None,
+            reserved_prefixes: default_reserved_prefixes(),
+            namespaces: HashMap::new(),
+        }
+    }
+}
+
+impl NamingPolicy {
+    /// Reject patterns that don't compile, so a bad setting fails at startup
+    pub fn check(&self) -> Result<()> {
+        let namespaced = self
+            .namespaces
+            .values()
+            .flat_map(|ns| [ns.name_pattern.as_ref(), ns.key_pattern.as_ref()]);
+        for pattern in [Some(&self.name_pattern), self.key_pattern.as_ref()]
+            .into_iter()
+            .chain(namespaced)
+            .flatten()
+        {
+            compile(pattern)?;
+        }
+        Ok(())
+    }
+
+    /// Reject a configuration name breaking the conventions of its namespace
+    pub fn check_name(&self, namespace: &str, name: &str) -> Result<()> {
+        self.check_reserved("name", name)?;
+        let pattern = self
+            .namespaces
+            .get(namespace)
+            .and_then(|ns| ns.name_pattern.as_ref())
+            .unwrap_or(&self.name_pattern);
+        if !compile(pattern)?.is_match(name) {
+            return Err(config_common::Error::Validation(format!(
+                "config name {} does not match {}",
+                name, pattern
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject content keys breaking the conventions of a namespace
+    pub fn check_keys(&self, namespace: &str, keys: &[String]) -> Result<()> {
+        let pattern = self
+            .namespaces
+            .get(namespace)
+            .and_then(|ns| ns.key_pattern.as_ref())
+            .or(self.key_pattern.as_ref())
+            .map(|p| compile(p).map(|re| (p, re)))
+            .transpose()?;
+
+        for key in keys {
+            self.check_reserved("key", key)?;
+            if let Some((pattern, re)) = &pattern {
+                if !re.is_match(key) {
+                    return Err(config_common::Error::Validation(format!(
+                        "key {} does not match {}",
+                        key, pattern
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_reserved(&self, what: &str, value: &str) -> Result<()> {
+        match self
+            .reserved_prefixes
+            .iter()
+            .find(|prefix| value.starts_with(prefix.as_str()))
+        {
+            Some(prefix) => Err(config_common::Error::Validation(format!(
+                "{} {} uses the reserved prefix {}",
+                what, value, prefix
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<regex::Regex> {
+    regex::Regex::new(pattern).map_err(|e| {
+        config_common::Error::Config(format!("invalid naming pattern {}: {}", pattern, e))
+    })
+}
+
+/// Enforces the naming policy on new configuration names and on content keys
+pub struct NamingValidator {
+    policy: NamingPolicy,
+}
+
+impl NamingValidator {
+    pub fn new(policy: NamingPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for NamingValidator {
+    async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        // Names are only checked at create time so existing configs stay writable
+        if ctx.config_id.is_none() {
+            self.policy.check_name(&ctx.namespace, &ctx.name)?;
+        }
+        if content.is_encrypted {
+            return Ok(());
+        }
+        let value = format::parse(content.format, &content.content)
+            .map_err(config_common::Error::InvalidContent)?;
+        self.policy
+            .check_keys(&ctx.namespace, &format::flattened_keys(&value))
+    }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use config_common::{ConfigEvent, ConfigEventType, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::selector::LabelSelector;
+
+/// Message used when a subscription has no template of its own
+pub const DEFAULT_TEMPLATE: &str =
+    "[{environment}] {namespace}/{config_id} {event_type} to {version} by {user}";
+
+/// Message used for events that aren't about one config, such as audit anomalies
+pub const ALERT_TEMPLATE: &str = "{event_type}: {message}";
+
+/// Where a subscription's notifications are sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Slack incoming webhook
+    Slack { webhook_url: String },
+    /// DingTalk robot webhook, signed when the robot has a secret
+    #[serde(rename = "dingtalk")]
+    DingTalk {
+        webhook_url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+    },
+    /// Mail sent through the configured SMTP server
+    Email { recipients: Vec<String> },
+}
+
+impl NotificationChannel {
+    /// Reject channels that can't possibly deliver
+    pub fn check(&self) -> Result<()> {
+        match self {
+            NotificationChannel::Slack { webhook_url }
+            | NotificationChannel::DingTalk { webhook_url, .. } => {
+                if !webhook_url.starts_with("https://") {
+                    return Err(config_common::Error::Validation(format!(
+                        "webhook url must be https: {}",
+                        webhook_url
+                    )));
+                }
+            }
+            NotificationChannel::Email { recipients } => {
+                if recipients.is_empty() {
+                    return Err(config_common::Error::Validation(
+                        "email channel has no recipients".to_string(),
+                    ));
+                }
+                if let Some(address) = recipients.iter().find(|a| !a.contains('@')) {
+                    return Err(config_common::Error::Validation(format!(
+                        "invalid email address: {}",
+                        address
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Events a subscription is notified of; empty lists match anything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationFilter {
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub environments: Vec<String>,
+    #[serde(default)]
+    pub event_types: Vec<ConfigEventType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<LabelSelector>,
+}
+
+impl NotificationFilter {
+    pub fn matches(&self, event: &ConfigEvent) -> bool {
+        (self.namespaces.is_empty() || self.namespaces.contains(&event.namespace))
+            && (self.environments.is_empty() || self.environments.contains(&event.environment))
+            && (self.event_types.is_empty() || self.event_types.contains(&event.event_type))
+            && self
+                .labels
+                .as_ref()
+                .is_none_or(|selector| selector.matches(&event.labels))
+    }
+}
+
+/// Channel notified of the events matching a filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSubscription {
+    pub id: String,
+    pub name: String,
+    pub channel: NotificationChannel,
+    pub filter: NotificationFilter,
+    /// Message body; may use `{config_id}`, `{namespace}`, `{environment}`, `{event_type}`,
+    /// `{version}`, `{user}`, `{timestamp}` and `{message}`
+    pub template: Option<String>,
+    /// Seconds events are collected before being sent together, keeping only the latest
+    /// event of each config; zero sends each event right away
+    #[serde(default)]
+    pub debounce_secs: u64,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+impl NotificationSubscription {
+    /// Message body for an event
+    pub fn render(&self, event: &ConfigEvent) -> String {
+        let fallback = match event.message {
+            Some(_) => ALERT_TEMPLATE,
+            None => DEFAULT_TEMPLATE,
+        };
+        render(self.template.as_deref().unwrap_or(fallback), event)
+    }
+}
+
+/// Manager for notification subscriptions
+#[async_trait]
+pub trait NotificationManager: Send + Sync {
+    async fn create_subscription(
+        &self,
+        name: &str,
+        channel: NotificationChannel,
+        filter: NotificationFilter,
+        template: Option<String>,
+        debounce_secs: u64,
+        created_by: &str,
+    ) -> Result<NotificationSubscription>;
+
+    async fn list_subscriptions(&self) -> Result<Vec<NotificationSubscription>>;
+
+    /// Remove a subscription
+    async fn delete_subscription(&self, id: &str) -> Result<bool>;
+}
+
+/// Fill the placeholders of a template with the fields of an event
+pub fn render(template: &str, event: &ConfigEvent) -> String {
+    let timestamp = chrono::DateTime::from_timestamp(event.timestamp, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| event.timestamp.to_string());
+    template
+        .replace("{config_id}", &event.config_id)
+        .replace("{namespace}", &event.namespace)
+        .replace("{environment}", &event.environment)
+        .replace("{event_type}", event.event_type.as_str())
+        .replace("{version}", &event.version)
+        .replace("{user}", &event.user)
+        .replace("{timestamp}", &timestamp)
+        .replace("{message}", event.message.as_deref().unwrap_or_default())
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use config_common::{ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+
+/// Request to copy a version of a config into the same config of another environment,
+/// applied once someone other than the requester approves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Promotion {
+    pub id: String,
+    pub source_id: String,
+    pub source_version: String,
+    pub target_environment: String,
+    /// Config receiving the content; created on approval when absent
+    pub target_id: Option<String>,
+    /// Version of the target the request was made against
+    pub target_version: Option<String>,
+    pub change_reason: Option<String>,
+    pub status: PromotionStatus,
+    pub requested_by: String,
+    pub requested_at: i64,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<i64>,
+    /// Version of the target written on approval
+    pub applied_version: Option<String>,
+}
+
+/// Promotion lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromotionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl PromotionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromotionStatus::Pending => "pending",
+            PromotionStatus::Approved => "approved",
+            PromotionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for PromotionStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(PromotionStatus::Pending),
+            "approved" => Ok(PromotionStatus::Approved),
+            "rejected" => Ok(PromotionStatus::Rejected),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown promotion status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Manager for promotion requests
+#[async_trait]
+pub trait PromotionManager: Send + Sync {
+    /// Record a pending promotion of the current version of `source`
+    async fn create_promotion(
+        &self,
+        source: &ConfigMeta,
+        target_environment: &str,
+        target: Option<&ConfigMeta>,
+        change_reason: Option<&str>,
+        requested_by: &str,
+    ) -> Result<Promotion>;
+
+    async fn get_promotion(&self, id: &str) -> Result<Promotion>;
+
+    /// List promotions, newest first, optionally only those in the given status
+    async fn list_promotions(&self, status: Option<PromotionStatus>) -> Result<Vec<Promotion>>;
+
+    /// Close a pending promotion; fails when it was already reviewed
+    async fn review_promotion(
+        &self,
+        id: &str,
+        status: PromotionStatus,
+        reviewed_by: &str,
+        applied_version: Option<&str>,
+    ) -> Result<Promotion>;
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::{ConfigContent, Result};
+use serde::{Deserialize, Serialize};
+
+/// Start of content encrypted by clients, followed by the base64 JSON envelope
+pub const CLIENT_CIPHERTEXT_PREFIX: &str = "cse:v1:";
+
+/// First line of every binary age file
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1\n";
+
+/// Length of an AES-GCM nonce
+const NONCE_LEN: usize = 12;
+
+/// Schemes clients encrypt content with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientScheme {
+    /// age file; recipient stanzas live in its header
+    Age,
+    /// AES-256-GCM content key wrapped with RSA-OAEP for each recipient
+    RsaOaep,
+}
+
+/// Public key content of a namespace is encrypted to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipientKey {
+    /// Name clients and envelopes refer to the key by
+    pub id: String,
+    pub scheme: ClientScheme,
+    /// `age1...` recipient or PEM encoded RSA public key
+    pub public_key: String,
+}
+
+impl RecipientKey {
+    /// Reject keys that can't be of the scheme they claim
+    pub fn check(&self) -> Result<()> {
+        if self.id.trim().is_empty() {
+            return Err(config_common::Error::Validation(
+                "recipient id is empty".to_string(),
+            ));
+        }
+        let valid = match self.scheme {
+            ClientScheme::Age => self.public_key.starts_with("age1"),
+            ClientScheme::RsaOaep => self
+                .public_key
+                .trim_start()
+                .starts_with("-----BEGIN PUBLIC KEY-----"),
+        };
+        if !valid {
+            return Err(config_common::Error::Validation(format!(
+                "recipient {} is not a valid {:?} public key",
+                self.id, self.scheme
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Storage for the recipient keys of each namespace; a namespace with recipients only
+/// accepts content encrypted client-side
+#[async_trait]
+pub trait RecipientKeyManager: Send + Sync {
+    /// Recipient keys of a namespace, empty when it is not in client-side mode
+    async fn get_recipients(&self, namespace: &str) -> Result<Vec<RecipientKey>>;
+
+    /// Replace the recipient keys of a namespace
+    async fn set_recipients(
+        &self,
+        namespace: &str,
+        recipients: Vec<RecipientKey>,
+        updated_by: &str,
+    ) -> Result<()>;
+}
+
+/// Content key wrapped for one recipient
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipientStanza {
+    pub key_id: String,
+    /// Base64 RSA-OAEP wrapped content key; absent for age
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapped_key: Option<String>,
+}
+
+/// Content encrypted by a client, which the server stores but can't read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientEnvelope {
+    pub scheme: ClientScheme,
+    pub recipients: Vec<RecipientStanza>,
+    /// Base64 AES-GCM nonce; absent for age
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Base64 ciphertext; for age, the whole binary age file
+    pub ciphertext: String,
+}
+
+impl ClientEnvelope {
+    /// Decode the envelope of stored content
+    pub fn parse(content: &str) -> Result<Self> {
+        let encoded = content
+            .strip_prefix(CLIENT_CIPHERTEXT_PREFIX)
+            .ok_or_else(|| {
+                config_common::Error::Validation(format!(
+                    "client-side encrypted content must start with {}",
+                    CLIENT_CIPHERTEXT_PREFIX
+                ))
+            })?;
+        let json = decode("envelope", encoded)?;
+        serde_json::from_slice(&json).map_err(|e| {
+            config_common::Error::Validation(format!("invalid client envelope: {}", e))
+        })
+    }
+
+    /// Check the envelope is well formed and encrypted to exactly the given recipients
+    pub fn check(&self, recipients: &[RecipientKey]) -> Result<()> {
+        let ciphertext = decode("ciphertext", &self.ciphertext)?;
+        match self.scheme {
+            ClientScheme::Age => {
+                if !ciphertext.starts_with(AGE_HEADER) {
+                    return Err(config_common::Error::Validation(
+                        "ciphertext is not an age file".to_string(),
+                    ));
+                }
+                if self.nonce.is_some() || self.recipients.iter().any(|r| r.wrapped_key.is_some()) {
+                    return Err(config_common::Error::Validation(
+                        "age envelopes carry no nonce or wrapped keys".to_string(),
+                    ));
+                }
+            }
+            ClientScheme::RsaOaep => {
+                let nonce = self.nonce.as_deref().ok_or_else(|| {
+                    config_common::Error::Validation("envelope has no nonce".to_string())
+                })?;
+                if decode("nonce", nonce)?.len() != NONCE_LEN {
+                    return Err(config_common::Error::Validation(format!(
+                        "nonce must be {} bytes",
+                        NONCE_LEN
+                    )));
+                }
+                for stanza in &self.recipients {
+                    let wrapped = stanza.wrapped_key.as_deref().ok_or_else(|| {
+                        config_common::Error::Validation(format!(
+                            "no wrapped key for recipient {}",
+                            stanza.key_id
+                        ))
+                    })?;
+                    decode("wrapped key", wrapped)?;
+                }
+            }
+        }
+
+        for stanza in &self.recipients {
+            let known = recipients
+                .iter()
+                .any(|key| key.id == stanza.key_id && key.scheme == self.scheme);
+            if !known {
+                return Err(config_common::Error::Validation(format!(
+                    "{} is not a {:?} recipient of the namespace",
+                    stanza.key_id, self.scheme
+                )));
+            }
+        }
+        // Every key holder of the namespace must be able to decrypt
+        if let Some(missing) = recipients
+            .iter()
+            .find(|key| !self.recipients.iter().any(|r| r.key_id == key.id))
+        {
+            return Err(config_common::Error::Validation(format!(
+                "content is not encrypted to recipient {}",
+                missing.id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Whether content was encrypted client-side
+pub fn is_client_sealed(content: &ConfigContent) -> bool {
+    content.is_encrypted && content.content.starts_with(CLIENT_CIPHERTEXT_PREFIX)
+}
+
+fn decode(what: &str, text: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| config_common::Error::Validation(format!("invalid {}: {}", what, e)))
+}
@@ -0,0 +1,162 @@
+use config_common::{Error, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::resolve::{KeySource, Resolved};
+use crate::{ConfigManager, NamespaceManager};
+
+/// Start of a reference placeholder, `${ref:<namespace>/<application>/<key>}`
+pub const REF_PREFIX: &str = "${ref:";
+
+/// Deepest chain of references followed before giving up
+const MAX_DEPTH: usize = 8;
+
+/// Whether content holds any reference placeholder
+pub fn has_references(content: &str) -> bool {
+    content.contains(REF_PREFIX)
+}
+
+/// Content with its references replaced and the configs the values were taken from
+#[derive(Debug, Clone)]
+pub struct Expanded {
+    pub content: String,
+    pub sources: Vec<KeySource>,
+}
+
+/// Replace every `${ref:<namespace>/<application>/<key>}` in `content` with the scalar at
+/// dotted path `key` of the application's resolved document in `environment`. Referenced
+/// values may hold references themselves; a reference reached again through itself is a cycle.
+pub async fn expand(
+    namespaces: &dyn NamespaceManager,
+    configs: &dyn ConfigManager,
+    environment: &str,
+    content: &str,
+) -> Result<Expanded> {
+    let mut expander = Expander {
+        namespaces,
+        configs,
+        environment,
+        documents: HashMap::new(),
+        sources: Vec::new(),
+    };
+    let content = expander.expand(content.to_string(), Vec::new()).await?;
+    Ok(Expanded {
+        content,
+        sources: expander.sources,
+    })
+}
+
+struct Expander<'a> {
+    namespaces: &'a dyn NamespaceManager,
+    configs: &'a dyn ConfigManager,
+    environment: &'a str,
+    /// Resolved documents by `(namespace, application)`
+    documents: HashMap<(String, String), Resolved>,
+    sources: Vec<KeySource>,
+}
+
+impl<'a> Expander<'a> {
+    fn expand(
+        &mut self,
+        text: String,
+        stack: Vec<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + '_>> {
+        Box::pin(async move {
+            let mut out = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find(REF_PREFIX) {
+                out.push_str(&rest[..start]);
+                let after = &rest[start + REF_PREFIX.len()..];
+                let end = after.find('}').ok_or_else(|| {
+                    Error::Validation(format!("unterminated reference at '{}'", &rest[start..]))
+                })?;
+                let reference = &after[..end];
+                if stack.iter().any(|seen| seen == reference) {
+                    let mut chain = stack.clone();
+                    chain.push(reference.to_string());
+                    return Err(Error::Validation(format!(
+                        "reference cycle: {}",
+                        chain.join(" -> ")
+                    )));
+                }
+                if stack.len() >= MAX_DEPTH {
+                    return Err(Error::Validation(format!(
+                        "references nested deeper than {} at '{}'",
+                        MAX_DEPTH, reference
+                    )));
+                }
+
+                let value = self.lookup(reference).await?;
+                let value = if has_references(&value) {
+                    let mut stack = stack.clone();
+                    stack.push(reference.to_string());
+                    self.expand(value, stack).await?
+                } else {
+                    value
+                };
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            out.push_str(rest);
+            Ok(out)
+        })
+    }
+
+    /// Scalar a reference points at, rendered as text
+    async fn lookup(&mut self, reference: &str) -> Result<String> {
+        let mut parts = reference.splitn(3, '/');
+        let (namespace, application, key) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(namespace), Some(application), Some(key))
+                if !namespace.is_empty() && !application.is_empty() && !key.is_empty() =>
+            {
+                (namespace, application, key)
+            }
+            _ => {
+                return Err(Error::Validation(format!(
+                    "reference '{}' is not <namespace>/<application>/<key>",
+                    reference
+                )))
+            }
+        };
+
+        let slot = (namespace.to_string(), application.to_string());
+        if !self.documents.contains_key(&slot) {
+            let resolved = crate::resolve::resolve(
+                self.namespaces,
+                self.configs,
+                namespace,
+                application,
+                self.environment,
+                None,
+                None,
+            )
+            .await?;
+            self.documents.insert(slot.clone(), resolved);
+        }
+        let resolved = &self.documents[&slot];
+
+        let value = key
+            .split('.')
+            .try_fold(&resolved.document, |value, segment| value.get(segment))
+            .ok_or_else(|| Error::NotFound(format!("referenced key '{}'", reference)))?;
+        let text = match value {
+            Value::String(value) => value.clone(),
+            Value::Number(value) => value.to_string(),
+            Value::Bool(value) => value.to_string(),
+            _ => {
+                return Err(Error::Validation(format!(
+                    "reference '{}' must point at a string, number or boolean",
+                    reference
+                )))
+            }
+        };
+        if let Some(source) = resolved.provenance.get(key) {
+            if !self.sources.iter().any(|s| s.config_id == source.config_id) {
+                self.sources.push(source.clone());
+            }
+        }
+        Ok(text)
+    }
+}
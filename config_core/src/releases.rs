@@ -0,0 +1,446 @@
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use config_common::{ConfigContent, ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+use crate::gates::{GateResult, ReleaseGate};
+use crate::{ConfigVersionControl, LabelSelector};
+
+/// Which configs serve only published versions, and how many approvals a publish needs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReleasePolicy {
+    /// Namespaces whose new configs have their first version published on creation, so
+    /// later updates stay drafts until released
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// Approvals from users other than the requester before a release is published
+    #[serde(default)]
+    pub required_approvals: usize,
+    /// Offset from UTC of the timezone scheduled times without an offset are read in
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    /// External checks every release must pass before it is published
+    #[serde(default)]
+    pub gates: Vec<ReleaseGate>,
+}
+
+impl ReleasePolicy {
+    pub fn is_managed(&self, namespace: &str) -> bool {
+        self.namespaces.iter().any(|ns| ns == namespace)
+    }
+
+    /// Unix time of a scheduled publish, given in RFC 3339 or as a local
+    /// `YYYY-MM-DDTHH:MM[:SS]` time in the policy's timezone
+    pub fn schedule_time(&self, value: &str) -> Result<i64> {
+        let value = value.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+            return Ok(time.timestamp());
+        }
+        let invalid = || {
+            config_common::Error::Validation(format!(
+                "'{}' is not an RFC 3339 or YYYY-MM-DDTHH:MM time",
+                value
+            ))
+        };
+        let offset = FixedOffset::east_opt(self.utc_offset_minutes * 60).ok_or_else(|| {
+            config_common::Error::Config(format!(
+                "invalid release utc_offset_minutes {}",
+                self.utc_offset_minutes
+            ))
+        })?;
+        let local = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .ok_or_else(invalid)?;
+        offset
+            .from_local_datetime(&local)
+            .single()
+            .map(|time| time.timestamp())
+            .ok_or_else(invalid)
+    }
+}
+
+/// Request to serve a version of a config to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    pub id: String,
+    pub config_id: String,
+    pub version: String,
+    /// Version served when the release was requested
+    pub previous_version: Option<String>,
+    pub change_reason: Option<String>,
+    pub status: ReleaseStatus,
+    pub requested_by: String,
+    pub requested_at: i64,
+    pub required_approvals: usize,
+    pub approvals: Vec<String>,
+    /// Clients served the version while it is rolling out
+    pub rollout: Option<RolloutRule>,
+    /// When an approved release is published
+    pub scheduled_at: Option<i64>,
+    /// Latest results of the release gates
+    pub gate_results: Vec<GateResult>,
+    pub published_at: Option<i64>,
+    /// User who rejected, cancelled or aborted the release
+    pub closed_by: Option<String>,
+    pub closed_at: Option<i64>,
+}
+
+/// Release lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseStatus {
+    /// Waiting for approvals
+    Pending,
+    /// Approved and waiting for its scheduled time
+    Scheduled,
+    /// Served to the clients its rollout rule selects
+    Rolling,
+    Published,
+    Rejected,
+    /// Scheduled and called off before its time
+    Cancelled,
+    /// Stopped by a failed release gate
+    Blocked,
+    /// Withdrawn after being served to some or all clients
+    Aborted,
+}
+
+impl ReleaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseStatus::Pending => "pending",
+            ReleaseStatus::Scheduled => "scheduled",
+            ReleaseStatus::Rolling => "rolling",
+            ReleaseStatus::Published => "published",
+            ReleaseStatus::Rejected => "rejected",
+            ReleaseStatus::Cancelled => "cancelled",
+            ReleaseStatus::Blocked => "blocked",
+            ReleaseStatus::Aborted => "aborted",
+        }
+    }
+}
+
+impl std::str::FromStr for ReleaseStatus {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(ReleaseStatus::Pending),
+            "scheduled" => Ok(ReleaseStatus::Scheduled),
+            "rolling" => Ok(ReleaseStatus::Rolling),
+            "published" => Ok(ReleaseStatus::Published),
+            "rejected" => Ok(ReleaseStatus::Rejected),
+            "cancelled" => Ok(ReleaseStatus::Cancelled),
+            "blocked" => Ok(ReleaseStatus::Blocked),
+            "aborted" => Ok(ReleaseStatus::Aborted),
+            other => Err(config_common::Error::Validation(format!(
+                "unknown release status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Release to record
+#[derive(Debug, Clone, Default)]
+pub struct NewRelease {
+    pub config_id: String,
+    pub version: String,
+    pub change_reason: Option<String>,
+    pub requested_by: String,
+    pub required_approvals: usize,
+    /// Clients served the version until the rollout is finalized; every client when unset
+    pub rollout: Option<RolloutRule>,
+    /// Publish at this time once approved rather than right away
+    pub scheduled_at: Option<i64>,
+    /// Gates checked ahead of an immediate publish; any failure blocks the release
+    pub gate_results: Vec<GateResult>,
+}
+
+/// Declaration that one config must be released before another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigDependency {
+    pub config_id: String,
+    /// Config whose releases must be published before those of `config_id`
+    pub depends_on: String,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+/// Order configs so each comes after the configs among them it depends on, keeping the
+/// given order otherwise
+pub fn dependency_order(
+    config_ids: &[String],
+    dependencies: &[ConfigDependency],
+) -> Result<Vec<String>> {
+    let mut remaining: Vec<&String> = Vec::new();
+    for id in config_ids {
+        if !remaining.contains(&id) {
+            remaining.push(id);
+        }
+    }
+
+    let mut ordered: Vec<String> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|id| {
+            !dependencies.iter().any(|dep| {
+                &dep.config_id == *id
+                    && dep.depends_on != **id
+                    && remaining.contains(&&dep.depends_on)
+            })
+        });
+        match ready {
+            Some(index) => ordered.push(remaining.remove(index).clone()),
+            None => {
+                return Err(config_common::Error::Validation(format!(
+                    "dependency cycle between {}",
+                    remaining
+                        .iter()
+                        .map(|id| id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )))
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+/// Fail when a config depends on configs with releases still in progress
+pub async fn check_release_order(releases: &dyn ReleaseManager, config_id: &str) -> Result<()> {
+    let unreleased = releases.unreleased_dependencies(config_id).await?;
+    if unreleased.is_empty() {
+        return Ok(());
+    }
+    Err(config_common::Error::Validation(format!(
+        "{} must be released before {}",
+        unreleased.join(", "),
+        config_id
+    )))
+}
+
+/// Manager for releases
+#[async_trait]
+pub trait ReleaseManager: Send + Sync {
+    /// Record a release; once it needs no more approvals it is published, or rolled out to
+    /// the clients its rule selects, at its scheduled time if it has one
+    async fn create_release(&self, release: NewRelease) -> Result<Release>;
+
+    async fn get_release(&self, id: &str) -> Result<Release>;
+
+    /// List releases, newest first, optionally of one config or in one status
+    async fn list_releases(
+        &self,
+        config_id: Option<&str>,
+        status: Option<ReleaseStatus>,
+    ) -> Result<Vec<Release>>;
+
+    /// Add an approval to a pending release, publishing or rolling it out once it has enough
+    async fn approve_release(&self, id: &str, approver: &str) -> Result<Release>;
+
+    /// Close a pending release without publishing it
+    async fn reject_release(&self, id: &str, rejected_by: &str) -> Result<Release>;
+
+    /// Record passing gate results of a release
+    async fn record_gates(&self, id: &str, results: Vec<GateResult>) -> Result<Release>;
+
+    /// Stop a pending or scheduled release that failed its gates
+    async fn block_release(&self, id: &str, results: Vec<GateResult>) -> Result<Release>;
+
+    /// Call off a scheduled release
+    async fn cancel_release(&self, id: &str, cancelled_by: &str) -> Result<Release>;
+
+    /// Scheduled releases whose time has come
+    async fn due_releases(&self, now: i64) -> Result<Vec<Release>>;
+
+    /// Publish or start rolling out a scheduled release
+    async fn run_scheduled(&self, id: &str) -> Result<Release>;
+
+    /// Change the clients a rolling release is served to
+    async fn set_rollout(&self, id: &str, rollout: RolloutRule) -> Result<Release>;
+
+    /// Serve a rolling release to every client
+    async fn finalize_release(&self, id: &str) -> Result<Release>;
+
+    /// Withdraw a rolling release, or the released one so clients go back to the release
+    /// before it
+    async fn abort_release(&self, id: &str, aborted_by: &str) -> Result<Release>;
+
+    /// Release of a config currently rolling out, if any
+    async fn rolling_release(&self, config_id: &str) -> Result<Option<Release>>;
+
+    /// Version clients are served; unset for configs never released, which serve their
+    /// latest version
+    async fn released_version(&self, config_id: &str) -> Result<Option<String>>;
+
+    /// Declare that `depends_on` must be released before `config_id`; fails on a cycle
+    async fn add_dependency(
+        &self,
+        config_id: &str,
+        depends_on: &str,
+        created_by: &str,
+    ) -> Result<ConfigDependency>;
+
+    async fn remove_dependency(&self, config_id: &str, depends_on: &str) -> Result<bool>;
+
+    /// Dependencies of a config, or of every config
+    async fn list_dependencies(&self, config_id: Option<&str>) -> Result<Vec<ConfigDependency>>;
+
+    /// Configs a config depends on that have a pending, scheduled, blocked or rolling
+    /// release
+    async fn unreleased_dependencies(&self, config_id: &str) -> Result<Vec<String>>;
+}
+
+/// Clients a rolling release is served to; a client must satisfy every condition set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RolloutRule {
+    /// Share of clients, from 1 to 99, by their client ID
+    #[serde(default)]
+    pub percentage: Option<u8>,
+    /// Condition on the labels clients report, e.g. `labels contains "region=eu"`
+    #[serde(default)]
+    pub labels: Option<LabelSelector>,
+    /// Address ranges clients must connect from, e.g. `10.1.0.0/16`
+    #[serde(default)]
+    pub networks: Vec<String>,
+}
+
+impl RolloutRule {
+    pub fn validate(&self) -> Result<()> {
+        if self.percentage.is_none() && self.labels.is_none() && self.networks.is_empty() {
+            return Err(config_common::Error::Validation(
+                "rollout rule selects every client; publish the release instead".to_string(),
+            ));
+        }
+        if let Some(percentage) = self.percentage {
+            if !(1..=99).contains(&percentage) {
+                return Err(config_common::Error::Validation(
+                    "rollout percentage must be between 1 and 99".to_string(),
+                ));
+            }
+        }
+        for network in &self.networks {
+            parse_network(network)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the rule selects a client of a config
+    pub fn includes(&self, config_id: &str, client: &RolloutClient) -> bool {
+        if let Some(labels) = &self.labels {
+            if !labels.matches(&client.labels) {
+                return false;
+            }
+        }
+        if !self.networks.is_empty() {
+            let Some(address) = client.address else {
+                return false;
+            };
+            let in_network = self.networks.iter().any(|network| {
+                parse_network(network)
+                    .map(|(base, prefix)| network_contains(base, prefix, address))
+                    .unwrap_or(false)
+            });
+            if !in_network {
+                return false;
+            }
+        }
+        match self.percentage {
+            Some(percentage) => client
+                .id
+                .as_deref()
+                .is_some_and(|id| in_rollout(config_id, id, percentage)),
+            None => true,
+        }
+    }
+}
+
+/// What a client tells about itself when fetching configs
+#[derive(Debug, Clone, Default)]
+pub struct RolloutClient {
+    /// Stable identity placing the client in or out of percentage rollouts
+    pub id: Option<String>,
+    /// Labels such as `region=eu` or `cluster=blue`
+    pub labels: Vec<String>,
+    pub address: Option<IpAddr>,
+}
+
+/// Address and prefix length of a `<address>/<prefix>` range; a bare address is a range of one
+fn parse_network(network: &str) -> Result<(IpAddr, u8)> {
+    let invalid =
+        || config_common::Error::Validation(format!("'{}' is not an address range", network));
+    let (address, prefix) = match network.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (network, None),
+    };
+    let address: IpAddr = address.trim().parse().map_err(|_| invalid())?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix.trim().parse().map_err(|_| invalid())?,
+        None => max,
+    };
+    if prefix > max {
+        return Err(invalid());
+    }
+    Ok((address, prefix))
+}
+
+fn network_contains(base: IpAddr, prefix: u8, address: IpAddr) -> bool {
+    let (base, address, bits) = match (base, address) {
+        (IpAddr::V4(base), IpAddr::V4(address)) => {
+            (u32::from(base) as u128, u32::from(address) as u128, 32)
+        }
+        (IpAddr::V6(base), IpAddr::V6(address)) => (u128::from(base), u128::from(address), 128),
+        _ => return false,
+    };
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - u32::from(prefix);
+    base >> shift == address >> shift
+}
+
+/// Whether a client falls within the first `percentage` of clients of a config's rollout;
+/// a client keeps its bucket as the percentage grows
+pub fn in_rollout(config_id: &str, client_id: &str, percentage: u8) -> bool {
+    // FNV-1a, stable across builds unlike the std hasher
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in config_id.bytes().chain([b'/']).chain(client_id.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    (hash % 100) < u64::from(percentage)
+}
+
+/// Config as served to a client: a rolling release when its rule selects the client, the
+/// released version when there is one, the latest otherwise
+pub async fn served(
+    releases: &dyn ReleaseManager,
+    version_control: &dyn ConfigVersionControl,
+    meta: ConfigMeta,
+    content: ConfigContent,
+    client: &RolloutClient,
+) -> Result<(ConfigMeta, ConfigContent)> {
+    let rolling = releases
+        .rolling_release(&meta.id)
+        .await?
+        .filter(|r| {
+            r.rollout
+                .as_ref()
+                .is_some_and(|rule| rule.includes(&meta.id, client))
+        })
+        .map(|r| r.version);
+    let version = match rolling {
+        Some(version) => Some(version),
+        None => releases.released_version(&meta.id).await?,
+    };
+    match version {
+        Some(version) if version != meta.version => {
+            let (_, content) = version_control.get_version(&meta.id, &version).await?;
+            Ok((ConfigMeta { version, ..meta }, content))
+        }
+        _ => Ok((meta, content)),
+    }
+}
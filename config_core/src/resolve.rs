@@ -0,0 +1,189 @@
+use config_common::{ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::releases::RolloutClient;
+use crate::{ConfigFilter, ConfigManager, ConfigVersionControl, NamespaceManager, ReleaseManager};
+
+/// Application holding the defaults of every application
+pub const SHARED_APPLICATION: &str = "application";
+
+/// Environment holding the configs that apply in every environment
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// Layer a resolved value came from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    /// The shared application in the default environment
+    Global,
+    /// The application in the default environment
+    Application,
+    /// The application in the requested environment
+    Environment,
+}
+
+/// Config a resolved key was taken from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeySource {
+    pub layer: Layer,
+    pub config_id: String,
+    pub namespace: String,
+    pub application: String,
+    pub environment: String,
+    pub name: String,
+    pub version: String,
+}
+
+/// Effective document of an application in an environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resolved {
+    pub document: Value,
+    /// Source of each leaf by dotted path; arrays count as a single leaf
+    pub provenance: BTreeMap<String, KeySource>,
+    /// Encrypted configs, which can't be merged and are left out
+    pub skipped: Vec<String>,
+}
+
+/// Releases a client is served resolved configs from
+pub struct Serving<'a> {
+    pub releases: &'a dyn ReleaseManager,
+    pub version_control: &'a dyn ConfigVersionControl,
+    pub client: &'a RolloutClient,
+}
+
+/// Merge the global defaults, the application's defaults and its environment overrides,
+/// later layers winning. Each layer holds the configs visible in the namespace, including
+/// inherited ones, merged in name order; `name` limits them to configs of that name. With
+/// `serving`, each config is taken at the version the client is served rather than its latest.
+pub async fn resolve(
+    namespaces: &dyn NamespaceManager,
+    configs: &dyn ConfigManager,
+    namespace: &str,
+    application: &str,
+    environment: &str,
+    name: Option<&str>,
+    serving: Option<&Serving<'_>>,
+) -> Result<Resolved> {
+    let mut layers = vec![(Layer::Global, SHARED_APPLICATION, DEFAULT_ENVIRONMENT)];
+    if application != SHARED_APPLICATION {
+        layers.push((Layer::Application, application, DEFAULT_ENVIRONMENT));
+    }
+    if environment != DEFAULT_ENVIRONMENT {
+        layers.push((Layer::Environment, application, environment));
+    }
+
+    let mut resolved = Resolved {
+        document: Value::Object(Default::default()),
+        provenance: BTreeMap::new(),
+        skipped: Vec::new(),
+    };
+    for (layer, application, environment) in layers {
+        let filter = ConfigFilter {
+            application: Some(application.to_string()),
+            environment: Some(environment.to_string()),
+            ..Default::default()
+        };
+        let mut metas: Vec<ConfigMeta> =
+            crate::namespaces::inherited_configs(namespaces, configs, namespace, filter)
+                .await?
+                .into_iter()
+                .filter(|meta| name.is_none_or(|name| meta.name == name))
+                .collect();
+        metas.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for meta in metas {
+            let (meta, content) = configs.get_config(&meta.id).await?;
+            let (meta, content) = match serving {
+                Some(serving) => {
+                    crate::releases::served(
+                        serving.releases,
+                        serving.version_control,
+                        meta,
+                        content,
+                        serving.client,
+                    )
+                    .await?
+                }
+                None => (meta, content),
+            };
+            if content.is_encrypted {
+                resolved.skipped.push(meta.id);
+                continue;
+            }
+            let document = crate::format::parse(content.format, &content.content)
+                .map_err(config_common::Error::InvalidContent)?;
+            let source = KeySource {
+                layer,
+                config_id: meta.id,
+                namespace: meta.namespace,
+                application: meta.application,
+                environment: meta.environment,
+                name: meta.name,
+                version: meta.version,
+            };
+            merge(
+                &mut resolved.document,
+                document,
+                "",
+                &source,
+                &mut resolved.provenance,
+            );
+        }
+    }
+    Ok(resolved)
+}
+
+/// Deep-merge `overlay` into `base` at `path`; tables merge key by key, anything else
+/// replaces what was there
+fn merge(
+    base: &mut Value,
+    overlay: Value,
+    path: &str,
+    source: &KeySource,
+    provenance: &mut BTreeMap<String, KeySource>,
+) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                let entry = base.entry(key.clone()).or_insert(Value::Null);
+                merge(entry, value, &join(&key), source, provenance);
+            }
+        }
+        // A bare scalar document has no key to merge into
+        (_, overlay) if path.is_empty() && !overlay.is_object() => {}
+        (base, overlay) => {
+            let nested = format!("{}.", path);
+            provenance.retain(|key, _| key != path && !key.starts_with(&nested));
+            record(&overlay, path, source, provenance);
+            *base = overlay;
+        }
+    }
+}
+
+/// Attribute every leaf of a value placed at `path` to `source`
+fn record(
+    value: &Value,
+    path: &str,
+    source: &KeySource,
+    provenance: &mut BTreeMap<String, KeySource>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                record(value, &format!("{}.{}", path, key), source, provenance);
+            }
+        }
+        _ => {
+            provenance.insert(path.to_string(), source.clone());
+        }
+    }
+}
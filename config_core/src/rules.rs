@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ContentError, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{format, ConfigValidator, ValidationContext};
+
+/// Check applied to one key of a configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRule {
+    /// Dotted path of the key, e.g. `server.port`
+    pub key: String,
+    #[serde(flatten)]
+    pub validator: ValidatorType,
+    /// Reported instead of the default message when the rule fails
+    pub message: Option<String>,
+}
+
+/// Kinds of rule the engine evaluates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ValidatorType {
+    /// The key must be present and not null
+    Required,
+    /// The value, as text, must match a regular expression
+    Format { pattern: String },
+    /// The value must be a number within the bounds
+    Range { min: Option<f64>, max: Option<f64> },
+    /// A named built-in check
+    Custom { check: CustomCheck },
+}
+
+/// Built-in checks for common value shapes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomCheck {
+    Boolean,
+    Integer,
+    Port,
+    Ip,
+    Url,
+    Email,
+}
+
+impl CustomCheck {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            CustomCheck::Boolean => matches!(text, "true" | "false"),
+            CustomCheck::Integer => text.parse::<i64>().is_ok(),
+            CustomCheck::Port => text.parse::<u16>().is_ok_and(|port| port > 0),
+            CustomCheck::Ip => text.parse::<std::net::IpAddr>().is_ok(),
+            CustomCheck::Url => text
+                .split_once("://")
+                .is_some_and(|(scheme, rest)| !scheme.is_empty() && !rest.is_empty()),
+            CustomCheck::Email => text
+                .split_once('@')
+                .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.')),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            CustomCheck::Boolean => "boolean",
+            CustomCheck::Integer => "integer",
+            CustomCheck::Port => "port",
+            CustomCheck::Ip => "ip",
+            CustomCheck::Url => "url",
+            CustomCheck::Email => "email",
+        }
+    }
+}
+
+/// Storage for the validation rules of each configuration
+#[async_trait]
+pub trait ValidationRuleManager: Send + Sync {
+    /// Rules of a configuration, empty when none are set
+    async fn get_rules(&self, config_id: &str) -> Result<Vec<ValidationRule>>;
+
+    /// Replace the rules of a configuration
+    async fn set_rules(
+        &self,
+        config_id: &str,
+        rules: Vec<ValidationRule>,
+        updated_by: &str,
+    ) -> Result<()>;
+}
+
+/// Reject rules that can never be evaluated, such as malformed patterns
+pub fn check_rules(rules: &[ValidationRule]) -> Result<()> {
+    for rule in rules {
+        if rule.key.is_empty() {
+            return Err(config_common::Error::Validation(
+                "rule has an empty key".to_string(),
+            ));
+        }
+        match &rule.validator {
+            ValidatorType::Format { pattern } => {
+                regex::Regex::new(pattern).map_err(|e| {
+                    config_common::Error::Validation(format!(
+                        "invalid pattern for {}: {}",
+                        rule.key, e
+                    ))
+                })?;
+            }
+            ValidatorType::Range {
+                min: Some(min),
+                max: Some(max),
+            } if min > max => {
+                return Err(config_common::Error::Validation(format!(
+                    "empty range for {}: {} > {}",
+                    rule.key, min, max
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Failed rules as `<key>: <message>`
+pub fn evaluate(rules: &[ValidationRule], value: &serde_json::Value) -> Vec<String> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let failure = evaluate_rule(rule, lookup(value, &rule.key))?;
+            Some(format!(
+                "{}: {}",
+                rule.key,
+                rule.message.clone().unwrap_or(failure)
+            ))
+        })
+        .collect()
+}
+
+/// Default message when the rule fails, `None` when it passes
+fn evaluate_rule(rule: &ValidationRule, value: Option<&serde_json::Value>) -> Option<String> {
+    let value = match value {
+        Some(serde_json::Value::Null) | None => {
+            return matches!(rule.validator, ValidatorType::Required)
+                .then(|| "is required".to_string());
+        }
+        Some(value) => value,
+    };
+    let text = match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    match &rule.validator {
+        ValidatorType::Required => None,
+        ValidatorType::Format { pattern } => match regex::Regex::new(pattern) {
+            Ok(re) if re.is_match(&text) => None,
+            Ok(_) => Some(format!("does not match {}", pattern)),
+            Err(e) => Some(format!("invalid pattern: {}", e)),
+        },
+        ValidatorType::Range { min, max } => {
+            let number = match value {
+                serde_json::Value::Number(n) => n.as_f64(),
+                _ => text.trim().parse::<f64>().ok(),
+            };
+            match number {
+                None => Some("is not a number".to_string()),
+                Some(n) if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) => {
+                    Some(format!(
+                        "{} is outside {}..{}",
+                        n,
+                        min.map(|m| m.to_string()).unwrap_or_default(),
+                        max.map(|m| m.to_string()).unwrap_or_default()
+                    ))
+                }
+                Some(_) => None,
+            }
+        }
+        ValidatorType::Custom { check } => {
+            (!check.matches(&text)).then(|| format!("is not a valid {}", check.as_str()))
+        }
+    }
+}
+
+/// Value at a dotted path; flat documents such as properties match the whole key first
+fn lookup<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    if let Some(found) = value.get(key) {
+        return Some(found);
+    }
+    key.split('.')
+        .try_fold(value, |current, part| match current {
+            serde_json::Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+            _ => current.get(part),
+        })
+}
+
+/// Validates content against the rules set on its configuration
+pub struct RuleValidator {
+    rules: Arc<dyn ValidationRuleManager>,
+}
+
+impl RuleValidator {
+    pub fn new(rules: Arc<dyn ValidationRuleManager>) -> Self {
+        Self { rules }
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for RuleValidator {
+    async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        // New configurations have no rules yet
+        let Some(config_id) = &ctx.config_id else {
+            return Ok(());
+        };
+        if content.is_encrypted {
+            return Ok(());
+        }
+        let rules = self.rules.get_rules(config_id).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let value = format::parse(content.format, &content.content)
+            .map_err(config_common::Error::InvalidContent)?;
+        let violations = evaluate(&rules, &value);
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let mut err = ContentError::new(
+            content.format,
+            format!("content violates {} validation rule(s)", violations.len()),
+        );
+        err.violations = violations;
+        Err(config_common::Error::InvalidContent(err))
+    }
+}
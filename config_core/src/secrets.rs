@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ConfigMeta, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{format, ConfigEncryption};
+
+/// Start of every encrypted value, whole documents and single fields alike
+pub const CIPHERTEXT_PREFIX: &str = "enc:";
+
+/// Shown instead of secret values to readers who may not decrypt them
+pub const REDACTED: &str = "***";
+
+/// Storage for the paths of each configuration whose values are secret
+#[async_trait]
+pub trait SecretPathManager: Send + Sync {
+    /// Dotted paths of the secret values of a configuration, empty when none are set
+    async fn get_secret_paths(&self, config_id: &str) -> Result<Vec<String>>;
+
+    /// Replace the secret paths of a configuration
+    async fn set_secret_paths(
+        &self,
+        config_id: &str,
+        paths: Vec<String>,
+        updated_by: &str,
+    ) -> Result<()>;
+}
+
+/// Expiry or rotation-due date of the secrets of a configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretExpiry {
+    pub config_id: String,
+    pub expires_at: i64,
+    pub updated_at: i64,
+    pub updated_by: String,
+}
+
+/// Storage for the expiry dates of encrypted configurations
+#[async_trait]
+pub trait SecretExpiryManager: Send + Sync {
+    async fn get_expiry(&self, config_id: &str) -> Result<Option<SecretExpiry>>;
+
+    /// Set the expiry date of a configuration, or clear it with `None`
+    async fn set_expiry(
+        &self,
+        config_id: &str,
+        expires_at: Option<i64>,
+        updated_by: &str,
+    ) -> Result<()>;
+
+    /// Expiry dates at or before the given time, soonest first
+    async fn list_expiring(&self, before: i64) -> Result<Vec<SecretExpiry>>;
+}
+
+/// When secrets count as near expiry and how often the reminder job runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretExpiryConfig {
+    #[serde(default = "default_warn_days")]
+    pub warn_days: u32,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_warn_days() -> u32 {
+    14
+}
+
+fn default_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for SecretExpiryConfig {
+    fn default() -> Self {
+        Self {
+            warn_days: default_warn_days(),
+            interval_secs: default_interval_secs(),
+        }
+    }
+}
+
+impl SecretExpiryConfig {
+    /// Expiry dates up to this time are reported as near expiry
+    pub fn warn_until(&self, now: i64) -> i64 {
+        now + i64::from(self.warn_days) * 24 * 60 * 60
+    }
+}
+
+/// Single-use link letting its holder read the decrypted content of a configuration once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretShare {
+    pub id: String,
+    pub config_id: String,
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub created_by: String,
+    pub consumed_at: Option<i64>,
+}
+
+/// Storage for one-time secret shares; only a hash of each token is kept
+#[async_trait]
+pub trait SecretShareManager: Send + Sync {
+    /// Create a share valid until `expires_at`, returned with its token
+    async fn create_share(
+        &self,
+        config_id: &str,
+        expires_at: i64,
+        created_by: &str,
+    ) -> Result<(SecretShare, String)>;
+
+    /// Share of a token without using it; fails when unknown, expired or already used
+    async fn get_share(&self, token: &str) -> Result<SecretShare>;
+
+    /// Mark the share of a token as used; fails when unknown, expired or already used
+    async fn consume_share(&self, token: &str) -> Result<SecretShare>;
+}
+
+/// Authorization and auditing of reads of decrypted content
+#[async_trait]
+pub trait SecretAccessControl: Send + Sync {
+    /// Fail unless the reader may see the decrypted content of the configuration
+    async fn authorize_read(&self, reader: &str, meta: &ConfigMeta) -> Result<()>;
+
+    /// Record that the reader decrypted content protected by the given master keys
+    async fn record_read(&self, reader: &str, meta: &ConfigMeta, key_ids: &[String]) -> Result<()>;
+}
+
+/// Master keys protecting the content or its encrypted values
+pub fn key_ids(content: &ConfigContent, encryption: &dyn ConfigEncryption) -> Vec<String> {
+    let mut ids: Vec<String> = if content.is_encrypted {
+        encryption.key_id(&content.content).into_iter().collect()
+    } else if let Ok(mut value) = parse(content) {
+        let mut sealed = Vec::new();
+        collect_sealed(&mut value, &mut sealed);
+        sealed
+            .iter()
+            .filter_map(|field| field.as_str().and_then(|s| encryption.key_id(s)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Whether structured content may hold encrypted values
+pub fn has_secret_fields(content: &ConfigContent) -> bool {
+    !content.is_encrypted && content.content.contains(CIPHERTEXT_PREFIX)
+}
+
+/// Encrypt the values at `paths`, leaving the rest of the document readable
+pub async fn encrypt_fields(
+    content: ConfigContent,
+    paths: &[String],
+    encryption: &dyn ConfigEncryption,
+) -> Result<ConfigContent> {
+    let mut value = parse(&content)?;
+    let mut changed = false;
+    for path in paths {
+        let Some(field) = lookup_mut(&mut value, path) else {
+            continue;
+        };
+        match field {
+            Value::Null => continue,
+            // Already sealed, e.g. content patched from its stored form
+            Value::String(s) if s.starts_with(CIPHERTEXT_PREFIX) => continue,
+            Value::String(s) if is_redacted(s) => {
+                return Err(config_common::Error::Validation(format!(
+                    "secret {} is redacted; send its plaintext value",
+                    path
+                )));
+            }
+            _ => {}
+        }
+        // Encrypt the JSON encoding so non-string values keep their type
+        let plaintext = serde_json::to_string(field)?;
+        *field = Value::String(encryption.encrypt(&plaintext).await?);
+        changed = true;
+    }
+
+    if !changed {
+        return Ok(content);
+    }
+    render(&content, &value)
+}
+
+/// Decrypt every encrypted value of a document
+pub async fn decrypt_fields(
+    content: ConfigContent,
+    encryption: &dyn ConfigEncryption,
+) -> Result<ConfigContent> {
+    if !has_secret_fields(&content) {
+        return Ok(content);
+    }
+    let mut value = parse(&content)?;
+    let mut sealed = Vec::new();
+    collect_sealed(&mut value, &mut sealed);
+    for field in sealed {
+        let Value::String(ciphertext) = field else {
+            continue;
+        };
+        let plaintext = encryption.decrypt(ciphertext).await?;
+        *field = serde_json::from_str(&plaintext)?;
+    }
+    render(&content, &value)
+}
+
+/// Encrypt again, under the active master key, content or values stored encrypted
+pub async fn reencrypt(
+    content: ConfigContent,
+    encryption: &dyn ConfigEncryption,
+) -> Result<ConfigContent> {
+    if content.is_encrypted {
+        let plaintext = encryption.decrypt(&content.content).await?;
+        return Ok(ConfigContent {
+            content: encryption.encrypt(&plaintext).await?,
+            ..content
+        });
+    }
+    if !has_secret_fields(&content) {
+        return Ok(content);
+    }
+    let mut value = parse(&content)?;
+    let mut sealed = Vec::new();
+    collect_sealed(&mut value, &mut sealed);
+    for field in sealed {
+        let Value::String(ciphertext) = field else {
+            continue;
+        };
+        let plaintext = encryption.decrypt(ciphertext).await?;
+        *ciphertext = encryption.encrypt(&plaintext).await?;
+    }
+    render(&content, &value)
+}
+
+/// Replace encrypted content, or every encrypted value of a document, with `***` followed
+/// by the checksum of the plaintext when the encryption records one
+pub fn redact(
+    content: ConfigContent,
+    encryption: Option<&dyn ConfigEncryption>,
+) -> Result<ConfigContent> {
+    // Only clients hold the keys, so their ciphertext is what readers need
+    if crate::recipients::is_client_sealed(&content) {
+        return Ok(content);
+    }
+    if content.is_encrypted {
+        return Ok(ConfigContent {
+            content: placeholder(&content.content, encryption),
+            ..content
+        });
+    }
+    if !has_secret_fields(&content) {
+        return Ok(content);
+    }
+    let mut value = parse(&content)?;
+    let mut sealed = Vec::new();
+    collect_sealed(&mut value, &mut sealed);
+    for field in sealed {
+        let redacted = placeholder(field.as_str().unwrap_or_default(), encryption);
+        *field = Value::String(redacted);
+    }
+    render(&content, &value)
+}
+
+/// Whether a value was redacted by [`redact`]
+pub fn is_redacted(value: &str) -> bool {
+    value == REDACTED || value.starts_with(&format!("{}:", REDACTED))
+}
+
+fn placeholder(ciphertext: &str, encryption: Option<&dyn ConfigEncryption>) -> String {
+    match encryption.and_then(|encryption| encryption.checksum(ciphertext)) {
+        Some(checksum) => format!("{}:{}", REDACTED, checksum),
+        None => REDACTED.to_string(),
+    }
+}
+
+fn parse(content: &ConfigContent) -> Result<Value> {
+    format::parse(content.format, &content.content).map_err(config_common::Error::InvalidContent)
+}
+
+fn render(content: &ConfigContent, value: &Value) -> Result<ConfigContent> {
+    let text = format::render(content.format, value).map_err(|e| {
+        config_common::Error::Internal(format!("render {}: {}", content.format.as_str(), e))
+    })?;
+    Ok(ConfigContent {
+        format: content.format,
+        content: text,
+        is_encrypted: false,
+    })
+}
+
+/// Every string value starting with the ciphertext prefix
+fn collect_sealed<'a>(value: &'a mut Value, sealed: &mut Vec<&'a mut Value>) {
+    if value
+        .as_str()
+        .is_some_and(|s| s.starts_with(CIPHERTEXT_PREFIX))
+    {
+        sealed.push(value);
+        return;
+    }
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|v| collect_sealed(v, sealed)),
+        Value::Object(map) => map.values_mut().for_each(|v| collect_sealed(v, sealed)),
+        _ => {}
+    }
+}
+
+/// Value at a dotted path; flat documents such as properties match the whole key first
+fn lookup_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    if value.get(path).is_some() {
+        return value.get_mut(path);
+    }
+    path.split('.')
+        .try_fold(value, |current, part| match current {
+            Value::Array(items) => items.get_mut(part.parse::<usize>().ok()?),
+            _ => current.get_mut(part),
+        })
+}
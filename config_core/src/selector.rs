@@ -0,0 +1,88 @@
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Condition on the labels of a configuration, written as clauses joined by `and`, e.g.
+/// `labels contains "feature-flag" and labels not contains "deprecated"`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LabelSelector {
+    /// Labels that must all be present
+    pub contains: Vec<String>,
+    /// Labels that must all be absent
+    pub excludes: Vec<String>,
+}
+
+impl LabelSelector {
+    pub fn matches(&self, labels: &[String]) -> bool {
+        self.contains.iter().all(|label| labels.contains(label))
+            && !self.excludes.iter().any(|label| labels.contains(label))
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = config_common::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut selector = LabelSelector::default();
+        for clause in s.split(" and ") {
+            let words: Vec<&str> = clause.split_whitespace().collect();
+            let (negated, label) = match words.as_slice() {
+                ["labels", "contains", label] => (false, label),
+                ["labels", "not", "contains", label] => (true, label),
+                _ => {
+                    return Err(config_common::Error::Validation(format!(
+                        "invalid label selector clause: {}",
+                        clause.trim()
+                    )))
+                }
+            };
+            let label = label
+                .strip_prefix('"')
+                .and_then(|label| label.strip_suffix('"'))
+                .unwrap_or(label);
+            if label.is_empty() {
+                return Err(config_common::Error::Validation(format!(
+                    "empty label in selector clause: {}",
+                    clause.trim()
+                )));
+            }
+            if negated {
+                selector.excludes.push(label.to_string());
+            } else {
+                selector.contains.push(label.to_string());
+            }
+        }
+        Ok(selector)
+    }
+}
+
+impl TryFrom<String> for LabelSelector {
+    type Error = config_common::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<LabelSelector> for String {
+    fn from(selector: LabelSelector) -> Self {
+        selector.to_string()
+    }
+}
+
+impl fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let contains = self
+            .contains
+            .iter()
+            .map(|label| format!("labels contains \"{}\"", label));
+        let excludes = self
+            .excludes
+            .iter()
+            .map(|label| format!("labels not contains \"{}\"", label));
+        let clauses: Vec<String> = contains.chain(excludes).collect();
+        write!(f, "{}", clauses.join(" and "))
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long computed namespace stats are served before being recomputed
+pub const STATS_TTL: Duration = Duration::from_secs(60);
+
+/// Editors listed in namespace stats
+pub const TOP_EDITORS: usize = 5;
+
+/// Size and recent activity of a namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceStats {
+    pub namespace: String,
+    pub config_count: i64,
+    /// Stored size of the current content, after compression
+    pub storage_bytes: i64,
+    pub writes_24h: i64,
+    pub writes_7d: i64,
+    /// Users with the most writes over the last 7 days, most first
+    pub top_editors: Vec<EditorActivity>,
+    /// Unset when the namespace has no configs
+    pub last_change_at: Option<i64>,
+    pub computed_at: i64,
+}
+
+/// Writes by one user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditorActivity {
+    pub user: String,
+    pub writes: i64,
+}
+
+/// Source of namespace stats
+#[async_trait]
+pub trait NamespaceStatsProvider: Send + Sync {
+    async fn namespace_stats(&self, namespace: &str) -> Result<NamespaceStats>;
+}
+
+/// Serves stats computed in the last [`STATS_TTL`] instead of recomputing them
+pub struct CachedNamespaceStats {
+    inner: Arc<dyn NamespaceStatsProvider>,
+    entries: Mutex<HashMap<String, (NamespaceStats, Instant)>>,
+}
+
+impl CachedNamespaceStats {
+    pub fn new(inner: Arc<dyn NamespaceStatsProvider>) -> Self {
+        Self {
+            inner,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl NamespaceStatsProvider for CachedNamespaceStats {
+    async fn namespace_stats(&self, namespace: &str) -> Result<NamespaceStats> {
+        if let Some((stats, expires_at)) = self.entries.lock().await.get(namespace) {
+            if Instant::now() < *expires_at {
+                return Ok(stats.clone());
+            }
+        }
+
+        let stats = self.inner.namespace_stats(namespace).await?;
+        let mut entries = self.entries.lock().await;
+        let now = Instant::now();
+        entries.retain(|_, (_, expires_at)| now < *expires_at);
+        entries.insert(namespace.to_string(), (stats.clone(), now + STATS_TTL));
+        Ok(stats)
+    }
+}
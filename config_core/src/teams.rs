@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{ConfigValidator, ValidationContext};
+
+/// Prefix of the policy group each team's members belong to
+pub const TEAM_ROLE_PREFIX: &str = "team:";
+
+/// Policy group of a team's members, e.g. `team:payments`
+pub fn team_role(name: &str) -> String {
+    format!("{}{}", TEAM_ROLE_PREFIX, name)
+}
+
+/// Department owning configs, with the users belonging to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<String>,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+/// Whether configs may only name registered teams as their department
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TeamPolicy {
+    #[serde(default)]
+    pub require_registered: bool,
+}
+
+/// Manager for teams and their membership
+#[async_trait]
+pub trait TeamManager: Send + Sync {
+    async fn create_team(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<Team>;
+
+    async fn get_team(&self, name: &str) -> Result<Team>;
+
+    async fn list_teams(&self) -> Result<Vec<Team>>;
+
+    /// Remove a team and its membership
+    async fn delete_team(&self, name: &str) -> Result<bool>;
+
+    /// Add a member; adding an existing member changes nothing
+    async fn add_member(&self, team: &str, user: &str, added_by: &str) -> Result<Team>;
+
+    async fn remove_member(&self, team: &str, user: &str) -> Result<bool>;
+}
+
+/// Rejects new configs whose department is not a registered team
+pub struct DepartmentValidator {
+    teams: Arc<dyn TeamManager>,
+}
+
+impl DepartmentValidator {
+    pub fn new(teams: Arc<dyn TeamManager>) -> Self {
+        Self { teams }
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for DepartmentValidator {
+    async fn validate(&self, ctx: &ValidationContext, _content: &ConfigContent) -> Result<()> {
+        // The department is fixed at creation
+        if ctx.config_id.is_some() {
+            return Ok(());
+        }
+        match self.teams.get_team(&ctx.department).await {
+            Ok(_) => Ok(()),
+            Err(config_common::Error::NotFound(_)) => Err(config_common::Error::Validation(
+                format!("department {} is not a registered team", ctx.department),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
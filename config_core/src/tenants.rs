@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::ConfigEncryption;
+
+/// Department whose configs live in a Postgres schema of their own, optionally encrypted
+/// under a master key of their own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tenant {
+    /// Department the tenant's configs belong to
+    pub name: String,
+    pub schema: String,
+    /// Master key encrypting the tenant's content; the active key when unset
+    pub key_id: Option<String>,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+/// Manager for isolated tenants
+#[async_trait]
+pub trait TenantManager: Send + Sync {
+    /// Create the schema of a department without configs yet and route its configs there
+    async fn provision_tenant(
+        &self,
+        name: &str,
+        key_id: Option<&str>,
+        created_by: &str,
+    ) -> Result<Tenant>;
+
+    async fn get_tenant(&self, name: &str) -> Result<Tenant>;
+
+    async fn list_tenants(&self) -> Result<Vec<Tenant>>;
+}
+
+/// Encryption under a fixed master key rather than the active one
+pub struct KeyedEncryption {
+    inner: Arc<dyn ConfigEncryption>,
+    key_id: String,
+}
+
+impl KeyedEncryption {
+    pub fn new(inner: Arc<dyn ConfigEncryption>, key_id: &str) -> Self {
+        Self {
+            inner,
+            key_id: key_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConfigEncryption for KeyedEncryption {
+    async fn encrypt(&self, content: &str) -> Result<String> {
+        self.inner.encrypt_with_key(content, &self.key_id).await
+    }
+
+    async fn decrypt(&self, content: &str) -> Result<String> {
+        self.inner.decrypt(content).await
+    }
+
+    async fn encrypt_with_key(&self, content: &str, key_id: &str) -> Result<String> {
+        self.inner.encrypt_with_key(content, key_id).await
+    }
+
+    fn key_id(&self, ciphertext: &str) -> Option<String> {
+        self.inner.key_id(ciphertext)
+    }
+
+    fn checksum(&self, ciphertext: &str) -> Option<String> {
+        self.inner.checksum(ciphertext)
+    }
+}
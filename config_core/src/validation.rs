@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ContentError, Result};
+use std::sync::Arc;
+
+use crate::{format, ConfigSchema, ConfigValidator, SchemaManager, ValidationContext};
+
+/// Rejects content that doesn't parse as its declared format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FormatValidator;
+
+#[async_trait]
+impl ConfigValidator for FormatValidator {
+    async fn validate(&self, _ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        // Ciphertext can't be parsed; it is checked before encryption
+        if content.is_encrypted {
+            return Ok(());
+        }
+        format::parse(content.format, &content.content)
+            .map(|_| ())
+            .map_err(config_common::Error::InvalidContent)
+    }
+}
+
+/// Rejects content larger than a configured number of bytes
+#[derive(Debug, Clone, Copy)]
+pub struct SizeLimitValidator {
+    max_bytes: usize,
+}
+
+impl SizeLimitValidator {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for SizeLimitValidator {
+    async fn validate(&self, _ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        if content.content.len() > self.max_bytes {
+            return Err(config_common::Error::Validation(format!(
+                "content is {} bytes, more than the limit of {}",
+                content.content.len(),
+                self.max_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Validates content against the schemas attached to its namespace and application
+pub struct SchemaValidator {
+    schemas: Arc<dyn SchemaManager>,
+}
+
+impl SchemaValidator {
+    pub fn new(schemas: Arc<dyn SchemaManager>) -> Self {
+        Self { schemas }
+    }
+}
+
+#[async_trait]
+impl ConfigValidator for SchemaValidator {
+    async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        if content.is_encrypted {
+            return Ok(());
+        }
+        let schemas = self
+            .schemas
+            .schemas_for(&ctx.namespace, &ctx.application)
+            .await?;
+        if schemas.is_empty() {
+            return Ok(());
+        }
+
+        let value = format::parse(content.format, &content.content)
+            .map_err(config_common::Error::InvalidContent)?;
+        for schema in &schemas {
+            let violations = check_schema(schema, &value)?;
+            if !violations.is_empty() {
+                let mut err = ContentError::new(
+                    content.format,
+                    format!("content does not match schema {}", schema.id),
+                );
+                err.violations = violations;
+                return Err(config_common::Error::InvalidContent(err));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reject a schema document that isn't a valid JSON Schema
+pub fn compile_schema(schema: &serde_json::Value) -> Result<jsonschema::Validator> {
+    jsonschema::validator_for(schema)
+        .map_err(|e| config_common::Error::Validation(format!("invalid schema: {}", e)))
+}
+
+/// Violations of `schema` by `value`, each as `<instance path>: <message>`
+fn check_schema(schema: &ConfigSchema, value: &serde_json::Value) -> Result<Vec<String>> {
+    let validator = compile_schema(&schema.schema)?;
+    Ok(validator
+        .iter_errors(value)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            let path = if path.is_empty() { "/" } else { path.as_str() };
+            format!("{}: {}", path, e)
+        })
+        .collect())
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use config_common::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::model::DataKeyCacheConfig;
+use crate::provider::{DataKey, KeyProvider};
+
+/// Wrapped data key and the master key that wrapped it
+type CacheKey = (String, Vec<u8>);
+
+/// Keeps unwrapped data keys for a while so hot reads don't call the KMS every time
+pub struct CachingKeyProvider {
+    inner: Arc<dyn KeyProvider>,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<CacheKey, (Vec<u8>, Instant)>>,
+}
+
+impl CachingKeyProvider {
+    pub fn new(inner: Arc<dyn KeyProvider>, config: &DataKeyCacheConfig) -> Self {
+        Self {
+            inner,
+            ttl: Duration::from_secs(config.ttl_secs),
+            max_entries: config.max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl KeyProvider for CachingKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.inner.active_key_id()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        self.inner.set_active_key(key_id)
+    }
+
+    async fn rotate_key(&self) -> Result<String> {
+        self.inner.rotate_key().await
+    }
+
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey> {
+        self.inner.generate_data_key_with(key_id).await
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let cache_key = (key_id.to_string(), wrapped.to_vec());
+        if let Some((key, expires_at)) = self.entries.lock().await.get(&cache_key) {
+            if Instant::now() < *expires_at {
+                return Ok(key.clone());
+            }
+        }
+
+        let key = self.inner.unwrap_data_key(key_id, wrapped).await?;
+        if self.max_entries > 0 {
+            let mut entries = self.entries.lock().await;
+            let now = Instant::now();
+            entries.retain(|_, (_, expires_at)| now < *expires_at);
+            if entries.len() >= self.max_entries {
+                if let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, (_, expires_at))| *expires_at)
+                    .map(|(k, _)| k.clone())
+                {
+                    entries.remove(&oldest);
+                }
+            }
+            entries.insert(cache_key, (key.clone(), now + self.ttl));
+        }
+        Ok(key)
+    }
+}
@@ -0,0 +1,170 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::Result;
+use config_core::ConfigEncryption;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+
+use crate::provider::{DataKey, KeyProvider};
+
+/// Prefix of stored ciphertext, naming the envelope version; extends `CIPHERTEXT_PREFIX`
+const ENVELOPE_PREFIX: &str = "enc:v1:";
+
+/// Encrypted content with everything needed to decrypt it except the master key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    /// Master key that wrapped the data key
+    pub key_id: String,
+    /// Base64 data key wrapped by the master key
+    pub wrapped_key: String,
+    /// Base64 random nonce of the content encryption
+    pub nonce: String,
+    /// Base64 AES-256-GCM ciphertext
+    pub ciphertext: String,
+    /// Truncated HMAC-SHA256 of the plaintext under the server's checksum key, shown in place
+    /// of redacted values. Envelopes sealed before it was keyed carry a plain `checksum`,
+    /// which is ignored as low-entropy secrets could be guessed from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+}
+
+impl Envelope {
+    /// Parse stored ciphertext
+    pub fn decode(stored: &str) -> Result<Self> {
+        let encoded = stored.strip_prefix(ENVELOPE_PREFIX).ok_or_else(|| {
+            config_common::Error::Validation("content is not an encryption envelope".to_string())
+        })?;
+        let json = b64_decode(encoded)?;
+        serde_json::from_slice(&json).map_err(|e| {
+            config_common::Error::Validation(format!("malformed encryption envelope: {}", e))
+        })
+    }
+
+    /// Serialize for storage
+    pub fn encode(&self) -> Result<String> {
+        let json = serde_json::to_vec(self)?;
+        Ok(format!("{}{}", ENVELOPE_PREFIX, b64_encode(&json)))
+    }
+}
+
+/// Envelope encryption: a fresh data key and nonce per encryption, wrapped by a master key
+pub struct EnvelopeEncryption {
+    provider: Arc<dyn KeyProvider>,
+    checksum_key: Option<Vec<u8>>,
+}
+
+impl EnvelopeEncryption {
+    pub fn new(provider: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            provider,
+            checksum_key: None,
+        }
+    }
+
+    /// Record a checksum of the plaintext keyed with this secret in every envelope
+    pub fn with_checksum_key(mut self, key: &str) -> Self {
+        self.checksum_key = Some(key.as_bytes().to_vec());
+        self
+    }
+
+    pub fn provider(&self) -> &Arc<dyn KeyProvider> {
+        &self.provider
+    }
+
+    /// Encrypt content with a data key into a stored envelope
+    fn seal(&self, data_key: DataKey, content: &str) -> Result<String> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key.plaintext));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, content.as_bytes())
+            .map_err(|e| config_common::Error::Internal(format!("encrypt content: {}", e)))?;
+
+        Envelope {
+            key_id: data_key.key_id,
+            wrapped_key: b64_encode(&data_key.wrapped),
+            nonce: b64_encode(&nonce),
+            ciphertext: b64_encode(&ciphertext),
+            mac: self
+                .checksum_key
+                .as_deref()
+                .map(|key| checksum(key, content)),
+        }
+        .encode()
+    }
+}
+
+#[async_trait]
+impl ConfigEncryption for EnvelopeEncryption {
+    async fn encrypt(&self, content: &str) -> Result<String> {
+        let data_key = self.provider.generate_data_key().await?;
+        self.seal(data_key, content)
+    }
+
+    async fn encrypt_with_key(&self, content: &str, key_id: &str) -> Result<String> {
+        let data_key = self.provider.generate_data_key_with(key_id).await?;
+        self.seal(data_key, content)
+    }
+
+    fn key_id(&self, ciphertext: &str) -> Option<String> {
+        Envelope::decode(ciphertext)
+            .ok()
+            .map(|envelope| envelope.key_id)
+    }
+
+    fn checksum(&self, ciphertext: &str) -> Option<String> {
+        Envelope::decode(ciphertext).ok()?.mac
+    }
+
+    async fn decrypt(&self, content: &str) -> Result<String> {
+        let envelope = Envelope::decode(content)?;
+        let data_key = self
+            .provider
+            .unwrap_data_key(&envelope.key_id, &b64_decode(&envelope.wrapped_key)?)
+            .await?;
+        let cipher = Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|e| config_common::Error::Internal(format!("invalid data key: {}", e)))?;
+        let nonce = b64_decode(&envelope.nonce)?;
+        if nonce.len() != crate::local::NONCE_LEN {
+            return Err(config_common::Error::Validation(
+                "malformed encryption envelope: bad nonce".to_string(),
+            ));
+        }
+
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                b64_decode(&envelope.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| {
+                config_common::Error::Internal("content can't be decrypted".to_string())
+            })?;
+        String::from_utf8(plaintext)
+            .map_err(|e| config_common::Error::Internal(format!("decrypted content: {}", e)))
+    }
+}
+
+/// Hex digits of the plaintext HMAC kept in an envelope, enough to tell values apart
+const CHECKSUM_LEN: usize = 12;
+
+fn checksum(key: &[u8], plaintext: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(plaintext.as_bytes());
+    let digest = format!("{:x}", mac.finalize().into_bytes());
+    digest[..CHECKSUM_LEN].to_string()
+}
+
+fn b64_encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn b64_decode(text: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| {
+            config_common::Error::Validation(format!("malformed encryption envelope: {}", e))
+        })
+}
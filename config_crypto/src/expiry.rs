@@ -0,0 +1,147 @@
+use config_common::{ConfigEvent, ConfigEventType, Result};
+use config_core::{ConfigManager, SecretExpiryConfig, SecretExpiryManager};
+use prometheus::{IntGauge, Registry};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// User recorded on expiry reminder events
+pub const SECRET_EXPIRY_USER: &str = "secret-expiry";
+
+/// Capacity of the reminder event channel
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Secret expiry job metrics
+#[derive(Clone)]
+pub struct SecretExpiryMetrics {
+    pub expiring: IntGauge,
+    pub expired: IntGauge,
+}
+
+impl SecretExpiryMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let expiring = IntGauge::new(
+            "secrets_expiring",
+            "Encrypted configs whose secrets expire within the warning period",
+        )?;
+        let expired = IntGauge::new(
+            "secrets_expired",
+            "Encrypted configs whose secrets are past their expiry date",
+        )?;
+
+        registry.register(Box::new(expiring.clone()))?;
+        registry.register(Box::new(expired.clone()))?;
+
+        Ok(Self { expiring, expired })
+    }
+}
+
+/// Background job reminding of secrets near or past their expiry date
+pub struct SecretExpiryJob {
+    expiry: Arc<dyn SecretExpiryManager>,
+    config_manager: Arc<dyn ConfigManager>,
+    config: SecretExpiryConfig,
+    metrics: SecretExpiryMetrics,
+    events: broadcast::Sender<ConfigEvent>,
+    /// Expiry date of each config when last reported and whether it had passed
+    notified: Mutex<HashMap<String, (i64, bool)>>,
+}
+
+impl SecretExpiryJob {
+    pub fn new(
+        expiry: Arc<dyn SecretExpiryManager>,
+        config_manager: Arc<dyn ConfigManager>,
+        config: SecretExpiryConfig,
+        metrics: SecretExpiryMetrics,
+    ) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            expiry,
+            config_manager,
+            config,
+            metrics,
+            events,
+            notified: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Receive an event when a secret comes near or passes its expiry date
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigEvent> {
+        self.events.subscribe()
+    }
+
+    /// Run the job periodically until the task is aborted
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_once().await {
+                    tracing::error!(error = %e, "Secret expiry check failed");
+                }
+            }
+        })
+    }
+
+    /// Update the metrics and send an event for each config whose state changed
+    pub async fn run_once(&self) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let expiring = self
+            .expiry
+            .list_expiring(self.config.warn_until(now))
+            .await?;
+        let expired = expiring
+            .iter()
+            .filter(|expiry| expiry.expires_at <= now)
+            .count();
+        self.metrics.expired.set(expired as i64);
+        self.metrics.expiring.set((expiring.len() - expired) as i64);
+
+        let mut pending = Vec::new();
+        {
+            let mut notified = self.notified.lock().unwrap();
+            notified.retain(|id, _| expiring.iter().any(|expiry| &expiry.config_id == id));
+            for expiry in &expiring {
+                let state = (expiry.expires_at, expiry.expires_at <= now);
+                if notified.insert(expiry.config_id.clone(), state) != Some(state) {
+                    pending.push((expiry.config_id.clone(), state));
+                }
+            }
+        }
+
+        for (config_id, (expires_at, expired)) in pending {
+            let meta = match self.config_manager.get_config(&config_id).await {
+                Ok((meta, _)) => meta,
+                Err(config_common::Error::NotFound(_)) => continue,
+                Err(e) => {
+                    // Try again on the next run
+                    self.notified.lock().unwrap().remove(&config_id);
+                    return Err(e);
+                }
+            };
+            let event_type = if expired {
+                tracing::warn!(config = %config_id, expires_at, "Secret is past its expiry date");
+                ConfigEventType::SecretExpired
+            } else {
+                tracing::warn!(config = %config_id, expires_at, "Secret expires soon");
+                ConfigEventType::SecretExpiring
+            };
+            // Nobody may be listening; the metrics already report the state
+            let _ = self.events.send(ConfigEvent {
+                config_id,
+                namespace: meta.namespace,
+                environment: meta.environment,
+                labels: meta.labels,
+                event_type,
+                version: meta.version,
+                timestamp: now,
+                user: SECRET_EXPIRY_USER.to_string(),
+                message: None,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+use aes_gcm::aead::{KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::Result;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::model::{AwsKmsConfig, GcpKmsConfig};
+use crate::provider::{DataKey, KeyProvider};
+
+/// GCE metadata endpoint issuing tokens for the instance's service account
+const GCP_METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Fetch a new metadata token this long before the current one expires
+const GCP_TOKEN_RENEW_MARGIN: Duration = Duration::from_secs(60);
+
+#[cfg(feature = "aws-kms")]
+pub(crate) async fn aws_kms_provider(config: &AwsKmsConfig) -> Result<Arc<dyn KeyProvider>> {
+    Ok(Arc::new(AwsKmsKeyProvider::new(config).await))
+}
+
+#[cfg(not(feature = "aws-kms"))]
+pub(crate) async fn aws_kms_provider(_config: &AwsKmsConfig) -> Result<Arc<dyn KeyProvider>> {
+    Err(config_common::Error::Config(
+        "aws kms key provider requires the `aws-kms` feature".to_string(),
+    ))
+}
+
+/// Master key held by AWS KMS, which generates and unwraps data keys
+#[cfg(feature = "aws-kms")]
+pub struct AwsKmsKeyProvider {
+    client: aws_sdk_kms::Client,
+    key_id: RwLock<String>,
+}
+
+#[cfg(feature = "aws-kms")]
+impl AwsKmsKeyProvider {
+    /// Credentials come from the default AWS provider chain
+    pub async fn new(config: &AwsKmsConfig) -> Self {
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(region) = &config.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        Self {
+            client: aws_sdk_kms::Client::new(&loader.load().await),
+            key_id: RwLock::new(config.key_id.clone()),
+        }
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+fn aws_error<E: std::error::Error + 'static>(
+    e: aws_sdk_kms::error::SdkError<E>,
+) -> config_common::Error {
+    config_common::Error::Internal(format!(
+        "aws kms: {}",
+        aws_sdk_kms::error::DisplayErrorContext(e)
+    ))
+}
+
+#[cfg(feature = "aws-kms")]
+#[async_trait]
+impl KeyProvider for AwsKmsKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.key_id.read().unwrap().clone()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        *self.key_id.write().unwrap() = key_id.to_string();
+        Ok(())
+    }
+
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey> {
+        let output = self
+            .client
+            .generate_data_key()
+            .key_id(key_id)
+            .key_spec(aws_sdk_kms::types::DataKeySpec::Aes256)
+            .send()
+            .await
+            .map_err(aws_error)?;
+
+        let missing = || config_common::Error::Internal("aws kms returned no data key".to_string());
+        Ok(DataKey {
+            // KMS reports the key ARN, which stays valid if the configured alias moves
+            key_id: output.key_id().unwrap_or(key_id).to_string(),
+            plaintext: output.plaintext().ok_or_else(missing)?.as_ref().to_vec(),
+            wrapped: output
+                .ciphertext_blob()
+                .ok_or_else(missing)?
+                .as_ref()
+                .to_vec(),
+        })
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let output = self
+            .client
+            .decrypt()
+            .key_id(key_id)
+            .ciphertext_blob(aws_sdk_kms::primitives::Blob::new(wrapped))
+            .send()
+            .await
+            .map_err(aws_error)?;
+
+        output
+            .plaintext()
+            .map(|key| key.as_ref().to_vec())
+            .ok_or_else(|| {
+                config_common::Error::Internal("aws kms returned no data key".to_string())
+            })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpEncryptResponse {
+    name: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpDecryptResponse {
+    plaintext: String,
+}
+
+/// Master key held by Google Cloud KMS; data keys are generated locally and wrapped by KMS
+pub struct GcpKmsKeyProvider {
+    config: GcpKmsConfig,
+    client: reqwest::Client,
+    token: Mutex<Option<(String, Instant)>>,
+    key_name: RwLock<String>,
+}
+
+impl GcpKmsKeyProvider {
+    pub fn new(config: GcpKmsConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| config_common::Error::Config(format!("gcp kms client: {}", e)))?;
+        Ok(Self {
+            key_name: RwLock::new(config.key_name.clone()),
+            config,
+            client,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Configured token, or one from the metadata server renewed before it expires
+    async fn token(&self) -> Result<String> {
+        if let Some(token) = &self.config.access_token {
+            return Ok(token.clone());
+        }
+        let mut cached = self.token.lock().await;
+        if let Some((token, renew_at)) = cached.as_ref() {
+            if Instant::now() < *renew_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let token: GcpToken = self
+            .client
+            .get(GCP_METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| config_common::Error::Internal(format!("gcp metadata token: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("gcp metadata token: {}", e)))?;
+        let renew_at = Instant::now()
+            + Duration::from_secs(token.expires_in).saturating_sub(GCP_TOKEN_RENEW_MARGIN);
+        *cached = Some((token.access_token.clone(), renew_at));
+        Ok(token.access_token)
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        key_name: &str,
+        operation: &str,
+        body: serde_json::Value,
+    ) -> Result<T> {
+        let url = format!(
+            "{}/v1/{}:{}",
+            self.config.endpoint.trim_end_matches('/'),
+            key_name,
+            operation
+        );
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(self.token().await?)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("gcp kms: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let message = format!("gcp kms responded {}: {}", status, text);
+            // Only server-side failures are worth retrying
+            return Err(if status.is_server_error() {
+                config_common::Error::Internal(message)
+            } else {
+                config_common::Error::Config(message)
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("gcp kms response: {}", e)))
+    }
+}
+
+#[async_trait]
+impl KeyProvider for GcpKmsKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.key_name.read().unwrap().clone()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        *self.key_name.write().unwrap() = key_id.to_string();
+        Ok(())
+    }
+
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey> {
+        let plaintext = Aes256Gcm::generate_key(OsRng).to_vec();
+        let wrapped: GcpEncryptResponse = self
+            .call(
+                key_id,
+                "encrypt",
+                json!({ "plaintext": encode(&plaintext) }),
+            )
+            .await?;
+        Ok(DataKey {
+            // GCP reports the key version used; decrypt takes the key itself
+            key_id: crypto_key_name(&wrapped.name).to_string(),
+            plaintext,
+            wrapped: decode(&wrapped.ciphertext)?,
+        })
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let key: GcpDecryptResponse = self
+            .call(key_id, "decrypt", json!({ "ciphertext": encode(wrapped) }))
+            .await?;
+        decode(&key.plaintext)
+    }
+}
+
+/// `projects/../cryptoKeys/<key>` from a key or key version name
+fn crypto_key_name(name: &str) -> &str {
+    name.split_once("/cryptoKeyVersions/")
+        .map_or(name, |(key, _)| key)
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(text: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| config_common::Error::Internal(format!("gcp kms data key: {}", e)))
+}
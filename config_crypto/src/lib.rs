@@ -0,0 +1,27 @@
+pub mod cache;
+pub mod envelope;
+pub mod expiry;
+pub mod kms;
+pub mod local;
+pub mod model;
+pub mod provider;
+pub mod retry;
+pub mod rotation;
+pub mod vault;
+
+pub use cache::CachingKeyProvider;
+
+pub use envelope::{Envelope, EnvelopeEncryption};
+pub use expiry::{SecretExpiryJob, SecretExpiryMetrics};
+#[cfg(feature = "aws-kms")]
+pub use kms::AwsKmsKeyProvider;
+pub use kms::GcpKmsKeyProvider;
+pub use local::LocalKeyProvider;
+pub use model::{
+    AwsKmsConfig, DataKeyCacheConfig, EncryptionConfig, GcpKmsConfig, KeyProviderConfig,
+    RetryConfig, VaultAuth, VaultConfig,
+};
+pub use provider::{build_key_provider, DataKey, KeyProvider};
+pub use retry::RetryingKeyProvider;
+pub use rotation::KeyRotationService;
+pub use vault::VaultKeyProvider;
@@ -0,0 +1,107 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::Result;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::provider::{DataKey, KeyProvider};
+
+/// Length of an AES-GCM nonce in bytes
+pub(crate) const NONCE_LEN: usize = 12;
+
+/// Master keys from the server configuration, wrapping data keys with AES-256-GCM
+pub struct LocalKeyProvider {
+    active_key: RwLock<String>,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl LocalKeyProvider {
+    pub fn new(active_key: &str, keys: &HashMap<String, String>) -> Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|(id, encoded)| {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|e| {
+                        config_common::Error::Config(format!("master key {}: {}", id, e))
+                    })?;
+                if bytes.len() != 32 {
+                    return Err(config_common::Error::Config(format!(
+                        "master key {} must be 32 bytes, got {}",
+                        id,
+                        bytes.len()
+                    )));
+                }
+                let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes));
+                Ok((id.clone(), cipher))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        if !keys.contains_key(active_key) {
+            return Err(config_common::Error::Config(format!(
+                "active master key {} is not configured",
+                active_key
+            )));
+        }
+        Ok(Self {
+            active_key: RwLock::new(active_key.to_string()),
+            keys,
+        })
+    }
+
+    fn cipher(&self, key_id: &str) -> Result<&Aes256Gcm> {
+        self.keys
+            .get(key_id)
+            .ok_or_else(|| config_common::Error::NotFound(format!("master key {}", key_id)))
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.active_key.read().unwrap().clone()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        self.cipher(key_id)?;
+        *self.active_key.write().unwrap() = key_id.to_string();
+        Ok(())
+    }
+
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey> {
+        let plaintext = Aes256Gcm::generate_key(OsRng).to_vec();
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher(key_id)?
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| config_common::Error::Internal(format!("wrap data key: {}", e)))?;
+
+        // Wrapped key is nonce || sealed key
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&sealed);
+        Ok(DataKey {
+            key_id: key_id.to_string(),
+            plaintext,
+            wrapped,
+        })
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if wrapped.len() <= NONCE_LEN {
+            return Err(config_common::Error::Internal(
+                "wrapped data key is truncated".to_string(),
+            ));
+        }
+        let (nonce, sealed) = wrapped.split_at(NONCE_LEN);
+        self.cipher(key_id)?
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| {
+                config_common::Error::Internal(format!(
+                    "data key can't be unwrapped with master key {}",
+                    key_id
+                ))
+            })
+    }
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Encryption settings; encrypted writes are refused when no provider is configured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    #[serde(default)]
+    pub provider: Option<KeyProviderConfig>,
+    /// Caching of unwrapped data keys for remote providers
+    #[serde(default)]
+    pub cache: DataKeyCacheConfig,
+    /// Retries of failed calls to remote providers
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Stored contents re-encrypted per batch during a key rotation
+    #[serde(default = "default_rotation_batch_size")]
+    pub rotation_batch_size: usize,
+    /// Secret keying the checksums shown in place of redacted values, the same on every
+    /// node; redacted values carry no checksum when unset
+    #[serde(default)]
+    pub checksum_key: Option<String>,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            cache: DataKeyCacheConfig::default(),
+            retry: RetryConfig::default(),
+            rotation_batch_size: default_rotation_batch_size(),
+            checksum_key: None,
+        }
+    }
+}
+
+/// Cache of data keys unwrapped by a remote provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataKeyCacheConfig {
+    #[serde(default = "default_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Zero disables the cache
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+impl Default for DataKeyCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: default_cache_ttl_secs(),
+            max_entries: default_cache_max_entries(),
+        }
+    }
+}
+
+/// Retry policy for calls to a remote provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further one
+    #[serde(default = "default_retry_backoff_ms")]
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            backoff_ms: default_retry_backoff_ms(),
+        }
+    }
+}
+
+/// Where master keys live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KeyProviderConfig {
+    /// Master keys held in the server configuration
+    Local {
+        /// ID of the key wrapping new data keys
+        active_key: String,
+        /// Base64-encoded 256-bit keys by ID; retired keys stay for decryption
+        keys: HashMap<String, String>,
+    },
+    /// Master key held by a Vault transit engine
+    Vault(VaultConfig),
+    /// Master key held by AWS KMS
+    AwsKms(AwsKmsConfig),
+    /// Master key held by Google Cloud KMS
+    GcpKms(GcpKmsConfig),
+}
+
+/// AWS KMS key; credentials come from the default provider chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AwsKmsConfig {
+    /// Key ID, ARN or alias, e.g. `alias/config-server`
+    pub key_id: String,
+    /// Overrides the region from the environment
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+/// Google Cloud KMS key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcpKmsConfig {
+    /// `projects/<project>/locations/<location>/keyRings/<ring>/cryptoKeys/<key>`
+    pub key_name: String,
+    /// Fixed OAuth token; the GCE metadata server is used when unset
+    #[serde(default)]
+    pub access_token: Option<String>,
+    #[serde(default = "default_gcp_kms_endpoint")]
+    pub endpoint: String,
+    #[serde(default = "default_kms_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Vault transit engine wrapping data keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Vault address, e.g. `https://vault.internal:8200`
+    pub address: String,
+    /// Mount path of the transit engine
+    #[serde(default = "default_transit_mount")]
+    pub mount: String,
+    /// Name of the transit key
+    pub key_name: String,
+    pub auth: VaultAuth,
+    /// Vault Enterprise namespace
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default = "default_vault_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// How the server authenticates to Vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum VaultAuth {
+    /// Fixed token
+    Token { token: String },
+    /// AppRole login, renewed when the token expires
+    AppRole {
+        role_id: String,
+        secret_id: String,
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+}
+
+fn default_transit_mount() -> String {
+    "transit".to_string()
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
+fn default_vault_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_gcp_kms_endpoint() -> String {
+    "https://cloudkms.googleapis.com".to_string()
+}
+
+fn default_kms_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_rotation_batch_size() -> usize {
+    100
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_cache_max_entries() -> usize {
+    1000
+}
+
+fn default_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    200
+}
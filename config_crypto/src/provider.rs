@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use config_common::Result;
+use std::sync::Arc;
+
+use crate::cache::CachingKeyProvider;
+use crate::kms::{aws_kms_provider, GcpKmsKeyProvider};
+use crate::local::LocalKeyProvider;
+use crate::model::{EncryptionConfig, KeyProviderConfig};
+use crate::retry::RetryingKeyProvider;
+use crate::vault::VaultKeyProvider;
+
+/// Data key generated for one encryption
+pub struct DataKey {
+    /// Master key that wrapped this data key
+    pub key_id: String,
+    /// 256-bit key used to encrypt content, never stored
+    pub plaintext: Vec<u8>,
+    /// Data key wrapped by the master key, stored next to the ciphertext
+    pub wrapped: Vec<u8>,
+}
+
+/// Source of master keys wrapping per-content data keys, e.g. a local keyring or a KMS
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// ID of the master key used for new data keys
+    fn active_key_id(&self) -> String;
+
+    /// Use another master key for new data keys
+    fn set_active_key(&self, key_id: &str) -> Result<()>;
+
+    /// Create a new version of the active master key, for providers managing key versions
+    async fn rotate_key(&self) -> Result<String> {
+        Err(config_common::Error::Validation(
+            "key provider can't create key versions; name the key to activate".to_string(),
+        ))
+    }
+
+    /// Generate a data key wrapped by the active master key
+    async fn generate_data_key(&self) -> Result<DataKey> {
+        self.generate_data_key_with(&self.active_key_id()).await
+    }
+
+    /// Generate a data key wrapped by the given master key
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey>;
+
+    /// Unwrap a data key with the master key that wrapped it
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Create the key provider selected in the configuration, if any
+pub async fn build_key_provider(config: &EncryptionConfig) -> Result<Option<Arc<dyn KeyProvider>>> {
+    let remote: Arc<dyn KeyProvider> = match &config.provider {
+        None => return Ok(None),
+        Some(KeyProviderConfig::Local { active_key, keys }) => {
+            return Ok(Some(Arc::new(LocalKeyProvider::new(active_key, keys)?)));
+        }
+        Some(KeyProviderConfig::Vault(vault)) => Arc::new(VaultKeyProvider::new(vault.clone())?),
+        Some(KeyProviderConfig::AwsKms(aws)) => aws_kms_provider(aws).await?,
+        Some(KeyProviderConfig::GcpKms(gcp)) => Arc::new(GcpKmsKeyProvider::new(gcp.clone())?),
+    };
+
+    // Cache outside the retries so a cached key never waits on a backoff
+    let retrying = Arc::new(RetryingKeyProvider::new(remote, &config.retry));
+    Ok(Some(Arc::new(CachingKeyProvider::new(
+        retrying,
+        &config.cache,
+    ))))
+}
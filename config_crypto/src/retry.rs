@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use config_common::Result;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::model::RetryConfig;
+use crate::provider::{DataKey, KeyProvider};
+
+/// Retries KMS calls that fail for transient reasons, with exponential backoff
+pub struct RetryingKeyProvider {
+    inner: Arc<dyn KeyProvider>,
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl RetryingKeyProvider {
+    pub fn new(inner: Arc<dyn KeyProvider>, config: &RetryConfig) -> Self {
+        Self {
+            inner,
+            max_attempts: config.max_attempts.max(1),
+            backoff: Duration::from_millis(config.backoff_ms),
+        }
+    }
+
+    async fn retry<T, F, Fut>(&self, operation: &str, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                // Transport and service errors are reported as Internal; anything else is final
+                Err(config_common::Error::Internal(e)) if attempt < self.max_attempts => {
+                    tracing::warn!(
+                        "Key provider {} failed (attempt {}): {}",
+                        operation,
+                        attempt,
+                        e
+                    );
+                    tokio::time::sleep(self.backoff * 2u32.pow(attempt - 1)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl KeyProvider for RetryingKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.inner.active_key_id()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        self.inner.set_active_key(key_id)
+    }
+
+    async fn rotate_key(&self) -> Result<String> {
+        self.inner.rotate_key().await
+    }
+
+    async fn generate_data_key_with(&self, key_id: &str) -> Result<DataKey> {
+        self.retry("generate_data_key", || {
+            self.inner.generate_data_key_with(key_id)
+        })
+        .await
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        self.retry("unwrap_data_key", || {
+            self.inner.unwrap_data_key(key_id, wrapped)
+        })
+        .await
+    }
+}
@@ -0,0 +1,454 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ConfigFormat, Result};
+use config_core::{
+    ConfigEncryption, KeyRotation, KeyRotationManager, KeyRotationStatus, KeyedEncryption,
+};
+use config_storage::compression;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::envelope::EnvelopeEncryption;
+
+/// Columns selected for a key rotation row
+const ROTATION_COLUMNS: &str = "id, key_id, status, total, processed, failed, error, \
+     started_at, started_by, finished_at";
+
+/// Rows that may hold ciphertext; compressed rows are only known after decoding. Content in
+/// the blob store never holds ciphertext.
+const ENCRYPTED_FILTER: &str = "(content_encoding IS DISTINCT FROM 'blob' \
+     AND (is_encrypted OR content_encoding IS NOT NULL OR content LIKE '%enc:%'))";
+
+#[derive(sqlx::FromRow)]
+struct RotationRow {
+    id: String,
+    key_id: String,
+    status: String,
+    total: i64,
+    processed: i64,
+    failed: i64,
+    error: Option<String>,
+    started_at: i64,
+    started_by: String,
+    finished_at: Option<i64>,
+}
+
+impl TryFrom<RotationRow> for KeyRotation {
+    type Error = config_common::Error;
+
+    fn try_from(row: RotationRow) -> Result<Self> {
+        Ok(KeyRotation {
+            id: row.id,
+            key_id: row.key_id,
+            status: row.status.parse::<KeyRotationStatus>()?,
+            total: row.total,
+            processed: row.processed,
+            failed: row.failed,
+            error: row.error,
+            started_at: row.started_at,
+            started_by: row.started_by,
+            finished_at: row.finished_at,
+        })
+    }
+}
+
+/// Stored content row; `key` is the config ID or the version sequence number
+#[derive(sqlx::FromRow)]
+struct StoredRow<K> {
+    key: K,
+    format: String,
+    content: String,
+    content_encoding: Option<String>,
+    is_encrypted: bool,
+}
+
+/// How far a rotation got, saved with each batch so a restarted server resumes from it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RotationProgress {
+    /// Prefix of the schema being re-encrypted; schemas before it are done
+    prefix: String,
+    /// Last config done in the schema
+    config_id: String,
+    /// Set once the configs of the schema are done and its versions are being re-encrypted
+    versions: bool,
+    /// Last version done in the schema
+    seq: i64,
+}
+
+/// Config tables of one schema and the encryption their content is kept under
+struct Scope {
+    /// Prefix qualifying the tables, empty for the shared schema
+    prefix: String,
+    encryption: Arc<dyn ConfigEncryption>,
+}
+
+/// Rotates the master key and re-encrypts stored content under it in batches, including the
+/// content of tenant schemas, which keeps the master key of its tenant when it has one
+#[derive(Clone)]
+pub struct KeyRotationService {
+    pool: Arc<PgPool>,
+    encryption: Arc<EnvelopeEncryption>,
+    batch_size: i64,
+    compress_above: usize,
+}
+
+impl KeyRotationService {
+    pub fn new(
+        pool: Arc<PgPool>,
+        encryption: Arc<EnvelopeEncryption>,
+        batch_size: usize,
+        compress_above: usize,
+    ) -> Self {
+        Self {
+            pool,
+            encryption,
+            batch_size: batch_size.max(1) as i64,
+            compress_above,
+        }
+    }
+
+    /// Activate the key of the latest rotation and resume it if the server stopped midway
+    pub async fn restore(&self) -> Result<()> {
+        let Some(rotation) = self.rotation_status().await? else {
+            return Ok(());
+        };
+        self.encryption
+            .provider()
+            .set_active_key(&rotation.key_id)?;
+        if rotation.status == KeyRotationStatus::Running {
+            tracing::info!(rotation = %rotation.id, "Resuming key rotation");
+            self.spawn(rotation.id);
+        }
+        Ok(())
+    }
+
+    fn spawn(&self, rotation_id: String) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let result = service.reencrypt_all(&rotation_id).await;
+            if let Err(e) = &result {
+                tracing::error!(rotation = %rotation_id, error = %e, "Key rotation failed");
+            }
+            if let Err(e) = service.finish(&rotation_id, result.err()).await {
+                tracing::error!(
+                    rotation = %rotation_id,
+                    error = %e,
+                    "Recording key rotation failed"
+                );
+            }
+        });
+    }
+
+    /// The shared schema followed by every tenant schema
+    async fn scopes(&self) -> Result<Vec<Scope>> {
+        let encryption: Arc<dyn ConfigEncryption> = self.encryption.clone();
+        let tenants = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT schema_name, key_id FROM tenants ORDER BY name",
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let mut scopes = vec![Scope {
+            prefix: String::new(),
+            encryption: encryption.clone(),
+        }];
+        scopes.extend(tenants.into_iter().map(|(schema, key_id)| Scope {
+            prefix: format!("\"{}\".", schema),
+            encryption: match key_id {
+                Some(key_id) => Arc::new(KeyedEncryption::new(encryption.clone(), &key_id)),
+                None => encryption.clone(),
+            },
+        }));
+        Ok(scopes)
+    }
+
+    async fn reencrypt_all(&self, rotation_id: &str) -> Result<()> {
+        let scopes = self.scopes().await?;
+        // A rotation stopped in a schema dropped since starts over
+        let progress = self
+            .progress(rotation_id)
+            .await?
+            .filter(|progress| scopes.iter().any(|s| s.prefix == progress.prefix))
+            .unwrap_or_default();
+        for scope in scopes.iter().skip_while(|s| s.prefix != progress.prefix) {
+            let from = if scope.prefix == progress.prefix {
+                progress.clone()
+            } else {
+                RotationProgress {
+                    prefix: scope.prefix.clone(),
+                    ..Default::default()
+                }
+            };
+            self.reencrypt_scope(rotation_id, scope, from).await?;
+        }
+        Ok(())
+    }
+
+    async fn reencrypt_scope(
+        &self,
+        rotation_id: &str,
+        scope: &Scope,
+        mut progress: RotationProgress,
+    ) -> Result<()> {
+        while !progress.versions {
+            let rows = sqlx::query_as::<_, StoredRow<String>>(&format!(
+                "SELECT id AS key, format, content, content_encoding, is_encrypted \
+                 FROM {}configs WHERE id > $1 AND {} ORDER BY id LIMIT $2",
+                scope.prefix, ENCRYPTED_FILTER
+            ))
+            .bind(&progress.config_id)
+            .bind(self.batch_size)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            let Some(last) = rows.last() else {
+                progress.versions = true;
+                break;
+            };
+            progress.config_id = last.key.clone();
+            let table = format!("{}configs", scope.prefix);
+            self.reencrypt_batch(rotation_id, scope, &table, "id", rows, &progress)
+                .await?;
+        }
+
+        loop {
+            let rows = sqlx::query_as::<_, StoredRow<i64>>(&format!(
+                "SELECT seq AS key, format, content, content_encoding, is_encrypted \
+                 FROM {}config_versions WHERE seq > $1 AND {} ORDER BY seq LIMIT $2",
+                scope.prefix, ENCRYPTED_FILTER
+            ))
+            .bind(progress.seq)
+            .bind(self.batch_size)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            progress.seq = last.key;
+            let table = format!("{}config_versions", scope.prefix);
+            self.reencrypt_batch(rotation_id, scope, &table, "seq", rows, &progress)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn reencrypt_batch<K>(
+        &self,
+        rotation_id: &str,
+        scope: &Scope,
+        table: &str,
+        key_column: &str,
+        rows: Vec<StoredRow<K>>,
+        progress: &RotationProgress,
+    ) -> Result<()>
+    where
+        K: for<'q> sqlx::Encode<'q, sqlx::Postgres> + sqlx::Type<sqlx::Postgres> + Send + 'static,
+    {
+        let (mut processed, mut failed) = (0i64, 0i64);
+        for row in rows {
+            let original = row.content.clone();
+            match self.reencrypt_row(scope, &row).await {
+                Ok(None) => continue,
+                Ok(Some((stored, encoding))) => {
+                    // A row rewritten meanwhile is already encrypted under the new key
+                    sqlx::query(&format!(
+                        "UPDATE {} SET content = $1, content_encoding = $2 \
+                         WHERE {} = $3 AND content = $4",
+                        table, key_column
+                    ))
+                    .bind(stored)
+                    .bind(encoding)
+                    .bind(row.key)
+                    .bind(original)
+                    .execute(&*self.pool)
+                    .await
+                    .map_err(|e| config_common::Error::Database(e.to_string()))?;
+                    processed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        rotation = %rotation_id,
+                        table,
+                        error = %e,
+                        "Re-encryption failed"
+                    );
+                    failed += 1;
+                }
+            }
+        }
+
+        // Saved with the counts, so a resumed rotation counts no row twice
+        sqlx::query(
+            "UPDATE key_rotations SET processed = processed + $2, failed = failed + $3, \
+             progress = $4 WHERE id = $1",
+        )
+        .bind(rotation_id)
+        .bind(processed)
+        .bind(failed)
+        .bind(Json(progress))
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// New stored form of a row, `None` when it holds no ciphertext
+    async fn reencrypt_row<K>(
+        &self,
+        scope: &Scope,
+        row: &StoredRow<K>,
+    ) -> Result<Option<(String, Option<&'static str>)>> {
+        let content = ConfigContent {
+            format: row.format.parse::<ConfigFormat>()?,
+            content: compression::decode(row.content.clone(), row.content_encoding.as_deref())?,
+            is_encrypted: row.is_encrypted,
+        };
+        if config_core::recipients::is_client_sealed(&content)
+            || (!content.is_encrypted && !config_core::secrets::has_secret_fields(&content))
+        {
+            return Ok(None);
+        }
+        let content = config_core::secrets::reencrypt(content, scope.encryption.as_ref()).await?;
+        compression::encode(&content.content, self.compress_above).map(Some)
+    }
+
+    async fn finish(&self, rotation_id: &str, error: Option<config_common::Error>) -> Result<()> {
+        let status = match (&error, self.get(rotation_id).await?.failed) {
+            (None, 0) => KeyRotationStatus::Completed,
+            _ => KeyRotationStatus::Failed,
+        };
+        sqlx::query(
+            "UPDATE key_rotations SET status = $2, error = $3, finished_at = $4 WHERE id = $1",
+        )
+        .bind(rotation_id)
+        .bind(status.as_str())
+        .bind(error.map(|e| e.to_string()))
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn progress(&self, rotation_id: &str) -> Result<Option<RotationProgress>> {
+        let progress = sqlx::query_scalar::<_, Option<Json<RotationProgress>>>(
+            "SELECT progress FROM key_rotations WHERE id = $1",
+        )
+        .bind(rotation_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(progress.flatten().map(|progress| progress.0))
+    }
+
+    async fn get(&self, rotation_id: &str) -> Result<KeyRotation> {
+        sqlx::query_as::<_, RotationRow>(&format!(
+            "SELECT {} FROM key_rotations WHERE id = $1",
+            ROTATION_COLUMNS
+        ))
+        .bind(rotation_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("key rotation {}", rotation_id)))?
+        .try_into()
+    }
+
+    async fn count_candidates(&self) -> Result<i64> {
+        let mut total = 0;
+        for scope in self.scopes().await? {
+            total += sqlx::query_scalar::<_, i64>(&format!(
+                "SELECT (SELECT COUNT(*) FROM {0}configs WHERE {1}) \
+                 + (SELECT COUNT(*) FROM {0}config_versions WHERE {1})",
+                scope.prefix, ENCRYPTED_FILTER
+            ))
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        }
+        Ok(total)
+    }
+}
+
+#[async_trait]
+impl KeyRotationManager for KeyRotationService {
+    async fn start_rotation(&self, key_id: Option<&str>, started_by: &str) -> Result<KeyRotation> {
+        if let Some(current) = self.rotation_status().await? {
+            if current.status == KeyRotationStatus::Running {
+                return Err(config_common::Error::AlreadyExists(format!(
+                    "key rotation {} is still running",
+                    current.id
+                )));
+            }
+        }
+
+        let provider = self.encryption.provider();
+        let previous_key = provider.active_key_id();
+        let key_id = match key_id {
+            Some(key_id) => {
+                provider.set_active_key(key_id)?;
+                key_id.to_string()
+            }
+            None => provider.rotate_key().await?,
+        };
+
+        let rotation = KeyRotation {
+            id: uuid::Uuid::new_v4().to_string(),
+            key_id,
+            status: KeyRotationStatus::Running,
+            total: self.count_candidates().await?,
+            processed: 0,
+            failed: 0,
+            error: None,
+            started_at: chrono::Utc::now().timestamp(),
+            started_by: started_by.to_string(),
+            finished_at: None,
+        };
+        // The index on running rotations refuses a rotation started meanwhile
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO key_rotations (id, key_id, status, total, processed, failed,
+                started_at, started_by)
+            VALUES ($1, $2, $3, $4, 0, 0, $5, $6)
+            "#,
+        )
+        .bind(&rotation.id)
+        .bind(&rotation.key_id)
+        .bind(rotation.status.as_str())
+        .bind(rotation.total)
+        .bind(rotation.started_at)
+        .bind(&rotation.started_by)
+        .execute(&*self.pool)
+        .await;
+        if let Err(e) = inserted {
+            provider.set_active_key(&previous_key)?;
+            return Err(match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    config_common::Error::AlreadyExists(
+                        "another key rotation is still running".to_string(),
+                    )
+                }
+                e => config_common::Error::Database(e.to_string()),
+            });
+        }
+
+        tracing::info!(rotation = %rotation.id, key = %rotation.key_id, "Started key rotation");
+        self.spawn(rotation.id.clone());
+        Ok(rotation)
+    }
+
+    async fn rotation_status(&self) -> Result<Option<KeyRotation>> {
+        sqlx::query_as::<_, RotationRow>(&format!(
+            "SELECT {} FROM key_rotations ORDER BY started_at DESC LIMIT 1",
+            ROTATION_COLUMNS
+        ))
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(KeyRotation::try_from)
+        .transpose()
+    }
+}
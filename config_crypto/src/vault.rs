@@ -0,0 +1,212 @@
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::Result;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::model::{VaultAuth, VaultConfig};
+use crate::provider::{DataKey, KeyProvider};
+
+/// Renew an AppRole token this long before Vault expires it
+const TOKEN_RENEW_MARGIN: Duration = Duration::from_secs(30);
+
+/// Envelope of every Vault response
+#[derive(Debug, Deserialize)]
+struct VaultResponse<T> {
+    data: Option<T>,
+    auth: Option<VaultLogin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VaultLogin {
+    client_token: String,
+    lease_duration: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DataKeyResponse {
+    plaintext: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DecryptResponse {
+    plaintext: String,
+}
+
+/// Token in use and when it must be renewed; `None` for tokens that don't expire
+struct CachedToken {
+    token: String,
+    renew_at: Option<Instant>,
+}
+
+/// Master key held by a Vault transit engine; only wrapped data keys leave Vault
+pub struct VaultKeyProvider {
+    config: VaultConfig,
+    client: reqwest::Client,
+    token: Mutex<Option<CachedToken>>,
+    key_name: RwLock<String>,
+}
+
+impl VaultKeyProvider {
+    pub fn new(config: VaultConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| config_common::Error::Config(format!("vault client: {}", e)))?;
+        Ok(Self {
+            key_name: RwLock::new(config.key_name.clone()),
+            config,
+            client,
+            token: Mutex::new(None),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v1/{}", self.config.address.trim_end_matches('/'), path)
+    }
+
+    /// Current token, logging in again when it is missing or about to expire
+    async fn token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.renew_at.is_none_or(|at| Instant::now() < at) {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let token = match &self.config.auth {
+            VaultAuth::Token { token } => CachedToken {
+                token: token.clone(),
+                renew_at: None,
+            },
+            VaultAuth::AppRole {
+                role_id,
+                secret_id,
+                mount,
+            } => {
+                let body = json!({ "role_id": role_id, "secret_id": secret_id });
+                let response: VaultResponse<serde_json::Value> = self
+                    .send(
+                        self.client.post(self.url(&format!("auth/{}/login", mount))),
+                        &body,
+                    )
+                    .await?;
+                let login = response.auth.ok_or_else(|| {
+                    config_common::Error::Auth("vault login returned no token".to_string())
+                })?;
+                let lease = Duration::from_secs(login.lease_duration);
+                CachedToken {
+                    token: login.client_token,
+                    renew_at: (!lease.is_zero())
+                        .then(|| Instant::now() + lease.saturating_sub(TOKEN_RENEW_MARGIN)),
+                }
+            }
+        };
+        let value = token.token.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        body: &serde_json::Value,
+    ) -> Result<T> {
+        let request = match &self.config.namespace {
+            Some(namespace) => request.header("X-Vault-Namespace", namespace),
+            None => request,
+        };
+        let response = request
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("vault: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(config_common::Error::Internal(format!(
+                "vault responded {}: {}",
+                status, text
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("vault response: {}", e)))
+    }
+
+    /// Call a transit endpoint for a key
+    async fn transit<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        key_name: &str,
+        body: serde_json::Value,
+    ) -> Result<T> {
+        let url = self.url(&format!("{}/{}/{}", self.config.mount, operation, key_name));
+        let request = self
+            .client
+            .post(url)
+            .header("X-Vault-Token", self.token().await?);
+        let response: VaultResponse<T> = self.send(request, &body).await?;
+        response.data.ok_or_else(|| {
+            config_common::Error::Internal(format!("vault {} returned no data", operation))
+        })
+    }
+}
+
+#[async_trait]
+impl KeyProvider for VaultKeyProvider {
+    fn active_key_id(&self) -> String {
+        self.key_name.read().unwrap().clone()
+    }
+
+    fn set_active_key(&self, key_id: &str) -> Result<()> {
+        *self.key_name.write().unwrap() = key_id.to_string();
+        Ok(())
+    }
+
+    async fn rotate_key(&self) -> Result<String> {
+        let key_name = self.active_key_id();
+        let url = self.url(&format!("{}/keys/{}/rotate", self.config.mount, key_name));
+        let request = self
+            .client
+            .post(url)
+            .header("X-Vault-Token", self.token().await?);
+        let _: serde_json::Value = self.send(request, &json!({})).await?;
+        Ok(key_name)
+    }
+
+    async fn generate_data_key_with(&self, key_name: &str) -> Result<DataKey> {
+        let key: DataKeyResponse = self
+            .transit("datakey/plaintext", key_name, json!({ "bits": 256 }))
+            .await?;
+        Ok(DataKey {
+            key_id: key_name.to_string(),
+            plaintext: decode(&key.plaintext)?,
+            // Vault's ciphertext, e.g. `vault:v1:...`, carries the transit key version
+            wrapped: key.ciphertext.into_bytes(),
+        })
+    }
+
+    async fn unwrap_data_key(&self, key_id: &str, wrapped: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = std::str::from_utf8(wrapped).map_err(|_| {
+            config_common::Error::Internal("wrapped data key is not a vault ciphertext".to_string())
+        })?;
+        let key: DecryptResponse = self
+            .transit("decrypt", key_id, json!({ "ciphertext": ciphertext }))
+            .await?;
+        decode(&key.plaintext)
+    }
+}
+
+fn decode(text: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| config_common::Error::Internal(format!("vault data key: {}", e)))
+}
@@ -0,0 +1,79 @@
+use config_common::Result;
+use std::sync::Arc;
+
+#[cfg(feature = "email")]
+use async_trait::async_trait;
+#[cfg(feature = "email")]
+use lettre::message::Mailbox;
+#[cfg(feature = "email")]
+use lettre::transport::smtp::authentication::Credentials;
+#[cfg(feature = "email")]
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::model::SmtpConfig;
+use crate::notify::Mailer;
+
+#[cfg(feature = "email")]
+pub(crate) fn smtp_mailer(config: &SmtpConfig) -> Result<Arc<dyn Mailer>> {
+    Ok(Arc::new(SmtpMailer::new(config)?))
+}
+
+#[cfg(not(feature = "email"))]
+pub(crate) fn smtp_mailer(_config: &SmtpConfig) -> Result<Arc<dyn Mailer>> {
+    Err(config_common::Error::Config(
+        "email notifications require the `email` feature".to_string(),
+    ))
+}
+
+/// Sends notification mail through an SMTP server
+#[cfg(feature = "email")]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+#[cfg(feature = "email")]
+impl SmtpMailer {
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let from: Mailbox = config.from.parse().map_err(|e| {
+            config_common::Error::Config(format!("invalid smtp sender {}: {}", config.from, e))
+        })?;
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+            .map_err(|e| config_common::Error::Config(format!("smtp relay: {}", e)))?
+            .port(config.port);
+        if let Some(username) = &config.username {
+            transport = transport.credentials(Credentials::new(
+                username.clone(),
+                config.password.clone().unwrap_or_default(),
+            ));
+        }
+
+        Ok(Self {
+            transport: transport.build(),
+            from,
+        })
+    }
+}
+
+#[cfg(feature = "email")]
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, recipients: &[String], subject: &str, body: &str) -> Result<()> {
+        let mut message = Message::builder().from(self.from.clone()).subject(subject);
+        for recipient in recipients {
+            let to: Mailbox = recipient.parse().map_err(|e| {
+                config_common::Error::Validation(format!("invalid recipient {}: {}", recipient, e))
+            })?;
+            message = message.to(to);
+        }
+        let message = message
+            .body(body.to_string())
+            .map_err(|e| config_common::Error::Internal(format!("building mail: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("smtp send: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use config_common::Result;
+use config_core::EventConsumer;
+use std::sync::Arc;
+
+#[cfg(feature = "kafka")]
+use async_trait::async_trait;
+#[cfg(feature = "kafka")]
+use config_core::PublishedEvent;
+#[cfg(feature = "kafka")]
+use std::time::Duration;
+
+use crate::model::KafkaPublisherConfig;
+
+#[cfg(feature = "kafka")]
+pub(crate) fn kafka_publisher(config: &KafkaPublisherConfig) -> Result<Arc<dyn EventConsumer>> {
+    Ok(Arc::new(KafkaPublisher::new(config)?))
+}
+
+#[cfg(not(feature = "kafka"))]
+pub(crate) fn kafka_publisher(_config: &KafkaPublisherConfig) -> Result<Arc<dyn EventConsumer>> {
+    Err(config_common::Error::Config(
+        "kafka event publisher requires the `kafka` feature".to_string(),
+    ))
+}
+
+/// Publishes events to a Kafka topic; keying by config ID keeps the events of a config
+/// in order on one partition
+#[cfg(feature = "kafka")]
+pub struct KafkaPublisher {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+    name: String,
+    send_timeout: Duration,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaPublisher {
+    pub fn new(config: &KafkaPublisherConfig) -> Result<Self> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set("message.timeout.ms", config.send_timeout_ms.to_string())
+            .create()
+            .map_err(|e| config_common::Error::Config(e.to_string()))?;
+
+        Ok(Self {
+            producer,
+            name: format!("kafka:{}", config.topic),
+            topic: config.topic.clone(),
+            send_timeout: Duration::from_millis(config.send_timeout_ms),
+        })
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl EventConsumer for KafkaPublisher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        self.producer
+            .send(
+                rdkafka::producer::FutureRecord::to(&self.topic)
+                    .key(&event.event.config_id)
+                    .payload(&payload),
+                self.send_timeout,
+            )
+            .await
+            .map_err(|(e, _)| config_common::Error::Internal(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+pub mod email;
+pub mod kafka;
+pub mod model;
+pub mod mqtt;
+pub mod nats;
+pub mod notify;
+
+#[cfg(feature = "email")]
+pub use email::SmtpMailer;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+pub use model::{
+    KafkaPublisherConfig, MqttPublisherConfig, NatsPublisherConfig, NotificationConfig,
+    PublisherConfig, SmtpConfig, SubjectMapping,
+};
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+pub use notify::{Mailer, Notifier};
+
+use config_common::Result;
+use config_core::EventConsumer;
+use std::sync::Arc;
+
+/// Create the publishers described by the configuration
+pub async fn build_publishers(configs: &[PublisherConfig]) -> Result<Vec<Arc<dyn EventConsumer>>> {
+    let mut publishers = Vec::with_capacity(configs.len());
+    for config in configs {
+        publishers.push(match config {
+            PublisherConfig::Kafka(kafka) => kafka::kafka_publisher(kafka)?,
+            PublisherConfig::Nats(nats) => nats::nats_publisher(nats).await?,
+            PublisherConfig::Mqtt(mqtt) => mqtt::mqtt_publisher(mqtt)?,
+        });
+    }
+    Ok(publishers)
+}
@@ -0,0 +1,138 @@
+use config_common::ConfigEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// External system receiving every configuration change event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublisherConfig {
+    Kafka(KafkaPublisherConfig),
+    Nats(NatsPublisherConfig),
+    Mqtt(MqttPublisherConfig),
+}
+
+/// Kafka topic events are published to, keyed by config ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaPublisherConfig {
+    pub brokers: String,
+    pub topic: String,
+    #[serde(default = "default_send_timeout_ms")]
+    pub send_timeout_ms: u64,
+}
+
+fn default_send_timeout_ms() -> u64 {
+    5000
+}
+
+/// Subject or topic of each event; templates may use `{namespace}`, `{config_id}` and
+/// `{event_type}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectMapping {
+    pub default: String,
+    /// Templates replacing the default for single namespaces
+    #[serde(default)]
+    pub namespaces: HashMap<String, String>,
+}
+
+impl SubjectMapping {
+    pub fn subject(&self, event: &ConfigEvent) -> String {
+        self.namespaces
+            .get(&event.namespace)
+            .unwrap_or(&self.default)
+            .replace("{namespace}", &event.namespace)
+            .replace("{config_id}", &event.config_id)
+            .replace("{event_type}", event.event_type.as_str())
+    }
+}
+
+/// NATS server events are published to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsPublisherConfig {
+    /// e.g. `nats://localhost:4222`
+    pub url: String,
+    #[serde(default = "default_nats_subjects")]
+    pub subjects: SubjectMapping,
+    /// Credentials file for NKey/JWT authentication
+    #[serde(default)]
+    pub credentials_file: Option<String>,
+}
+
+fn default_nats_subjects() -> SubjectMapping {
+    SubjectMapping {
+        default: "config.events.{namespace}".to_string(),
+        namespaces: HashMap::new(),
+    }
+}
+
+/// MQTT broker events are published to with QoS 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttPublisherConfig {
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default = "default_mqtt_topics")]
+    pub topics: SubjectMapping,
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "config-server".to_string()
+}
+
+fn default_mqtt_topics() -> SubjectMapping {
+    SubjectMapping {
+        default: "config/events/{namespace}".to_string(),
+        namespaces: HashMap::new(),
+    }
+}
+
+/// Delivery settings of notification channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// Timeout of each webhook request
+    #[serde(default = "default_notification_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Mail server for email channels; email channels fail without one
+    #[serde(default)]
+    pub smtp: Option<SmtpConfig>,
+}
+
+fn default_notification_timeout_ms() -> u64 {
+    5000
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_notification_timeout_ms(),
+            smtp: None,
+        }
+    }
+}
+
+/// SMTP server notification mail is sent through, using STARTTLS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Sender address, e.g. `Config Server <config@example.com>`
+    pub from: String,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
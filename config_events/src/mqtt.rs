@@ -0,0 +1,87 @@
+use config_common::Result;
+use config_core::EventConsumer;
+use std::sync::Arc;
+
+#[cfg(feature = "mqtt")]
+use async_trait::async_trait;
+#[cfg(feature = "mqtt")]
+use config_core::PublishedEvent;
+#[cfg(feature = "mqtt")]
+use std::time::Duration;
+
+use crate::model::MqttPublisherConfig;
+#[cfg(feature = "mqtt")]
+use crate::model::SubjectMapping;
+
+#[cfg(feature = "mqtt")]
+pub(crate) fn mqtt_publisher(config: &MqttPublisherConfig) -> Result<Arc<dyn EventConsumer>> {
+    Ok(Arc::new(MqttPublisher::new(config)))
+}
+
+#[cfg(not(feature = "mqtt"))]
+pub(crate) fn mqtt_publisher(_config: &MqttPublisherConfig) -> Result<Arc<dyn EventConsumer>> {
+    Err(config_common::Error::Config(
+        "mqtt event publisher requires the `mqtt` feature".to_string(),
+    ))
+}
+
+/// Publishes events with QoS 1 to MQTT topics mapped from their namespace
+#[cfg(feature = "mqtt")]
+pub struct MqttPublisher {
+    client: rumqttc::AsyncClient,
+    topics: SubjectMapping,
+    name: String,
+}
+
+#[cfg(feature = "mqtt")]
+impl MqttPublisher {
+    /// Requests queued for the connection before publishing waits
+    const REQUEST_CAPACITY: usize = 64;
+    const KEEP_ALIVE: Duration = Duration::from_secs(30);
+    const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+    /// The connection is driven, and re-established, by a background task
+    pub fn new(config: &MqttPublisherConfig) -> Self {
+        let mut options = rumqttc::MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Self::KEEP_ALIVE);
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, Self::REQUEST_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = eventloop.poll().await {
+                    tracing::warn!(error = %e, "MQTT connection failed, reconnecting");
+                    tokio::time::sleep(Self::RECONNECT_DELAY).await;
+                }
+            }
+        });
+
+        Self {
+            client,
+            topics: config.topics.clone(),
+            name: format!("mqtt:{}:{}", config.host, config.port),
+        }
+    }
+}
+
+#[cfg(feature = "mqtt")]
+#[async_trait]
+impl EventConsumer for MqttPublisher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        self.client
+            .publish(
+                self.topics.subject(&event.event),
+                rumqttc::QoS::AtLeastOnce,
+                false,
+                payload,
+            )
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("mqtt publish: {}", e)))
+    }
+}
@@ -0,0 +1,79 @@
+use config_common::Result;
+use config_core::EventConsumer;
+use std::sync::Arc;
+
+#[cfg(feature = "nats")]
+use async_trait::async_trait;
+#[cfg(feature = "nats")]
+use config_core::PublishedEvent;
+
+use crate::model::NatsPublisherConfig;
+#[cfg(feature = "nats")]
+use crate::model::SubjectMapping;
+
+#[cfg(feature = "nats")]
+pub(crate) async fn nats_publisher(config: &NatsPublisherConfig) -> Result<Arc<dyn EventConsumer>> {
+    Ok(Arc::new(NatsPublisher::connect(config).await?))
+}
+
+#[cfg(not(feature = "nats"))]
+pub(crate) async fn nats_publisher(
+    _config: &NatsPublisherConfig,
+) -> Result<Arc<dyn EventConsumer>> {
+    Err(config_common::Error::Config(
+        "nats event publisher requires the `nats` feature".to_string(),
+    ))
+}
+
+/// Publishes events to NATS subjects mapped from their namespace
+#[cfg(feature = "nats")]
+pub struct NatsPublisher {
+    client: async_nats::Client,
+    subjects: SubjectMapping,
+    name: String,
+}
+
+#[cfg(feature = "nats")]
+impl NatsPublisher {
+    /// Connecting keeps retrying in the background when the server is unreachable
+    pub async fn connect(config: &NatsPublisherConfig) -> Result<Self> {
+        let mut options = async_nats::ConnectOptions::new().retry_on_initial_connect();
+        if let Some(path) = &config.credentials_file {
+            options = options
+                .credentials_file(path)
+                .await
+                .map_err(|e| config_common::Error::Config(format!("nats credentials: {}", e)))?;
+        }
+        let client = options
+            .connect(config.url.as_str())
+            .await
+            .map_err(|e| config_common::Error::Config(format!("nats: {}", e)))?;
+
+        Ok(Self {
+            client,
+            subjects: config.subjects.clone(),
+            name: format!("nats:{}", config.url),
+        })
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl EventConsumer for NatsPublisher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        self.client
+            .publish(self.subjects.subject(&event.event), payload.into())
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("nats publish: {}", e)))?;
+        // The event only counts as delivered once the server has it
+        self.client
+            .flush()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("nats flush: {}", e)))
+    }
+}
@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use base64::Engine;
+use config_common::{webhook, ConfigEvent, Result};
+use config_core::{
+    EventConsumer, NotificationChannel, NotificationManager, NotificationSubscription,
+    PublishedEvent,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::model::NotificationConfig;
+
+/// Sends mail for email channels
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, recipients: &[String], subject: &str, body: &str) -> Result<()>;
+}
+
+/// Events of a debounced subscription waiting to be sent, one per config; alerts are all kept
+#[derive(Default)]
+struct Batch {
+    events: Vec<ConfigEvent>,
+}
+
+impl Batch {
+    fn add(&mut self, event: &ConfigEvent) {
+        match self.events.iter_mut().find(|pending| {
+            event.message.is_none()
+                && pending.message.is_none()
+                && pending.config_id == event.config_id
+        }) {
+            Some(pending) => *pending = event.clone(),
+            None => self.events.push(event.clone()),
+        }
+    }
+}
+
+/// Sends every event to the channels of the subscriptions whose filter it matches; events
+/// of debounced subscriptions are held in memory until their window closes
+pub struct Notifier {
+    subscriptions: Arc<dyn NotificationManager>,
+    channels: Arc<Channels>,
+    /// Open batches by subscription ID
+    batches: Arc<Mutex<HashMap<String, Batch>>>,
+}
+
+impl Notifier {
+    pub fn new(
+        subscriptions: Arc<dyn NotificationManager>,
+        config: &NotificationConfig,
+    ) -> Result<Self> {
+        let client = webhook::client(Duration::from_millis(config.timeout_ms))?;
+        let mailer = match &config.smtp {
+            Some(smtp) => Some(crate::email::smtp_mailer(smtp)?),
+            None => None,
+        };
+
+        Ok(Self {
+            subscriptions,
+            channels: Arc::new(Channels { client, mailer }),
+            batches: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Add an event to the subscription's batch, opening one that is sent once the
+    /// debounce window closes
+    fn debounce(&self, subscription: &NotificationSubscription, event: &ConfigEvent) {
+        let mut batches = self.batches.lock().unwrap();
+        if let Some(batch) = batches.get_mut(&subscription.id) {
+            batch.add(event);
+            return;
+        }
+        let mut batch = Batch::default();
+        batch.add(event);
+        batches.insert(subscription.id.clone(), batch);
+
+        let subscription = subscription.clone();
+        let channels = self.channels.clone();
+        let batches = self.batches.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(subscription.debounce_secs)).await;
+            let Some(batch) = batches.lock().unwrap().remove(&subscription.id) else {
+                return;
+            };
+            if let Err(e) = channels.send(&subscription, &batch.events).await {
+                tracing::warn!(
+                    subscription = %subscription.id,
+                    events = batch.events.len(),
+                    error = %e,
+                    "Failed to send notification batch"
+                );
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl EventConsumer for Notifier {
+    fn name(&self) -> &str {
+        "notifications"
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        let subscriptions = self.subscriptions.list_subscriptions().await?;
+        // A failing channel must not hold up, or repeat, the others
+        for subscription in subscriptions
+            .iter()
+            .filter(|s| s.filter.matches(&event.event))
+        {
+            if subscription.debounce_secs > 0 {
+                self.debounce(subscription, &event.event);
+                continue;
+            }
+            let events = std::slice::from_ref(&event.event);
+            if let Err(e) = self.channels.send(subscription, events).await {
+                tracing::warn!(
+                    subscription = %subscription.id,
+                    seq = event.seq,
+                    error = %e,
+                    "Failed to send notification"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Clients delivering messages to each kind of channel
+struct Channels {
+    client: reqwest::Client,
+    mailer: Option<Arc<dyn Mailer>>,
+}
+
+impl Channels {
+    /// Send events as one message, a line per event
+    async fn send(
+        &self,
+        subscription: &NotificationSubscription,
+        events: &[ConfigEvent],
+    ) -> Result<()> {
+        let text = events
+            .iter()
+            .map(|event| subscription.render(event))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match &subscription.channel {
+            NotificationChannel::Slack { webhook_url } => {
+                webhook::post_json(
+                    self.client.post(webhook_url),
+                    &serde_json::json!({ "text": text }),
+                )
+                .await
+            }
+            NotificationChannel::DingTalk {
+                webhook_url,
+                secret,
+            } => {
+                let mut request = self.client.post(webhook_url);
+                if let Some(secret) = secret {
+                    let timestamp = chrono::Utc::now().timestamp_millis();
+                    request = request.query(&[
+                        ("timestamp", timestamp.to_string()),
+                        ("sign", dingtalk_sign(secret, timestamp)?),
+                    ]);
+                }
+                webhook::post_json(
+                    request,
+                    &serde_json::json!({ "msgtype": "text", "text": { "content": text } }),
+                )
+                .await
+            }
+            NotificationChannel::Email { recipients } => {
+                let mailer = self.mailer.as_ref().ok_or_else(|| {
+                    config_common::Error::Config("no smtp server is configured".to_string())
+                })?;
+                let subject = match events {
+                    [ConfigEvent {
+                        message: Some(message),
+                        event_type,
+                        ..
+                    }] => format!("{} {}", event_type.as_str(), message),
+                    [event] => format!(
+                        "{} {}/{} {}",
+                        event.environment,
+                        event.namespace,
+                        event.config_id,
+                        event.event_type.as_str()
+                    ),
+                    _ => format!("{} configuration changes", events.len()),
+                };
+                mailer.send(recipients, &subject, &text).await
+            }
+        }
+    }
+}
+
+/// Signature DingTalk robots with a secret require on each request
+fn dingtalk_sign(secret: &str, timestamp: i64) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+    mac.update(format!("{}\n{}", timestamp, secret).as_bytes());
+    Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+}
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use config_common::{ConfigEventType, Result};
+use config_core::{ConfigFilter, ConfigManager, EventConsumer, PublishedEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::import::{config_files, path_prefix, ConfigFile};
+use crate::repo::{GitRepo, Identity};
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_committer_name() -> String {
+    "config-server".to_string()
+}
+
+fn default_committer_email() -> String {
+    "config-server@localhost".to_string()
+}
+
+/// Repository a namespace is exported to, in the layout imports read:
+/// `<path>/<application>/<environment>/<name>.<format>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitExportConfig {
+    /// Name of the export, part of its event consumer name
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// Directory of the repository holding the configs; the root when unset
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Namespace whose changes are exported
+    pub namespace: String,
+    /// Where the repository is checked out; under the temporary directory when unset
+    #[serde(default)]
+    pub checkout_dir: Option<PathBuf>,
+    #[serde(default = "default_committer_name")]
+    pub committer_name: String,
+    /// Email of the commits; their author is the user who made the change
+    #[serde(default = "default_committer_email")]
+    pub committer_email: String,
+}
+
+/// Commits each applied change of a namespace to a Git branch. Encrypted configs aren't
+/// exported. Failed pushes are retried with the event, on top of the branch as it is then.
+pub struct GitExporter {
+    config: GitExportConfig,
+    consumer_name: String,
+    config_manager: Arc<dyn ConfigManager>,
+    repo: GitRepo,
+    committer: Identity,
+    /// Serialises writes to the checkout
+    lock: Mutex<()>,
+}
+
+impl GitExporter {
+    pub fn new(config_manager: Arc<dyn ConfigManager>, config: GitExportConfig) -> Self {
+        let dir = config.checkout_dir.clone().unwrap_or_else(|| {
+            std::env::temp_dir()
+                .join("config-server-git-export")
+                .join(&config.name)
+        });
+        Self {
+            consumer_name: format!("git-export:{}", config.name),
+            repo: GitRepo::new(dir, &config.url, &config.branch),
+            committer: Identity {
+                name: config.committer_name.clone(),
+                email: config.committer_email.clone(),
+            },
+            config_manager,
+            config,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Write the current content of a changed config
+    async fn export_config(&self, config_id: &str) -> Result<Option<String>> {
+        let (meta, content) = match self.config_manager.get_config(config_id).await {
+            Ok(config) => config,
+            // Deleted since; its delete event removes the file
+            Err(config_common::Error::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if content.is_encrypted {
+            tracing::debug!(config = %config_id, "Not exporting encrypted config");
+            return Ok(None);
+        }
+        let content = self.config_manager.redact_content(content)?;
+
+        let prefix = path_prefix(self.config.path.as_deref());
+        let path = format!(
+            "{}{}/{}/{}.{}",
+            prefix,
+            meta.application,
+            meta.environment,
+            meta.name,
+            content.format.as_str()
+        );
+        // A file of the config under another extension is replaced
+        for file in self.exported_files().await? {
+            let same_config = file.application == meta.application
+                && file.environment == meta.environment
+                && file.name == meta.name;
+            if same_config && file.path != path {
+                self.remove(&file.path).await?;
+            }
+        }
+        let destination = self.repo.dir().join(&path);
+        if let Some(parent) = destination.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| config_common::Error::Internal(format!("{}: {}", path, e)))?;
+        }
+        tokio::fs::write(&destination, content.content)
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("{}: {}", path, e)))?;
+
+        Ok(Some(format!(
+            "Update {}/{}/{} to version {}",
+            meta.application, meta.environment, meta.name, meta.version
+        )))
+    }
+
+    /// Remove the files of configs that no longer exist in the namespace
+    async fn remove_deleted(&self) -> Result<Option<String>> {
+        let filter = ConfigFilter {
+            namespace: Some(self.config.namespace.clone()),
+            ..Default::default()
+        };
+        let existing: HashSet<(String, String, String)> =
+            config_core::list_all_configs(self.config_manager.as_ref(), filter)
+                .await?
+                .into_iter()
+                .map(|meta| (meta.application, meta.environment, meta.name))
+                .collect();
+
+        let mut removed = Vec::new();
+        for file in self.exported_files().await? {
+            let key = (file.application, file.environment, file.name);
+            if !existing.contains(&key) {
+                self.remove(&file.path).await?;
+                removed.push(format!("{}/{}/{}", key.0, key.1, key.2));
+            }
+        }
+        Ok(match removed.as_slice() {
+            [] => None,
+            [config] => Some(format!("Delete {}", config)),
+            configs => Some(format!("Delete {}", configs.join(", "))),
+        })
+    }
+
+    /// Config files in the checkout; none before the first export created the directory
+    async fn exported_files(&self) -> Result<Vec<ConfigFile>> {
+        let root = self
+            .repo
+            .dir()
+            .join(path_prefix(self.config.path.as_deref()));
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+        config_files(self.repo.dir(), self.config.path.as_deref()).await
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        tokio::fs::remove_file(self.repo.dir().join(path))
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("{}: {}", path, e)))
+    }
+}
+
+#[async_trait]
+impl EventConsumer for GitExporter {
+    fn name(&self) -> &str {
+        &self.consumer_name
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        let event = &event.event;
+        if event.namespace != self.config.namespace {
+            return Ok(());
+        }
+        let _guard = self.lock.lock().await;
+        let subject = match event.event_type {
+            ConfigEventType::Created
+            | ConfigEventType::Updated
+            | ConfigEventType::Released
+            | ConfigEventType::Rolled => {
+                self.repo.pull().await?;
+                self.export_config(&event.config_id).await?
+            }
+            ConfigEventType::Deleted => {
+                self.repo.pull().await?;
+                self.remove_deleted().await?
+            }
+            ConfigEventType::SecretExpiring
+            | ConfigEventType::SecretExpired
+            | ConfigEventType::ApprovalRequested
+            | ConfigEventType::AuditAnomaly => None,
+        };
+        let Some(subject) = subject else {
+            return Ok(());
+        };
+
+        let message = format!(
+            "{}\n\n{} by {} in namespace {}",
+            subject,
+            event.event_type.as_str(),
+            event.user,
+            event.namespace
+        );
+        // Changes that came from an import match the branch already
+        if self
+            .repo
+            .commit_all(&message, &event.user, &self.committer)
+            .await?
+        {
+            self.repo.push().await?;
+            tracing::info!(
+                export = %self.config.name,
+                config = %event.config_id,
+                "Exported config change to Git"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,390 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ConfigFormat, ConfigMeta, Result};
+use config_core::{ConfigFilter, ConfigManager, GitSync, GitSyncReport};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::repo::GitRepo;
+
+/// Prefix of the label marking configs a repository manages, followed by its name
+const IMPORT_LABEL_PREFIX: &str = "git:";
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_department() -> String {
+    "default".to_string()
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+/// Repository configs are imported from. Files map to configs by path:
+/// `<path>/<application>/<environment>/<name>.<yaml|yml|json|toml|properties>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitImportConfig {
+    /// Name of the repository in the API and in the label of its configs
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_branch")]
+    pub branch: String,
+    /// Directory of the repository holding the configs; the root when unset
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Namespace the configs are imported into
+    pub namespace: String,
+    /// Department of configs created by the import
+    #[serde(default = "default_department")]
+    pub department: String,
+    /// Seconds between pulls; zero leaves syncing to webhooks
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Where the repository is checked out; under the temporary directory when unset
+    #[serde(default)]
+    pub checkout_dir: Option<PathBuf>,
+    /// Secret webhooks sign their requests with
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+}
+
+/// Config file found in a checkout
+pub(crate) struct ConfigFile {
+    /// Path relative to the repository root
+    pub(crate) path: String,
+    pub(crate) application: String,
+    pub(crate) environment: String,
+    pub(crate) name: String,
+    pub(crate) format: ConfigFormat,
+}
+
+struct ImportedRepo {
+    config: GitImportConfig,
+    repo: GitRepo,
+    /// Commit last applied in full; held while syncing so syncs of a repository don't overlap
+    last_commit: Mutex<Option<String>>,
+}
+
+/// Applies the configs of Git repositories through the normal write path, with the author of
+/// the last commit touching a file as the user making the change. Configs created by an
+/// import are labelled `git:<repository>` and deleted when their file goes away.
+pub struct GitImporter {
+    config_manager: Arc<dyn ConfigManager>,
+    repositories: HashMap<String, ImportedRepo>,
+}
+
+impl GitImporter {
+    pub fn new(config_manager: Arc<dyn ConfigManager>, imports: &[GitImportConfig]) -> Self {
+        let repositories = imports
+            .iter()
+            .map(|config| {
+                let dir = config.checkout_dir.clone().unwrap_or_else(|| {
+                    std::env::temp_dir()
+                        .join("config-server-git")
+                        .join(&config.name)
+                });
+                let imported = ImportedRepo {
+                    config: config.clone(),
+                    repo: GitRepo::new(dir, &config.url, &config.branch),
+                    last_commit: Mutex::new(None),
+                };
+                (config.name.clone(), imported)
+            })
+            .collect();
+        Self {
+            config_manager,
+            repositories,
+        }
+    }
+
+    /// Pull each repository with a polling interval periodically until the tasks are aborted
+    pub fn spawn(self: &Arc<Self>) -> Vec<JoinHandle<()>> {
+        self.repositories
+            .values()
+            .filter(|imported| imported.config.interval_secs > 0)
+            .map(|imported| {
+                let importer = self.clone();
+                let name = imported.config.name.clone();
+                let interval = Duration::from_secs(imported.config.interval_secs);
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    loop {
+                        ticker.tick().await;
+                        if let Err(e) = importer.sync(&name).await {
+                            tracing::error!(repository = %name, error = %e, "Git import failed");
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn repository(&self, name: &str) -> Result<&ImportedRepo> {
+        self.repositories
+            .get(name)
+            .ok_or_else(|| config_common::Error::NotFound(format!("git repository {}", name)))
+    }
+
+    async fn sync_repo(&self, imported: &ImportedRepo) -> Result<GitSyncReport> {
+        let config = &imported.config;
+        let repo = &imported.repo;
+        let mut last_commit = imported.last_commit.lock().await;
+        let commit = repo.pull().await?;
+        let mut report = GitSyncReport {
+            repository: config.name.clone(),
+            commit: commit.clone(),
+            ..Default::default()
+        };
+        if last_commit.as_deref() == Some(commit.as_str()) {
+            return Ok(report);
+        }
+
+        let files = config_files(repo.dir(), config.path.as_deref()).await?;
+        // After a full sync only files changed since need applying
+        let changed: Option<HashSet<String>> = match last_commit.as_deref() {
+            Some(previous) => Some(
+                repo.changed_files(previous, &commit)
+                    .await?
+                    .into_iter()
+                    .collect(),
+            ),
+            None => None,
+        };
+        let filter = ConfigFilter {
+            namespace: Some(config.namespace.clone()),
+            ..Default::default()
+        };
+        let mut existing: HashMap<(String, String, String), ConfigMeta> =
+            config_core::list_all_configs(self.config_manager.as_ref(), filter)
+                .await?
+                .into_iter()
+                .map(|meta| {
+                    let key = (
+                        meta.application.clone(),
+                        meta.environment.clone(),
+                        meta.name.clone(),
+                    );
+                    (key, meta)
+                })
+                .collect();
+
+        let label = format!("{}{}", IMPORT_LABEL_PREFIX, config.name);
+        let mut failed = false;
+        for file in &files {
+            let key = (
+                file.application.clone(),
+                file.environment.clone(),
+                file.name.clone(),
+            );
+            let meta = existing.remove(&key);
+            let unchanged = changed
+                .as_ref()
+                .is_some_and(|changed| !changed.contains(&file.path));
+            if unchanged && meta.is_some() {
+                continue;
+            }
+            match self.apply(imported, file, meta, &label).await {
+                Ok(Applied::Created) => report.created += 1,
+                Ok(Applied::Updated) => report.updated += 1,
+                Ok(Applied::Unchanged) => {}
+                Err(e) => {
+                    failed = true;
+                    tracing::error!(
+                        repository = %config.name,
+                        file = %file.path,
+                        error = %e,
+                        "Failed to import config file"
+                    );
+                }
+            }
+        }
+
+        // Only configs the repository manages go away with their file
+        for meta in existing.into_values() {
+            if !meta.labels.contains(&label) {
+                continue;
+            }
+            match self.config_manager.delete_config(&meta.id).await {
+                Ok(_) => report.deleted += 1,
+                Err(e) => {
+                    failed = true;
+                    tracing::error!(
+                        repository = %config.name,
+                        config = %meta.id,
+                        error = %e,
+                        "Failed to delete config removed from Git"
+                    );
+                }
+            }
+        }
+
+        // A failed file is retried by comparing every file on the next sync
+        *last_commit = (!failed).then_some(commit);
+        if report.created + report.updated + report.deleted > 0 {
+            tracing::info!(
+                repository = %config.name,
+                commit = %report.commit,
+                created = report.created,
+                updated = report.updated,
+                deleted = report.deleted,
+                "Imported configs from Git"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Create or update the config of a file
+    async fn apply(
+        &self,
+        imported: &ImportedRepo,
+        file: &ConfigFile,
+        meta: Option<ConfigMeta>,
+        label: &str,
+    ) -> Result<Applied> {
+        let config = &imported.config;
+        let repo = &imported.repo;
+        let content = tokio::fs::read_to_string(repo.dir().join(&file.path))
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("{}: {}", file.path, e)))?;
+        let content = ConfigContent {
+            format: file.format,
+            content,
+            is_encrypted: false,
+        };
+        let (author, summary) = repo.last_change(&file.path).await?;
+
+        let (meta, applied) = match meta {
+            None => {
+                let meta = self
+                    .config_manager
+                    .create_config(
+                        &file.name,
+                        &config.namespace,
+                        &config.department,
+                        &file.application,
+                        &file.environment,
+                        None,
+                        content,
+                        &author,
+                    )
+                    .await?;
+                (meta, Applied::Created)
+            }
+            Some(meta) => {
+                let (_, current) = self.config_manager.get_config(&meta.id).await?;
+                if !current.is_encrypted && current.content == content.content {
+                    return Ok(Applied::Unchanged);
+                }
+                let reason = format!("git {}", summary);
+                let meta = self
+                    .config_manager
+                    .update_config(&meta.id, None, content, Some(&reason), &author)
+                    .await?;
+                (meta, Applied::Updated)
+            }
+        };
+
+        if !meta.labels.iter().any(|l| l == label) {
+            let mut labels = meta.labels.clone();
+            labels.push(label.to_string());
+            labels.sort();
+            self.config_manager
+                .update_labels(&meta.id, labels, &author)
+                .await?;
+        }
+        Ok(applied)
+    }
+}
+
+enum Applied {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+#[async_trait]
+impl GitSync for GitImporter {
+    async fn sync(&self, repository: &str) -> Result<GitSyncReport> {
+        self.sync_repo(self.repository(repository)?).await
+    }
+
+    fn webhook_secret(&self, repository: &str) -> Result<Option<String>> {
+        Ok(self.repository(repository)?.config.webhook_secret.clone())
+    }
+}
+
+/// Files of a checkout laid out as `<application>/<environment>/<name>.<extension>` below
+/// `path`; anything else is ignored
+pub(crate) async fn config_files(checkout: &Path, path: Option<&str>) -> Result<Vec<ConfigFile>> {
+    let prefix = path_prefix(path);
+    let root = checkout.join(&prefix);
+    let mut files = Vec::new();
+    for application in subdirectories(&root).await? {
+        for environment in subdirectories(&root.join(&application)).await? {
+            let dir = root.join(&application).join(&environment);
+            for (file_name, is_dir) in entries(&dir).await? {
+                let Some((name, extension)) = file_name.rsplit_once('.') else {
+                    continue;
+                };
+                let format = match extension {
+                    "yaml" | "yml" => ConfigFormat::Yaml,
+                    "json" => ConfigFormat::Json,
+                    "toml" => ConfigFormat::Toml,
+                    "properties" => ConfigFormat::Properties,
+                    _ => continue,
+                };
+                if is_dir || name.is_empty() {
+                    continue;
+                }
+                files.push(ConfigFile {
+                    path: format!("{}{}/{}/{}", prefix, application, environment, file_name),
+                    application: application.clone(),
+                    environment: environment.clone(),
+                    name: name.to_string(),
+                    format,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Directory of the configs relative to the repository root, with a trailing slash unless
+/// it is the root
+pub(crate) fn path_prefix(path: Option<&str>) -> String {
+    match path.map(|p| p.trim_matches('/')) {
+        Some(p) if !p.is_empty() => format!("{}/", p),
+        _ => String::new(),
+    }
+}
+
+/// Directories in a directory, skipping hidden ones such as `.git`
+async fn subdirectories(dir: &Path) -> Result<Vec<String>> {
+    Ok(entries(dir)
+        .await?
+        .into_iter()
+        .filter(|(name, is_dir)| *is_dir && !name.starts_with('.'))
+        .map(|(name, _)| name)
+        .collect())
+}
+
+/// Names of the entries in a directory and whether each is a directory
+async fn entries(dir: &Path) -> Result<Vec<(String, bool)>> {
+    let io_error =
+        |e: std::io::Error| config_common::Error::Internal(format!("{}: {}", dir.display(), e));
+    let mut entries = Vec::new();
+    let mut read_dir = tokio::fs::read_dir(dir).await.map_err(io_error)?;
+    while let Some(entry) = read_dir.next_entry().await.map_err(io_error)? {
+        let is_dir = entry.file_type().await.map_err(io_error)?.is_dir();
+        if let Some(name) = entry.file_name().to_str() {
+            entries.push((name.to_string(), is_dir));
+        }
+    }
+    Ok(entries)
+}
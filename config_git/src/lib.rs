@@ -0,0 +1,20 @@
+pub mod export;
+pub mod import;
+pub mod repo;
+
+use serde::{Deserialize, Serialize};
+
+pub use export::{GitExportConfig, GitExporter};
+pub use import::{GitImportConfig, GitImporter};
+pub use repo::GitRepo;
+
+/// Git synchronization settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Repositories configs are imported from
+    #[serde(default)]
+    pub imports: Vec<GitImportConfig>,
+    /// Repositories namespaces are exported to
+    #[serde(default)]
+    pub exports: Vec<GitExportConfig>,
+}
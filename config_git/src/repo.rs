@@ -0,0 +1,128 @@
+use config_common::Result;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Name and email commits are made under
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+/// Local checkout of a branch of a remote repository, driven through the `git` command
+pub struct GitRepo {
+    dir: PathBuf,
+    url: String,
+    branch: String,
+}
+
+impl GitRepo {
+    pub fn new(dir: PathBuf, url: &str, branch: &str) -> Self {
+        Self {
+            dir,
+            url: url.to_string(),
+            branch: branch.to_string(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Clone the branch, or move the checkout to its latest commit; returns that commit
+    pub async fn pull(&self) -> Result<String> {
+        if self.dir.join(".git").exists() {
+            self.git(&["fetch", "origin", &self.branch]).await?;
+            self.git(&["reset", "--hard", "FETCH_HEAD"]).await?;
+        } else {
+            if let Some(parent) = self.dir.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+            }
+            let dir = self.dir.to_string_lossy();
+            run(Command::new("git").args([
+                "clone",
+                "--branch",
+                &self.branch,
+                "--single-branch",
+                &self.url,
+                &dir,
+            ]))
+            .await?;
+        }
+        self.head().await
+    }
+
+    pub async fn head(&self) -> Result<String> {
+        Ok(self.git(&["rev-parse", "HEAD"]).await?.trim().to_string())
+    }
+
+    /// Paths changed between two commits, relative to the repository root
+    pub async fn changed_files(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        let range = format!("{}..{}", from, to);
+        let output = self.git(&["diff", "--name-only", &range]).await?;
+        Ok(output.lines().map(String::from).collect())
+    }
+
+    /// Author email and `<short hash> <subject>` of the last commit touching a path
+    pub async fn last_change(&self, path: &str) -> Result<(String, String)> {
+        let output = self
+            .git(&["log", "-1", "--format=%ae%n%h %s", "--", path])
+            .await?;
+        let mut lines = output.lines();
+        let author = lines.next().unwrap_or_default().to_string();
+        let summary = lines.next().unwrap_or_default().to_string();
+        Ok((author, summary))
+    }
+
+    /// Commit every change of the working tree; `false` when there was nothing to commit
+    pub async fn commit_all(
+        &self,
+        message: &str,
+        author: &str,
+        committer: &Identity,
+    ) -> Result<bool> {
+        self.git(&["add", "--all"]).await?;
+        if self
+            .git(&["status", "--porcelain"])
+            .await?
+            .trim()
+            .is_empty()
+        {
+            return Ok(false);
+        }
+        let name = format!("user.name={}", committer.name);
+        let email = format!("user.email={}", committer.email);
+        let author = format!("--author={} <{}>", author, committer.email);
+        self.git(&["-c", &name, "-c", &email, "commit", "-m", message, &author])
+            .await?;
+        Ok(true)
+    }
+
+    /// Push the checkout to the branch it tracks
+    pub async fn push(&self) -> Result<()> {
+        let refspec = format!("HEAD:{}", self.branch);
+        self.git(&["push", "origin", &refspec]).await?;
+        Ok(())
+    }
+
+    /// Run a git command in the checkout and return its output
+    pub async fn git(&self, args: &[&str]) -> Result<String> {
+        run(Command::new("git").arg("-C").arg(&self.dir).args(args)).await
+    }
+}
+
+async fn run(command: &mut Command) -> Result<String> {
+    let output = command
+        .output()
+        .await
+        .map_err(|e| config_common::Error::Internal(format!("failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(config_common::Error::Internal(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
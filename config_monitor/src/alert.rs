@@ -0,0 +1,214 @@
+use config_common::Result;
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::Registry;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::model::{AlertRule, AlertRules};
+use crate::notify::{Notification, NotificationStatus, Notifier};
+
+/// Load alert rules from a YAML file
+pub fn load_rules(path: impl AsRef<Path>) -> Result<Vec<AlertRule>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| config_common::Error::Config(format!("{}: {}", path.display(), e)))?;
+    let rules: AlertRules = serde_yaml::from_str(&content)
+        .map_err(|e| config_common::Error::Config(format!("{}: {}", path.display(), e)))?;
+    Ok(rules.rules)
+}
+
+/// Evaluation state of an alert rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Inactive,
+    Pending,
+    Firing,
+}
+
+/// Current state of a single alert rule
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertStatus {
+    pub name: String,
+    pub severity: String,
+    pub state: AlertState,
+    /// Last observed value; absent when the metric has no matching series
+    pub value: Option<f64>,
+    pub threshold: f64,
+    /// When the condition started holding
+    pub active_since: Option<i64>,
+    pub last_evaluated: Option<i64>,
+}
+
+/// In-process evaluator of threshold alert rules
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    registry: Registry,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    states: RwLock<HashMap<String, AlertStatus>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, registry: Registry) -> Self {
+        let states = rules
+            .iter()
+            .map(|rule| {
+                let status = AlertStatus {
+                    name: rule.name.clone(),
+                    severity: rule.severity.clone(),
+                    state: AlertState::Inactive,
+                    value: None,
+                    threshold: rule.threshold,
+                    active_since: None,
+                    last_evaluated: None,
+                };
+                (rule.name.clone(), status)
+            })
+            .collect();
+
+        Self {
+            rules,
+            registry,
+            notifiers: Vec::new(),
+            states: RwLock::new(states),
+        }
+    }
+
+    pub fn with_notifiers(mut self, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        self.notifiers = notifiers;
+        self
+    }
+
+    /// Current state of every rule, sorted by name
+    pub async fn alerts(&self) -> Vec<AlertStatus> {
+        let mut alerts: Vec<_> = self.states.read().await.values().cloned().collect();
+        alerts.sort_by(|a, b| a.name.cmp(&b.name));
+        alerts
+    }
+
+    /// Evaluate all rules once against the registry
+    pub async fn evaluate(&self) {
+        let families = self.registry.gather();
+        let now = chrono::Utc::now().timestamp();
+        let mut notifications = Vec::new();
+
+        {
+            let mut states = self.states.write().await;
+            for rule in &self.rules {
+                let Some(status) = states.get_mut(&rule.name) else {
+                    continue;
+                };
+                let value = metric_value(&families, rule);
+                let holds = value.is_some_and(|v| rule.comparison.matches(v, rule.threshold));
+                status.value = value;
+                status.last_evaluated = Some(now);
+
+                let transition = match (status.state, holds) {
+                    (AlertState::Inactive, true) => {
+                        status.active_since = Some(now);
+                        status.state = AlertState::Pending;
+                        None
+                    }
+                    (AlertState::Pending | AlertState::Firing, false) => {
+                        let was_firing = status.state == AlertState::Firing;
+                        status.state = AlertState::Inactive;
+                        status.active_since = None;
+                        was_firing.then_some(NotificationStatus::Resolved)
+                    }
+                    _ => None,
+                };
+
+                if status.state == AlertState::Pending
+                    && now - status.active_since.unwrap_or(now) >= rule.for_secs as i64
+                {
+                    status.state = AlertState::Firing;
+                    notifications.push(notification(rule, value, NotificationStatus::Firing, now));
+                } else if let Some(transition) = transition {
+                    notifications.push(notification(rule, value, transition, now));
+                }
+            }
+        }
+
+        for notification in &notifications {
+            tracing::warn!(
+                alert = %notification.alert,
+                status = ?notification.status,
+                value = notification.value,
+                "Alert state changed"
+            );
+            for notifier in &self.notifiers {
+                if let Err(e) = notifier.notify(notification).await {
+                    tracing::error!(
+                        notifier = notifier.name(),
+                        alert = %notification.alert,
+                        error = %e,
+                        "Failed to send alert notification"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Evaluate rules periodically
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.evaluate().await;
+            }
+        })
+    }
+}
+
+fn notification(
+    rule: &AlertRule,
+    value: Option<f64>,
+    status: NotificationStatus,
+    timestamp: i64,
+) -> Notification {
+    Notification {
+        alert: rule.name.clone(),
+        status,
+        severity: rule.severity.clone(),
+        value: value.unwrap_or_default(),
+        threshold: rule.threshold,
+        description: rule.description.clone(),
+        timestamp,
+    }
+}
+
+/// Sum of all series of the rule's metric that carry the rule's labels
+fn metric_value(families: &[MetricFamily], rule: &AlertRule) -> Option<f64> {
+    let family = families.iter().find(|f| f.name() == rule.metric)?;
+    let values: Vec<f64> = family
+        .metric
+        .iter()
+        .filter(|metric| {
+            rule.labels.iter().all(|(name, value)| {
+                metric
+                    .label
+                    .iter()
+                    .any(|label| label.name() == name && label.value() == value)
+            })
+        })
+        .map(|metric| match family.type_() {
+            MetricType::COUNTER => metric.counter.value(),
+            MetricType::GAUGE => metric.gauge.value(),
+            MetricType::UNTYPED => metric.untyped.value(),
+            MetricType::HISTOGRAM => metric.histogram.sample_count() as f64,
+            MetricType::SUMMARY => metric.summary.sample_count() as f64,
+        })
+        .collect();
+
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum())
+    }
+}
@@ -0,0 +1,145 @@
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::task::JoinHandle;
+
+use crate::metrics::SystemMetrics;
+
+/// Longest a pool probe waits for a connection before counting as timed out
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Background task sampling process, runtime and backend statistics
+pub struct SystemCollector {
+    metrics: SystemMetrics,
+    interval: Duration,
+    pool: Option<Arc<PgPool>>,
+    redis: Option<redis::Client>,
+}
+
+impl SystemCollector {
+    pub fn new(metrics: SystemMetrics, interval: Duration) -> Self {
+        Self {
+            metrics,
+            interval,
+            pool: None,
+            redis: None,
+        }
+    }
+
+    /// Also sample database pool statistics
+    pub fn with_pool(mut self, pool: Arc<PgPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Also probe Redis availability
+    pub fn with_redis(mut self, client: redis::Client) -> Self {
+        self.redis = Some(client);
+        self
+    }
+
+    /// Sample until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut system = System::new();
+            let pid = sysinfo::get_current_pid().ok();
+            let mut ticker = tokio::time::interval(self.interval);
+
+            loop {
+                ticker.tick().await;
+                if let Some(pid) = pid {
+                    self.sample_process(&mut system, pid);
+                }
+                self.sample_runtime();
+                self.sample_pool();
+                self.sample_redis().await;
+            }
+        })
+    }
+
+    fn sample_process(&self, system: &mut System, pid: Pid) {
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        if let Some(process) = system.process(pid) {
+            self.metrics.set_cpu_usage(process.cpu_usage() as f64);
+            self.metrics.set_memory_usage(process.memory() as i64);
+        }
+        if let Some(sockets) = count_sockets() {
+            self.metrics.set_open_connections(sockets);
+        }
+    }
+
+    fn sample_runtime(&self) {
+        let metrics = tokio::runtime::Handle::current().metrics();
+        self.metrics.set_runtime_tasks(
+            metrics.num_workers() as i64,
+            metrics.num_alive_tasks() as i64,
+        );
+    }
+
+    /// Pool occupancy, and a timed checkout standing in for the wait requests see at the
+    /// moment. The checkout runs in a task of its own and gives up after `PROBE_TIMEOUT` or
+    /// the interval, whichever is shorter, so a saturated pool doesn't hold up the other
+    /// samples.
+    fn sample_pool(&self) {
+        let Some(pool) = &self.pool else {
+            return;
+        };
+        self.metrics.set_db_pool(
+            pool.size(),
+            pool.num_idle() as u32,
+            pool.options().get_max_connections(),
+        );
+
+        let pool = pool.clone();
+        let metrics = self.metrics.clone();
+        let timeout = PROBE_TIMEOUT.min(self.interval);
+        tokio::spawn(async move {
+            let started = Instant::now();
+            match tokio::time::timeout(timeout, pool.acquire()).await {
+                Ok(Ok(_)) => metrics.observe_db_pool_acquire(Some(started.elapsed())),
+                Ok(Err(sqlx::Error::PoolTimedOut)) | Err(_) => {
+                    tracing::warn!("Timed out waiting for a database connection");
+                    metrics.observe_db_pool_acquire(None);
+                }
+                Ok(Err(e)) => tracing::warn!(error = %e, "Database pool checkout failed"),
+            }
+        });
+    }
+
+    async fn sample_redis(&self) {
+        let Some(client) = &self.redis else {
+            return;
+        };
+
+        let started = Instant::now();
+        let result = async {
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            redis::cmd("PING").query_async::<String>(&mut conn).await
+        }
+        .await;
+
+        match result {
+            Ok(_) => self.metrics.set_redis_ping(Some(started.elapsed())),
+            Err(e) => {
+                tracing::warn!(error = %e, "Redis ping failed");
+                self.metrics.set_redis_ping(None);
+            }
+        }
+    }
+}
+
+/// Number of open sockets of this process; only available on Linux
+fn count_sockets() -> Option<i64> {
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?;
+    let count = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| std::fs::read_link(entry.path()).ok())
+        .filter(|target| target.to_string_lossy().starts_with("socket:"))
+        .count();
+    Some(count as i64)
+}
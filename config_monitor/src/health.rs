@@ -0,0 +1,163 @@
+use actix_web::{web, HttpResponse};
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest a single dependency check may take before it counts as down
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Dependency probed by the readiness endpoint
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    /// Name reported in the readiness response
+    fn name(&self) -> &str;
+
+    /// Succeeds when the dependency is usable
+    async fn check(&self) -> Result<(), String>;
+}
+
+/// Outcome of a single dependency check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckStatus {
+    pub status: &'static str,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Readiness report over all dependencies
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub checks: BTreeMap<String, CheckStatus>,
+}
+
+impl HealthReport {
+    pub fn is_ready(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+/// Runs the registered dependency checks
+#[derive(Default)]
+pub struct HealthService {
+    checks: Vec<Arc<dyn HealthCheck>>,
+}
+
+impl HealthService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_check(mut self, check: Arc<dyn HealthCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Run all checks concurrently
+    pub async fn report(&self) -> HealthReport {
+        let results = futures_util::future::join_all(self.checks.iter().map(|check| async move {
+            let started = Instant::now();
+            let result = match tokio::time::timeout(CHECK_TIMEOUT, check.check()).await {
+                Ok(result) => result,
+                Err(_) => Err("timed out".to_string()),
+            };
+            let status = CheckStatus {
+                status: if result.is_ok() { "up" } else { "down" },
+                latency_ms: started.elapsed().as_millis() as u64,
+                error: result.err(),
+            };
+            (check.name().to_string(), status)
+        }))
+        .await;
+
+        let checks: BTreeMap<_, _> = results.into_iter().collect();
+        let ready = checks.values().all(|c| c.status == "up");
+        HealthReport {
+            status: if ready { "ok" } else { "unavailable" },
+            checks,
+        }
+    }
+}
+
+/// Postgres connectivity check
+pub struct PostgresCheck {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresCheck {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for PostgresCheck {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        sqlx::query("SELECT 1")
+            .execute(self.pool.as_ref())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Redis connectivity check
+pub struct RedisCheck {
+    client: redis::Client,
+}
+
+impl RedisCheck {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RedisCheck {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let mut conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Liveness handler; answers as long as the process serves requests
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness handler; fails when any dependency is down
+pub async fn readyz(health: web::Data<HealthService>) -> HttpResponse {
+    let report = health.report().await;
+    if report.is_ready() {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+/// Configure the health routes on an existing app
+pub fn configure_routes(config: &mut web::ServiceConfig, health: Arc<HealthService>) {
+    config.app_data(web::Data::from(health));
+    config.route("/healthz", web::get().to(healthz));
+    config.route("/readyz", web::get().to(readyz));
+}
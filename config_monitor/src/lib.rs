@@ -0,0 +1,110 @@
+pub mod alert;
+pub mod collector;
+pub mod health;
+pub mod logging;
+pub mod metrics;
+pub mod model;
+pub mod notify;
+
+pub use alert::{AlertEngine, AlertState, AlertStatus};
+pub use collector::SystemCollector;
+pub use health::{HealthCheck, HealthService, PostgresCheck, RedisCheck};
+pub use logging::LogLevel;
+pub use metrics::{ClientMetrics, ClientStats, ConfigMetrics, OperationTimer, SystemMetrics};
+pub use model::{AlertRule, Comparison, MonitorConfig, NotifierConfig};
+pub use notify::{Notifier, WebhookNotifier};
+
+use actix_web::{dev::Server, web, App, HttpResponse, HttpServer};
+use config_common::Result;
+use prometheus::{Encoder, Registry, TextEncoder};
+use std::sync::Arc;
+
+/// Monitoring service owning the metrics registry
+pub struct MonitoringService {
+    registry: Registry,
+    config_metrics: ConfigMetrics,
+    system_metrics: SystemMetrics,
+    client_metrics: ClientMetrics,
+}
+
+impl MonitoringService {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        let config_metrics = ConfigMetrics::new(&registry)?;
+        let system_metrics = SystemMetrics::new(&registry)?;
+        let client_metrics = ClientMetrics::new(&registry)?;
+
+        Ok(Self {
+            registry,
+            config_metrics,
+            system_metrics,
+            client_metrics,
+        })
+    }
+
+    /// Registry other components register their metrics with
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub fn config_metrics(&self) -> &ConfigMetrics {
+        &self.config_metrics
+    }
+
+    pub fn system_metrics(&self) -> &SystemMetrics {
+        &self.system_metrics
+    }
+
+    pub fn client_metrics(&self) -> &ClientMetrics {
+        &self.client_metrics
+    }
+
+    /// Encode all registered metrics in the Prometheus text format
+    pub fn encode(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| config_common::Error::Internal(e.to_string()))
+    }
+}
+
+/// Metrics scrape handler
+pub async fn metrics(
+    monitoring: web::Data<MonitoringService>,
+) -> config_common::Result<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(monitoring.encode()?))
+}
+
+/// Configure the metrics route on an existing app
+pub fn configure_routes(
+    config: &mut web::ServiceConfig,
+    monitoring: Arc<MonitoringService>,
+    path: &str,
+) {
+    config.app_data(web::Data::from(monitoring));
+    config.route(path, web::get().to(metrics));
+}
+
+/// Start a dedicated metrics listener
+pub fn serve_metrics(
+    monitoring: Arc<MonitoringService>,
+    config: &MonitorConfig,
+) -> std::io::Result<Option<Server>> {
+    let Some(port) = config.metrics_port else {
+        return Ok(None);
+    };
+
+    let path = config.metrics_path.clone();
+    let server = HttpServer::new(move || {
+        let monitoring = monitoring.clone();
+        let path = path.clone();
+        App::new().configure(move |cfg| configure_routes(cfg, monitoring, &path))
+    })
+    .workers(1)
+    .bind(("0.0.0.0", port))?
+    .run();
+
+    tracing::info!(port, "Serving metrics on dedicated listener");
+    Ok(Some(server))
+}
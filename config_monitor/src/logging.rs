@@ -0,0 +1,58 @@
+use config_common::Result;
+use std::sync::RwLock;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Filter applied when `RUST_LOG` isn't set
+const DEFAULT_FILTER: &str = "info";
+
+/// Handle for changing the tracing filter of a running process
+pub struct LogLevel {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: RwLock<String>,
+}
+
+impl LogLevel {
+    /// Install the global subscriber with a reloadable filter
+    pub fn init() -> Self {
+        let mut directives =
+            std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+        let filter = EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+            eprintln!("Invalid log filter {:?}: {}", directives, e);
+            directives = DEFAULT_FILTER.to_string();
+            EnvFilter::new(DEFAULT_FILTER)
+        });
+        let (filter, handle) = reload::Layer::new(filter);
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer())
+            .init();
+
+        Self {
+            handle,
+            current: RwLock::new(directives),
+        }
+    }
+
+    /// Directives currently in effect, e.g. `info,raft=debug`
+    pub fn current(&self) -> String {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the filter with new directives
+    pub fn set(&self, directives: &str) -> Result<()> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| config_common::Error::Validation(e.to_string()))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = directives.to_string();
+        Ok(())
+    }
+}
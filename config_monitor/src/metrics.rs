@@ -0,0 +1,296 @@
+use config_common::Result;
+use prometheus::{
+    Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Label value used when the namespace or environment isn't known
+pub const UNKNOWN_LABEL: &str = "unknown";
+
+/// Configuration operation metrics, labelled by namespace, environment and operation
+#[derive(Clone)]
+pub struct ConfigMetrics {
+    operations: IntCounterVec,
+    duration: HistogramVec,
+}
+
+impl ConfigMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let operations = IntCounterVec::new(
+            Opts::new("config_operations_total", "Configuration operations"),
+            &["namespace", "environment", "operation", "status"],
+        )?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new(
+                "config_operation_duration_seconds",
+                "Configuration operation latency",
+            ),
+            &["namespace", "environment", "operation"],
+        )?;
+
+        registry.register(Box::new(operations.clone()))?;
+        registry.register(Box::new(duration.clone()))?;
+
+        Ok(Self {
+            operations,
+            duration,
+        })
+    }
+
+    /// Record a completed operation
+    pub fn record(
+        &self,
+        namespace: &str,
+        environment: &str,
+        operation: &str,
+        success: bool,
+        elapsed: Duration,
+    ) {
+        let status = if success { "success" } else { "error" };
+        self.operations
+            .with_label_values(&[namespace, environment, operation, status])
+            .inc();
+        self.duration
+            .with_label_values(&[namespace, environment, operation])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Start timing an operation
+    pub fn start(&self, operation: &str) -> OperationTimer {
+        OperationTimer {
+            metrics: self.clone(),
+            operation: operation.to_string(),
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Timer for a single configuration operation
+pub struct OperationTimer {
+    metrics: ConfigMetrics,
+    operation: String,
+    started: Instant,
+}
+
+impl OperationTimer {
+    /// Record the outcome; labels fall back to `unknown` when the config isn't known
+    pub fn finish<T>(self, namespace: Option<&str>, environment: Option<&str>, result: &Result<T>) {
+        self.metrics.record(
+            namespace.unwrap_or(UNKNOWN_LABEL),
+            environment.unwrap_or(UNKNOWN_LABEL),
+            &self.operation,
+            result.is_ok(),
+            self.started.elapsed(),
+        );
+    }
+}
+
+/// Process and host metrics
+#[derive(Clone)]
+pub struct SystemMetrics {
+    cpu_usage: Gauge,
+    memory_usage: IntGauge,
+    open_connections: IntGauge,
+    runtime_workers: IntGauge,
+    runtime_alive_tasks: IntGauge,
+    db_pool_size: IntGauge,
+    db_pool_idle: IntGauge,
+    db_pool_max: IntGauge,
+    db_pool_saturation: Gauge,
+    db_pool_acquire_seconds: Histogram,
+    db_pool_acquire_timeouts: IntCounter,
+    redis_up: IntGauge,
+    redis_ping_seconds: Gauge,
+}
+
+impl SystemMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let cpu_usage = Gauge::new("system_cpu_usage_percent", "Process CPU usage")?;
+        let memory_usage = IntGauge::new("system_memory_usage_bytes", "Process resident memory")?;
+        let open_connections = IntGauge::new("system_open_connections", "Open network sockets")?;
+        let runtime_workers = IntGauge::new("tokio_workers", "Tokio runtime worker threads")?;
+        let runtime_alive_tasks =
+            IntGauge::new("tokio_alive_tasks", "Tasks alive in the Tokio runtime")?;
+        let db_pool_size = IntGauge::new("db_pool_connections", "Database pool connections")?;
+        let db_pool_idle =
+            IntGauge::new("db_pool_idle_connections", "Idle database pool connections")?;
+        let db_pool_max = IntGauge::new(
+            "db_pool_max_connections",
+            "Largest size of the database pool",
+        )?;
+        let db_pool_saturation = Gauge::new(
+            "db_pool_saturation_ratio",
+            "Share of the largest database pool size in use",
+        )?;
+        // From an idle connection handed over at once to the probe giving up after a second
+        let db_pool_acquire_seconds = Histogram::with_opts(
+            HistogramOpts::new(
+                "db_pool_acquire_seconds",
+                "Wait of a probe checkout of a database pool connection",
+            )
+            .buckets(vec![
+                0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+            ]),
+        )?;
+        let db_pool_acquire_timeouts = IntCounter::new(
+            "db_pool_acquire_timeouts_total",
+            "Probe checkouts of a database pool connection that gave up waiting",
+        )?;
+        let redis_up = IntGauge::new("redis_up", "Whether Redis answered the last ping")?;
+        let redis_ping_seconds =
+            Gauge::new("redis_ping_seconds", "Latency of the last Redis ping")?;
+
+        registry.register(Box::new(cpu_usage.clone()))?;
+        registry.register(Box::new(memory_usage.clone()))?;
+        registry.register(Box::new(open_connections.clone()))?;
+        registry.register(Box::new(runtime_workers.clone()))?;
+        registry.register(Box::new(runtime_alive_tasks.clone()))?;
+        registry.register(Box::new(db_pool_size.clone()))?;
+        registry.register(Box::new(db_pool_idle.clone()))?;
+        registry.register(Box::new(db_pool_max.clone()))?;
+        registry.register(Box::new(db_pool_saturation.clone()))?;
+        registry.register(Box::new(db_pool_acquire_seconds.clone()))?;
+        registry.register(Box::new(db_pool_acquire_timeouts.clone()))?;
+        registry.register(Box::new(redis_up.clone()))?;
+        registry.register(Box::new(redis_ping_seconds.clone()))?;
+
+        Ok(Self {
+            cpu_usage,
+            memory_usage,
+            open_connections,
+            runtime_workers,
+            runtime_alive_tasks,
+            db_pool_size,
+            db_pool_idle,
+            db_pool_max,
+            db_pool_saturation,
+            db_pool_acquire_seconds,
+            db_pool_acquire_timeouts,
+            redis_up,
+            redis_ping_seconds,
+        })
+    }
+
+    pub fn set_cpu_usage(&self, percent: f64) {
+        self.cpu_usage.set(percent);
+    }
+
+    pub fn set_memory_usage(&self, bytes: i64) {
+        self.memory_usage.set(bytes);
+    }
+
+    pub fn set_open_connections(&self, count: i64) {
+        self.open_connections.set(count);
+    }
+
+    pub fn set_runtime_tasks(&self, workers: i64, alive_tasks: i64) {
+        self.runtime_workers.set(workers);
+        self.runtime_alive_tasks.set(alive_tasks);
+    }
+
+    pub fn set_db_pool(&self, size: u32, idle: u32, max: u32) {
+        self.db_pool_size.set(size as i64);
+        self.db_pool_idle.set(idle as i64);
+        self.db_pool_max.set(max as i64);
+        let in_use = size.saturating_sub(idle);
+        self.db_pool_saturation
+            .set(in_use as f64 / max.max(1) as f64);
+    }
+
+    /// Record a probe checkout; `None` when it gave up waiting
+    pub fn observe_db_pool_acquire(&self, wait: Option<Duration>) {
+        match wait {
+            Some(wait) => self.db_pool_acquire_seconds.observe(wait.as_secs_f64()),
+            None => self.db_pool_acquire_timeouts.inc(),
+        }
+    }
+
+    pub fn set_redis_ping(&self, latency: Option<Duration>) {
+        match latency {
+            Some(latency) => {
+                self.redis_up.set(1);
+                self.redis_ping_seconds.set(latency.as_secs_f64());
+            }
+            None => self.redis_up.set(0),
+        }
+    }
+}
+
+/// Request volume of a single client
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClientStats {
+    pub client: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub bytes_sent: u64,
+    pub last_seen: i64,
+}
+
+/// Per-client request and traffic metrics
+#[derive(Clone)]
+pub struct ClientMetrics {
+    requests: IntCounterVec,
+    bytes_sent: IntCounterVec,
+    stats: Arc<Mutex<HashMap<String, ClientStats>>>,
+}
+
+impl ClientMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let requests = IntCounterVec::new(
+            Opts::new("client_requests_total", "API requests per client"),
+            &["client", "status"],
+        )?;
+        let bytes_sent = IntCounterVec::new(
+            Opts::new(
+                "client_response_bytes_total",
+                "Response bytes sent per client",
+            ),
+            &["client"],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(bytes_sent.clone()))?;
+
+        Ok(Self {
+            requests,
+            bytes_sent,
+            stats: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Record a served request
+    pub fn record(&self, client: &str, success: bool, bytes: u64) {
+        let status = if success { "success" } else { "error" };
+        self.requests.with_label_values(&[client, status]).inc();
+        self.bytes_sent.with_label_values(&[client]).inc_by(bytes);
+
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats
+            .entry(client.to_string())
+            .or_insert_with(|| ClientStats {
+                client: client.to_string(),
+                ..Default::default()
+            });
+        entry.requests += 1;
+        entry.errors += (!success) as u64;
+        entry.bytes_sent += bytes;
+        entry.last_seen = chrono::Utc::now().timestamp();
+    }
+
+    /// Clients with the most requests, busiest first
+    pub fn top_clients(&self, limit: usize) -> Vec<ClientStats> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let mut clients: Vec<_> = stats.values().cloned().collect();
+        clients.sort_by(|a, b| {
+            b.requests
+                .cmp(&a.requests)
+                .then_with(|| b.bytes_sent.cmp(&a.bytes_sent))
+        });
+        clients.truncate(limit);
+        clients
+    }
+}
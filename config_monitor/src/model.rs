@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Monitoring configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    /// Serve metrics on a dedicated port instead of the API listener
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    /// Path the metrics are served on
+    #[serde(default = "default_metrics_path")]
+    pub metrics_path: String,
+    /// Seconds between system metrics samples
+    #[serde(default = "default_collect_interval")]
+    pub collect_interval_secs: u64,
+    /// YAML file with alert rules; alerting is disabled when unset
+    #[serde(default)]
+    pub alert_rules: Option<String>,
+    /// Seconds between alert rule evaluations
+    #[serde(default = "default_alert_interval")]
+    pub alert_interval_secs: u64,
+    /// Where alert state changes are sent
+    #[serde(default)]
+    pub notifiers: Vec<NotifierConfig>,
+}
+
+fn default_metrics_path() -> String {
+    "/metrics".to_string()
+}
+
+fn default_collect_interval() -> u64 {
+    15
+}
+
+fn default_alert_interval() -> u64 {
+    15
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            metrics_port: None,
+            metrics_path: default_metrics_path(),
+            collect_interval_secs: default_collect_interval(),
+            alert_rules: None,
+            alert_interval_secs: default_alert_interval(),
+            notifiers: Vec::new(),
+        }
+    }
+}
+
+/// Alert notification destination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    /// POST each notification as JSON to an HTTP endpoint
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default = "default_webhook_timeout")]
+        timeout_ms: u64,
+    },
+}
+
+fn default_webhook_timeout() -> u64 {
+    5_000
+}
+
+/// File layout of the alert rules YAML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertRules {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+/// Threshold over a metric that must hold for a duration before firing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    /// Metric family name; matching series are summed
+    pub metric: String,
+    /// Only series carrying all of these labels are considered
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// Seconds the condition must hold before the alert fires
+    #[serde(default)]
+    pub for_secs: u64,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_severity() -> String {
+    "warning".to_string()
+}
+
+/// Comparison between the metric value and the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Ge => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Le => value <= threshold,
+        }
+    }
+}
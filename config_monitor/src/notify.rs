@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use config_common::{Result, Webhook};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::model::NotifierConfig;
+
+/// Alert state change sent to notifiers
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub alert: String,
+    pub status: NotificationStatus,
+    pub severity: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub description: Option<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationStatus {
+    Firing,
+    Resolved,
+}
+
+/// Destination for alert notifications
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Notifier name used in logs
+    fn name(&self) -> &str;
+
+    async fn notify(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Create the notifiers described by the configuration
+pub fn build_notifiers(configs: &[NotifierConfig]) -> Result<Vec<Arc<dyn Notifier>>> {
+    configs
+        .iter()
+        .map(|config| -> Result<Arc<dyn Notifier>> {
+            match config {
+                NotifierConfig::Webhook {
+                    url,
+                    headers,
+                    timeout_ms,
+                } => Ok(Arc::new(WebhookNotifier::new(
+                    url,
+                    headers.clone(),
+                    Duration::from_millis(*timeout_ms),
+                )?)),
+            }
+        })
+        .collect()
+}
+
+/// Notifier posting each notification as JSON
+pub struct WebhookNotifier {
+    webhook: Webhook,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, headers: HashMap<String, String>, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            webhook: Webhook::new(url, headers, timeout)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        self.webhook.post(notification).await
+    }
+}
@@ -1,7 +1,14 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Services implement only the RPCs they serve; the rest answer `Unimplemented`
     tonic_build::configure()
         .build_server(true)
-        .compile_protos(&["proto/config_service.proto"], &["proto"])?;
+        .generate_default_stubs(true)
+        // Content fields share the receive buffer instead of being copied into a Vec
+        .bytes([".config_service"])
+        .compile_protos(
+            &["proto/config_service.proto", "proto/etcd.proto"],
+            &["proto"],
+        )?;
 
     Ok(())
 }
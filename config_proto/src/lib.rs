@@ -4,3 +4,8 @@ tonic::include_proto!("config_service");
 // Re-export commonly used types
 pub use config_service_client::*;
 pub use config_service_server::*;
+
+/// etcd v3 KV and Watch services, for the etcd gateway
+pub mod etcd {
+    tonic::include_proto!("etcdserverpb");
+}
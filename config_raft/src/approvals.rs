@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, Result};
+use config_core::{
+    with_approval, ApprovalManager, ApprovalPolicy, ApprovalRequest, ApprovalStatus, ConfigManager,
+    StagedChange, ValidationContext,
+};
+use config_storage::ApprovalStorage;
+use std::sync::Arc;
+
+use crate::{RaftCommand, RaftConfigManager};
+
+/// Updates of protected namespaces held in storage and proposed through Raft once approved
+pub struct RaftApprovalManager {
+    manager: Arc<RaftConfigManager>,
+    store: Arc<dyn ApprovalStorage>,
+    policy: ApprovalPolicy,
+}
+
+impl RaftApprovalManager {
+    pub fn new(
+        manager: Arc<RaftConfigManager>,
+        store: Arc<dyn ApprovalStorage>,
+        policy: ApprovalPolicy,
+    ) -> Self {
+        Self {
+            manager,
+            store,
+            policy,
+        }
+    }
+
+    async fn pending_approval(&self, id: &str, reviewer: &str) -> Result<ApprovalRequest> {
+        let approval = self.store.get_approval(id).await?;
+        if approval.status != ApprovalStatus::Pending {
+            return Err(config_common::Error::Validation(format!(
+                "approval request {} is {}",
+                id,
+                approval.status.as_str()
+            )));
+        }
+        if approval.requested_by == reviewer {
+            return Err(config_common::Error::Authorization(
+                "a change must be reviewed by someone other than its requester".to_string(),
+            ));
+        }
+        Ok(approval)
+    }
+}
+
+#[async_trait]
+impl ApprovalManager for RaftApprovalManager {
+    async fn request_change(
+        &self,
+        config_id: &str,
+        description: Option<&str>,
+        content: ConfigContent,
+        change_reason: Option<&str>,
+        requested_by: &str,
+    ) -> Result<ApprovalRequest> {
+        let (current, _) = self.manager.get_config(config_id).await?;
+        let protection = self.policy.protection(&current.namespace).ok_or_else(|| {
+            config_common::Error::Validation(format!(
+                "namespace {} does not require approvals",
+                current.namespace
+            ))
+        })?;
+        let ctx = ValidationContext::of(&current);
+        // Fail early on freezes; the approval guard is what sent the change here
+        with_approval(self.manager.check_write(&ctx)).await?;
+        let content = self.manager.seal(&ctx, content).await?;
+
+        let approval = ApprovalRequest {
+            id: uuid::Uuid::new_v4().to_string(),
+            namespace: current.namespace.clone(),
+            change: StagedChange {
+                config_id: current.id,
+                base_version: current.version,
+                description: description.map(String::from),
+                content,
+            },
+            change_reason: change_reason.map(String::from),
+            status: ApprovalStatus::Pending,
+            requested_by: requested_by.to_string(),
+            requested_at: chrono::Utc::now().timestamp(),
+            reviewers: protection.reviewers.clone(),
+            required_approvals: protection.required_approvals,
+            approvals: Vec::new(),
+            reviewed_at: None,
+            rejected_by: None,
+        };
+
+        self.store.save_approval(&approval).await?;
+        Ok(approval)
+    }
+
+    async fn get_approval(&self, id: &str) -> Result<ApprovalRequest> {
+        self.store.get_approval(id).await
+    }
+
+    async fn list_approvals(&self, status: Option<ApprovalStatus>) -> Result<Vec<ApprovalRequest>> {
+        self.store.list_approvals(status).await
+    }
+
+    async fn approve(&self, id: &str, reviewer: &str) -> Result<ApprovalRequest> {
+        let mut approval = self.pending_approval(id, reviewer).await?;
+        if approval.approvals.iter().any(|a| a == reviewer) {
+            return Err(config_common::Error::Validation(format!(
+                "{} already approved request {}",
+                reviewer, id
+            )));
+        }
+        approval.approvals.push(reviewer.to_string());
+
+        if approval.approvals.len() >= approval.required_approvals {
+            // Refuse to overwrite a config updated since the change was requested
+            let change = &approval.change;
+            let (current, _) = self.manager.get_config(&change.config_id).await?;
+            with_approval(self.manager.check_write(&ValidationContext::of(&current))).await?;
+            if current.version != change.base_version {
+                return Err(config_common::Error::Validation(format!(
+                    "config {} changed from version {} to {} since the change was requested",
+                    change.config_id, change.base_version, current.version
+                )));
+            }
+
+            let cmd = RaftCommand::UpdateConfig {
+                id: change.config_id.clone(),
+                description: change.description.clone(),
+                content: change.content.clone(),
+                change_reason: approval.change_reason.clone(),
+                updated_by: approval.requested_by.clone(),
+            };
+            self.manager.propose_command(cmd).await?;
+
+            approval.status = ApprovalStatus::Applied;
+            approval.reviewed_at = Some(chrono::Utc::now().timestamp());
+        }
+
+        self.store.save_approval(&approval).await?;
+        Ok(approval)
+    }
+
+    async fn reject(&self, id: &str, reviewer: &str) -> Result<ApprovalRequest> {
+        let mut approval = self.pending_approval(id, reviewer).await?;
+        approval.status = ApprovalStatus::Rejected;
+        approval.rejected_by = Some(reviewer.to_string());
+        approval.reviewed_at = Some(chrono::Utc::now().timestamp());
+
+        self.store.save_approval(&approval).await?;
+        Ok(approval)
+    }
+}
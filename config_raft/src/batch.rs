@@ -0,0 +1,136 @@
+use config_common::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::metrics::RaftMetrics;
+use crate::RaftNode;
+
+/// Proposal waiting to be batched: an encoded `RaftCommand` and who to tell once it commits
+struct Pending {
+    data: Vec<u8>,
+    done: oneshot::Sender<Result<()>>,
+}
+
+/// Coalesces commands proposed within a short window into one log entry, so a burst of
+/// writes such as a bulk import costs one round of consensus rather than one per write
+pub(crate) struct ProposalBatcher {
+    tx: mpsc::Sender<Pending>,
+}
+
+impl ProposalBatcher {
+    /// A batch closes `window` after its first command, or earlier once it holds
+    /// `max_commands` commands or another would take it past `max_bytes`
+    pub(crate) fn spawn(
+        node: Arc<RaftNode>,
+        metrics: RaftMetrics,
+        window: Duration,
+        max_commands: usize,
+        max_bytes: usize,
+    ) -> Self {
+        let max_commands = max_commands.max(1);
+        let (tx, rx) = mpsc::channel(max_commands * 4);
+        tokio::spawn(collect(node, metrics, rx, window, max_commands, max_bytes));
+        Self { tx }
+    }
+
+    /// Propose an encoded command, returning once the entry carrying it is committed
+    pub(crate) async fn propose(&self, data: Vec<u8>) -> Result<()> {
+        let (done, committed) = oneshot::channel();
+        self.tx
+            .send(Pending { data, done })
+            .await
+            .map_err(|_| stopped())?;
+        committed.await.map_err(|_| stopped())?
+    }
+}
+
+fn stopped() -> config_common::Error {
+    config_common::Error::Internal("proposal batcher stopped".to_string())
+}
+
+async fn collect(
+    node: Arc<RaftNode>,
+    metrics: RaftMetrics,
+    mut rx: mpsc::Receiver<Pending>,
+    window: Duration,
+    max_commands: usize,
+    max_bytes: usize,
+) {
+    let mut carried: Option<Pending> = None;
+    loop {
+        let first = match carried.take() {
+            Some(pending) => pending,
+            None => match rx.recv().await {
+                Some(pending) => pending,
+                None => return,
+            },
+        };
+        let deadline = Instant::now() + window;
+        let mut bytes = first.data.len();
+        let mut batch = vec![first];
+        while batch.len() < max_commands {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(pending)) if bytes + pending.data.len() > max_bytes => {
+                    carried = Some(pending);
+                    break;
+                }
+                Ok(Some(pending)) => {
+                    bytes += pending.data.len();
+                    batch.push(pending);
+                }
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        // Batches don't wait on each other to commit; commands proposed concurrently
+        // were never ordered
+        metrics.observe_batch(batch.len());
+        tokio::spawn(propose(node.clone(), batch));
+    }
+}
+
+async fn propose(node: Arc<RaftNode>, mut batch: Vec<Pending>) {
+    let data = if batch.len() == 1 {
+        std::mem::take(&mut batch[0].data)
+    } else {
+        coalesced(&batch)
+    };
+    match node.propose(data).await {
+        Ok(()) => {
+            for pending in batch {
+                let _ = pending.done.send(Ok(()));
+            }
+        }
+        Err(e) => {
+            let message = e.to_string();
+            for pending in batch {
+                let _ = pending
+                    .done
+                    .send(Err(config_common::Error::Internal(message.clone())));
+            }
+        }
+    }
+}
+
+/// Encoding of `RaftCommand::Coalesced` holding the batch's commands, spliced from their
+/// encodings rather than decoding and encoding each again
+fn coalesced(batch: &[Pending]) -> Vec<u8> {
+    const OPEN: &[u8] = br#"{"Coalesced":{"commands":["#;
+    const CLOSE: &[u8] = b"]}}";
+    let len = batch
+        .iter()
+        .map(|pending| pending.data.len() + 1)
+        .sum::<usize>();
+    let mut data = Vec::with_capacity(OPEN.len() + len + CLOSE.len());
+    data.extend_from_slice(OPEN);
+    for (i, pending) in batch.iter().enumerate() {
+        if i > 0 {
+            data.push(b',');
+        }
+        data.extend_from_slice(&pending.data);
+    }
+    data.extend_from_slice(CLOSE);
+    data
+}
@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use config_common::{ConfigMeta, Result};
+use config_core::{
+    ChangeSet, ChangeSetManager, ChangeSetStatus, ConfigManager, StagedChange, ValidationContext,
+};
+use config_storage::ChangeSetStorage;
+use std::sync::Arc;
+
+use crate::{RaftCommand, RaftConfigManager};
+
+/// Change sets staged in storage and applied as a single Raft proposal
+pub struct RaftChangeSetManager {
+    manager: Arc<RaftConfigManager>,
+    store: Arc<dyn ChangeSetStorage>,
+}
+
+impl RaftChangeSetManager {
+    pub fn new(manager: Arc<RaftConfigManager>, store: Arc<dyn ChangeSetStorage>) -> Self {
+        Self { manager, store }
+    }
+
+    async fn open_changeset(&self, id: &str) -> Result<ChangeSet> {
+        let changeset = self.store.get_changeset(id).await?;
+        if changeset.status != ChangeSetStatus::Open {
+            return Err(config_common::Error::Validation(format!(
+                "change set {} is {}",
+                id,
+                changeset.status.as_str()
+            )));
+        }
+        Ok(changeset)
+    }
+}
+
+#[async_trait]
+impl ChangeSetManager for RaftChangeSetManager {
+    async fn create_changeset(
+        &self,
+        title: &str,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<ChangeSet> {
+        let now = chrono::Utc::now().timestamp();
+        let changeset = ChangeSet {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: ChangeSetStatus::Open,
+            changes: Vec::new(),
+            created_at: now,
+            created_by: created_by.to_string(),
+            updated_at: now,
+            applied_at: None,
+            applied_by: None,
+        };
+
+        self.store.save_changeset(&changeset).await?;
+        Ok(changeset)
+    }
+
+    async fn get_changeset(&self, id: &str) -> Result<ChangeSet> {
+        self.store.get_changeset(id).await
+    }
+
+    async fn list_changesets(&self, status: Option<ChangeSetStatus>) -> Result<Vec<ChangeSet>> {
+        self.store.list_changesets(status).await
+    }
+
+    async fn stage_change(
+        &self,
+        id: &str,
+        mut change: StagedChange,
+        _user: &str,
+    ) -> Result<ChangeSet> {
+        let mut changeset = self.open_changeset(id).await?;
+        let (current, _) = self.manager.get_config(&change.config_id).await?;
+        change.content = self
+            .manager
+            .seal(&ValidationContext::of(&current), change.content)
+            .await?;
+        changeset
+            .changes
+            .retain(|c| c.config_id != change.config_id);
+        changeset.changes.push(change);
+        changeset.updated_at = chrono::Utc::now().timestamp();
+
+        self.store.save_changeset(&changeset).await?;
+        Ok(changeset)
+    }
+
+    async fn unstage_change(&self, id: &str, config_id: &str, _user: &str) -> Result<ChangeSet> {
+        let mut changeset = self.open_changeset(id).await?;
+        let staged = changeset.changes.len();
+        changeset.changes.retain(|c| c.config_id != config_id);
+        if changeset.changes.len() == staged {
+            return Err(config_common::Error::NotFound(format!(
+                "no staged change for config {}",
+                config_id
+            )));
+        }
+        changeset.updated_at = chrono::Utc::now().timestamp();
+
+        self.store.save_changeset(&changeset).await?;
+        Ok(changeset)
+    }
+
+    async fn discard_changeset(&self, id: &str, _user: &str) -> Result<ChangeSet> {
+        let mut changeset = self.open_changeset(id).await?;
+        changeset.status = ChangeSetStatus::Discarded;
+        changeset.updated_at = chrono::Utc::now().timestamp();
+
+        self.store.save_changeset(&changeset).await?;
+        Ok(changeset)
+    }
+
+    async fn apply_changeset(&self, id: &str, user: &str) -> Result<Vec<ConfigMeta>> {
+        let changeset = self.open_changeset(id).await?;
+        if changeset.changes.is_empty() {
+            return Err(config_common::Error::Validation(format!(
+                "change set {} has no staged changes",
+                id
+            )));
+        }
+
+        // Refuse to overwrite configurations updated since the edits were staged
+        for change in &changeset.changes {
+            let (current, _) = self.manager.get_config(&change.config_id).await?;
+            self.manager
+                .check_write(&ValidationContext::of(&current))
+                .await?;
+            if current.version != change.base_version {
+                return Err(config_common::Error::Validation(format!(
+                    "config {} changed from version {} to {} since it was staged",
+                    change.config_id, change.base_version, current.version
+                )));
+            }
+        }
+
+        let cmd = RaftCommand::ApplyChangeSet {
+            id: changeset.id,
+            changes: changeset.changes,
+            applied_by: user.to_string(),
+        };
+
+        self.manager.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied, which marks the change set applied,
+        // and return the updated configurations
+        todo!()
+    }
+}
@@ -1,8 +1,29 @@
+pub mod approvals;
+mod batch;
+pub mod changeset;
+pub mod metrics;
+pub mod scheduler;
+
+pub use approvals::RaftApprovalManager;
+pub use changeset::RaftChangeSetManager;
+pub use metrics::RaftMetrics;
+pub use scheduler::ReleaseScheduler;
+
 use async_trait::async_trait;
-use config_common::{ConfigContent, ConfigMeta, Result};
-use config_core::{ConfigFilter, ConfigManager};
+use config_common::{ConfigContent, ConfigEvent, ConfigEventType, ConfigMeta, Result};
+use config_core::recipients::is_client_sealed;
+use config_core::{
+    BatchWrite, ClientEnvelope, ConfigEncryption, ConfigFilter, ConfigManager, ConfigMetaStream,
+    ConfigSnapshot, ConfigValidator, ConfigVersion, ConfigVersionControl, EventBus,
+    FormatValidator, KeyedEncryption, RecipientKeyManager, SecretAccessControl, SecretPathManager,
+    StagedChange, Tenant, TenantManager, ValidationContext, WriteGuard,
+};
+use config_storage::ConfigStorage;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::batch::ProposalBatcher;
 
 /// Raft configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +35,21 @@ pub struct RaftConfig {
     pub snapshot_interval: u64,
     pub max_size_per_msg: u64,
     pub max_inflight_msgs: usize,
+    /// How long a proposal waits for others to share its log entry; 0 proposes each command
+    /// on its own
+    #[serde(default = "default_batch_window_ms")]
+    pub batch_window_ms: u64,
+    /// Most commands carried by one log entry
+    #[serde(default = "default_max_batch_commands")]
+    pub max_batch_commands: usize,
+}
+
+fn default_batch_window_ms() -> u64 {
+    2
+}
+
+fn default_max_batch_commands() -> usize {
+    128
 }
 
 /// Raft peer configuration
@@ -35,39 +71,314 @@ pub enum RaftCommand {
         description: Option<String>,
         content: ConfigContent,
         created_by: String,
+        owners: Vec<String>,
     },
     UpdateConfig {
         id: String,
         description: Option<String>,
         content: ConfigContent,
+        change_reason: Option<String>,
+        updated_by: String,
+    },
+    UpdateOwners {
+        id: String,
+        owners: Vec<String>,
+        updated_by: String,
+    },
+    UpdateLabels {
+        id: String,
+        labels: Vec<String>,
         updated_by: String,
     },
     DeleteConfig {
         id: String,
     },
+    Rollback {
+        id: String,
+        version: String,
+        change_reason: Option<String>,
+        updated_by: String,
+    },
+    TagVersion {
+        id: String,
+        version: String,
+        tag: String,
+        tagged_by: String,
+    },
+    UntagVersion {
+        id: String,
+        tag: String,
+        untagged_by: String,
+    },
+    PinVersion {
+        id: String,
+        version: String,
+        pinned: bool,
+        updated_by: String,
+    },
+    /// Apply every staged edit of a change set, or none of them
+    ApplyChangeSet {
+        id: String,
+        changes: Vec<StagedChange>,
+        applied_by: String,
+    },
+    /// Apply every create and update of a batch, or none of them
+    ApplyBatch {
+        writes: Vec<BatchWrite>,
+        change_reason: Option<String>,
+        applied_by: String,
+    },
+    /// Unrelated commands proposed close together and carried by one log entry; each is
+    /// applied on its own, in order, as if it had its own entry
+    Coalesced {
+        commands: Vec<RaftCommand>,
+    },
+}
+
+impl RaftCommand {
+    /// Event type and user of a command that changes configuration content; deletes
+    /// record no user
+    fn event(&self) -> Option<(ConfigEventType, &str)> {
+        match self {
+            RaftCommand::CreateConfig { created_by, .. } => {
+                Some((ConfigEventType::Created, created_by))
+            }
+            RaftCommand::UpdateConfig { updated_by, .. } => {
+                Some((ConfigEventType::Updated, updated_by))
+            }
+            RaftCommand::DeleteConfig { .. } => Some((ConfigEventType::Deleted, "")),
+            RaftCommand::Rollback { updated_by, .. } => Some((ConfigEventType::Rolled, updated_by)),
+            RaftCommand::ApplyChangeSet { applied_by, .. } => {
+                Some((ConfigEventType::Updated, applied_by))
+            }
+            RaftCommand::ApplyBatch { applied_by, .. } => {
+                Some((ConfigEventType::Updated, applied_by))
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Raft-based configuration manager
 pub struct RaftConfigManager {
     node: Arc<RaftNode>,
+    metrics: RaftMetrics,
+    /// Unset when commands are proposed one by one
+    batcher: Option<ProposalBatcher>,
+    validators: Vec<Arc<dyn ConfigValidator>>,
+    encryption: Option<Arc<dyn ConfigEncryption>>,
+    secret_paths: Option<Arc<dyn SecretPathManager>>,
+    secret_access: Option<Arc<dyn SecretAccessControl>>,
+    recipients: Option<Arc<dyn RecipientKeyManager>>,
+    tenants: Option<Arc<dyn TenantManager>>,
+    guards: Vec<Arc<dyn WriteGuard>>,
 }
 
 impl RaftConfigManager {
-    pub async fn new(config: RaftConfig) -> Result<Self> {
-        let node = RaftNode::new(config).await?;
+    pub async fn new(
+        config: RaftConfig,
+        storage: Arc<dyn ConfigStorage>,
+        metrics: RaftMetrics,
+        events: Arc<EventBus>,
+    ) -> Result<Self> {
+        let batching = (config.batch_window_ms > 0).then(|| {
+            (
+                Duration::from_millis(config.batch_window_ms),
+                config.max_batch_commands,
+                config.max_size_per_msg as usize,
+            )
+        });
+        let node = Arc::new(RaftNode::new(config, storage, metrics.clone(), events).await?);
+        let batcher = batching.map(|(window, max_commands, max_bytes)| {
+            ProposalBatcher::spawn(
+                node.clone(),
+                metrics.clone(),
+                window,
+                max_commands,
+                max_bytes,
+            )
+        });
         Ok(Self {
-            node: Arc::new(node),
+            node,
+            metrics,
+            batcher,
+            validators: vec![Arc::new(FormatValidator)],
+            encryption: None,
+            secret_paths: None,
+            secret_access: None,
+            recipients: None,
+            tenants: None,
+            guards: Vec::new(),
+        })
+    }
+
+    /// Run an additional validator on content before it is proposed
+    pub fn with_validator(mut self, validator: Arc<dyn ConfigValidator>) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Encrypt content marked as encrypted before it is proposed
+    pub fn with_encryption(mut self, encryption: Arc<dyn ConfigEncryption>) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Encrypt the values at the secret paths of each configuration
+    pub fn with_secret_paths(mut self, secret_paths: Arc<dyn SecretPathManager>) -> Self {
+        self.secret_paths = Some(secret_paths);
+        self
+    }
+
+    /// Authorize and audit every read of decrypted content
+    pub fn with_secret_access(mut self, secret_access: Arc<dyn SecretAccessControl>) -> Self {
+        self.secret_access = Some(secret_access);
+        self
+    }
+
+    /// Only accept client-side encrypted content in namespaces with recipient keys
+    pub fn with_recipients(mut self, recipients: Arc<dyn RecipientKeyManager>) -> Self {
+        self.recipients = Some(recipients);
+        self
+    }
+
+    /// Run an additional check before any change to a configuration is proposed
+    pub fn with_write_guard(mut self, guard: Arc<dyn WriteGuard>) -> Self {
+        self.guards.push(guard);
+        self
+    }
+
+    /// Encrypt the content of isolated tenants under their own master keys
+    pub fn with_tenants(mut self, tenants: Arc<dyn TenantManager>) -> Self {
+        self.tenants = Some(tenants);
+        self
+    }
+
+    fn encryption(&self) -> Result<&Arc<dyn ConfigEncryption>> {
+        self.encryption.as_ref().ok_or_else(|| {
+            config_common::Error::Validation("encryption is not configured".to_string())
         })
     }
 
-    async fn propose_command(&self, cmd: RaftCommand) -> Result<()> {
+    /// Encryption of a department's content, under its tenant's master key if it has one
+    async fn encryption_for(&self, department: &str) -> Result<Arc<dyn ConfigEncryption>> {
+        let encryption = self.encryption()?.clone();
+        let Some(tenants) = &self.tenants else {
+            return Ok(encryption);
+        };
+        match tenants.get_tenant(department).await {
+            Ok(Tenant {
+                key_id: Some(key_id),
+                ..
+            }) => Ok(Arc::new(KeyedEncryption::new(encryption, &key_id))),
+            Ok(_) | Err(config_common::Error::NotFound(_)) => Ok(encryption),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Validate plaintext content, then encrypt it whole if it is marked as encrypted, or
+    /// encrypt the values at its secret paths; client-side encrypted content is only
+    /// checked against the recipient keys of its namespace
+    pub async fn seal(
+        &self,
+        ctx: &ValidationContext,
+        content: ConfigContent,
+    ) -> Result<ConfigContent> {
+        let recipients = match &self.recipients {
+            Some(recipients) => recipients.get_recipients(&ctx.namespace).await?,
+            None => Vec::new(),
+        };
+        if !recipients.is_empty() {
+            if !is_client_sealed(&content) {
+                return Err(config_common::Error::Validation(format!(
+                    "namespace {} only accepts content encrypted client-side",
+                    ctx.namespace
+                )));
+            }
+            ClientEnvelope::parse(&content.content)?.check(&recipients)?;
+            return Ok(content);
+        }
+        if is_client_sealed(&content) {
+            return Err(config_common::Error::Validation(format!(
+                "namespace {} has no recipient keys for client-side encryption",
+                ctx.namespace
+            )));
+        }
+
+        if !content.is_encrypted {
+            self.validate(ctx, &content).await?;
+            let paths = match (&ctx.config_id, &self.secret_paths) {
+                (Some(id), Some(secret_paths)) => secret_paths.get_secret_paths(id).await?,
+                _ => Vec::new(),
+            };
+            if paths.is_empty() {
+                return Ok(content);
+            }
+            let encryption = self.encryption_for(&ctx.department).await?;
+            return config_core::secrets::encrypt_fields(content, &paths, encryption.as_ref())
+                .await;
+        }
+        let encryption = self.encryption_for(&ctx.department).await?;
+
+        let plaintext = ConfigContent {
+            is_encrypted: false,
+            ..content
+        };
+        self.validate(ctx, &plaintext).await?;
+        Ok(ConfigContent {
+            content: encryption.encrypt(&plaintext.content).await?,
+            is_encrypted: true,
+            ..plaintext
+        })
+    }
+
+    /// Check a change to a configuration against every write guard
+    pub async fn check_write(&self, ctx: &ValidationContext) -> Result<()> {
+        for guard in &self.guards {
+            guard.check_write(ctx).await?;
+        }
+        Ok(())
+    }
+
+    /// Check a write leaving the content of an existing configuration as is against every
+    /// write guard
+    pub(crate) async fn check_write_to(&self, id: &str) -> Result<()> {
+        if self.guards.is_empty() {
+            return Ok(());
+        }
+        let (current, _) = self.get_config(id).await?;
+        self.check_write(&ValidationContext::metadata_of(&current)).await
+    }
+
+    /// Check content against every validator
+    pub async fn validate(&self, ctx: &ValidationContext, content: &ConfigContent) -> Result<()> {
+        for validator in &self.validators {
+            validator.validate(ctx, content).await?;
+        }
+        Ok(())
+    }
+
+    /// Current cluster leader, if one has been elected
+    pub fn leader_id(&self) -> Option<u64> {
+        self.node.leader_id()
+    }
+
+    pub(crate) async fn propose_command(&self, cmd: RaftCommand) -> Result<()> {
         let data = serde_json::to_vec(&cmd)
             .map_err(|e| config_common::Error::Internal(e.to_string()))?;
-        
-        self.node
-            .propose(data)
-            .await
-            .map_err(|e| config_common::Error::Internal(e.to_string()))
+
+        self.metrics.proposal_started();
+        let started = Instant::now();
+        let result = match &self.batcher {
+            Some(batcher) => batcher.propose(data).await,
+            None => self
+                .node
+                .propose(data)
+                .await
+                .map_err(|e| config_common::Error::Internal(e.to_string())),
+        };
+        self.metrics.proposal_finished(started.elapsed());
+        result
     }
 }
 
@@ -80,6 +391,46 @@ impl ConfigManager for RaftConfigManager {
             .map_err(|e| config_common::Error::Internal(e.to_string()))
     }
 
+    async fn decrypt_content(
+        &self,
+        meta: &ConfigMeta,
+        content: ConfigContent,
+        reader: &str,
+    ) -> Result<ConfigContent> {
+        if is_client_sealed(&content) {
+            return Err(config_common::Error::Validation(
+                "content is encrypted client-side; the server holds no key for it".to_string(),
+            ));
+        }
+        let has_fields = config_core::secrets::has_secret_fields(&content);
+        if !content.is_encrypted && !has_fields {
+            return Ok(content);
+        }
+        let encryption = self.encryption()?;
+        if let Some(access) = &self.secret_access {
+            access.authorize_read(reader, meta).await?;
+        }
+        let key_ids = config_core::secrets::key_ids(&content, encryption.as_ref());
+
+        let plaintext = if has_fields {
+            config_core::secrets::decrypt_fields(content, encryption.as_ref()).await?
+        } else {
+            ConfigContent {
+                content: encryption.decrypt(&content.content).await?,
+                is_encrypted: false,
+                ..content
+            }
+        };
+        if let Some(access) = &self.secret_access {
+            access.record_read(reader, meta, &key_ids).await?;
+        }
+        Ok(plaintext)
+    }
+
+    fn redact_content(&self, content: ConfigContent) -> Result<ConfigContent> {
+        config_core::secrets::redact(content, self.encryption.as_deref())
+    }
+
     async fn create_config(
         &self,
         name: &str,
@@ -91,6 +442,18 @@ impl ConfigManager for RaftConfigManager {
         content: ConfigContent,
         created_by: &str,
     ) -> Result<ConfigMeta> {
+        let ctx = ValidationContext {
+            config_id: None,
+            namespace: namespace.to_string(),
+            department: department.to_string(),
+            application: application.to_string(),
+            environment: environment.to_string(),
+            name: name.to_string(),
+            changes_content: true,
+        };
+        self.check_write(&ctx).await?;
+        let content = self.seal(&ctx, content).await?;
+
         let cmd = RaftCommand::CreateConfig {
             name: name.to_string(),
             namespace: namespace.to_string(),
@@ -100,6 +463,7 @@ impl ConfigManager for RaftConfigManager {
             description: description.map(String::from),
             content,
             created_by: created_by.to_string(),
+            owners: vec![created_by.to_string()],
         };
 
         self.propose_command(cmd).await?;
@@ -113,12 +477,19 @@ impl ConfigManager for RaftConfigManager {
         id: &str,
         description: Option<&str>,
         content: ConfigContent,
+        change_reason: Option<&str>,
         updated_by: &str,
     ) -> Result<ConfigMeta> {
+        let (current, _) = self.get_config(id).await?;
+        let ctx = ValidationContext::of(&current);
+        self.check_write(&ctx).await?;
+        let content = self.seal(&ctx, content).await?;
+
         let cmd = RaftCommand::UpdateConfig {
             id: id.to_string(),
             description: description.map(String::from),
             content,
+            change_reason: change_reason.map(String::from),
             updated_by: updated_by.to_string(),
         };
 
@@ -128,7 +499,46 @@ impl ConfigManager for RaftConfigManager {
         todo!()
     }
 
+    async fn update_owners(
+        &self,
+        id: &str,
+        owners: Vec<String>,
+        updated_by: &str,
+    ) -> Result<ConfigMeta> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::UpdateOwners {
+            id: id.to_string(),
+            owners,
+            updated_by: updated_by.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
+    async fn update_labels(
+        &self,
+        id: &str,
+        labels: Vec<String>,
+        updated_by: &str,
+    ) -> Result<ConfigMeta> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::UpdateLabels {
+            id: id.to_string(),
+            labels,
+            updated_by: updated_by.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
     async fn delete_config(&self, id: &str) -> Result<bool> {
+        self.check_write_to(id).await?;
         let cmd = RaftCommand::DeleteConfig {
             id: id.to_string(),
         };
@@ -139,6 +549,90 @@ impl ConfigManager for RaftConfigManager {
         todo!()
     }
 
+    async fn apply_batch(
+        &self,
+        writes: Vec<BatchWrite>,
+        change_reason: Option<&str>,
+        user: &str,
+    ) -> Result<Vec<ConfigMeta>> {
+        if writes.is_empty() {
+            return Err(config_common::Error::Validation(
+                "batch has no writes".to_string(),
+            ));
+        }
+
+        // Check and seal every write before proposing any of them
+        let mut sealed = Vec::with_capacity(writes.len());
+        for write in writes {
+            sealed.push(match write {
+                BatchWrite::Create {
+                    name,
+                    namespace,
+                    department,
+                    application,
+                    environment,
+                    description,
+                    content,
+                } => {
+                    let ctx = ValidationContext {
+                        config_id: None,
+                        namespace: namespace.clone(),
+                        department: department.clone(),
+                        application: application.clone(),
+                        environment: environment.clone(),
+                        name: name.clone(),
+                        changes_content: true,
+                    };
+                    self.check_write(&ctx).await?;
+                    let content = self.seal(&ctx, content).await?;
+                    BatchWrite::Create {
+                        name,
+                        namespace,
+                        department,
+                        application,
+                        environment,
+                        description,
+                        content,
+                    }
+                }
+                BatchWrite::Update {
+                    config_id,
+                    base_version,
+                    description,
+                    content,
+                } => {
+                    let (current, _) = self.get_config(&config_id).await?;
+                    let ctx = ValidationContext::of(&current);
+                    self.check_write(&ctx).await?;
+                    if current.version != base_version {
+                        return Err(config_common::Error::Validation(format!(
+                            "config {} changed from version {} to {} since the batch was prepared",
+                            config_id, base_version, current.version
+                        )));
+                    }
+                    let content = self.seal(&ctx, content).await?;
+                    BatchWrite::Update {
+                        config_id,
+                        base_version,
+                        description,
+                        content,
+                    }
+                }
+            });
+        }
+
+        let cmd = RaftCommand::ApplyBatch {
+            writes: sealed,
+            change_reason: change_reason.map(String::from),
+            applied_by: user.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the configurations
+        todo!()
+    }
+
     async fn list_configs(
         &self,
         filter: ConfigFilter,
@@ -150,16 +644,157 @@ impl ConfigManager for RaftConfigManager {
             .await
             .map_err(|e| config_common::Error::Internal(e.to_string()))
     }
+
+    async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream> {
+        self.node.stream_configs(filter).await
+    }
+}
+
+#[async_trait]
+impl ConfigVersionControl for RaftConfigManager {
+    async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>> {
+        self.node.get_version_history(id).await
+    }
+
+    async fn get_version(&self, id: &str, version: &str) -> Result<(ConfigVersion, ConfigContent)> {
+        self.node.get_version(id, version).await
+    }
+
+    async fn get_version_at(&self, id: &str, at: i64) -> Result<ConfigSnapshot> {
+        self.node.get_version_at(id, at).await
+    }
+
+    async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>> {
+        self.node.get_namespace_at(namespace, at).await
+    }
+
+    async fn rollback(
+        &self,
+        id: &str,
+        version: &str,
+        change_reason: Option<&str>,
+        user: &str,
+    ) -> Result<ConfigMeta> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::Rollback {
+            id: id.to_string(),
+            version: version.to_string(),
+            change_reason: change_reason.map(String::from),
+            updated_by: user.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
+    async fn tag_version(&self, id: &str, version: &str, tag: &str, user: &str) -> Result<()> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::TagVersion {
+            id: id.to_string(),
+            version: version.to_string(),
+            tag: tag.to_string(),
+            tagged_by: user.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
+    async fn untag_version(&self, id: &str, tag: &str, user: &str) -> Result<bool> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::UntagVersion {
+            id: id.to_string(),
+            tag: tag.to_string(),
+            untagged_by: user.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
+    async fn pin_version(&self, id: &str, version: &str, pinned: bool, user: &str) -> Result<()> {
+        self.check_write_to(id).await?;
+        let cmd = RaftCommand::PinVersion {
+            id: id.to_string(),
+            version: version.to_string(),
+            pinned,
+            updated_by: user.to_string(),
+        };
+
+        self.propose_command(cmd).await?;
+
+        // TODO: Wait for command to be applied and return the result
+        todo!()
+    }
+
+    async fn get_tagged_version(
+        &self,
+        id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        self.node.get_tagged_version(id, tag).await
+    }
 }
 
 /// Raft node implementation
 pub struct RaftNode {
-    // TODO: Implement Raft node with storage and transport
+    // TODO: Implement Raft node with transport
+    node_id: u64,
+    storage: Arc<dyn ConfigStorage>,
+    metrics: RaftMetrics,
+    events: Arc<EventBus>,
 }
 
 impl RaftNode {
-    pub async fn new(config: RaftConfig) -> Result<Self> {
-        // TODO: Initialize Raft node
+    pub async fn new(
+        config: RaftConfig,
+        storage: Arc<dyn ConfigStorage>,
+        metrics: RaftMetrics,
+        events: Arc<EventBus>,
+    ) -> Result<Self> {
+        // TODO: Initialize Raft node and call `metrics.observe_state` after each ready cycle
+        // and `publish_applied` after applying each committed entry, once per command of a
+        // `RaftCommand::Coalesced` entry
+        todo!()
+    }
+
+    /// Publish the events of a command the state machine applied; `applied` holds the
+    /// resulting metadata of each affected config, as it was before deletion for deletes
+    pub async fn publish_applied(&self, cmd: &RaftCommand, applied: &[ConfigMeta]) -> Result<()> {
+        // Every node applies every entry; only the leader reports it
+        if self.leader_id() != Some(self.node_id) {
+            return Ok(());
+        }
+        let Some((event_type, user)) = cmd.event() else {
+            return Ok(());
+        };
+        let timestamp = chrono::Utc::now().timestamp();
+        for meta in applied {
+            self.events
+                .publish(ConfigEvent {
+                    config_id: meta.id.clone(),
+                    namespace: meta.namespace.clone(),
+                    environment: meta.environment.clone(),
+                    labels: meta.labels.clone(),
+                    event_type,
+                    version: meta.version.clone(),
+                    timestamp,
+                    user: user.to_string(),
+                    message: None,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub fn leader_id(&self) -> Option<u64> {
+        // TODO: Read leader from raft status
         todo!()
     }
 
@@ -182,4 +817,36 @@ impl RaftNode {
         // TODO: Implement list_configs
         todo!()
     }
-} 
\ No newline at end of file
+
+    pub async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream> {
+        self.storage.stream_configs(filter).await
+    }
+
+    pub async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>> {
+        self.storage.get_version_history(id).await
+    }
+
+    pub async fn get_version(
+        &self,
+        id: &str,
+        version: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        self.storage.get_version(id, version).await
+    }
+
+    pub async fn get_tagged_version(
+        &self,
+        id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        self.storage.get_tagged_version(id, tag).await
+    }
+
+    pub async fn get_version_at(&self, id: &str, at: i64) -> Result<ConfigSnapshot> {
+        self.storage.get_version_at(id, at).await
+    }
+
+    pub async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>> {
+        self.storage.get_namespace_at(namespace, at).await
+    }
+}
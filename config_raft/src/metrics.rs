@@ -0,0 +1,101 @@
+use config_common::Result;
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Leader id recorded before any leader has been observed
+const NO_LEADER: u64 = 0;
+
+/// Consensus metrics exported by a Raft node
+#[derive(Clone)]
+pub struct RaftMetrics {
+    term: IntGauge,
+    commit_index: IntGauge,
+    applied_index: IntGauge,
+    is_leader: IntGauge,
+    leader_changes: IntCounter,
+    proposal_latency: Histogram,
+    pending_proposals: IntGauge,
+    batch_size: Histogram,
+    last_leader: Arc<AtomicU64>,
+}
+
+impl RaftMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let term = IntGauge::new("raft_term", "Current Raft term")?;
+        let commit_index = IntGauge::new("raft_commit_index", "Highest committed log index")?;
+        let applied_index = IntGauge::new("raft_applied_index", "Highest applied log index")?;
+        let is_leader = IntGauge::new("raft_is_leader", "Whether this node is the leader")?;
+        let leader_changes =
+            IntCounter::new("raft_leader_changes_total", "Observed leader changes")?;
+        let proposal_latency = Histogram::with_opts(HistogramOpts::new(
+            "raft_proposal_latency_seconds",
+            "Time from proposal to commit",
+        ))?;
+        let pending_proposals =
+            IntGauge::new("raft_pending_proposals", "Proposals awaiting commit")?;
+        let batch_size = Histogram::with_opts(
+            HistogramOpts::new("raft_batch_commands", "Commands carried by one log entry")
+                .buckets(vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0]),
+        )?;
+
+        registry.register(Box::new(term.clone()))?;
+        registry.register(Box::new(commit_index.clone()))?;
+        registry.register(Box::new(applied_index.clone()))?;
+        registry.register(Box::new(is_leader.clone()))?;
+        registry.register(Box::new(leader_changes.clone()))?;
+        registry.register(Box::new(proposal_latency.clone()))?;
+        registry.register(Box::new(pending_proposals.clone()))?;
+        registry.register(Box::new(batch_size.clone()))?;
+
+        Ok(Self {
+            term,
+            commit_index,
+            applied_index,
+            is_leader,
+            leader_changes,
+            proposal_latency,
+            pending_proposals,
+            batch_size,
+            last_leader: Arc::new(AtomicU64::new(NO_LEADER)),
+        })
+    }
+
+    /// Record the node's consensus state after each ready cycle
+    pub fn observe_state(
+        &self,
+        node_id: u64,
+        term: u64,
+        commit_index: u64,
+        applied_index: u64,
+        leader_id: Option<u64>,
+    ) {
+        self.term.set(term as i64);
+        self.commit_index.set(commit_index as i64);
+        self.applied_index.set(applied_index as i64);
+        self.is_leader.set((leader_id == Some(node_id)) as i64);
+
+        let leader = leader_id.unwrap_or(NO_LEADER);
+        let previous = self.last_leader.swap(leader, Ordering::Relaxed);
+        if leader != NO_LEADER && leader != previous {
+            self.leader_changes.inc();
+        }
+    }
+
+    /// Mark a proposal as submitted
+    pub fn proposal_started(&self) {
+        self.pending_proposals.inc();
+    }
+
+    /// Record how many commands a proposed log entry carries
+    pub fn observe_batch(&self, commands: usize) {
+        self.batch_size.observe(commands as f64);
+    }
+
+    /// Mark a proposal as finished, successfully or not
+    pub fn proposal_finished(&self, elapsed: Duration) {
+        self.pending_proposals.dec();
+        self.proposal_latency.observe(elapsed.as_secs_f64());
+    }
+}
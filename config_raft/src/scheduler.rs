@@ -0,0 +1,131 @@
+use config_common::{ConfigEvent, ConfigEventType, Result};
+use config_core::{
+    check_release_order, ConfigManager, EventBus, GateSubject, ReleaseGates, ReleaseManager,
+    ReleaseStatus,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::RaftConfigManager;
+
+/// How often due releases are looked for
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// User recorded on events of releases published by the scheduler
+pub const SCHEDULER_USER: &str = "scheduler";
+
+/// Publishes scheduled releases once their time has come. Only the Raft leader runs them, so
+/// a release is published once however many nodes the cluster has.
+pub struct ReleaseScheduler {
+    manager: Arc<RaftConfigManager>,
+    releases: Arc<dyn ReleaseManager>,
+    gates: Arc<ReleaseGates>,
+    events: Arc<EventBus>,
+    node_id: u64,
+}
+
+impl ReleaseScheduler {
+    pub fn new(
+        manager: Arc<RaftConfigManager>,
+        releases: Arc<dyn ReleaseManager>,
+        gates: Arc<ReleaseGates>,
+        events: Arc<EventBus>,
+        node_id: u64,
+    ) -> Self {
+        Self {
+            manager,
+            releases,
+            gates,
+            events,
+            node_id,
+        }
+    }
+
+    /// Run due releases periodically until the task is aborted
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(SCHEDULE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                match scheduler.run_once().await {
+                    Ok(0) => {}
+                    Ok(published) => tracing::info!(published, "Ran scheduled releases"),
+                    Err(e) => tracing::error!(error = %e, "Scheduled release run failed"),
+                }
+            }
+        })
+    }
+
+    /// Publish or start rolling out every due release that passes its gates, when this node
+    /// leads the cluster
+    pub async fn run_once(&self) -> Result<usize> {
+        if self.manager.leader_id() != Some(self.node_id) {
+            return Ok(0);
+        }
+
+        let mut ran = 0;
+        for release in self
+            .releases
+            .due_releases(chrono::Utc::now().timestamp())
+            .await?
+        {
+            // Held back until the configs it depends on are released, then retried
+            if let Err(e) = check_release_order(self.releases.as_ref(), &release.config_id).await {
+                tracing::debug!(release = %release.id, error = %e, "Scheduled release waiting");
+                continue;
+            }
+
+            let (meta, _) = self.manager.get_config(&release.config_id).await?;
+            let results = self
+                .gates
+                .check(GateSubject {
+                    release_id: Some(&release.id),
+                    meta: &meta,
+                    version: &release.version,
+                    requested_by: &release.requested_by,
+                    change_reason: release.change_reason.as_deref(),
+                })
+                .await;
+            if !config_core::gates::all_passed(&results) {
+                self.releases.block_release(&release.id, results).await?;
+                continue;
+            }
+            if !results.is_empty() {
+                self.releases.record_gates(&release.id, results).await?;
+            }
+
+            let release = match self.releases.run_scheduled(&release.id).await {
+                Ok(release) => release,
+                Err(e) => {
+                    tracing::error!(release = %release.id, error = %e, "Scheduled release failed");
+                    continue;
+                }
+            };
+            ran += 1;
+
+            tracing::info!(
+                release = %release.id,
+                config_id = %release.config_id,
+                version = %release.version,
+                rolling = release.status == ReleaseStatus::Rolling,
+                "Ran scheduled release"
+            );
+            self.events
+                .publish(ConfigEvent {
+                    config_id: meta.id.clone(),
+                    namespace: meta.namespace.clone(),
+                    environment: meta.environment.clone(),
+                    labels: meta.labels.clone(),
+                    event_type: ConfigEventType::Released,
+                    version: release.version.clone(),
+                    timestamp: chrono::Utc::now().timestamp(),
+                    user: SCHEDULER_USER.to_string(),
+                    message: None,
+                })
+                .await?;
+        }
+        Ok(ran)
+    }
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use config_monitor::HealthCheck;
+use config_raft::RaftConfigManager;
+use std::sync::Arc;
+
+/// Ready only while the Raft cluster has an elected leader
+pub struct RaftLeaderCheck {
+    manager: Arc<RaftConfigManager>,
+}
+
+impl RaftLeaderCheck {
+    pub fn new(manager: Arc<RaftConfigManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RaftLeaderCheck {
+    fn name(&self) -> &str {
+        "raft"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        match self.manager.leader_id() {
+            Some(_) => Ok(()),
+            None => Err("no leader elected".to_string()),
+        }
+    }
+}
@@ -0,0 +1,462 @@
+mod health;
+mod settings;
+
+use actix_web::middleware::{Compress, Condition, Logger};
+use actix_web::{App, HttpServer};
+use config_api::{ApiServices, EtcdGateway, GrpcApi, PolicySecretAccess, RateLimiter};
+use config_audit::{
+    AnomalyDetector, AuditService, DbAuditService, FanoutAuditService, RetentionJob,
+    RetentionMetrics,
+};
+use config_auth::{DbPolicyStore, PolicyEnforcer, PolicyService, PolicyStore, PolicySync};
+use config_core::{
+    ApprovalGuard, BackupManager, CachedNamespaceStats, CanaryMonitor, DepartmentValidator,
+    EventBus, FreezeWindowGuard, GitSync, KeyRotationManager, NamespaceFreezeGuard,
+    NamingValidator, ReleaseGates, RuleValidator, SchemaValidator, SizeLimitValidator,
+    TenantManager, WebhookValidator,
+};
+use config_crypto::{
+    build_key_provider, EnvelopeEncryption, KeyRotationService, SecretExpiryJob,
+    SecretExpiryMetrics,
+};
+use config_events::Notifier;
+use config_git::{GitExporter, GitImporter};
+use config_monitor::{
+    AlertEngine, HealthService, LogLevel, MonitoringService, PostgresCheck, RedisCheck,
+    SystemCollector,
+};
+use config_raft::{
+    RaftApprovalManager, RaftChangeSetManager, RaftConfigManager, RaftMetrics, ReleaseScheduler,
+};
+use config_storage::{
+    ConfigStorage, PgBackupService, PgConfigStorage, TenantStorage, VersionCompactionJob,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::health::RaftLeaderCheck;
+use crate::settings::{migrate_only, verify_backup_arg, RestoreArgs, ServerConfig};
+
+/// Interval between sweeps of expired temporary grants
+const GRANT_EXPIRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Interval between purges of old events from the outbox
+const EVENT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let log_level = Arc::new(LogLevel::init());
+
+    let config = ServerConfig::load()?;
+    let restore = RestoreArgs::from_args()?;
+    let migrate_only = migrate_only();
+    let verify_backup = verify_backup_arg()?;
+
+    // Storage
+    let pool = Arc::new(config.database.create_pool().await?);
+    let redis = config.cache.create_client()?;
+    if migrate_only || config.database.migrate_on_startup {
+        config_storage::migrate::run(&config.database, &pool).await?;
+        if migrate_only {
+            tracing::info!("Schema migrations applied");
+            return Ok(());
+        }
+    } else {
+        let pending = config_storage::migrate::pending(&config.database, &pool).await?;
+        if !pending.is_empty() {
+            anyhow::bail!(
+                "schema migrations are pending, run with --migrate: {}",
+                pending.join(", ")
+            );
+        }
+    }
+
+    // Restore a backup instead of serving, so nothing writes while tables are replaced
+    if let Some(restore) = restore {
+        let report = PgBackupService::new(pool.clone(), config.backup.clone())
+            .restore_backup(&restore.backup, restore.until, "startup")
+            .await?;
+        tracing::info!(
+            backup = %report.backup,
+            restored_to = report.restored_to,
+            tables = report.tables,
+            rows = report.rows,
+            replayed_versions = report.replayed_versions,
+            discarded_versions = report.discarded_versions,
+            configs_reset = report.configs_reset,
+            "Restore finished"
+        );
+        return Ok(());
+    }
+
+    // Verify a backup and exit, failing when any of its checks failed
+    if let Some(name) = verify_backup {
+        let mut backups = PgBackupService::new(pool.clone(), config.backup.clone());
+        if let Some(provider) = build_key_provider(&config.encryption).await? {
+            backups = backups.with_encryption(Arc::new(EnvelopeEncryption::new(provider)));
+        }
+        let signed = backups.verify_backup(&name, "startup").await?;
+        let verification = &signed.verification;
+        for check in verification.checks.iter().filter(|check| !check.passed) {
+            tracing::error!(
+                check = %check.name,
+                detail = check.detail.as_deref().unwrap_or_default(),
+                "Backup check failed"
+            );
+        }
+        tracing::info!(
+            backup = %verification.backup,
+            passed = verification.passed,
+            schema_version = ?verification.schema_version,
+            secrets_sampled = verification.secrets_sampled,
+            secrets_decrypted = verification.secrets_decrypted,
+            signature = %signed.signature,
+            "Verification finished"
+        );
+        if !verification.passed {
+            anyhow::bail!("backup {} failed verification", name);
+        }
+        return Ok(());
+    }
+
+    // Monitoring
+    let monitoring = Arc::new(MonitoringService::new()?);
+    SystemCollector::new(
+        monitoring.system_metrics().clone(),
+        Duration::from_secs(config.monitor.collect_interval_secs.max(1)),
+    )
+    .with_pool(pool.clone())
+    .with_redis(redis.clone())
+    .spawn();
+
+    let alert_rules = match &config.monitor.alert_rules {
+        Some(path) => config_monitor::alert::load_rules(path)?,
+        None => Vec::new(),
+    };
+    let alert_engine = Arc::new(
+        AlertEngine::new(alert_rules, monitoring.registry().clone()).with_notifiers(
+            config_monitor::notify::build_notifiers(&config.monitor.notifiers)?,
+        ),
+    );
+    alert_engine.clone().spawn(Duration::from_secs(
+        config.monitor.alert_interval_secs.max(1),
+    ));
+
+    // Audit
+    let mut sinks = config_audit::build_sinks(&config.audit.sinks)?;
+    let anomalies = if config.audit.anomaly_rules.is_empty() {
+        None
+    } else {
+        let detector = Arc::new(AnomalyDetector::new(
+            config.audit.anomaly_rules.clone(),
+            monitoring.registry(),
+        )?);
+        sinks.push(detector.clone());
+        Some(detector)
+    };
+    let audit: Arc<dyn AuditService> = Arc::new(FanoutAuditService::new(
+        Arc::new(DbAuditService::new(pool.clone())),
+        sinks,
+        &config.audit.delivery,
+    ));
+    if let Some(retention) = config.audit.retention.clone() {
+        let metrics = RetentionMetrics::new(monitoring.registry())?;
+        RetentionJob::new(pool.clone(), retention, metrics).spawn();
+    }
+
+    // Authorization
+    if config.auth.trust_identity_header {
+        tracing::warn!(
+            "auth.trust_identity_header is set: caller identities are taken on trust, \
+             so the server must only be reachable through the authenticating gateway"
+        );
+    } else if config.auth.gateway_token.is_none() {
+        tracing::warn!(
+            "auth.gateway_token is not set: caller identities are rejected and requests \
+             run anonymously"
+        );
+    }
+    let store: Arc<dyn PolicyStore> = Arc::new(DbPolicyStore::new(pool.clone()));
+    let enforcer = Arc::new(PolicyEnforcer::new(&config.auth, &[], &[]).await?);
+    let sync = Arc::new(PolicySync::new(
+        redis.clone(),
+        &config.auth.policy_channel,
+        &config.raft.node_id.to_string(),
+    ));
+    let policy_service = Arc::new(
+        PolicyService::new(enforcer.clone(), store.clone())
+            .with_sync(sync.clone())
+            .with_audit(audit.clone()),
+    );
+    policy_service.reload().await?;
+    sync.spawn_listener(enforcer, store);
+    config_auth::service::spawn_grant_expiry(policy_service.clone(), GRANT_EXPIRY_INTERVAL);
+
+    // Configuration management
+    config.naming.check()?;
+    let raft_metrics = RaftMetrics::new(monitoring.registry())?;
+    let mut pg_storage =
+        PgConfigStorage::new(pool.clone()).with_compression(config.content.compress_above_bytes);
+    if let Some(blobs) = config.blobs.create_store().await? {
+        pg_storage = pg_storage.with_blobs(blobs, config.blobs.store_above_bytes);
+    }
+    if let Some(replicas) = config
+        .database
+        .create_replicas(pool.clone(), config.database.max_connections)?
+    {
+        replicas.clone().spawn(Duration::from_secs(
+            config.database.replica_check_secs.max(1),
+        ));
+        pg_storage = pg_storage.with_replicas(replicas);
+    }
+    let pg_storage = Arc::new(pg_storage);
+    let mut tenants: Option<Arc<TenantStorage>> = None;
+    let storage: Arc<dyn ConfigStorage> = if config.tenancy.isolated {
+        let tenant_storage = Arc::new(TenantStorage::new(
+            pg_storage.clone(),
+            config.database.clone(),
+            config.tenancy.clone(),
+        ));
+        tenant_storage.load().await?;
+        tenants = Some(tenant_storage.clone());
+        tenant_storage
+    } else {
+        pg_storage.clone()
+    };
+    VersionCompactionJob::new(pool.clone(), config.versions.clone()).spawn();
+    let events = Arc::new(EventBus::new(config.events.clone()).with_outbox(pg_storage.clone()));
+    if let Some(anomalies) = &anomalies {
+        events.forward(anomalies.subscribe());
+    }
+    events.spawn_purge(EVENT_PURGE_INTERVAL);
+    for publisher in config_events::build_publishers(&config.event_publishers).await? {
+        events.spawn_consumer(publisher);
+    }
+    events.spawn_consumer(Arc::new(Notifier::new(
+        pg_storage.clone(),
+        &config.notifications,
+    )?));
+    let mut manager =
+        RaftConfigManager::new(config.raft.clone(), storage, raft_metrics, events.clone())
+            .await?
+            .with_validator(Arc::new(NamingValidator::new(config.naming.clone())))
+            .with_validator(Arc::new(SizeLimitValidator::new(
+                config.content.max_content_bytes,
+            )))
+            .with_validator(Arc::new(SchemaValidator::new(pg_storage.clone())))
+            .with_validator(Arc::new(RuleValidator::new(pg_storage.clone())))
+            .with_validator(Arc::new(WebhookValidator::new(pg_storage.clone())))
+            .with_write_guard(Arc::new(NamespaceFreezeGuard::new(pg_storage.clone())))
+            .with_write_guard(Arc::new(FreezeWindowGuard::new(pg_storage.clone())))
+            .with_write_guard(Arc::new(ApprovalGuard::new(config.approvals.clone())))
+            .with_secret_paths(pg_storage.clone())
+            .with_recipients(pg_storage.clone())
+            .with_secret_access(Arc::new(PolicySecretAccess::new(
+                policy_service.enforcer(),
+                audit.clone(),
+            )));
+    if let Some(tenants) = &tenants {
+        manager = manager.with_tenants(tenants.clone());
+    }
+    if config.teams.require_registered {
+        manager = manager.with_validator(Arc::new(DepartmentValidator::new(pg_storage.clone())));
+    }
+    let mut key_rotation: Option<Arc<dyn KeyRotationManager>> = None;
+    let mut backup_encryption = None;
+    if let Some(provider) = build_key_provider(&config.encryption).await? {
+        let mut encryption = EnvelopeEncryption::new(provider);
+        if let Some(key) = &config.encryption.checksum_key {
+            encryption = encryption.with_checksum_key(key);
+        }
+        let encryption = Arc::new(encryption);
+        backup_encryption = Some(encryption.clone());
+        let rotation = KeyRotationService::new(
+            pool.clone(),
+            encryption.clone(),
+            config.encryption.rotation_batch_size,
+            config.content.compress_above_bytes,
+        );
+        rotation.restore().await?;
+        key_rotation = Some(Arc::new(rotation));
+        manager = manager.with_encryption(encryption);
+    }
+    let raft_manager = Arc::new(manager);
+    let secret_expiry = Arc::new(SecretExpiryJob::new(
+        pg_storage.clone(),
+        raft_manager.clone(),
+        config.secret_expiry.clone(),
+        SecretExpiryMetrics::new(monitoring.registry())?,
+    ));
+    events.forward(secret_expiry.subscribe());
+    secret_expiry.spawn();
+
+    // Health
+    let health = Arc::new(
+        HealthService::new()
+            .with_check(Arc::new(PostgresCheck::new(pool.clone())))
+            .with_check(Arc::new(RedisCheck::new(redis.clone())))
+            .with_check(Arc::new(RaftLeaderCheck::new(raft_manager.clone()))),
+    );
+
+    let canary = Arc::new(CanaryMonitor::new(
+        raft_manager.clone(),
+        pg_storage.clone(),
+        events.clone(),
+        config.canary.clone(),
+    ));
+
+    let release_gates = Arc::new(ReleaseGates::new(config.releases.gates.clone()));
+    Arc::new(ReleaseScheduler::new(
+        raft_manager.clone(),
+        pg_storage.clone(),
+        release_gates.clone(),
+        events.clone(),
+        config.raft.node_id,
+    ))
+    .spawn();
+
+    // etcd clients read and watch configs over gRPC on their own port
+    if let Some(listen) = config.etcd.listen {
+        let gateway = EtcdGateway::new(
+            raft_manager.clone(),
+            raft_manager.clone(),
+            pg_storage.clone(),
+            events.clone(),
+        );
+        tracing::info!(%listen, "Starting etcd gateway");
+        tokio::spawn(async move {
+            if let Err(e) = gateway.serve(listen).await {
+                tracing::error!(error = %e, "etcd gateway stopped");
+            }
+        });
+    }
+
+    // gRPC clients stream large listings on their own port
+    if let Some(listen) = config.grpc.listen {
+        let api = GrpcApi::new(
+            raft_manager.clone(),
+            raft_manager.clone(),
+            pg_storage.clone(),
+            audit.clone(),
+            policy_service.enforcer(),
+            events.clone(),
+        );
+        tracing::info!(%listen, "Starting gRPC API");
+        tokio::spawn(async move {
+            if let Err(e) = api.serve(listen).await {
+                tracing::error!(error = %e, "gRPC API stopped");
+            }
+        });
+    }
+
+    // GitOps
+    let mut git_sync: Option<Arc<dyn GitSync>> = None;
+    if !config.git.imports.is_empty() {
+        let importer = Arc::new(GitImporter::new(raft_manager.clone(), &config.git.imports));
+        importer.spawn();
+        git_sync = Some(importer);
+    }
+    for export in &config.git.exports {
+        events.spawn_consumer(Arc::new(GitExporter::new(
+            raft_manager.clone(),
+            export.clone(),
+        )));
+    }
+
+    let backups = config.backup.is_enabled().then(|| {
+        let mut backups = PgBackupService::new(pool.clone(), config.backup.clone());
+        if let Some(encryption) = backup_encryption {
+            backups = backups.with_encryption(encryption);
+        }
+        Arc::new(backups) as Arc<dyn BackupManager>
+    });
+
+    let rate_limiter = if config.rate_limits.is_enabled() {
+        let limiter = RateLimiter::new(config.rate_limits.clone(), Some(redis))?;
+        Some(Arc::new(limiter))
+    } else {
+        None
+    };
+
+    let services = ApiServices {
+        config_manager: raft_manager.clone(),
+        version_control: raft_manager.clone(),
+        changesets: Arc::new(RaftChangeSetManager::new(
+            raft_manager.clone(),
+            pg_storage.clone(),
+        )),
+        approvals: Arc::new(RaftApprovalManager::new(
+            raft_manager,
+            pg_storage.clone(),
+            config.approvals.clone(),
+        )),
+        schemas: pg_storage.clone(),
+        rules: pg_storage.clone(),
+        secret_paths: pg_storage.clone(),
+        secret_expiry: pg_storage.clone(),
+        secret_shares: pg_storage.clone(),
+        recipients: pg_storage.clone(),
+        secret_expiry_policy: config.secret_expiry.clone(),
+        validation_hooks: pg_storage.clone(),
+        notifications: pg_storage.clone(),
+        namespaces: pg_storage.clone(),
+        promotions: pg_storage.clone(),
+        freeze_windows: pg_storage.clone(),
+        teams: pg_storage.clone(),
+        flags: pg_storage.clone(),
+        releases: pg_storage.clone(),
+        namespace_stats: Arc::new(CachedNamespaceStats::new(pg_storage)),
+        policy_service,
+        audit_service: audit,
+        monitoring: monitoring.clone(),
+        alert_engine,
+        log_level,
+        change_reason: config.change_reason.clone(),
+        release_policy: config.releases.clone(),
+        release_gates,
+        approval_policy: config.approvals.clone(),
+        canary,
+        events,
+        key_rotation,
+        git_sync,
+        tenants: tenants.map(|tenants| tenants as Arc<dyn TenantManager>),
+        backups,
+        rate_limiter,
+        max_content_bytes: config.content.max_content_bytes,
+        cache_policy: config.http.cache.clone(),
+    };
+
+    // Metrics on a dedicated port when configured, otherwise on the API listener
+    let metrics_server = config_monitor::serve_metrics(monitoring.clone(), &config.monitor)?;
+    let serve_metrics_inline = metrics_server.is_none();
+    if let Some(server) = metrics_server {
+        tokio::spawn(server);
+    }
+
+    let metrics_path = config.monitor.metrics_path.clone();
+    let compress = config.http.compress;
+    tracing::info!(host = %config.http.host, port = config.http.port, "Starting config server");
+
+    HttpServer::new(move || {
+        let services = services.clone();
+        let monitoring = monitoring.clone();
+        let health = health.clone();
+        let metrics_path = metrics_path.clone();
+
+        App::new()
+            .wrap(Condition::new(compress, Compress::default()))
+            .wrap(Logger::default())
+            .configure(move |cfg| {
+                config_api::configure_routes(cfg, services);
+                config_monitor::health::configure_routes(cfg, health);
+                if serve_metrics_inline {
+                    config_monitor::configure_routes(cfg, monitoring, &metrics_path);
+                }
+            })
+    })
+    .bind((config.http.host.as_str(), config.http.port))?
+    .run()
+    .await?;
+
+    Ok(())
+}
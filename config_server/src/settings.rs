@@ -0,0 +1,170 @@
+use config_api::{CachePolicy, EtcdConfig, GrpcConfig, RateLimitConfig};
+use config_audit::AuditConfig;
+use config_auth::AuthConfig;
+use config_core::{
+    ApprovalPolicy, CanaryConfig, ChangeReasonPolicy, EventBusConfig, NamingPolicy, ReleasePolicy,
+    SecretExpiryConfig, TeamPolicy,
+};
+use config_crypto::EncryptionConfig;
+use config_events::{NotificationConfig, PublisherConfig};
+use config_git::GitConfig;
+use config_monitor::MonitorConfig;
+use config_raft::RaftConfig;
+use config_storage::{
+    BackupConfig, BlobConfig, CacheConfig, ContentLimits, DatabaseConfig, TenancyConfig,
+    VersionRetentionConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Environment variable prefix for overriding settings, e.g. `CONFIG_SERVER__HTTP__PORT`
+const ENV_PREFIX: &str = "CONFIG_SERVER";
+
+/// Server settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    pub http: HttpConfig,
+    pub database: DatabaseConfig,
+    pub cache: CacheConfig,
+    pub raft: RaftConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub monitor: MonitorConfig,
+    #[serde(default)]
+    pub versions: VersionRetentionConfig,
+    #[serde(default)]
+    pub change_reason: ChangeReasonPolicy,
+    #[serde(default)]
+    pub canary: CanaryConfig,
+    #[serde(default)]
+    pub content: ContentLimits,
+    #[serde(default)]
+    pub naming: NamingPolicy,
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub secret_expiry: SecretExpiryConfig,
+    #[serde(default)]
+    pub events: EventBusConfig,
+    #[serde(default)]
+    pub event_publishers: Vec<PublisherConfig>,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub etcd: EtcdConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub git: GitConfig,
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
+    #[serde(default)]
+    pub teams: TeamPolicy,
+    #[serde(default)]
+    pub releases: ReleasePolicy,
+    #[serde(default)]
+    pub approvals: ApprovalPolicy,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub blobs: BlobConfig,
+    #[serde(default)]
+    pub rate_limits: RateLimitConfig,
+}
+
+/// HTTP listener settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    pub host: String,
+    pub port: u16,
+    /// Compress responses with gzip, brotli or zstd when the client accepts it
+    #[serde(default = "default_compress")]
+    pub compress: bool,
+    #[serde(default)]
+    pub cache: CachePolicy,
+}
+
+fn default_compress() -> bool {
+    true
+}
+
+/// Whether `--migrate` was passed: apply pending schema migrations, then exit
+pub fn migrate_only() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--migrate")
+}
+
+/// Backup to verify instead of serving, from `--verify-backup <backup>`
+pub fn verify_backup_arg() -> anyhow::Result<Option<String>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--verify-backup" {
+            return args
+                .next()
+                .map(Some)
+                .ok_or_else(|| anyhow::anyhow!("--verify-backup needs a backup name"));
+        }
+    }
+    Ok(None)
+}
+
+/// Backup to restore on startup, from `--restore <backup> [--restore-until <time>]`
+#[derive(Debug, Clone)]
+pub struct RestoreArgs {
+    pub backup: String,
+    /// Unix seconds to restore configs to
+    pub until: Option<i64>,
+}
+
+impl RestoreArgs {
+    /// Parse the restore flags from the command line, `None` when `--restore` is absent
+    pub fn from_args() -> anyhow::Result<Option<Self>> {
+        let mut backup = None;
+        let mut until = None;
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--restore" => {
+                    backup = Some(
+                        args.next()
+                            .ok_or_else(|| anyhow::anyhow!("--restore needs a backup name"))?,
+                    );
+                }
+                "--restore-until" => {
+                    let time = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("--restore-until needs a time"))?;
+                    until = Some(match time.parse::<i64>() {
+                        Ok(secs) => secs,
+                        Err(_) => chrono::DateTime::parse_from_rfc3339(&time)
+                            .map_err(|e| anyhow::anyhow!("invalid restore time {}: {}", time, e))?
+                            .timestamp(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        match (backup, until) {
+            (Some(backup), until) => Ok(Some(Self { backup, until })),
+            (None, Some(_)) => anyhow::bail!("--restore-until needs --restore"),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Load settings from `config/server.*` and the environment
+    pub fn load() -> anyhow::Result<Self> {
+        dotenv::dotenv().ok();
+
+        let settings = ::config::Config::builder()
+            .add_source(::config::File::with_name("config/server").required(false))
+            .add_source(::config::Environment::with_prefix(ENV_PREFIX).separator("__"))
+            .build()?
+            .try_deserialize()?;
+
+        Ok(settings)
+    }
+}
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS audit_logs (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    resource TEXT NOT NULL,
+    details TEXT NOT NULL,
+    timestamp BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS audit_logs_user_id_idx ON audit_logs (user_id);
+CREATE INDEX IF NOT EXISTS audit_logs_action_idx ON audit_logs (action);
+CREATE INDEX IF NOT EXISTS audit_logs_resource_idx ON audit_logs (resource);
+CREATE INDEX IF NOT EXISTS audit_logs_timestamp_idx ON audit_logs (timestamp);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS casbin_rule (
+    id SERIAL PRIMARY KEY,
+    ptype TEXT NOT NULL,
+    v0 TEXT NOT NULL DEFAULT '',
+    v1 TEXT NOT NULL DEFAULT '',
+    v2 TEXT NOT NULL DEFAULT '',
+    v3 TEXT NOT NULL DEFAULT '',
+    v4 TEXT NOT NULL DEFAULT '',
+    v5 TEXT NOT NULL DEFAULT '',
+    UNIQUE (ptype, v0, v1, v2, v3, v4, v5)
+);
+CREATE TABLE IF NOT EXISTS temporary_grants (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    granted_by TEXT NOT NULL,
+    granted_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS temporary_grants_expires_at_idx ON temporary_grants (expires_at);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS changesets (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL,
+    changes JSONB NOT NULL DEFAULT '[]',
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    applied_at BIGINT,
+    applied_by TEXT
+);
+CREATE INDEX IF NOT EXISTS changesets_status_idx ON changesets (status, created_at);
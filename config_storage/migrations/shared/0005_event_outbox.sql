@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS config_events (
+    seq BIGSERIAL PRIMARY KEY,
+    config_id TEXT NOT NULL,
+    event JSONB NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS config_events_created_at_idx ON config_events (created_at);
+CREATE TABLE IF NOT EXISTS config_event_offsets (
+    consumer TEXT PRIMARY KEY,
+    seq BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
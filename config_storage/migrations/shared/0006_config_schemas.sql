@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS config_schemas (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    application TEXT,
+    description TEXT,
+    schema JSONB NOT NULL,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    updated_by TEXT NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS config_schemas_target_idx
+    ON config_schemas (namespace, COALESCE(application, ''));
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS config_secret_paths (
+    config_id TEXT PRIMARY KEY REFERENCES configs(id) ON DELETE CASCADE,
+    paths JSONB NOT NULL DEFAULT '[]',
+    updated_at BIGINT NOT NULL,
+    updated_by TEXT NOT NULL
+);
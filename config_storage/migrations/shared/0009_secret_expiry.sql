@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS config_secret_expiry (
+    config_id TEXT PRIMARY KEY REFERENCES configs(id) ON DELETE CASCADE,
+    expires_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    updated_by TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS config_secret_expiry_expires_at_idx
+    ON config_secret_expiry (expires_at);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS namespace_recipients (
+    namespace TEXT PRIMARY KEY,
+    recipients JSONB NOT NULL DEFAULT '[]',
+    updated_at BIGINT NOT NULL,
+    updated_by TEXT NOT NULL
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS key_rotations (
+    id TEXT PRIMARY KEY,
+    key_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    total BIGINT NOT NULL,
+    processed BIGINT NOT NULL DEFAULT 0,
+    failed BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at BIGINT NOT NULL,
+    started_by TEXT NOT NULL,
+    finished_at BIGINT
+);
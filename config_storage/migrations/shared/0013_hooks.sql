@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS config_validation_hooks (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    url TEXT NOT NULL,
+    timeout_ms BIGINT NOT NULL,
+    fail_open BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS config_validation_hooks_namespace_idx
+    ON config_validation_hooks (namespace);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS notification_subscriptions (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    channel JSONB NOT NULL,
+    filter JSONB NOT NULL,
+    template TEXT,
+    debounce_secs BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL
+);
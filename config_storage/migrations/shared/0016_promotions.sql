@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS config_promotions (
+    id TEXT PRIMARY KEY,
+    source_id TEXT NOT NULL,
+    source_version TEXT NOT NULL,
+    target_environment TEXT NOT NULL,
+    target_id TEXT,
+    target_version TEXT,
+    change_reason TEXT,
+    status TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at BIGINT NOT NULL,
+    reviewed_by TEXT,
+    reviewed_at BIGINT,
+    applied_version TEXT
+);
+CREATE INDEX IF NOT EXISTS config_promotions_status_idx
+    ON config_promotions (status, requested_at);
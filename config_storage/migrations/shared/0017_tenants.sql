@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS tenants (
+    name TEXT PRIMARY KEY,
+    schema_name TEXT NOT NULL UNIQUE,
+    key_id TEXT,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tenant_configs (
+    config_id TEXT PRIMARY KEY,
+    tenant TEXT NOT NULL REFERENCES tenants (name)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS freeze_windows (
+    id TEXT PRIMARY KEY,
+    environment TEXT NOT NULL,
+    starts TEXT NOT NULL,
+    ends TEXT NOT NULL,
+    reason TEXT,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS freeze_windows_environment_idx
+    ON freeze_windows (environment);
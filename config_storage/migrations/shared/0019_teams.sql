@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS teams (
+    name TEXT PRIMARY KEY,
+    description TEXT,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS team_members (
+    team TEXT NOT NULL REFERENCES teams (name) ON DELETE CASCADE,
+    member TEXT NOT NULL,
+    added_at BIGINT NOT NULL,
+    added_by TEXT NOT NULL,
+    PRIMARY KEY (team, member)
+);
@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS config_releases (
+    id TEXT PRIMARY KEY,
+    config_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    previous_version TEXT,
+    change_reason TEXT,
+    status TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at BIGINT NOT NULL,
+    required_approvals INTEGER NOT NULL DEFAULT 0,
+    approvals TEXT[] NOT NULL DEFAULT '{}',
+    rollout JSONB,
+    scheduled_at BIGINT,
+    gate_results JSONB NOT NULL DEFAULT '[]',
+    published_at BIGINT,
+    closed_by TEXT,
+    closed_at BIGINT
+);
+CREATE INDEX IF NOT EXISTS config_releases_config_idx
+    ON config_releases (config_id, status, published_at);
+CREATE INDEX IF NOT EXISTS config_releases_scheduled_idx
+    ON config_releases (scheduled_at) WHERE status = 'scheduled';
+
+CREATE TABLE IF NOT EXISTS config_dependencies (
+    config_id TEXT NOT NULL,
+    depends_on TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    PRIMARY KEY (config_id, depends_on)
+);
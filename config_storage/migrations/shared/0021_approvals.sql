@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS approval_requests (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    config_id TEXT NOT NULL,
+    change JSONB NOT NULL,
+    change_reason TEXT,
+    status TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at BIGINT NOT NULL,
+    reviewers TEXT[] NOT NULL DEFAULT '{}',
+    required_approvals INTEGER NOT NULL DEFAULT 1,
+    approvals TEXT[] NOT NULL DEFAULT '{}',
+    reviewed_at BIGINT,
+    rejected_by TEXT
+);
+CREATE INDEX IF NOT EXISTS approval_requests_status_idx
+    ON approval_requests (status, requested_at);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS feature_flags (
+    key TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    description TEXT,
+    enabled BOOLEAN NOT NULL,
+    percentage SMALLINT,
+    allow_list TEXT[] NOT NULL DEFAULT '{}',
+    deny_list TEXT[] NOT NULL DEFAULT '{}',
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    updated_by TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS feature_flags_namespace_idx ON feature_flags (namespace);
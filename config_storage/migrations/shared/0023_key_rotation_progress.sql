@@ -0,0 +1,3 @@
+ALTER TABLE key_rotations ADD COLUMN IF NOT EXISTS progress JSONB;
+CREATE UNIQUE INDEX IF NOT EXISTS key_rotations_running_idx
+    ON key_rotations (status) WHERE status = 'running';
@@ -0,0 +1,45 @@
+CREATE TABLE IF NOT EXISTS configs (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    department TEXT NOT NULL,
+    application TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    version TEXT NOT NULL,
+    description TEXT,
+    owners TEXT[] NOT NULL DEFAULT '{}',
+    labels TEXT[] NOT NULL DEFAULT '{}',
+    format TEXT NOT NULL,
+    content TEXT NOT NULL,
+    content_encoding TEXT,
+    is_encrypted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    updated_by TEXT NOT NULL,
+    UNIQUE (namespace, application, environment, name)
+);
+CREATE TABLE IF NOT EXISTS config_versions (
+    seq BIGSERIAL PRIMARY KEY,
+    config_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    description TEXT,
+    change_reason TEXT,
+    format TEXT NOT NULL,
+    content TEXT NOT NULL,
+    content_encoding TEXT,
+    is_encrypted BOOLEAN NOT NULL DEFAULT FALSE,
+    pinned BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at BIGINT NOT NULL,
+    created_by TEXT NOT NULL,
+    UNIQUE (config_id, version)
+);
+CREATE INDEX IF NOT EXISTS config_versions_config_id_idx ON config_versions (config_id, created_at);
+CREATE TABLE IF NOT EXISTS config_version_tags (
+    config_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    version TEXT NOT NULL,
+    created_by TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (config_id, tag)
+);
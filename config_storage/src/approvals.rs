@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{ApprovalRequest, ApprovalStatus, StagedChange};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+use crate::store::ApprovalStorage;
+
+/// Columns selected for an approval request row
+const APPROVAL_COLUMNS: &str = "id, namespace, change, change_reason, status, requested_by, \
+     requested_at, reviewers, required_approvals, approvals, reviewed_at, rejected_by";
+
+#[derive(sqlx::FromRow)]
+struct ApprovalRow {
+    id: String,
+    namespace: String,
+    change: Json<StagedChange>,
+    change_reason: Option<String>,
+    status: String,
+    requested_by: String,
+    requested_at: i64,
+    reviewers: Vec<String>,
+    required_approvals: i32,
+    approvals: Vec<String>,
+    reviewed_at: Option<i64>,
+    rejected_by: Option<String>,
+}
+
+impl TryFrom<ApprovalRow> for ApprovalRequest {
+    type Error = config_common::Error;
+
+    fn try_from(row: ApprovalRow) -> Result<Self> {
+        Ok(ApprovalRequest {
+            id: row.id,
+            namespace: row.namespace,
+            change: row.change.0,
+            change_reason: row.change_reason,
+            status: row.status.parse()?,
+            requested_by: row.requested_by,
+            requested_at: row.requested_at,
+            reviewers: row.reviewers,
+            required_approvals: row.required_approvals.max(0) as usize,
+            approvals: row.approvals,
+            reviewed_at: row.reviewed_at,
+            rejected_by: row.rejected_by,
+        })
+    }
+}
+
+#[async_trait]
+impl ApprovalStorage for PgConfigStorage {
+    async fn save_approval(&self, approval: &ApprovalRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO approval_requests (id, namespace, config_id, change, change_reason,
+                status, requested_by, requested_at, reviewers, required_approvals, approvals,
+                reviewed_at, rejected_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (id) DO UPDATE
+            SET status = EXCLUDED.status, approvals = EXCLUDED.approvals,
+                reviewed_at = EXCLUDED.reviewed_at, rejected_by = EXCLUDED.rejected_by
+            "#,
+        )
+        .bind(&approval.id)
+        .bind(&approval.namespace)
+        .bind(&approval.change.config_id)
+        .bind(Json(&approval.change))
+        .bind(&approval.change_reason)
+        .bind(approval.status.as_str())
+        .bind(&approval.requested_by)
+        .bind(approval.requested_at)
+        .bind(&approval.reviewers)
+        .bind(approval.required_approvals as i32)
+        .bind(&approval.approvals)
+        .bind(approval.reviewed_at)
+        .bind(&approval.rejected_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_approval(&self, id: &str) -> Result<ApprovalRequest> {
+        sqlx::query_as::<_, ApprovalRow>(&format!(
+            "SELECT {} FROM approval_requests WHERE id = $1",
+            APPROVAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("approval request {}", id)))?
+        .try_into()
+    }
+
+    async fn list_approvals(&self, status: Option<ApprovalStatus>) -> Result<Vec<ApprovalRequest>> {
+        let rows = sqlx::query_as::<_, ApprovalRow>(&format!(
+            "SELECT {} FROM approval_requests WHERE ($1::TEXT IS NULL OR status = $1) \
+             ORDER BY requested_at DESC",
+            APPROVAL_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(ApprovalRequest::try_from).collect()
+    }
+}
@@ -0,0 +1,622 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::backup::{BACKUP_FORMAT, BACKUP_MANIFEST_PATH};
+use config_core::{
+    BackupColumn, BackupInfo, BackupJob, BackupJobStatus, BackupManager, BackupManifest,
+    BackupTable, ConfigEncryption, RestoreReport, SignedVerification,
+};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::restore::{load_backup, unpack_backup, LoadedBackup};
+
+/// File name prefix of backup archives
+pub const BACKUP_PREFIX: &str = "config-backup-";
+
+/// File name suffix of backup archives
+pub const BACKUP_EXTENSION: &str = ".tar.gz";
+
+/// Where online backups are written; backups are disabled when neither is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Local directory backups are written to, also used to stage uploads to S3
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    #[serde(default)]
+    pub s3: Option<S3BackupConfig>,
+    /// Key verification results are signed with; backups can't be verified when unset
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    /// Encrypted contents decrypted when verifying a backup
+    #[serde(default = "default_verify_sample")]
+    pub verify_sample: usize,
+}
+
+fn default_verify_sample() -> usize {
+    50
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            s3: None,
+            signing_key: None,
+            verify_sample: default_verify_sample(),
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.directory.is_some() || self.s3.is_some()
+    }
+}
+
+/// S3 or S3-compatible bucket; credentials come from the default AWS provider chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3BackupConfig {
+    pub bucket: String,
+    /// Key prefix backups are stored under, e.g. `backups/`
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Endpoint of an S3-compatible service such as MinIO
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl S3BackupConfig {
+    fn key(&self, name: &str) -> String {
+        if self.prefix.is_empty() || self.prefix.ends_with('/') {
+            format!("{}{}", self.prefix, name)
+        } else {
+            format!("{}/{}", self.prefix, name)
+        }
+    }
+}
+
+/// Writes consistent snapshots of every table to a tar.gz archive, one JSONL file per table
+pub struct PgBackupService {
+    pool: Arc<PgPool>,
+    config: BackupConfig,
+    jobs: Arc<RwLock<Vec<BackupJob>>>,
+    encryption: Option<Arc<dyn ConfigEncryption>>,
+}
+
+impl PgBackupService {
+    pub fn new(pool: Arc<PgPool>, config: BackupConfig) -> Self {
+        Self {
+            pool,
+            config,
+            jobs: Arc::new(RwLock::new(Vec::new())),
+            encryption: None,
+        }
+    }
+
+    /// Decrypt sampled content with the current keys when verifying backups
+    pub fn with_encryption(mut self, encryption: Arc<dyn ConfigEncryption>) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Directory archives are written to before any upload
+    fn staging_directory(&self) -> PathBuf {
+        self.config
+            .directory
+            .clone()
+            .unwrap_or_else(std::env::temp_dir)
+    }
+
+    /// Read and check a backup by name, downloading it first when it is stored in S3
+    pub async fn load(&self, name: &str) -> Result<LoadedBackup> {
+        self.read(name, load_backup).await
+    }
+
+    /// Read a backup by name with `read`, downloading it first when it is stored in S3
+    async fn read(
+        &self,
+        name: &str,
+        read: fn(&Path) -> Result<LoadedBackup>,
+    ) -> Result<LoadedBackup> {
+        if !name.starts_with(BACKUP_PREFIX)
+            || !name.ends_with(BACKUP_EXTENSION)
+            || name.contains(['/', '\\'])
+        {
+            return Err(config_common::Error::Validation(format!(
+                "invalid backup name {}",
+                name
+            )));
+        }
+
+        let path = self.staging_directory().join(name);
+        let downloaded = match &self.config.s3 {
+            Some(s3) if tokio::fs::metadata(&path).await.is_err() => {
+                s3_download(s3, &s3.key(name), &path).await?;
+                true
+            }
+            _ => false,
+        };
+        if !downloaded && tokio::fs::metadata(&path).await.is_err() {
+            return Err(config_common::Error::NotFound(format!("backup {}", name)));
+        }
+
+        let load_path = path.clone();
+        let loaded = tokio::task::spawn_blocking(move || read(&load_path))
+            .await
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        if downloaded && self.config.directory.is_none() {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+        loaded
+    }
+}
+
+/// Progress reporting of a running backup
+struct JobProgress {
+    jobs: Arc<RwLock<Vec<BackupJob>>>,
+    id: String,
+}
+
+impl JobProgress {
+    async fn update(&self, f: impl FnOnce(&mut BackupJob)) {
+        if let Some(job) = self.jobs.write().await.iter_mut().find(|j| j.id == self.id) {
+            f(job);
+        }
+    }
+}
+
+#[async_trait]
+impl BackupManager for PgBackupService {
+    async fn start_backup(&self, started_by: &str) -> Result<BackupJob> {
+        if !self.config.is_enabled() {
+            return Err(config_common::Error::Config(
+                "no backup directory or bucket is configured".to_string(),
+            ));
+        }
+
+        let now = chrono::Utc::now();
+        let name = format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            now.format("%Y%m%dT%H%M%SZ"),
+            BACKUP_EXTENSION
+        );
+        let path = self.staging_directory().join(&name);
+        let location = match &self.config.s3 {
+            Some(s3) => format!("s3://{}/{}", s3.bucket, s3.key(&name)),
+            None => path.display().to_string(),
+        };
+        let job = BackupJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            status: BackupJobStatus::Running,
+            location,
+            started_by: started_by.to_string(),
+            started_at: now.timestamp(),
+            finished_at: None,
+            tables_total: 0,
+            tables_done: 0,
+            rows: 0,
+            bytes: 0,
+            error: None,
+        };
+        {
+            let mut jobs = self.jobs.write().await;
+            if let Some(running) = jobs.iter().find(|j| j.status == BackupJobStatus::Running) {
+                return Err(config_common::Error::Validation(format!(
+                    "backup {} is still running",
+                    running.id
+                )));
+            }
+            jobs.insert(0, job.clone());
+        }
+
+        let pool = self.pool.clone();
+        let config = self.config.clone();
+        let progress = JobProgress {
+            jobs: self.jobs.clone(),
+            id: job.id.clone(),
+        };
+        let started_by = started_by.to_string();
+        tokio::spawn(async move {
+            let result = run_backup(&pool, &config, &path, &name, &started_by, &progress).await;
+            if let Err(e) = &result {
+                tracing::error!(backup = %progress.id, error = %e, "Backup failed");
+            }
+            progress
+                .update(|job| {
+                    job.finished_at = Some(chrono::Utc::now().timestamp());
+                    match result {
+                        Ok(()) => job.status = BackupJobStatus::Completed,
+                        Err(e) => {
+                            job.status = BackupJobStatus::Failed;
+                            job.error = Some(e.to_string());
+                        }
+                    }
+                })
+                .await;
+        });
+
+        Ok(job)
+    }
+
+    async fn get_backup_job(&self, id: &str) -> Result<BackupJob> {
+        self.jobs
+            .read()
+            .await
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+            .ok_or_else(|| config_common::Error::NotFound(format!("backup job {}", id)))
+    }
+
+    async fn list_backup_jobs(&self) -> Result<Vec<BackupJob>> {
+        Ok(self.jobs.read().await.clone())
+    }
+
+    async fn list_backups(&self) -> Result<Vec<BackupInfo>> {
+        let mut backups = match &self.config.s3 {
+            Some(s3) => s3_list(s3).await?,
+            None => match &self.config.directory {
+                Some(directory) => list_directory(directory).await?,
+                None => Vec::new(),
+            },
+        };
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    async fn restore_backup(
+        &self,
+        name: &str,
+        until: Option<i64>,
+        restored_by: &str,
+    ) -> Result<RestoreReport> {
+        if let Some(running) = self
+            .jobs
+            .read()
+            .await
+            .iter()
+            .find(|j| j.status == BackupJobStatus::Running)
+        {
+            return Err(config_common::Error::Validation(format!(
+                "backup {} is still running",
+                running.id
+            )));
+        }
+        let backup = self.load(name).await?;
+        crate::restore::restore(&self.pool, name, &backup, until, restored_by).await
+    }
+
+    async fn verify_backup(&self, name: &str, verified_by: &str) -> Result<SignedVerification> {
+        let key = self.config.signing_key.as_deref().ok_or_else(|| {
+            config_common::Error::Config(
+                "verifying backups requires backup.signing_key".to_string(),
+            )
+        })?;
+        // Checked by the verification, which reports every problem rather than the first
+        let backup = self.read(name, unpack_backup).await?;
+        let verification = crate::verify::verify(
+            name,
+            &backup,
+            self.encryption.as_deref(),
+            self.config.verify_sample,
+            verified_by,
+        )
+        .await;
+        crate::verify::sign(verification, key)
+    }
+}
+
+/// Snapshot the database to `path`, then upload it when a bucket is configured
+async fn run_backup(
+    pool: &PgPool,
+    config: &BackupConfig,
+    path: &Path,
+    name: &str,
+    started_by: &str,
+    progress: &JobProgress,
+) -> Result<()> {
+    let directory = path.parent().unwrap_or(Path::new("."));
+    tokio::fs::create_dir_all(directory)
+        .await
+        .map_err(io_error)?;
+
+    // Written under a temporary name so listings never show a partial backup
+    let partial = path.with_extension("partial");
+    if let Err(e) = write_snapshot(pool, &partial, started_by, progress).await {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&partial, path).await.map_err(io_error)?;
+
+    if let Some(s3) = &config.s3 {
+        s3_upload(s3, path, &s3.key(name)).await?;
+        if config.directory.is_none() {
+            tokio::fs::remove_file(path).await.map_err(io_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write every table of the current schema as seen by a single repeatable-read transaction
+async fn write_snapshot(
+    pool: &PgPool,
+    path: &Path,
+    started_by: &str,
+    progress: &JobProgress,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let tables: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT table_name::TEXT FROM information_schema.tables
+        WHERE table_schema = current_schema() AND table_type = 'BASE TABLE'
+        ORDER BY table_name
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    progress.update(|job| job.tables_total = tables.len()).await;
+
+    let created_at = chrono::Utc::now().timestamp();
+    let file = std::fs::File::create(path).map_err(io_error)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut manifest = BackupManifest {
+        format: BACKUP_FORMAT,
+        created_at,
+        created_by: started_by.to_string(),
+        tables: Vec::with_capacity(tables.len()),
+    };
+
+    for name in tables {
+        let columns = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT column_name::TEXT, data_type::TEXT FROM information_schema.columns
+            WHERE table_schema = current_schema() AND table_name = $1
+            ORDER BY ordinal_position
+            "#,
+        )
+        .bind(&name)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|(name, data_type)| BackupColumn { name, data_type })
+        .collect();
+
+        let mut data = Vec::new();
+        let mut rows = 0;
+        {
+            let sql = format!(
+                "SELECT row_to_json(t)::TEXT FROM \"{}\" t",
+                name.replace('"', "\"\"")
+            );
+            let mut stream = sqlx::query_scalar::<_, String>(&sql).fetch(&mut *tx);
+            while let Some(row) = stream.try_next().await? {
+                data.extend_from_slice(row.as_bytes());
+                data.push(b'\n');
+                rows += 1;
+            }
+        }
+
+        let table = BackupTable {
+            name,
+            columns,
+            rows,
+            sha256: format!("{:x}", Sha256::digest(&data)),
+        };
+        append(&mut builder, &table.path(), &data, created_at)?;
+        progress
+            .update(|job| {
+                job.tables_done += 1;
+                job.rows += rows;
+                job.bytes += data.len() as u64;
+            })
+            .await;
+        manifest.tables.push(table);
+    }
+    tx.commit().await?;
+
+    append(
+        &mut builder,
+        BACKUP_MANIFEST_PATH,
+        &serde_json::to_vec_pretty(&manifest)?,
+        created_at,
+    )?;
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|file| file.sync_all())
+        .map_err(io_error)
+}
+
+fn append<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+    mtime: i64,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime.max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, data)
+        .map_err(io_error)
+}
+
+async fn list_directory(directory: &Path) -> Result<Vec<BackupInfo>> {
+    let mut entries = match tokio::fs::read_dir(directory).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(e)),
+    };
+
+    let mut backups = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(BACKUP_PREFIX) || !name.ends_with(BACKUP_EXTENSION) {
+            continue;
+        }
+        let metadata = entry.metadata().await.map_err(io_error)?;
+        let created_at = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|age| age.as_secs() as i64)
+            .unwrap_or_default();
+        backups.push(BackupInfo {
+            location: entry.path().display().to_string(),
+            name,
+            size: metadata.len(),
+            created_at,
+        });
+    }
+    Ok(backups)
+}
+
+fn io_error(e: std::io::Error) -> config_common::Error {
+    config_common::Error::Internal(format!("backup: {}", e))
+}
+
+/// S3 client with credentials from the default AWS provider chain; a custom endpoint is
+/// addressed path-style, as S3-compatible services such as MinIO expect
+#[cfg(any(feature = "s3-backup", feature = "s3-blobs"))]
+pub(crate) async fn s3_client(region: Option<&str>, endpoint: Option<&str>) -> aws_sdk_s3::Client {
+    let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+    if let Some(region) = region {
+        loader = loader.region(aws_config::Region::new(region.to_string()));
+    }
+    let mut builder = aws_sdk_s3::config::Builder::from(&loader.load().await);
+    if let Some(endpoint) = endpoint {
+        builder = builder.endpoint_url(endpoint).force_path_style(true);
+    }
+    aws_sdk_s3::Client::from_conf(builder.build())
+}
+
+#[cfg(feature = "s3-backup")]
+fn s3_error<E: std::error::Error + 'static>(
+    e: aws_sdk_s3::error::SdkError<E>,
+) -> config_common::Error {
+    config_common::Error::Internal(format!(
+        "s3 backup: {}",
+        aws_sdk_s3::error::DisplayErrorContext(e)
+    ))
+}
+
+#[cfg(feature = "s3-backup")]
+async fn s3_upload(config: &S3BackupConfig, path: &Path, key: &str) -> Result<()> {
+    let body = aws_sdk_s3::primitives::ByteStream::from_path(path)
+        .await
+        .map_err(|e| config_common::Error::Internal(format!("s3 backup: {}", e)))?;
+    s3_client(config.region.as_deref(), config.endpoint.as_deref())
+        .await
+        .put_object()
+        .bucket(&config.bucket)
+        .key(key)
+        .body(body)
+        .send()
+        .await
+        .map_err(s3_error)?;
+    Ok(())
+}
+
+#[cfg(feature = "s3-backup")]
+async fn s3_download(config: &S3BackupConfig, key: &str, path: &Path) -> Result<()> {
+    let output = s3_client(config.region.as_deref(), config.endpoint.as_deref())
+        .await
+        .get_object()
+        .bucket(&config.bucket)
+        .key(key)
+        .send()
+        .await
+        .map_err(|e| match e.as_service_error() {
+            Some(err) if err.is_no_such_key() => {
+                config_common::Error::NotFound(format!("backup {}", key))
+            }
+            _ => s3_error(e),
+        })?;
+    let data = output
+        .body
+        .collect()
+        .await
+        .map_err(|e| config_common::Error::Internal(format!("s3 backup: {}", e)))?;
+    if let Some(directory) = path.parent() {
+        tokio::fs::create_dir_all(directory)
+            .await
+            .map_err(io_error)?;
+    }
+    tokio::fs::write(path, data.into_bytes())
+        .await
+        .map_err(io_error)
+}
+
+#[cfg(feature = "s3-backup")]
+async fn s3_list(config: &S3BackupConfig) -> Result<Vec<BackupInfo>> {
+    let client = s3_client(config.region.as_deref(), config.endpoint.as_deref()).await;
+    let prefix = config.key(BACKUP_PREFIX);
+    let mut backups = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let output = client
+            .list_objects_v2()
+            .bucket(&config.bucket)
+            .prefix(&prefix)
+            .set_continuation_token(token.take())
+            .send()
+            .await
+            .map_err(s3_error)?;
+        for object in output.contents() {
+            let Some(key) = object.key() else { continue };
+            if !key.ends_with(BACKUP_EXTENSION) {
+                continue;
+            }
+            backups.push(BackupInfo {
+                name: key.rsplit('/').next().unwrap_or(key).to_string(),
+                location: format!("s3://{}/{}", config.bucket, key),
+                size: object.size().unwrap_or_default().max(0) as u64,
+                created_at: object
+                    .last_modified()
+                    .map(|at| at.secs())
+                    .unwrap_or_default(),
+            });
+        }
+        match output.next_continuation_token() {
+            Some(next) => token = Some(next.to_string()),
+            None => break,
+        }
+    }
+    Ok(backups)
+}
+
+#[cfg(not(feature = "s3-backup"))]
+async fn s3_upload(_config: &S3BackupConfig, _path: &Path, _key: &str) -> Result<()> {
+    Err(s3_unsupported())
+}
+
+#[cfg(not(feature = "s3-backup"))]
+async fn s3_download(_config: &S3BackupConfig, _key: &str, _path: &Path) -> Result<()> {
+    Err(s3_unsupported())
+}
+
+#[cfg(not(feature = "s3-backup"))]
+async fn s3_list(_config: &S3BackupConfig) -> Result<Vec<BackupInfo>> {
+    Err(s3_unsupported())
+}
+
+#[cfg(not(feature = "s3-backup"))]
+fn s3_unsupported() -> config_common::Error {
+    config_common::Error::Config("s3 backups require the `s3-backup` feature".to_string())
+}
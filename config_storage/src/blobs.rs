@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use config_common::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// `content_encoding` of content kept in the blob store, with its key stored in its place
+pub const BLOB_ENCODING: &str = "blob";
+
+fn default_store_above_bytes() -> usize {
+    1024 * 1024
+}
+
+/// Blob tier for large content; off when no bucket is configured. Backups hold the keys of
+/// blobs rather than their content, so the bucket must be kept for as long as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobConfig {
+    #[serde(default)]
+    pub s3: Option<S3BlobConfig>,
+    /// Plaintext content longer than this is kept in the blob store. Content holding
+    /// ciphertext stays in Postgres, where key rotation re-encrypts it.
+    #[serde(default = "default_store_above_bytes")]
+    pub store_above_bytes: usize,
+}
+
+impl Default for BlobConfig {
+    fn default() -> Self {
+        Self {
+            s3: None,
+            store_above_bytes: default_store_above_bytes(),
+        }
+    }
+}
+
+/// S3 or S3-compatible bucket; credentials come from the default AWS provider chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3BlobConfig {
+    pub bucket: String,
+    /// Key prefix blobs are stored under, e.g. `blobs/`
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Endpoint of an S3-compatible service such as MinIO
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl BlobConfig {
+    /// Blob store of the configured bucket, `None` when the blob tier is off
+    pub async fn create_store(&self) -> Result<Option<Arc<dyn BlobStore>>> {
+        match &self.s3 {
+            Some(s3) => s3_store(s3).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Storage for content too large to keep in Postgres. Blobs are keyed by the SHA-256 of
+/// their content, so they are never overwritten with different data and versions with the
+/// same content share a blob.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+}
+
+/// Key of content in the blob store
+pub fn blob_key(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+#[cfg(feature = "s3-blobs")]
+struct S3BlobStore {
+    client: aws_sdk_s3::Client,
+    config: S3BlobConfig,
+}
+
+#[cfg(feature = "s3-blobs")]
+impl S3BlobStore {
+    fn object_key(&self, key: &str) -> String {
+        if self.config.prefix.is_empty() || self.config.prefix.ends_with('/') {
+            format!("{}{}", self.config.prefix, key)
+        } else {
+            format!("{}/{}", self.config.prefix, key)
+        }
+    }
+}
+
+#[cfg(feature = "s3-blobs")]
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(self.object_key(key))
+            .body(aws_sdk_s3::primitives::ByteStream::from(data.to_vec()))
+            .send()
+            .await
+            .map_err(s3_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.config.bucket)
+            .key(self.object_key(key))
+            .send()
+            .await
+            .map_err(|e| match e.as_service_error() {
+                Some(err) if err.is_no_such_key() => {
+                    config_common::Error::NotFound(format!("blob {}", key))
+                }
+                _ => s3_error(e),
+            })?;
+        let data = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| config_common::Error::Internal(format!("s3 blob store: {}", e)))?;
+        Ok(data.into_bytes().to_vec())
+    }
+}
+
+#[cfg(feature = "s3-blobs")]
+async fn s3_store(config: &S3BlobConfig) -> Result<Arc<dyn BlobStore>> {
+    let client =
+        crate::backup::s3_client(config.region.as_deref(), config.endpoint.as_deref()).await;
+    Ok(Arc::new(S3BlobStore {
+        client,
+        config: config.clone(),
+    }))
+}
+
+#[cfg(not(feature = "s3-blobs"))]
+async fn s3_store(_config: &S3BlobConfig) -> Result<Arc<dyn BlobStore>> {
+    Err(config_common::Error::Config(
+        "s3 blob storage requires the `s3-blobs` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "s3-blobs")]
+fn s3_error<E: std::error::Error + 'static>(
+    e: aws_sdk_s3::error::SdkError<E>,
+) -> config_common::Error {
+    config_common::Error::Internal(format!(
+        "s3 blob store: {}",
+        aws_sdk_s3::error::DisplayErrorContext(e)
+    ))
+}
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
 use config_common::{ConfigContent, ConfigMeta, Result};
+use config_core::{EventConsumer, PublishedEvent};
+use std::sync::Arc;
 
 /// Cache trait for configuration data
 #[async_trait]
@@ -16,3 +18,28 @@ pub trait ConfigCache: Send + Sync {
     /// Clear all cached configurations
     async fn clear_all(&self) -> Result<()>;
 }
+
+/// Consumer name under which the invalidator's event position is stored
+const CACHE_INVALIDATION_CONSUMER: &str = "cache-invalidation";
+
+/// Drops cached configurations whenever an event reports they changed
+pub struct CacheInvalidator {
+    cache: Arc<dyn ConfigCache>,
+}
+
+impl CacheInvalidator {
+    pub fn new(cache: Arc<dyn ConfigCache>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl EventConsumer for CacheInvalidator {
+    fn name(&self) -> &str {
+        CACHE_INVALIDATION_CONSUMER
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> Result<()> {
+        self.cache.delete_config(&event.event.config_id).await
+    }
+}
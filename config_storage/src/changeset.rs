@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{ChangeSet, ChangeSetStatus, StagedChange};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+use crate::store::ChangeSetStorage;
+
+/// Columns selected for a change set row
+const CHANGESET_COLUMNS: &str = "id, title, description, status, changes, created_at, \
+     created_by, updated_at, applied_at, applied_by";
+
+#[derive(sqlx::FromRow)]
+struct ChangeSetRow {
+    id: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+    changes: Json<Vec<StagedChange>>,
+    created_at: i64,
+    created_by: String,
+    updated_at: i64,
+    applied_at: Option<i64>,
+    applied_by: Option<String>,
+}
+
+impl TryFrom<ChangeSetRow> for ChangeSet {
+    type Error = config_common::Error;
+
+    fn try_from(row: ChangeSetRow) -> Result<Self> {
+        Ok(ChangeSet {
+            id: row.id,
+            title: row.title,
+            description: row.description,
+            status: row.status.parse::<ChangeSetStatus>()?,
+            changes: row.changes.0,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            applied_at: row.applied_at,
+            applied_by: row.applied_by,
+        })
+    }
+}
+
+#[async_trait]
+impl ChangeSetStorage for PgConfigStorage {
+    async fn save_changeset(&self, changeset: &ChangeSet) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO changesets (id, title, description, status, changes, created_at,
+                created_by, updated_at, applied_at, applied_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO UPDATE
+            SET title = EXCLUDED.title, description = EXCLUDED.description,
+                status = EXCLUDED.status, changes = EXCLUDED.changes,
+                updated_at = EXCLUDED.updated_at, applied_at = EXCLUDED.applied_at,
+                applied_by = EXCLUDED.applied_by
+            "#,
+        )
+        .bind(&changeset.id)
+        .bind(&changeset.title)
+        .bind(&changeset.description)
+        .bind(changeset.status.as_str())
+        .bind(Json(&changeset.changes))
+        .bind(changeset.created_at)
+        .bind(&changeset.created_by)
+        .bind(changeset.updated_at)
+        .bind(changeset.applied_at)
+        .bind(&changeset.applied_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_changeset(&self, id: &str) -> Result<ChangeSet> {
+        sqlx::query_as::<_, ChangeSetRow>(&format!(
+            "SELECT {} FROM changesets WHERE id = $1",
+            CHANGESET_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("change set {}", id)))?
+        .try_into()
+    }
+
+    async fn list_changesets(&self, status: Option<ChangeSetStatus>) -> Result<Vec<ChangeSet>> {
+        let rows = sqlx::query_as::<_, ChangeSetRow>(&format!(
+            "SELECT {} FROM changesets WHERE ($1::TEXT IS NULL OR status = $1) \
+             ORDER BY created_at DESC",
+            CHANGESET_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(ChangeSet::try_from).collect()
+    }
+}
@@ -0,0 +1,120 @@
+use config_common::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::model::{VersionRetentionConfig, VersionRetentionPolicy};
+
+/// Background job deleting versions outside the retention policy
+pub struct VersionCompactionJob {
+    pool: Arc<PgPool>,
+    config: VersionRetentionConfig,
+}
+
+impl VersionCompactionJob {
+    pub fn new(pool: Arc<PgPool>, config: VersionRetentionConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Run the job periodically until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+            loop {
+                ticker.tick().await;
+                match self.run_once().await {
+                    Ok(0) => {}
+                    Ok(deleted) => tracing::info!(deleted, "Compacted config versions"),
+                    Err(e) => tracing::error!(error = %e, "Version compaction run failed"),
+                }
+            }
+        })
+    }
+
+    /// Apply every namespace override, then the default policy to the remaining namespaces
+    pub async fn run_once(&self) -> Result<u64> {
+        let now = chrono::Utc::now().timestamp();
+        let mut deleted = 0;
+
+        for (namespace, policy) in &self.config.namespaces {
+            deleted += self
+                .compact(policy, now, NamespaceScope::Only(namespace))
+                .await?;
+        }
+
+        let overridden: Vec<String> = self.config.namespaces.keys().cloned().collect();
+        deleted += self
+            .compact(
+                &self.config.default,
+                now,
+                NamespaceScope::Except(&overridden),
+            )
+            .await?;
+
+        Ok(deleted)
+    }
+
+    async fn compact(
+        &self,
+        policy: &VersionRetentionPolicy,
+        now: i64,
+        scope: NamespaceScope<'_>,
+    ) -> Result<u64> {
+        if !policy.is_enabled() {
+            return Ok(0);
+        }
+
+        let keep_last = policy.keep_last.map(|n| n as i64);
+        let cutoff = policy
+            .keep_days
+            .map(|days| now - days as i64 * 24 * 60 * 60);
+
+        let namespace_filter = match scope {
+            NamespaceScope::Only(_) => "c.namespace = $3",
+            NamespaceScope::Except(_) => "NOT (c.namespace = ANY($3))",
+        };
+        let sql = format!(
+            r#"
+            WITH ranked AS (
+                SELECT seq, ROW_NUMBER() OVER (PARTITION BY config_id ORDER BY seq DESC) AS rn
+                FROM config_versions
+            )
+            DELETE FROM config_versions v
+            USING ranked r, configs c
+            WHERE v.seq = r.seq
+              AND c.id = v.config_id
+              AND {}
+              AND v.pinned = FALSE
+              AND v.version <> c.version
+              AND NOT EXISTS (
+                  SELECT 1 FROM config_version_tags t
+                  WHERE t.config_id = v.config_id AND t.version = v.version
+              )
+              AND ($1::BIGINT IS NULL OR r.rn > $1)
+              AND ($2::BIGINT IS NULL OR v.created_at < $2)
+            "#,
+            namespace_filter
+        );
+
+        let query = sqlx::query(&sql).bind(keep_last).bind(cutoff);
+        let query = match scope {
+            NamespaceScope::Only(namespace) => query.bind(namespace.to_string()),
+            NamespaceScope::Except(namespaces) => query.bind(namespaces.to_vec()),
+        };
+        let result = query
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Namespaces a policy applies to
+#[derive(Clone, Copy)]
+enum NamespaceScope<'a> {
+    Only(&'a str),
+    Except(&'a [String]),
+}
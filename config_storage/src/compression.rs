@@ -0,0 +1,50 @@
+use base64::Engine;
+use config_common::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// `content_encoding` of content stored as base64-encoded gzip
+pub const GZIP_ENCODING: &str = "gzip";
+
+/// Content as stored, gzipped when longer than `compress_above` bytes (0 disables compression)
+pub fn encode(content: &str, compress_above: usize) -> Result<(String, Option<&'static str>)> {
+    if compress_above == 0 || content.len() <= compress_above {
+        return Ok((content.to_string(), None));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(content.as_bytes())
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+
+    Ok((
+        base64::engine::general_purpose::STANDARD.encode(compressed),
+        Some(GZIP_ENCODING),
+    ))
+}
+
+/// Original content of a stored value
+pub fn decode(stored: String, encoding: Option<&str>) -> Result<String> {
+    match encoding {
+        None => Ok(stored),
+        Some(GZIP_ENCODING) => {
+            let compressed = base64::engine::general_purpose::STANDARD
+                .decode(stored)
+                .map_err(|e| config_common::Error::Database(format!("corrupt content: {}", e)))?;
+            let mut content = String::new();
+            GzDecoder::new(compressed.as_slice())
+                .read_to_string(&mut content)
+                .map_err(|e| config_common::Error::Database(format!("corrupt content: {}", e)))?;
+            Ok(content)
+        }
+        Some(other) => Err(config_common::Error::Database(format!(
+            "unknown content encoding: {}",
+            other
+        ))),
+    }
+}
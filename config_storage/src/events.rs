@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use config_common::{ConfigEvent, Result};
+use config_core::{EventFilter, EventOutbox, PublishedEvent};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+#[derive(sqlx::FromRow)]
+struct EventRow {
+    seq: i64,
+    event: Json<ConfigEvent>,
+}
+
+impl EventRow {
+    fn into_event(self) -> PublishedEvent {
+        PublishedEvent {
+            seq: self.seq,
+            event: self.event.0,
+        }
+    }
+}
+
+#[async_trait]
+impl EventOutbox for PgConfigStorage {
+    async fn append(&self, event: &ConfigEvent) -> Result<i64> {
+        sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO config_events (config_id, event, created_at)
+            VALUES ($1, $2, $3)
+            RETURNING seq
+            "#,
+        )
+        .bind(&event.config_id)
+        .bind(Json(event))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))
+    }
+
+    async fn read_after(&self, seq: i64, limit: i64) -> Result<Vec<PublishedEvent>> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            "SELECT seq, event FROM config_events WHERE seq > $1 ORDER BY seq LIMIT $2",
+        )
+        .bind(seq)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(EventRow::into_event).collect())
+    }
+
+    async fn list_events(&self, filter: &EventFilter, limit: i64) -> Result<Vec<PublishedEvent>> {
+        let rows = sqlx::query_as::<_, EventRow>(
+            r#"
+            SELECT seq, event FROM config_events
+            WHERE seq > $1
+              AND ($2::BIGINT IS NULL OR created_at >= $2)
+              AND ($3::TEXT IS NULL OR event->>'namespace' = $3)
+            ORDER BY seq
+            LIMIT $4
+            "#,
+        )
+        .bind(filter.after)
+        .bind(filter.since)
+        .bind(&filter.namespace)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(EventRow::into_event).collect())
+    }
+
+    async fn latest_seq(&self) -> Result<i64> {
+        sqlx::query_scalar::<_, i64>("SELECT COALESCE(MAX(seq), 0) FROM config_events")
+            .fetch_one(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))
+    }
+
+    async fn get_offset(&self, consumer: &str) -> Result<i64> {
+        let seq = sqlx::query_scalar::<_, i64>(
+            "SELECT seq FROM config_event_offsets WHERE consumer = $1",
+        )
+        .bind(consumer)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(seq.unwrap_or_default())
+    }
+
+    async fn set_offset(&self, consumer: &str, seq: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO config_event_offsets (consumer, seq, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (consumer) DO UPDATE
+            SET seq = EXCLUDED.seq, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(consumer)
+        .bind(seq)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn purge(&self, before: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM config_events WHERE created_at < $1")
+            .bind(before)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
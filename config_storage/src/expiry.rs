@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{SecretExpiry, SecretExpiryManager};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a secret expiry row
+const EXPIRY_COLUMNS: &str = "config_id, expires_at, updated_at, updated_by";
+
+#[derive(sqlx::FromRow)]
+struct ExpiryRow {
+    config_id: String,
+    expires_at: i64,
+    updated_at: i64,
+    updated_by: String,
+}
+
+impl From<ExpiryRow> for SecretExpiry {
+    fn from(row: ExpiryRow) -> Self {
+        SecretExpiry {
+            config_id: row.config_id,
+            expires_at: row.expires_at,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl SecretExpiryManager for PgConfigStorage {
+    async fn get_expiry(&self, config_id: &str) -> Result<Option<SecretExpiry>> {
+        let row = sqlx::query_as::<_, ExpiryRow>(&format!(
+            "SELECT {} FROM config_secret_expiry WHERE config_id = $1",
+            EXPIRY_COLUMNS
+        ))
+        .bind(config_id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(row.map(SecretExpiry::from))
+    }
+
+    async fn set_expiry(
+        &self,
+        config_id: &str,
+        expires_at: Option<i64>,
+        updated_by: &str,
+    ) -> Result<()> {
+        let Some(expires_at) = expires_at else {
+            sqlx::query("DELETE FROM config_secret_expiry WHERE config_id = $1")
+                .bind(config_id)
+                .execute(self.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_secret_expiry (config_id, expires_at, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (config_id) DO UPDATE
+            SET expires_at = EXCLUDED.expires_at, updated_at = EXCLUDED.updated_at,
+                updated_by = EXCLUDED.updated_by
+            "#,
+        )
+        .bind(config_id)
+        .bind(expires_at)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list_expiring(&self, before: i64) -> Result<Vec<SecretExpiry>> {
+        let rows = sqlx::query_as::<_, ExpiryRow>(&format!(
+            "SELECT {} FROM config_secret_expiry WHERE expires_at <= $1 ORDER BY expires_at",
+            EXPIRY_COLUMNS
+        ))
+        .bind(before)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(SecretExpiry::from).collect())
+    }
+}
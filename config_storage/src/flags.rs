@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::flags::{check_key, FeatureFlag, FlagManager, FlagSettings};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a flag row
+const FLAG_COLUMNS: &str = "key, namespace, description, enabled, percentage, allow_list, \
+     deny_list, created_at, created_by, updated_at, updated_by";
+
+#[derive(sqlx::FromRow)]
+struct FlagRow {
+    key: String,
+    namespace: String,
+    description: Option<String>,
+    enabled: bool,
+    percentage: Option<i16>,
+    allow_list: Vec<String>,
+    deny_list: Vec<String>,
+    created_at: i64,
+    created_by: String,
+    updated_at: i64,
+    updated_by: String,
+}
+
+impl From<FlagRow> for FeatureFlag {
+    fn from(row: FlagRow) -> Self {
+        FeatureFlag {
+            key: row.key,
+            namespace: row.namespace,
+            description: row.description,
+            settings: FlagSettings {
+                enabled: row.enabled,
+                percentage: row.percentage.map(|p| p.clamp(0, 100) as u8),
+                allow: row.allow_list,
+                deny: row.deny_list,
+            },
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl FlagManager for PgConfigStorage {
+    async fn create_flag(
+        &self,
+        key: &str,
+        namespace: &str,
+        description: Option<&str>,
+        settings: FlagSettings,
+        created_by: &str,
+    ) -> Result<FeatureFlag> {
+        check_key(key)?;
+        settings.validate()?;
+        let now = chrono::Utc::now().timestamp();
+        let flag = FeatureFlag {
+            key: key.to_string(),
+            namespace: namespace.to_string(),
+            description: description.map(String::from),
+            settings,
+            created_at: now,
+            created_by: created_by.to_string(),
+            updated_at: now,
+            updated_by: created_by.to_string(),
+        };
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO feature_flags (key, namespace, description, enabled, percentage,
+                allow_list, deny_list, created_at, created_by, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (key) DO NOTHING
+            "#,
+        )
+        .bind(&flag.key)
+        .bind(&flag.namespace)
+        .bind(&flag.description)
+        .bind(flag.settings.enabled)
+        .bind(flag.settings.percentage.map(i16::from))
+        .bind(&flag.settings.allow)
+        .bind(&flag.settings.deny)
+        .bind(flag.created_at)
+        .bind(&flag.created_by)
+        .bind(flag.updated_at)
+        .bind(&flag.updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!("flag {}", key)));
+        }
+        Ok(flag)
+    }
+
+    async fn get_flag(&self, key: &str) -> Result<FeatureFlag> {
+        sqlx::query_as::<_, FlagRow>(&format!(
+            "SELECT {} FROM feature_flags WHERE key = $1",
+            FLAG_COLUMNS
+        ))
+        .bind(key)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(FeatureFlag::from)
+        .ok_or_else(|| config_common::Error::NotFound(format!("flag {}", key)))
+    }
+
+    async fn list_flags(&self, namespace: Option<&str>) -> Result<Vec<FeatureFlag>> {
+        let rows = sqlx::query_as::<_, FlagRow>(&format!(
+            r#"
+            SELECT {} FROM feature_flags
+            WHERE ($1::TEXT IS NULL OR namespace = $1)
+            ORDER BY key
+            "#,
+            FLAG_COLUMNS
+        ))
+        .bind(namespace)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(FeatureFlag::from).collect())
+    }
+
+    async fn update_flag(
+        &self,
+        key: &str,
+        description: Option<&str>,
+        settings: FlagSettings,
+        updated_by: &str,
+    ) -> Result<FeatureFlag> {
+        settings.validate()?;
+        sqlx::query_as::<_, FlagRow>(&format!(
+            r#"
+            UPDATE feature_flags
+            SET description = $2, enabled = $3, percentage = $4, allow_list = $5,
+                deny_list = $6, updated_at = $7, updated_by = $8
+            WHERE key = $1
+            RETURNING {}
+            "#,
+            FLAG_COLUMNS
+        ))
+        .bind(key)
+        .bind(description)
+        .bind(settings.enabled)
+        .bind(settings.percentage.map(i16::from))
+        .bind(&settings.allow)
+        .bind(&settings.deny)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(FeatureFlag::from)
+        .ok_or_else(|| config_common::Error::NotFound(format!("flag {}", key)))
+    }
+
+    async fn delete_flag(&self, key: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE key = $1")
+            .bind(key)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
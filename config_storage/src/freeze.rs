@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::freeze::{week_minute, FreezeWindow, FreezeWindowManager};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a freeze window row
+const FREEZE_WINDOW_COLUMNS: &str = "id, environment, starts, ends, reason, created_at, created_by";
+
+#[derive(sqlx::FromRow)]
+struct FreezeWindowRow {
+    id: String,
+    environment: String,
+    starts: String,
+    ends: String,
+    reason: Option<String>,
+    created_at: i64,
+    created_by: String,
+}
+
+impl From<FreezeWindowRow> for FreezeWindow {
+    fn from(row: FreezeWindowRow) -> Self {
+        FreezeWindow {
+            id: row.id,
+            environment: row.environment,
+            starts: row.starts,
+            ends: row.ends,
+            reason: row.reason,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+#[async_trait]
+impl FreezeWindowManager for PgConfigStorage {
+    async fn create_freeze_window(
+        &self,
+        environment: &str,
+        starts: &str,
+        ends: &str,
+        reason: Option<&str>,
+        created_by: &str,
+    ) -> Result<FreezeWindow> {
+        if week_minute(starts)? == week_minute(ends)? {
+            return Err(config_common::Error::Validation(
+                "a freeze window must end at a different time than it starts".to_string(),
+            ));
+        }
+        let window = FreezeWindow {
+            id: uuid::Uuid::new_v4().to_string(),
+            environment: environment.to_string(),
+            starts: starts.trim().to_lowercase(),
+            ends: ends.trim().to_lowercase(),
+            reason: reason.map(String::from),
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO freeze_windows (id, environment, starts, ends, reason, created_at,
+                created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&window.id)
+        .bind(&window.environment)
+        .bind(&window.starts)
+        .bind(&window.ends)
+        .bind(&window.reason)
+        .bind(window.created_at)
+        .bind(&window.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(window)
+    }
+
+    async fn list_freeze_windows(&self, environment: Option<&str>) -> Result<Vec<FreezeWindow>> {
+        let rows = sqlx::query_as::<_, FreezeWindowRow>(&format!(
+            r#"
+            SELECT {} FROM freeze_windows
+            WHERE ($1::TEXT IS NULL OR environment = $1)
+            ORDER BY environment, created_at
+            "#,
+            FREEZE_WINDOW_COLUMNS
+        ))
+        .bind(environment)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(FreezeWindow::from).collect())
+    }
+
+    async fn delete_freeze_window(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM freeze_windows WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{ValidationHook, ValidationHookManager};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a validation hook row
+const HOOK_COLUMNS: &str = "id, namespace, url, timeout_ms, fail_open, created_at, created_by";
+
+#[derive(sqlx::FromRow)]
+struct HookRow {
+    id: String,
+    namespace: String,
+    url: String,
+    timeout_ms: i64,
+    fail_open: bool,
+    created_at: i64,
+    created_by: String,
+}
+
+impl From<HookRow> for ValidationHook {
+    fn from(row: HookRow) -> Self {
+        ValidationHook {
+            id: row.id,
+            namespace: row.namespace,
+            url: row.url,
+            timeout_ms: row.timeout_ms as u64,
+            fail_open: row.fail_open,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+#[async_trait]
+impl ValidationHookManager for PgConfigStorage {
+    async fn create_hook(
+        &self,
+        namespace: &str,
+        url: &str,
+        timeout_ms: u64,
+        fail_open: bool,
+        created_by: &str,
+    ) -> Result<ValidationHook> {
+        let hook = ValidationHook {
+            id: uuid::Uuid::new_v4().to_string(),
+            namespace: namespace.to_string(),
+            url: url.to_string(),
+            timeout_ms,
+            fail_open,
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_validation_hooks (id, namespace, url, timeout_ms, fail_open,
+                created_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&hook.id)
+        .bind(&hook.namespace)
+        .bind(&hook.url)
+        .bind(hook.timeout_ms as i64)
+        .bind(hook.fail_open)
+        .bind(hook.created_at)
+        .bind(&hook.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(hook)
+    }
+
+    async fn list_hooks(&self, namespace: Option<&str>) -> Result<Vec<ValidationHook>> {
+        let rows = sqlx::query_as::<_, HookRow>(&format!(
+            "SELECT {} FROM config_validation_hooks \
+             WHERE ($1::TEXT IS NULL OR namespace = $1) ORDER BY namespace, created_at",
+            HOOK_COLUMNS
+        ))
+        .bind(namespace)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(ValidationHook::from).collect())
+    }
+
+    async fn delete_hook(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM config_validation_hooks WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
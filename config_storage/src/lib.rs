@@ -1,5 +1,42 @@
 pub mod model;
-pub use model::{CacheConfig, DatabaseConfig};
-pub mod store;
+pub use model::{
+    CacheConfig, ContentLimits, DatabaseConfig, VersionRetentionConfig, VersionRetentionPolicy,
+};
+pub mod approvals;
+pub mod backup;
+pub mod blobs;
 pub mod cache;
+pub mod changeset;
+pub mod compaction;
+pub mod compression;
+pub mod events;
+pub mod expiry;
+pub mod flags;
+pub mod freeze;
+pub mod hooks;
+pub mod migrate;
+pub mod namespaces;
+pub mod notifications;
+pub mod postgres;
+pub mod promotions;
+pub mod recipients;
+pub mod releases;
+pub mod replicas;
+pub mod restore;
+pub mod rules;
+pub mod schema;
+pub mod secrets;
+pub mod shares;
+pub mod stats;
+pub mod store;
+pub mod teams;
+pub mod tenants;
+pub mod verify;
 
+pub use backup::{BackupConfig, PgBackupService, S3BackupConfig};
+pub use blobs::{BlobConfig, BlobStore, S3BlobConfig};
+pub use cache::CacheInvalidator;
+pub use compaction::VersionCompactionJob;
+pub use postgres::PgConfigStorage;
+pub use store::{ApprovalStorage, ChangeSetStorage, ConfigStorage};
+pub use tenants::{TenancyConfig, TenantStorage};
@@ -0,0 +1,99 @@
+use config_common::Result;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::{Executor, PgPool};
+use std::collections::HashSet;
+
+use crate::model::DatabaseConfig;
+
+/// Table in which applied migrations are recorded, in each migrated schema
+pub const MIGRATIONS_TABLE: &str = "_sqlx_migrations";
+
+/// Migrations of the shared schema, which holds every table of the server
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/shared");
+
+/// Migrations of the configuration tables in each tenant schema
+pub static TENANT_MIGRATOR: Migrator = sqlx::migrate!("./migrations/tenant");
+
+/// Apply pending migrations to the shared schema, then to every tenant schema
+pub async fn run(database: &DatabaseConfig, pool: &PgPool) -> Result<()> {
+    apply(&MIGRATOR, pool).await?;
+    for schema in tenant_schemas(pool).await? {
+        let tenant_pool = database.create_migration_pool(&schema).await?;
+        let result = run_tenant(&tenant_pool).await;
+        tenant_pool.close().await;
+        result?;
+    }
+    Ok(())
+}
+
+/// Apply pending migrations through a pool whose connections resolve tables in a tenant schema
+pub async fn run_tenant(pool: &PgPool) -> Result<()> {
+    apply(&TENANT_MIGRATOR, pool).await
+}
+
+/// Apply migrations on a connection taken out of the pool and lifted from the statement
+/// timeout, as building an index on a large table may outlast it. `run_direct` keeps the
+/// future `Send`, which provisioning a tenant from an `async_trait` method needs.
+async fn apply(migrator: &Migrator, pool: &PgPool) -> Result<()> {
+    let mut conn = pool.acquire().await?.detach();
+    conn.execute("SET statement_timeout = 0").await?;
+    migrator.run_direct(&mut conn).await.map_err(migrate_error)
+}
+
+/// Migrations not yet applied, as `<version> <description>`, prefixed by the schema for
+/// tenant schemas
+pub async fn pending(database: &DatabaseConfig, pool: &PgPool) -> Result<Vec<String>> {
+    let mut pending = pending_in(&MIGRATOR, pool).await?;
+    if !pending.is_empty() {
+        // Tenant schemas are listed by a table the pending migrations may create
+        return Ok(pending);
+    }
+
+    for schema in tenant_schemas(pool).await? {
+        let tenant_pool = database.create_migration_pool(&schema).await?;
+        let result = pending_in(&TENANT_MIGRATOR, &tenant_pool).await;
+        tenant_pool.close().await;
+        pending.extend(
+            result?
+                .into_iter()
+                .map(|migration| format!("{}: {}", schema, migration)),
+        );
+    }
+    Ok(pending)
+}
+
+async fn pending_in(migrator: &Migrator, pool: &PgPool) -> Result<Vec<String>> {
+    let recorded: Option<String> = sqlx::query_scalar("SELECT to_regclass($1)::TEXT")
+        .bind(MIGRATIONS_TABLE)
+        .fetch_one(pool)
+        .await?;
+    let applied: HashSet<i64> = match recorded {
+        Some(_) => sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT version FROM {} WHERE success",
+            MIGRATIONS_TABLE
+        ))
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect(),
+        None => HashSet::new(),
+    };
+
+    Ok(migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .map(|m| format!("{} {}", m.version, m.description))
+        .collect())
+}
+
+async fn tenant_schemas(pool: &PgPool) -> Result<Vec<String>> {
+    Ok(
+        sqlx::query_scalar("SELECT schema_name FROM tenants ORDER BY name")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+fn migrate_error(e: MigrateError) -> config_common::Error {
+    config_common::Error::Database(format!("migration failed: {}", e))
+}
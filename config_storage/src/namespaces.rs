@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{Namespace, NamespaceManager, NamespaceStatus};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a namespace row
+const NAMESPACE_COLUMNS: &str =
+    "name, parent, description, status, created_at, created_by, updated_at, updated_by";
+
+#[derive(sqlx::FromRow)]
+struct NamespaceRow {
+    name: String,
+    parent: Option<String>,
+    description: Option<String>,
+    status: String,
+    created_at: i64,
+    created_by: String,
+    updated_at: i64,
+    updated_by: String,
+}
+
+impl TryFrom<NamespaceRow> for Namespace {
+    type Error = config_common::Error;
+
+    fn try_from(row: NamespaceRow) -> Result<Self> {
+        Ok(Namespace {
+            name: row.name,
+            parent: row.parent,
+            description: row.description,
+            status: row.status.parse()?,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        })
+    }
+}
+
+impl PgConfigStorage {
+    async fn find_namespace(&self, name: &str) -> Result<Option<Namespace>> {
+        let row = sqlx::query_as::<_, NamespaceRow>(&format!(
+            "SELECT {} FROM namespaces WHERE name = $1",
+            NAMESPACE_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        row.map(Namespace::try_from).transpose()
+    }
+
+    /// Check that a parent exists and that `name` is not among its ancestors
+    async fn check_parent(&self, name: &str, parent: &str) -> Result<()> {
+        let mut current = Some(parent.to_string());
+        while let Some(ancestor) = current {
+            if ancestor == name {
+                return Err(config_common::Error::Validation(format!(
+                    "namespace {} can't be below itself",
+                    name
+                )));
+            }
+            current = self
+                .find_namespace(&ancestor)
+                .await?
+                .ok_or_else(|| {
+                    config_common::Error::Validation(format!(
+                        "parent namespace {} does not exist",
+                        ancestor
+                    ))
+                })?
+                .parent;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NamespaceManager for PgConfigStorage {
+    async fn create_namespace(
+        &self,
+        name: &str,
+        parent: Option<&str>,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<Namespace> {
+        if let Some(parent) = parent {
+            self.check_parent(name, parent).await?;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let namespace = Namespace {
+            name: name.to_string(),
+            parent: parent.map(String::from),
+            description: description.map(String::from),
+            status: NamespaceStatus::Active,
+            created_at: now,
+            created_by: created_by.to_string(),
+            updated_at: now,
+            updated_by: created_by.to_string(),
+        };
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO namespaces (name, parent, description, status, created_at, created_by,
+                updated_at, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (name) DO NOTHING
+            "#,
+        )
+        .bind(&namespace.name)
+        .bind(&namespace.parent)
+        .bind(&namespace.description)
+        .bind(namespace.status.as_str())
+        .bind(namespace.created_at)
+        .bind(&namespace.created_by)
+        .bind(namespace.updated_at)
+        .bind(&namespace.updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "namespace {}",
+                name
+            )));
+        }
+        Ok(namespace)
+    }
+
+    async fn get_namespace(&self, name: &str) -> Result<Namespace> {
+        self.find_namespace(name)
+            .await?
+            .ok_or_else(|| config_common::Error::NotFound(format!("namespace {}", name)))
+    }
+
+    async fn list_namespaces(&self, include_archived: bool) -> Result<Vec<Namespace>> {
+        let rows = sqlx::query_as::<_, NamespaceRow>(&format!(
+            "SELECT {} FROM namespaces WHERE $1 OR status <> 'archived' ORDER BY name",
+            NAMESPACE_COLUMNS
+        ))
+        .bind(include_archived)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(Namespace::try_from).collect()
+    }
+
+    async fn update_namespace(
+        &self,
+        name: &str,
+        parent: Option<&str>,
+        description: Option<&str>,
+        updated_by: &str,
+    ) -> Result<Namespace> {
+        if let Some(parent) = parent {
+            self.check_parent(name, parent).await?;
+        }
+        let row = sqlx::query_as::<_, NamespaceRow>(&format!(
+            r#"
+            UPDATE namespaces
+            SET parent = $2, description = $3, updated_at = $4, updated_by = $5
+            WHERE name = $1
+            RETURNING {}
+            "#,
+            NAMESPACE_COLUMNS
+        ))
+        .bind(name)
+        .bind(parent)
+        .bind(description)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        row.map(Namespace::try_from)
+            .transpose()?
+            .ok_or_else(|| config_common::Error::NotFound(format!("namespace {}", name)))
+    }
+
+    async fn set_namespace_status(
+        &self,
+        name: &str,
+        status: NamespaceStatus,
+        updated_by: &str,
+    ) -> Result<Namespace> {
+        let row = sqlx::query_as::<_, NamespaceRow>(&format!(
+            r#"
+            UPDATE namespaces
+            SET status = $2, updated_at = $3, updated_by = $4
+            WHERE name = $1
+            RETURNING {}
+            "#,
+            NAMESPACE_COLUMNS
+        ))
+        .bind(name)
+        .bind(status.as_str())
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        row.map(Namespace::try_from)
+            .transpose()?
+            .ok_or_else(|| config_common::Error::NotFound(format!("namespace {}", name)))
+    }
+
+    async fn delete_namespace(&self, name: &str) -> Result<bool> {
+        let (children, configs): (bool, bool) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM namespaces WHERE parent = $1),
+                   EXISTS (SELECT 1 FROM configs WHERE namespace = $1)
+            "#,
+        )
+        .bind(name)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if children {
+            return Err(config_common::Error::Validation(format!(
+                "namespace {} has child namespaces",
+                name
+            )));
+        }
+        if configs {
+            return Err(config_common::Error::Validation(format!(
+                "namespace {} still has configs",
+                name
+            )));
+        }
+
+        let result = sqlx::query("DELETE FROM namespaces WHERE name = $1")
+            .bind(name)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
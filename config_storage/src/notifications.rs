@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{
+    NotificationChannel, NotificationFilter, NotificationManager, NotificationSubscription,
+};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a notification subscription row
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, name, channel, filter, template, debounce_secs, created_at, created_by";
+
+#[derive(sqlx::FromRow)]
+struct SubscriptionRow {
+    id: String,
+    name: String,
+    channel: Json<NotificationChannel>,
+    filter: Json<NotificationFilter>,
+    template: Option<String>,
+    debounce_secs: i64,
+    created_at: i64,
+    created_by: String,
+}
+
+impl From<SubscriptionRow> for NotificationSubscription {
+    fn from(row: SubscriptionRow) -> Self {
+        NotificationSubscription {
+            id: row.id,
+            name: row.name,
+            channel: row.channel.0,
+            filter: row.filter.0,
+            template: row.template,
+            debounce_secs: row.debounce_secs as u64,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationManager for PgConfigStorage {
+    async fn create_subscription(
+        &self,
+        name: &str,
+        channel: NotificationChannel,
+        filter: NotificationFilter,
+        template: Option<String>,
+        debounce_secs: u64,
+        created_by: &str,
+    ) -> Result<NotificationSubscription> {
+        let subscription = NotificationSubscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            channel,
+            filter,
+            template,
+            debounce_secs,
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_subscriptions (id, name, channel, filter, template,
+                debounce_secs, created_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(&subscription.id)
+        .bind(&subscription.name)
+        .bind(Json(&subscription.channel))
+        .bind(Json(&subscription.filter))
+        .bind(&subscription.template)
+        .bind(subscription.debounce_secs as i64)
+        .bind(subscription.created_at)
+        .bind(&subscription.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(subscription)
+    }
+
+    async fn list_subscriptions(&self) -> Result<Vec<NotificationSubscription>> {
+        let rows = sqlx::query_as::<_, SubscriptionRow>(&format!(
+            "SELECT {} FROM notification_subscriptions ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(NotificationSubscription::from)
+            .collect())
+    }
+
+    async fn delete_subscription(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM notification_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,789 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ConfigFormat, ConfigMeta, Result};
+use config_core::{ConfigFilter, ConfigMetaStream, ConfigSnapshot, ConfigVersion, INITIAL_VERSION};
+use futures_util::stream::{self, StreamExt};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::blobs::{blob_key, BlobStore, BLOB_ENCODING};
+use crate::compression;
+use crate::replicas::ReplicaSet;
+use crate::store::ConfigStorage;
+
+/// Columns selected for a configuration row
+const CONFIG_COLUMNS: &str = "id, name, namespace, department, application, environment, \
+     version, description, owners, labels, created_at, updated_at, created_by, updated_by";
+
+/// Rows buffered between a streaming cursor and its consumer
+const STREAM_BUFFER: usize = 256;
+
+/// Columns selected for a version row of `config_versions v`
+const VERSION_COLUMNS: &str = "v.version, v.created_at, v.created_by, v.description, \
+     v.change_reason, v.pinned, \
+     ARRAY(SELECT t.tag FROM config_version_tags t \
+           WHERE t.config_id = v.config_id AND t.version = v.version ORDER BY t.tag) AS tags";
+
+/// Columns of `configs c` selected next to version columns in a snapshot
+const SNAPSHOT_CONFIG_COLUMNS: &str = "c.id, c.name, c.namespace, c.department, c.application, \
+     c.environment, c.owners, c.labels, c.created_at AS config_created_at, \
+     c.created_by AS config_created_by";
+
+/// Content columns of `config_versions v`
+const VERSION_CONTENT_COLUMNS: &str = "v.format, v.content, v.content_encoding, v.is_encrypted";
+
+#[derive(sqlx::FromRow)]
+struct ConfigRow {
+    id: String,
+    name: String,
+    namespace: String,
+    department: String,
+    application: String,
+    environment: String,
+    version: String,
+    description: Option<String>,
+    owners: Vec<String>,
+    labels: Vec<String>,
+    created_at: i64,
+    updated_at: i64,
+    created_by: String,
+    updated_by: String,
+}
+
+/// Config row with the number of configs matching the query
+#[derive(sqlx::FromRow)]
+struct CountedConfigRow {
+    #[sqlx(flatten)]
+    config: ConfigRow,
+    total: i64,
+}
+
+impl From<ConfigRow> for ConfigMeta {
+    fn from(row: ConfigRow) -> Self {
+        ConfigMeta {
+            id: row.id,
+            name: row.name,
+            namespace: row.namespace,
+            department: row.department,
+            application: row.application,
+            environment: row.environment,
+            version: row.version,
+            description: row.description,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            created_by: row.created_by,
+            updated_by: row.updated_by,
+            owners: row.owners,
+            labels: row.labels,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ContentRow {
+    format: String,
+    content: String,
+    content_encoding: Option<String>,
+    is_encrypted: bool,
+}
+
+impl TryFrom<ContentRow> for ConfigContent {
+    type Error = config_common::Error;
+
+    fn try_from(row: ContentRow) -> Result<Self> {
+        Ok(ConfigContent {
+            format: row.format.parse::<ConfigFormat>()?,
+            content: compression::decode(row.content, row.content_encoding.as_deref())?,
+            is_encrypted: row.is_encrypted,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct VersionInfoRow {
+    version: String,
+    created_at: i64,
+    created_by: String,
+    description: Option<String>,
+    change_reason: Option<String>,
+    pinned: bool,
+    tags: Vec<String>,
+}
+
+impl From<VersionInfoRow> for ConfigVersion {
+    fn from(row: VersionInfoRow) -> Self {
+        ConfigVersion {
+            version: row.version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            description: row.description,
+            change_reason: row.change_reason,
+            tags: row.tags,
+            pinned: row.pinned,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct VersionRow {
+    #[sqlx(flatten)]
+    info: VersionInfoRow,
+    #[sqlx(flatten)]
+    content: ContentRow,
+}
+
+impl VersionRow {
+    async fn into_version(
+        self,
+        storage: &PgConfigStorage,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        Ok((self.info.into(), storage.load_content(self.content).await?))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotRow {
+    id: String,
+    name: String,
+    namespace: String,
+    department: String,
+    application: String,
+    environment: String,
+    owners: Vec<String>,
+    labels: Vec<String>,
+    config_created_at: i64,
+    config_created_by: String,
+    #[sqlx(flatten)]
+    version: VersionRow,
+}
+
+impl SnapshotRow {
+    async fn into_snapshot(self, storage: &PgConfigStorage) -> Result<ConfigSnapshot> {
+        let (version, content) = self.version.into_version(storage).await?;
+        let meta = ConfigMeta {
+            id: self.id,
+            name: self.name,
+            namespace: self.namespace,
+            department: self.department,
+            application: self.application,
+            environment: self.environment,
+            version: version.version.clone(),
+            description: version.description.clone(),
+            created_at: self.config_created_at,
+            updated_at: version.created_at,
+            created_by: self.config_created_by,
+            updated_by: version.created_by.clone(),
+            owners: self.owners,
+            labels: self.labels,
+        };
+        Ok(ConfigSnapshot {
+            meta,
+            version,
+            content,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ConfigContentRow {
+    #[sqlx(flatten)]
+    meta: ConfigRow,
+    #[sqlx(flatten)]
+    content: ContentRow,
+}
+
+/// PostgreSQL storage keeping the current configurations and every version
+pub struct PgConfigStorage {
+    pool: Arc<PgPool>,
+    compress_above: usize,
+    blobs: Option<Arc<dyn BlobStore>>,
+    blob_above: usize,
+    replicas: Option<Arc<ReplicaSet>>,
+}
+
+impl PgConfigStorage {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self {
+            pool,
+            compress_above: 0,
+            blobs: None,
+            blob_above: 0,
+            replicas: None,
+        }
+    }
+
+    /// Gzip content longer than `bytes` at rest; 0 stores everything as is
+    pub fn with_compression(mut self, bytes: usize) -> Self {
+        self.compress_above = bytes;
+        self
+    }
+
+    pub(crate) fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub(crate) fn compress_above(&self) -> usize {
+        self.compress_above
+    }
+
+    /// Keep plaintext content longer than `bytes` in a blob store, with only its key in
+    /// Postgres
+    pub fn with_blobs(mut self, blobs: Arc<dyn BlobStore>, bytes: usize) -> Self {
+        self.blobs = Some(blobs);
+        self.blob_above = bytes;
+        self
+    }
+
+    pub(crate) fn blobs(&self) -> Option<(Arc<dyn BlobStore>, usize)> {
+        self.blobs.clone().map(|blobs| (blobs, self.blob_above))
+    }
+
+    /// Read configs, versions and history from replicas keeping up with the primary
+    pub fn with_replicas(mut self, replicas: Arc<ReplicaSet>) -> Self {
+        self.replicas = Some(replicas);
+        self
+    }
+
+    pub(crate) fn replicas(&self) -> Option<&Arc<ReplicaSet>> {
+        self.replicas.as_ref()
+    }
+
+    /// Pool for reads that may be slightly stale
+    fn read_pool(&self) -> &PgPool {
+        self.replicas
+            .as_ref()
+            .and_then(|replicas| replicas.pick())
+            .unwrap_or(&self.pool)
+    }
+
+    async fn fetch_config(&self, pool: &PgPool, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        let row = sqlx::query_as::<_, ConfigContentRow>(&format!(
+            "SELECT {}, format, content, content_encoding, is_encrypted FROM configs WHERE id = $1",
+            CONFIG_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("config {}", id)))?;
+
+        Ok((row.meta.into(), self.load_content(row.content).await?))
+    }
+
+    /// Content as stored: the key of a blob for large plaintext when a blob store is
+    /// configured, otherwise the content, gzipped above the compression threshold
+    async fn store_content(
+        &self,
+        content: &ConfigContent,
+    ) -> Result<(String, Option<&'static str>)> {
+        if let Some(blobs) = &self.blobs {
+            if content.content.len() > self.blob_above
+                && !content.is_encrypted
+                && !config_core::secrets::has_secret_fields(content)
+            {
+                let key = blob_key(&content.content);
+                blobs.put(&key, content.content.as_bytes()).await?;
+                return Ok((key, Some(BLOB_ENCODING)));
+            }
+        }
+        compression::encode(&content.content, self.compress_above)
+    }
+
+    /// Content of a row, fetched from the blob store when it is kept there
+    async fn load_content(&self, row: ContentRow) -> Result<ConfigContent> {
+        if row.content_encoding.as_deref() != Some(BLOB_ENCODING) {
+            return row.try_into();
+        }
+        let blobs = self.blobs.as_ref().ok_or_else(|| {
+            config_common::Error::Config(
+                "content is kept in a blob store, which is not configured".to_string(),
+            )
+        })?;
+        let data = blobs.get(&row.content).await?;
+        Ok(ConfigContent {
+            format: row.format.parse::<ConfigFormat>()?,
+            content: String::from_utf8(data).map_err(|e| {
+                config_common::Error::Database(format!("corrupt blob {}: {}", row.content, e))
+            })?,
+            is_encrypted: row.is_encrypted,
+        })
+    }
+}
+
+#[async_trait]
+impl ConfigStorage for PgConfigStorage {
+    async fn get_config(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        self.fetch_config(self.read_pool(), id).await
+    }
+
+    async fn get_config_linearizable(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        self.fetch_config(&self.pool, id).await
+    }
+
+    async fn create_config(
+        &self,
+        mut meta: ConfigMeta,
+        content: ConfigContent,
+    ) -> Result<ConfigMeta> {
+        meta.version = INITIAL_VERSION.to_string();
+        let (stored, encoding) = self.store_content(&content).await?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO configs (id, name, namespace, department, application, environment,
+                version, description, owners, labels, format, content, content_encoding,
+                is_encrypted, created_at, updated_at, created_by, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                $18)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&meta.id)
+        .bind(&meta.name)
+        .bind(&meta.namespace)
+        .bind(&meta.department)
+        .bind(&meta.application)
+        .bind(&meta.environment)
+        .bind(&meta.version)
+        .bind(&meta.description)
+        .bind(&meta.owners)
+        .bind(&meta.labels)
+        .bind(content.format.as_str())
+        .bind(&stored)
+        .bind(encoding)
+        .bind(content.is_encrypted)
+        .bind(meta.created_at)
+        .bind(meta.updated_at)
+        .bind(&meta.created_by)
+        .bind(&meta.updated_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "config {}/{}/{}/{}",
+                meta.namespace, meta.application, meta.environment, meta.name
+            )));
+        }
+
+        let version = version_of(&meta, meta.created_at, &meta.created_by);
+        insert_version(&mut *tx, &meta.id, &version, &stored, encoding, &content).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(meta)
+    }
+
+    async fn update_config(
+        &self,
+        mut meta: ConfigMeta,
+        content: ConfigContent,
+        change_reason: Option<&str>,
+    ) -> Result<ConfigMeta> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        // Lock the row so concurrent updates can't assign the same version
+        let current: String =
+            sqlx::query_scalar("SELECT version FROM configs WHERE id = $1 FOR UPDATE")
+                .bind(&meta.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?
+                .ok_or_else(|| config_common::Error::NotFound(format!("config {}", meta.id)))?;
+        meta.version = config_core::next_version(&current)?;
+        let (stored, encoding) = self.store_content(&content).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE configs
+            SET version = $2, description = $3, owners = $4, labels = $5, format = $6,
+                content = $7, content_encoding = $8, is_encrypted = $9, updated_at = $10,
+                updated_by = $11
+            WHERE id = $1
+            "#,
+        )
+        .bind(&meta.id)
+        .bind(&meta.version)
+        .bind(&meta.description)
+        .bind(&meta.owners)
+        .bind(&meta.labels)
+        .bind(content.format.as_str())
+        .bind(&stored)
+        .bind(encoding)
+        .bind(content.is_encrypted)
+        .bind(meta.updated_at)
+        .bind(&meta.updated_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::NotFound(format!(
+                "config {}",
+                meta.id
+            )));
+        }
+
+        let mut version = version_of(&meta, meta.updated_at, &meta.updated_by);
+        version.change_reason = change_reason.map(String::from);
+        insert_version(&mut *tx, &meta.id, &version, &stored, encoding, &content).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(meta)
+    }
+
+    async fn delete_config(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM configs WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_configs(
+        &self,
+        filter: ConfigFilter,
+        page_size: i32,
+        page_number: i32,
+    ) -> Result<(Vec<ConfigMeta>, i32)> {
+        let pool = self.read_pool();
+        let offset = ((page_number.max(1) - 1) * page_size) as i64;
+        let mut query = QueryBuilder::new(format!(
+            "SELECT {}, COUNT(*) OVER() AS total FROM configs",
+            CONFIG_COLUMNS
+        ));
+        push_filter(&mut query, &filter);
+        query
+            .push(" ORDER BY namespace, application, environment, name LIMIT ")
+            .push_bind(page_size as i64)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let rows = query
+            .build_query_as::<CountedConfigRow>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let total = match rows.first() {
+            Some(row) => row.total,
+            None if offset == 0 => 0,
+            // Past the last page, where no row carries the total
+            None => {
+                let mut count = QueryBuilder::new("SELECT COUNT(*) FROM configs");
+                push_filter(&mut count, &filter);
+                count
+                    .build_query_scalar::<i64>()
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| config_common::Error::Database(e.to_string()))?
+            }
+        };
+
+        Ok((
+            rows.into_iter()
+                .map(|row| ConfigMeta::from(row.config))
+                .collect(),
+            total as i32,
+        ))
+    }
+
+    async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream> {
+        let pool = self.read_pool().clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        // The cursor lives in its own task; the bounded channel applies backpressure
+        tokio::spawn(async move {
+            let mut query = QueryBuilder::new(format!("SELECT {} FROM configs", CONFIG_COLUMNS));
+            push_filter(&mut query, &filter);
+            query.push(" ORDER BY namespace, application, environment, name");
+            let mut rows = query.build_query_as::<ConfigRow>().fetch(&pool);
+            while let Some(row) = rows.next().await {
+                let meta = row
+                    .map(ConfigMeta::from)
+                    .map_err(|e| config_common::Error::Database(e.to_string()));
+                let failed = meta.is_err();
+                if tx.send(meta).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|meta| (meta, rx))
+        })
+        .boxed())
+    }
+
+    async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>> {
+        let rows = sqlx::query_as::<_, VersionInfoRow>(&format!(
+            "SELECT {} FROM config_versions v WHERE v.config_id = $1 \
+             ORDER BY v.created_at DESC, v.seq DESC",
+            VERSION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(ConfigVersion::from).collect())
+    }
+
+    async fn get_version(
+        &self,
+        config_id: &str,
+        version: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        let row = sqlx::query_as::<_, VersionRow>(&format!(
+            "SELECT {}, {} FROM config_versions v WHERE v.config_id = $1 AND v.version = $2",
+            VERSION_COLUMNS, VERSION_CONTENT_COLUMNS
+        ))
+        .bind(config_id)
+        .bind(version)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| {
+            config_common::Error::NotFound(format!("version {} of config {}", version, config_id))
+        })?;
+
+        row.into_version(self).await
+    }
+
+    async fn get_version_at(&self, config_id: &str, at: i64) -> Result<ConfigSnapshot> {
+        let row = sqlx::query_as::<_, SnapshotRow>(&format!(
+            "SELECT {}, {}, {} FROM config_versions v JOIN configs c ON c.id = v.config_id \
+             WHERE v.config_id = $1 AND v.created_at <= $2 \
+             ORDER BY v.created_at DESC, v.seq DESC LIMIT 1",
+            SNAPSHOT_CONFIG_COLUMNS, VERSION_COLUMNS, VERSION_CONTENT_COLUMNS
+        ))
+        .bind(config_id)
+        .bind(at)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("config {} at {}", config_id, at)))?;
+
+        row.into_snapshot(self).await
+    }
+
+    async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>> {
+        let rows = sqlx::query_as::<_, SnapshotRow>(&format!(
+            "SELECT DISTINCT ON (v.config_id) {}, {}, {} \
+             FROM config_versions v JOIN configs c ON c.id = v.config_id \
+             WHERE c.namespace = $1 AND v.created_at <= $2 \
+             ORDER BY v.config_id, v.created_at DESC, v.seq DESC",
+            SNAPSHOT_CONFIG_COLUMNS, VERSION_COLUMNS, VERSION_CONTENT_COLUMNS
+        ))
+        .bind(namespace)
+        .bind(at)
+        .fetch_all(self.read_pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let mut snapshots = Vec::with_capacity(rows.len());
+        for row in rows {
+            snapshots.push(row.into_snapshot(self).await?);
+        }
+        Ok(snapshots)
+    }
+
+    async fn tag_version(
+        &self,
+        config_id: &str,
+        version: &str,
+        tag: &str,
+        created_by: &str,
+        created_at: i64,
+    ) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO config_version_tags (config_id, tag, version, created_by, created_at)
+            SELECT config_id, $2, version, $4, $5
+            FROM config_versions
+            WHERE config_id = $1 AND version = $3
+            ON CONFLICT (config_id, tag) DO UPDATE
+            SET version = EXCLUDED.version, created_by = EXCLUDED.created_by,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(config_id)
+        .bind(tag)
+        .bind(version)
+        .bind(created_by)
+        .bind(created_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::NotFound(format!(
+                "version {} of config {}",
+                version, config_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn set_version_pinned(&self, config_id: &str, version: &str, pinned: bool) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE config_versions SET pinned = $3 WHERE config_id = $1 AND version = $2",
+        )
+        .bind(config_id)
+        .bind(version)
+        .bind(pinned)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::NotFound(format!(
+                "version {} of config {}",
+                version, config_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete_tag(&self, config_id: &str, tag: &str) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM config_version_tags WHERE config_id = $1 AND tag = $2")
+                .bind(config_id)
+                .bind(tag)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_tagged_version(
+        &self,
+        config_id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        let row = sqlx::query_as::<_, VersionRow>(&format!(
+            "SELECT {}, {} FROM config_versions v \
+             JOIN config_version_tags t ON t.config_id = v.config_id AND t.version = v.version \
+             WHERE t.config_id = $1 AND t.tag = $2",
+            VERSION_COLUMNS, VERSION_CONTENT_COLUMNS
+        ))
+        .bind(config_id)
+        .bind(tag)
+        .fetch_optional(self.read_pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| {
+            config_common::Error::NotFound(format!("tag {} of config {}", tag, config_id))
+        })?;
+
+        row.into_version(self).await
+    }
+
+    async fn create_version(
+        &self,
+        config_id: &str,
+        version: ConfigVersion,
+        content: ConfigContent,
+    ) -> Result<()> {
+        let (stored, encoding) = self.store_content(&content).await?;
+        insert_version(
+            &*self.pool,
+            config_id,
+            &version,
+            &stored,
+            encoding,
+            &content,
+        )
+        .await
+    }
+}
+
+/// Conditions of a config listing on the filter fields that are set. Each combination of
+/// fields yields the same SQL, which each connection prepares once; unlike conditions that
+/// skip NULL parameters, its plan can use the indexes of the fields it names.
+fn push_filter<'a>(query: &mut QueryBuilder<'a, Postgres>, filter: &'a ConfigFilter) {
+    query.push(" WHERE TRUE");
+    if let Some(namespace) = &filter.namespace {
+        query.push(" AND namespace = ").push_bind(namespace);
+    }
+    if let Some(department) = &filter.department {
+        query.push(" AND department = ").push_bind(department);
+    }
+    if let Some(application) = &filter.application {
+        query.push(" AND application = ").push_bind(application);
+    }
+    if let Some(environment) = &filter.environment {
+        query.push(" AND environment = ").push_bind(environment);
+    }
+    if !filter.exclude_namespaces.is_empty() {
+        query
+            .push(" AND namespace <> ALL(")
+            .push_bind(&filter.exclude_namespaces)
+            .push(")");
+    }
+}
+
+/// Insert a version row, inside a transaction or directly on the pool
+async fn insert_version<'e, E>(
+    executor: E,
+    config_id: &str,
+    version: &ConfigVersion,
+    stored: &str,
+    encoding: Option<&str>,
+    content: &ConfigContent,
+) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO config_versions (config_id, version, description, change_reason, format,
+            content, content_encoding, is_encrypted, created_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(config_id)
+    .bind(&version.version)
+    .bind(&version.description)
+    .bind(&version.change_reason)
+    .bind(content.format.as_str())
+    .bind(stored)
+    .bind(encoding)
+    .bind(content.is_encrypted)
+    .bind(version.created_at)
+    .bind(&version.created_by)
+    .execute(executor)
+    .await
+    .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Version entry recorded for a create or update
+fn version_of(meta: &ConfigMeta, created_at: i64, created_by: &str) -> ConfigVersion {
+    ConfigVersion {
+        version: meta.version.clone(),
+        created_at,
+        created_by: created_by.to_string(),
+        description: meta.description.clone(),
+        change_reason: None,
+        tags: Vec::new(),
+        pinned: false,
+    }
+}
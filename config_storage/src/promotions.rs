@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use config_common::{ConfigMeta, Result};
+use config_core::{Promotion, PromotionManager, PromotionStatus};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a promotion row
+const PROMOTION_COLUMNS: &str = "id, source_id, source_version, target_environment, target_id, \
+     target_version, change_reason, status, requested_by, requested_at, reviewed_by, \
+     reviewed_at, applied_version";
+
+#[derive(sqlx::FromRow)]
+struct PromotionRow {
+    id: String,
+    source_id: String,
+    source_version: String,
+    target_environment: String,
+    target_id: Option<String>,
+    target_version: Option<String>,
+    change_reason: Option<String>,
+    status: String,
+    requested_by: String,
+    requested_at: i64,
+    reviewed_by: Option<String>,
+    reviewed_at: Option<i64>,
+    applied_version: Option<String>,
+}
+
+impl TryFrom<PromotionRow> for Promotion {
+    type Error = config_common::Error;
+
+    fn try_from(row: PromotionRow) -> Result<Self> {
+        Ok(Promotion {
+            id: row.id,
+            source_id: row.source_id,
+            source_version: row.source_version,
+            target_environment: row.target_environment,
+            target_id: row.target_id,
+            target_version: row.target_version,
+            change_reason: row.change_reason,
+            status: row.status.parse()?,
+            requested_by: row.requested_by,
+            requested_at: row.requested_at,
+            reviewed_by: row.reviewed_by,
+            reviewed_at: row.reviewed_at,
+            applied_version: row.applied_version,
+        })
+    }
+}
+
+#[async_trait]
+impl PromotionManager for PgConfigStorage {
+    async fn create_promotion(
+        &self,
+        source: &ConfigMeta,
+        target_environment: &str,
+        target: Option<&ConfigMeta>,
+        change_reason: Option<&str>,
+        requested_by: &str,
+    ) -> Result<Promotion> {
+        let promotion = Promotion {
+            id: uuid::Uuid::new_v4().to_string(),
+            source_id: source.id.clone(),
+            source_version: source.version.clone(),
+            target_environment: target_environment.to_string(),
+            target_id: target.map(|t| t.id.clone()),
+            target_version: target.map(|t| t.version.clone()),
+            change_reason: change_reason.map(String::from),
+            status: PromotionStatus::Pending,
+            requested_by: requested_by.to_string(),
+            requested_at: chrono::Utc::now().timestamp(),
+            reviewed_by: None,
+            reviewed_at: None,
+            applied_version: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_promotions (id, source_id, source_version, target_environment,
+                target_id, target_version, change_reason, status, requested_by, requested_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(&promotion.id)
+        .bind(&promotion.source_id)
+        .bind(&promotion.source_version)
+        .bind(&promotion.target_environment)
+        .bind(&promotion.target_id)
+        .bind(&promotion.target_version)
+        .bind(&promotion.change_reason)
+        .bind(promotion.status.as_str())
+        .bind(&promotion.requested_by)
+        .bind(promotion.requested_at)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(promotion)
+    }
+
+    async fn get_promotion(&self, id: &str) -> Result<Promotion> {
+        sqlx::query_as::<_, PromotionRow>(&format!(
+            "SELECT {} FROM config_promotions WHERE id = $1",
+            PROMOTION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("promotion {}", id)))?
+        .try_into()
+    }
+
+    async fn list_promotions(&self, status: Option<PromotionStatus>) -> Result<Vec<Promotion>> {
+        let rows = sqlx::query_as::<_, PromotionRow>(&format!(
+            r#"
+            SELECT {} FROM config_promotions
+            WHERE ($1::TEXT IS NULL OR status = $1)
+            ORDER BY requested_at DESC
+            "#,
+            PROMOTION_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(Promotion::try_from).collect()
+    }
+
+    async fn review_promotion(
+        &self,
+        id: &str,
+        status: PromotionStatus,
+        reviewed_by: &str,
+        applied_version: Option<&str>,
+    ) -> Result<Promotion> {
+        let row = sqlx::query_as::<_, PromotionRow>(&format!(
+            r#"
+            UPDATE config_promotions
+            SET status = $2, reviewed_by = $3, reviewed_at = $4, applied_version = $5
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            PROMOTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(reviewed_by)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(applied_version)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => {
+                let promotion = self.get_promotion(id).await?;
+                Err(config_common::Error::Validation(format!(
+                    "promotion {} is already {}",
+                    id,
+                    promotion.status.as_str()
+                )))
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{RecipientKey, RecipientKeyManager};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+#[async_trait]
+impl RecipientKeyManager for PgConfigStorage {
+    async fn get_recipients(&self, namespace: &str) -> Result<Vec<RecipientKey>> {
+        let recipients = sqlx::query_scalar::<_, Json<Vec<RecipientKey>>>(
+            "SELECT recipients FROM namespace_recipients WHERE namespace = $1",
+        )
+        .bind(namespace)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(recipients
+            .map(|recipients| recipients.0)
+            .unwrap_or_default())
+    }
+
+    async fn set_recipients(
+        &self,
+        namespace: &str,
+        recipients: Vec<RecipientKey>,
+        updated_by: &str,
+    ) -> Result<()> {
+        if recipients.is_empty() {
+            sqlx::query("DELETE FROM namespace_recipients WHERE namespace = $1")
+                .bind(namespace)
+                .execute(self.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO namespace_recipients (namespace, recipients, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (namespace) DO UPDATE
+            SET recipients = EXCLUDED.recipients, updated_at = EXCLUDED.updated_at,
+                updated_by = EXCLUDED.updated_by
+            "#,
+        )
+        .bind(namespace)
+        .bind(Json(&recipients))
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+}
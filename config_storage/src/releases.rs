@@ -0,0 +1,582 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{
+    ConfigDependency, GateResult, NewRelease, Release, ReleaseManager, ReleaseStatus, RolloutRule,
+};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a release row
+const RELEASE_COLUMNS: &str = "id, config_id, version, previous_version, change_reason, status, \
+     requested_by, requested_at, required_approvals, approvals, rollout, scheduled_at, \
+     gate_results, published_at, closed_by, closed_at";
+
+/// Columns selected for a dependency row
+const DEPENDENCY_COLUMNS: &str = "config_id, depends_on, created_at, created_by";
+
+#[derive(sqlx::FromRow)]
+struct DependencyRow {
+    config_id: String,
+    depends_on: String,
+    created_at: i64,
+    created_by: String,
+}
+
+impl From<DependencyRow> for ConfigDependency {
+    fn from(row: DependencyRow) -> Self {
+        ConfigDependency {
+            config_id: row.config_id,
+            depends_on: row.depends_on,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ReleaseRow {
+    id: String,
+    config_id: String,
+    version: String,
+    previous_version: Option<String>,
+    change_reason: Option<String>,
+    status: String,
+    requested_by: String,
+    requested_at: i64,
+    required_approvals: i32,
+    approvals: Vec<String>,
+    rollout: Option<Json<RolloutRule>>,
+    scheduled_at: Option<i64>,
+    gate_results: Json<Vec<GateResult>>,
+    published_at: Option<i64>,
+    closed_by: Option<String>,
+    closed_at: Option<i64>,
+}
+
+impl TryFrom<ReleaseRow> for Release {
+    type Error = config_common::Error;
+
+    fn try_from(row: ReleaseRow) -> Result<Self> {
+        Ok(Release {
+            id: row.id,
+            config_id: row.config_id,
+            version: row.version,
+            previous_version: row.previous_version,
+            change_reason: row.change_reason,
+            status: row.status.parse()?,
+            requested_by: row.requested_by,
+            requested_at: row.requested_at,
+            required_approvals: row.required_approvals.max(0) as usize,
+            approvals: row.approvals,
+            rollout: row.rollout.map(|rule| rule.0),
+            scheduled_at: row.scheduled_at,
+            gate_results: row.gate_results.0,
+            published_at: row.published_at,
+            closed_by: row.closed_by,
+            closed_at: row.closed_at,
+        })
+    }
+}
+
+impl PgConfigStorage {
+    /// Explain why a release can't be reviewed or rolled out further
+    async fn release_closed(&self, id: &str) -> config_common::Error {
+        match self.get_release(id).await {
+            Ok(release) => config_common::Error::Validation(format!(
+                "release {} is {}",
+                id,
+                release.status.as_str()
+            )),
+            Err(e) => e,
+        }
+    }
+
+    /// Apply an update to a rolling release
+    async fn update_rolling(&self, id: &str, set: &str, value: Option<&str>) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases SET {}
+            WHERE id = $1 AND status = 'rolling'
+            RETURNING {}
+            "#,
+            set, RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(value)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(self.release_closed(id).await),
+        }
+    }
+}
+
+#[async_trait]
+impl ReleaseManager for PgConfigStorage {
+    async fn create_release(&self, new: NewRelease) -> Result<Release> {
+        let now = chrono::Utc::now().timestamp();
+        let status = if !config_core::gates::all_passed(&new.gate_results) {
+            ReleaseStatus::Blocked
+        } else if new.required_approvals > 0 {
+            ReleaseStatus::Pending
+        } else if new.scheduled_at.is_some_and(|at| at > now) {
+            ReleaseStatus::Scheduled
+        } else if new.rollout.is_some() {
+            ReleaseStatus::Rolling
+        } else {
+            ReleaseStatus::Published
+        };
+        let release = Release {
+            id: uuid::Uuid::new_v4().to_string(),
+            previous_version: self.released_version(&new.config_id).await?,
+            config_id: new.config_id,
+            version: new.version,
+            change_reason: new.change_reason,
+            status,
+            requested_by: new.requested_by,
+            requested_at: now,
+            required_approvals: new.required_approvals,
+            approvals: Vec::new(),
+            rollout: new.rollout,
+            scheduled_at: new.scheduled_at,
+            gate_results: new.gate_results,
+            published_at: (status == ReleaseStatus::Published).then_some(now),
+            closed_by: None,
+            closed_at: (status == ReleaseStatus::Blocked).then_some(now),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_releases (id, config_id, version, previous_version,
+                change_reason, status, requested_by, requested_at, required_approvals,
+                rollout, scheduled_at, gate_results, published_at, closed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
+        )
+        .bind(&release.id)
+        .bind(&release.config_id)
+        .bind(&release.version)
+        .bind(&release.previous_version)
+        .bind(&release.change_reason)
+        .bind(release.status.as_str())
+        .bind(&release.requested_by)
+        .bind(release.requested_at)
+        .bind(release.required_approvals as i32)
+        .bind(release.rollout.clone().map(Json))
+        .bind(release.scheduled_at)
+        .bind(Json(&release.gate_results))
+        .bind(release.published_at)
+        .bind(release.closed_at)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(release)
+    }
+
+    async fn get_release(&self, id: &str) -> Result<Release> {
+        sqlx::query_as::<_, ReleaseRow>(&format!(
+            "SELECT {} FROM config_releases WHERE id = $1",
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("release {}", id)))?
+        .try_into()
+    }
+
+    async fn list_releases(
+        &self,
+        config_id: Option<&str>,
+        status: Option<ReleaseStatus>,
+    ) -> Result<Vec<Release>> {
+        let rows = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            SELECT {} FROM config_releases
+            WHERE ($1::TEXT IS NULL OR config_id = $1)
+              AND ($2::TEXT IS NULL OR status = $2)
+            ORDER BY requested_at DESC
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(config_id)
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(Release::try_from).collect()
+    }
+
+    async fn approve_release(&self, id: &str, approver: &str) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases
+            SET approvals = array_append(approvals, $2),
+                status = CASE WHEN cardinality(approvals) + 1 < required_approvals THEN status
+                    WHEN scheduled_at > $3 THEN 'scheduled'
+                    WHEN rollout IS NULL THEN 'published' ELSE 'rolling' END,
+                published_at = CASE WHEN cardinality(approvals) + 1 >= required_approvals
+                    AND rollout IS NULL AND (scheduled_at IS NULL OR scheduled_at <= $3)
+                    THEN $3 ELSE published_at END
+            WHERE id = $1 AND status = 'pending' AND NOT ($2 = ANY(approvals))
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(approver)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => {
+                let release = self.get_release(id).await?;
+                if release.approvals.iter().any(|a| a == approver) {
+                    return Err(config_common::Error::Validation(format!(
+                        "{} already approved release {}",
+                        approver, id
+                    )));
+                }
+                Err(self.release_closed(id).await)
+            }
+        }
+    }
+
+    async fn reject_release(&self, id: &str, rejected_by: &str) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases SET status = 'rejected', closed_by = $2, closed_at = $3
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(rejected_by)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(self.release_closed(id).await),
+        }
+    }
+
+    async fn record_gates(&self, id: &str, results: Vec<GateResult>) -> Result<Release> {
+        sqlx::query_as::<_, ReleaseRow>(&format!(
+            "UPDATE config_releases SET gate_results = $2 WHERE id = $1 RETURNING {}",
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(Json(results))
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .ok_or_else(|| config_common::Error::NotFound(format!("release {}", id)))?
+        .try_into()
+    }
+
+    async fn block_release(&self, id: &str, results: Vec<GateResult>) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases SET status = 'blocked', gate_results = $2, closed_at = $3
+            WHERE id = $1 AND status IN ('pending', 'scheduled')
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(Json(results))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(self.release_closed(id).await),
+        }
+    }
+
+    async fn cancel_release(&self, id: &str, cancelled_by: &str) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases SET status = 'cancelled', closed_by = $2, closed_at = $3
+            WHERE id = $1 AND status = 'scheduled'
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(cancelled_by)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(self.release_closed(id).await),
+        }
+    }
+
+    async fn due_releases(&self, now: i64) -> Result<Vec<Release>> {
+        let rows = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            SELECT {} FROM config_releases
+            WHERE status = 'scheduled' AND scheduled_at <= $1
+            ORDER BY scheduled_at
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        rows.into_iter().map(Release::try_from).collect()
+    }
+
+    async fn run_scheduled(&self, id: &str) -> Result<Release> {
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases
+            SET status = CASE WHEN rollout IS NULL THEN 'published' ELSE 'rolling' END,
+                published_at = CASE WHEN rollout IS NULL THEN $2 ELSE published_at END
+            WHERE id = $1 AND status = 'scheduled'
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(self.release_closed(id).await),
+        }
+    }
+
+    async fn set_rollout(&self, id: &str, rollout: RolloutRule) -> Result<Release> {
+        let rollout = serde_json::to_string(&rollout)
+            .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+        self.update_rolling(id, "rollout = $2::JSONB", Some(&rollout))
+            .await
+    }
+
+    async fn finalize_release(&self, id: &str) -> Result<Release> {
+        self.update_rolling(id, "status = 'published', published_at = $3", None)
+            .await
+    }
+
+    async fn abort_release(&self, id: &str, aborted_by: &str) -> Result<Release> {
+        let release = self.get_release(id).await?;
+        if release.status != ReleaseStatus::Published {
+            return self
+                .update_rolling(
+                    id,
+                    "status = 'aborted', closed_by = $2, closed_at = $3",
+                    Some(aborted_by),
+                )
+                .await;
+        }
+
+        // Only the served release can be aborted, and only when there is one to go back to
+        let row = sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            UPDATE config_releases r
+            SET status = 'aborted', closed_by = $2, closed_at = $3
+            WHERE r.id = $1 AND r.status = 'published'
+              AND r.id = (
+                  SELECT id FROM config_releases
+                  WHERE config_id = r.config_id AND status = 'published'
+                  ORDER BY published_at DESC
+                  LIMIT 1
+              )
+              AND EXISTS (
+                  SELECT 1 FROM config_releases
+                  WHERE config_id = r.config_id AND status = 'published' AND id <> r.id
+              )
+            RETURNING {}
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(id)
+        .bind(aborted_by)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(config_common::Error::Validation(format!(
+                "release {} is not the served release of {} or has no earlier release to \
+                 revert to",
+                id, release.config_id
+            ))),
+        }
+    }
+
+    async fn rolling_release(&self, config_id: &str) -> Result<Option<Release>> {
+        sqlx::query_as::<_, ReleaseRow>(&format!(
+            r#"
+            SELECT {} FROM config_releases
+            WHERE config_id = $1 AND status = 'rolling'
+            ORDER BY requested_at DESC
+            LIMIT 1
+            "#,
+            RELEASE_COLUMNS
+        ))
+        .bind(config_id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(Release::try_from)
+        .transpose()
+    }
+
+    async fn released_version(&self, config_id: &str) -> Result<Option<String>> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT version FROM config_releases
+            WHERE config_id = $1 AND status = 'published'
+            ORDER BY published_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(config_id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))
+    }
+
+    async fn add_dependency(
+        &self,
+        config_id: &str,
+        depends_on: &str,
+        created_by: &str,
+    ) -> Result<ConfigDependency> {
+        if config_id == depends_on {
+            return Err(config_common::Error::Validation(format!(
+                "{} cannot depend on itself",
+                config_id
+            )));
+        }
+
+        // Reject the dependency when `depends_on` already depends on `config_id`, directly
+        // or through other configs
+        let cycle: bool = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE upstream (config_id) AS (
+                SELECT depends_on FROM config_dependencies WHERE config_id = $1
+                UNION
+                SELECT d.depends_on FROM config_dependencies d
+                JOIN upstream u ON d.config_id = u.config_id
+            )
+            SELECT EXISTS (SELECT 1 FROM upstream WHERE config_id = $2)
+            "#,
+        )
+        .bind(depends_on)
+        .bind(config_id)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        if cycle {
+            return Err(config_common::Error::Validation(format!(
+                "{} already depends on {}",
+                depends_on, config_id
+            )));
+        }
+
+        let dependency = ConfigDependency {
+            config_id: config_id.to_string(),
+            depends_on: depends_on.to_string(),
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+        let result = sqlx::query(
+            r#"
+            INSERT INTO config_dependencies (config_id, depends_on, created_at, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (config_id, depends_on) DO NOTHING
+            "#,
+        )
+        .bind(&dependency.config_id)
+        .bind(&dependency.depends_on)
+        .bind(dependency.created_at)
+        .bind(&dependency.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "dependency of {} on {}",
+                config_id, depends_on
+            )));
+        }
+        Ok(dependency)
+    }
+
+    async fn remove_dependency(&self, config_id: &str, depends_on: &str) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM config_dependencies WHERE config_id = $1 AND depends_on = $2")
+                .bind(config_id)
+                .bind(depends_on)
+                .execute(self.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_dependencies(&self, config_id: Option<&str>) -> Result<Vec<ConfigDependency>> {
+        let rows = sqlx::query_as::<_, DependencyRow>(&format!(
+            r#"
+            SELECT {} FROM config_dependencies
+            WHERE ($1::TEXT IS NULL OR config_id = $1)
+            ORDER BY config_id, depends_on
+            "#,
+            DEPENDENCY_COLUMNS
+        ))
+        .bind(config_id)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(ConfigDependency::from).collect())
+    }
+
+    async fn unreleased_dependencies(&self, config_id: &str) -> Result<Vec<String>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT d.depends_on FROM config_dependencies d
+            JOIN config_releases r ON r.config_id = d.depends_on
+            WHERE d.config_id = $1
+              AND r.status IN ('pending', 'scheduled', 'blocked', 'rolling')
+            ORDER BY d.depends_on
+            "#,
+        )
+        .bind(config_id)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))
+    }
+}
@@ -0,0 +1,157 @@
+use config_common::Result;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::model::DatabaseConfig;
+
+/// Read replica with whether it is keeping up with the primary
+struct Replica {
+    url: String,
+    /// Host, port and database, for logs
+    name: String,
+    pool: PgPool,
+    /// Shared by the sets of every schema on the replica
+    healthy: Arc<AtomicBool>,
+}
+
+/// Read replicas of the primary. Reads that may be slightly stale are spread over the
+/// replicas within the allowed lag; lagging or unreachable replicas are skipped until they
+/// catch up, and reads go to the primary when none is usable.
+pub struct ReplicaSet {
+    primary: Arc<PgPool>,
+    replicas: Vec<Replica>,
+    next: AtomicUsize,
+    max_lag: Duration,
+    /// Settings replica pools are created with
+    database: DatabaseConfig,
+}
+
+impl ReplicaSet {
+    /// Replicas start out unused until the first lag check
+    pub fn new(
+        primary: Arc<PgPool>,
+        database: &DatabaseConfig,
+        max_connections: u32,
+    ) -> Result<Self> {
+        let mut replicas = Vec::with_capacity(database.replicas.len());
+        for url in &database.replicas {
+            let options = database.pool_options(max_connections, None, None);
+            replicas.push(Replica {
+                url: url.clone(),
+                // Keep credentials out of logs
+                name: url
+                    .rsplit_once('@')
+                    .map_or(url.as_str(), |(_, host)| host)
+                    .to_string(),
+                pool: connect_lazy(options, url)?,
+                healthy: Arc::new(AtomicBool::new(false)),
+            });
+        }
+        Ok(Self {
+            primary,
+            replicas,
+            next: AtomicUsize::new(0),
+            max_lag: Duration::from_secs(database.max_replica_lag_secs),
+            database: database.clone(),
+        })
+    }
+
+    /// The same replicas seen through connections to one schema. Its replicas are usable
+    /// when this set's are, so only this set needs checking.
+    pub fn for_schema(&self, schema: &str, max_connections: u32) -> Result<Self> {
+        let mut replicas = Vec::with_capacity(self.replicas.len());
+        for replica in &self.replicas {
+            replicas.push(Replica {
+                url: replica.url.clone(),
+                name: replica.name.clone(),
+                pool: connect_lazy(
+                    self.database
+                        .pool_options(max_connections, Some(schema), Some(schema)),
+                    &replica.url,
+                )?,
+                healthy: replica.healthy.clone(),
+            });
+        }
+        Ok(Self {
+            primary: self.primary.clone(),
+            replicas,
+            next: AtomicUsize::new(0),
+            max_lag: self.max_lag,
+            database: self.database.clone(),
+        })
+    }
+
+    /// Pool for a read that may be slightly stale: the next replica within the allowed lag,
+    /// `None` when reads should go to the primary
+    pub fn pick(&self) -> Option<&PgPool> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.replicas.len())
+            .map(|offset| &self.replicas[(start + offset) % self.replicas.len()])
+            .find(|replica| replica.healthy.load(Ordering::Relaxed))
+            .map(|replica| &replica.pool)
+    }
+
+    /// Compare each replica's replay position with the primary's WAL position. A replica
+    /// behind the primary is usable while its last replayed transaction is recent enough.
+    pub async fn check_lag(&self) {
+        let position: Option<String> = match sqlx::query_scalar("SELECT pg_current_wal_lsn()::TEXT")
+            .fetch_one(&*self.primary)
+            .await
+        {
+            Ok(position) => Some(position),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read the primary's WAL position");
+                None
+            }
+        };
+
+        for replica in &self.replicas {
+            let usable = match &position {
+                Some(position) => self.within_lag(replica, position).await.unwrap_or_else(|e| {
+                    tracing::warn!(replica = %replica.name, error = %e, "Replica lag check failed");
+                    false
+                }),
+                None => false,
+            };
+            let was = replica.healthy.swap(usable, Ordering::Relaxed);
+            if was && !usable {
+                tracing::warn!(replica = %replica.name, "Replica lagging, reading from primary");
+            } else if !was && usable {
+                tracing::info!(replica = %replica.name, "Replica caught up, reading from it");
+            }
+        }
+    }
+
+    async fn within_lag(&self, replica: &Replica, position: &str) -> sqlx::Result<bool> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(pg_wal_lsn_diff($1::pg_lsn, pg_last_wal_replay_lsn()) <= 0, FALSE)
+                OR COALESCE(EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()), 0) <= $2
+            "#,
+        )
+        .bind(position)
+        .bind(self.max_lag.as_secs_f64())
+        .fetch_one(&replica.pool)
+        .await
+    }
+
+    /// Check replica lag every interval in the background
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check_lag().await;
+            }
+        })
+    }
+}
+
+fn connect_lazy(options: PgPoolOptions, url: &str) -> Result<PgPool> {
+    options
+        .connect_lazy(url)
+        .map_err(|e| config_common::Error::Database(e.to_string()))
+}
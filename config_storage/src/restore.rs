@@ -0,0 +1,329 @@
+use config_common::Result;
+use config_core::backup::{BACKUP_FORMAT, BACKUP_MANIFEST_PATH};
+use config_core::{BackupManifest, BackupTable, RestoreReport};
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use crate::migrate::MIGRATIONS_TABLE;
+
+/// Rows inserted per statement when loading a table
+const RESTORE_BATCH_ROWS: usize = 500;
+
+/// Tables whose rows after the backup are replayed, or discarded when restoring to an
+/// earlier time, with the column holding each row's time
+const HISTORY_TABLES: [(&str, &str); 3] = [
+    ("configs", "created_at"),
+    ("config_versions", "created_at"),
+    ("config_events", "created_at"),
+];
+
+/// Contents of a backup archive, keyed by path
+pub struct LoadedBackup {
+    pub manifest: BackupManifest,
+    pub files: HashMap<String, Vec<u8>>,
+}
+
+impl LoadedBackup {
+    /// Rows of a table as JSON objects
+    pub fn rows(&self, table: &str) -> impl Iterator<Item = &str> {
+        self.files
+            .get(&format!("tables/{}.jsonl", table))
+            .map(|data| std::str::from_utf8(data).unwrap_or_default())
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+    }
+}
+
+/// Read a backup archive, checking its format and the checksum of every table
+pub fn load_backup(path: &Path) -> Result<LoadedBackup> {
+    let backup = unpack_backup(path)?;
+    if backup.manifest.format > BACKUP_FORMAT {
+        return Err(invalid(format!(
+            "backup format {} is newer than the supported {}",
+            backup.manifest.format, BACKUP_FORMAT
+        )));
+    }
+    for table in &backup.manifest.tables {
+        if let Some(problem) = table_problem(&backup, table) {
+            return Err(invalid(problem));
+        }
+    }
+    Ok(backup)
+}
+
+/// Read a backup archive without checking it
+pub fn unpack_backup(path: &Path) -> Result<LoadedBackup> {
+    let file = std::fs::File::open(path).map_err(|e| invalid(e.to_string()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut files = HashMap::new();
+    for entry in archive.entries().map_err(|e| invalid(e.to_string()))? {
+        let mut entry = entry.map_err(|e| invalid(e.to_string()))?;
+        let path = entry
+            .path()
+            .map_err(|e| invalid(e.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| invalid(e.to_string()))?;
+        files.insert(path, data);
+    }
+
+    let manifest: BackupManifest = files
+        .get(BACKUP_MANIFEST_PATH)
+        .ok_or_else(|| invalid(format!("missing {}", BACKUP_MANIFEST_PATH)))
+        .and_then(|data| serde_json::from_slice(data).map_err(|e| invalid(e.to_string())))?;
+    Ok(LoadedBackup { manifest, files })
+}
+
+/// Why a table of a backup can't be trusted, `None` when its rows match the manifest
+pub(crate) fn table_problem(backup: &LoadedBackup, table: &BackupTable) -> Option<String> {
+    match backup.files.get(&table.path()) {
+        None => Some(format!("missing {}", table.path())),
+        Some(data) if format!("{:x}", Sha256::digest(data)) != table.sha256 => {
+            Some(format!("checksum mismatch of {}", table.path()))
+        }
+        Some(_) => None,
+    }
+}
+
+fn invalid(e: String) -> config_common::Error {
+    config_common::Error::Validation(format!("invalid backup: {}", e))
+}
+
+/// Replace the backed-up tables in one transaction, then bring config history to `until`
+pub(crate) async fn restore(
+    pool: &PgPool,
+    name: &str,
+    backup: &LoadedBackup,
+    until: Option<i64>,
+    restored_by: &str,
+) -> Result<RestoreReport> {
+    let backup_at = backup.manifest.created_at;
+    let restored_to = until.unwrap_or(backup_at);
+    let mut report = RestoreReport {
+        backup: name.to_string(),
+        backup_created_at: backup_at,
+        restored_to,
+        restored_by: restored_by.to_string(),
+        tables: 0,
+        rows: 0,
+        replayed_versions: 0,
+        replayed_events: 0,
+        discarded_versions: 0,
+        discarded_events: 0,
+        configs_reset: 0,
+    };
+
+    let mut tx = pool.begin().await?;
+
+    // History made after the backup, read before the tables holding it are replaced
+    let mut replay: Vec<(&str, Vec<String>)> = Vec::new();
+    if restored_to > backup_at {
+        for (table, column) in HISTORY_TABLES {
+            let rows = sqlx::query_scalar::<_, String>(&format!(
+                "SELECT row_to_json(t)::TEXT FROM {} t WHERE {} > $1 AND {} <= $2",
+                table, column, column
+            ))
+            .bind(backup_at)
+            .bind(restored_to)
+            .fetch_all(&mut *tx)
+            .await?;
+            replay.push((table, rows));
+        }
+    }
+
+    let existing: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT table_name::TEXT FROM information_schema.tables
+        WHERE table_schema = current_schema() AND table_type = 'BASE TABLE'
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    let tables: Vec<&str> = backup
+        .manifest
+        .tables
+        .iter()
+        .map(|table| table.name.as_str())
+        // Applied migrations describe the current schema, not the backup's
+        .filter(|table| *table != MIGRATIONS_TABLE)
+        .filter(|table| {
+            let found = existing.iter().any(|name| name == *table);
+            if !found {
+                tracing::warn!(
+                    table = *table,
+                    "Skipping backed-up table missing from the database"
+                );
+            }
+            found
+        })
+        .collect();
+    if !tables.is_empty() {
+        sqlx::query(&format!(
+            "TRUNCATE {}",
+            tables
+                .iter()
+                .map(|table| quote_ident(table))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+    for table in &tables {
+        let rows: Vec<&str> = backup.rows(table).collect();
+        report.rows += insert_rows(&mut tx, table, &rows).await?;
+        report.tables += 1;
+    }
+
+    for (table, rows) in &replay {
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        let inserted = insert_rows(&mut tx, table, &rows).await?;
+        match *table {
+            "config_versions" => report.replayed_versions = inserted,
+            "config_events" => report.replayed_events = inserted,
+            _ => {}
+        }
+    }
+    if restored_to > backup_at {
+        // Configs deleted after the backup stay deleted
+        sqlx::query(
+            r#"
+            DELETE FROM configs WHERE id IN (
+                SELECT config_id FROM config_events
+                WHERE created_at > $1 AND created_at <= $2 AND event->>'event_type' = 'Deleted'
+            )
+            "#,
+        )
+        .bind(backup_at)
+        .bind(restored_to)
+        .execute(&mut *tx)
+        .await?;
+    } else if restored_to < backup_at {
+        for (table, column) in HISTORY_TABLES {
+            let discarded = sqlx::query(&format!("DELETE FROM {} WHERE {} > $1", table, column))
+                .bind(restored_to)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            match table {
+                "config_versions" => report.discarded_versions = discarded,
+                "config_events" => report.discarded_events = discarded,
+                _ => {}
+            }
+        }
+    }
+
+    report.configs_reset = sqlx::query(
+        r#"
+        UPDATE configs c
+        SET version = v.version, description = v.description, format = v.format,
+            content = v.content, content_encoding = v.content_encoding,
+            is_encrypted = v.is_encrypted, updated_at = v.created_at, updated_by = v.created_by
+        FROM (
+            SELECT DISTINCT ON (config_id) * FROM config_versions
+            WHERE created_at <= $1
+            ORDER BY config_id, created_at DESC, seq DESC
+        ) v
+        WHERE v.config_id = c.id AND c.version <> v.version
+        "#,
+    )
+    .bind(restored_to)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    reset_sequences(&mut tx).await?;
+    check_consistency(&mut tx).await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        backup = name,
+        restored_to,
+        tables = report.tables,
+        rows = report.rows,
+        "Restored backup"
+    );
+    Ok(report)
+}
+
+/// Insert JSON rows into a table, skipping rows that conflict with existing ones
+async fn insert_rows(conn: &mut PgConnection, table: &str, rows: &[&str]) -> Result<u64> {
+    let mut inserted = 0;
+    for batch in rows.chunks(RESTORE_BATCH_ROWS) {
+        inserted += sqlx::query(&format!(
+            "INSERT INTO {0} SELECT * FROM json_populate_recordset(NULL::{0}, $1::JSON) \
+             ON CONFLICT DO NOTHING",
+            quote_ident(table)
+        ))
+        .bind(format!("[{}]", batch.join(",")))
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+    Ok(inserted)
+}
+
+/// Move every serial sequence past the largest value restored into its column
+async fn reset_sequences(conn: &mut PgConnection) -> Result<()> {
+    let serials = sqlx::query_as::<_, (String, String, Option<String>)>(
+        r#"
+        SELECT table_name::TEXT, column_name::TEXT,
+               pg_get_serial_sequence(quote_ident(table_name), column_name)
+        FROM information_schema.columns
+        WHERE table_schema = current_schema() AND column_default LIKE 'nextval(%'
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (table, column, sequence) in serials {
+        let Some(sequence) = sequence else { continue };
+        sqlx::query(&format!(
+            "SELECT setval($1, COALESCE((SELECT MAX({}) FROM {}), 0) + 1, false)",
+            quote_ident(&column),
+            quote_ident(&table)
+        ))
+        .bind(sequence)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Fail when a config's current version is missing from its history or differs from it,
+/// which the state machine would never produce
+async fn check_consistency(conn: &mut PgConnection) -> Result<()> {
+    let inconsistent: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT c.id FROM configs c
+        LEFT JOIN config_versions v ON v.config_id = c.id AND v.version = c.version
+        WHERE v.config_id IS NULL
+           OR (c.content_encoding IS NOT DISTINCT FROM v.content_encoding
+               AND c.content <> v.content)
+        ORDER BY c.id
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if inconsistent.is_empty() {
+        return Ok(());
+    }
+    Err(config_common::Error::Internal(format!(
+        "restored configs do not match their version history: {}",
+        inconsistent.join(", ")
+    )))
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{ValidationRule, ValidationRuleManager};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+#[async_trait]
+impl ValidationRuleManager for PgConfigStorage {
+    async fn get_rules(&self, config_id: &str) -> Result<Vec<ValidationRule>> {
+        let rules = sqlx::query_scalar::<_, Json<Vec<ValidationRule>>>(
+            "SELECT rules FROM config_validation_rules WHERE config_id = $1",
+        )
+        .bind(config_id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rules.map(|rules| rules.0).unwrap_or_default())
+    }
+
+    async fn set_rules(
+        &self,
+        config_id: &str,
+        rules: Vec<ValidationRule>,
+        updated_by: &str,
+    ) -> Result<()> {
+        if rules.is_empty() {
+            sqlx::query("DELETE FROM config_validation_rules WHERE config_id = $1")
+                .bind(config_id)
+                .execute(self.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_validation_rules (config_id, rules, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (config_id) DO UPDATE
+            SET rules = EXCLUDED.rules, updated_at = EXCLUDED.updated_at,
+                updated_by = EXCLUDED.updated_by
+            "#,
+        )
+        .bind(config_id)
+        .bind(Json(&rules))
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+}
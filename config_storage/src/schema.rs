@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{ConfigSchema, SchemaManager};
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a schema row
+const SCHEMA_COLUMNS: &str = "id, namespace, application, description, schema, created_at, \
+     created_by, updated_at, updated_by";
+
+#[derive(sqlx::FromRow)]
+struct SchemaRow {
+    id: String,
+    namespace: String,
+    application: Option<String>,
+    description: Option<String>,
+    schema: Json<serde_json::Value>,
+    created_at: i64,
+    created_by: String,
+    updated_at: i64,
+    updated_by: String,
+}
+
+impl From<SchemaRow> for ConfigSchema {
+    fn from(row: SchemaRow) -> Self {
+        ConfigSchema {
+            id: row.id,
+            namespace: row.namespace,
+            application: row.application,
+            description: row.description,
+            schema: row.schema.0,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl SchemaManager for PgConfigStorage {
+    async fn create_schema(
+        &self,
+        namespace: &str,
+        application: Option<&str>,
+        description: Option<&str>,
+        schema: serde_json::Value,
+        created_by: &str,
+    ) -> Result<ConfigSchema> {
+        let now = chrono::Utc::now().timestamp();
+        let schema = ConfigSchema {
+            id: uuid::Uuid::new_v4().to_string(),
+            namespace: namespace.to_string(),
+            application: application.map(String::from),
+            description: description.map(String::from),
+            schema,
+            created_at: now,
+            created_by: created_by.to_string(),
+            updated_at: now,
+            updated_by: created_by.to_string(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_schemas (id, namespace, application, description, schema,
+                created_at, created_by, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(&schema.id)
+        .bind(&schema.namespace)
+        .bind(&schema.application)
+        .bind(&schema.description)
+        .bind(Json(&schema.schema))
+        .bind(schema.created_at)
+        .bind(&schema.created_by)
+        .bind(schema.updated_at)
+        .bind(&schema.updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                config_common::Error::AlreadyExists(format!(
+                    "schema for {}/{}",
+                    namespace,
+                    application.unwrap_or("*")
+                ))
+            }
+            e => config_common::Error::Database(e.to_string()),
+        })?;
+
+        Ok(schema)
+    }
+
+    async fn get_schema(&self, id: &str) -> Result<ConfigSchema> {
+        sqlx::query_as::<_, SchemaRow>(&format!(
+            "SELECT {} FROM config_schemas WHERE id = $1",
+            SCHEMA_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(ConfigSchema::from)
+        .ok_or_else(|| config_common::Error::NotFound(format!("schema {}", id)))
+    }
+
+    async fn list_schemas(&self, namespace: Option<&str>) -> Result<Vec<ConfigSchema>> {
+        let rows = sqlx::query_as::<_, SchemaRow>(&format!(
+            "SELECT {} FROM config_schemas WHERE ($1::TEXT IS NULL OR namespace = $1) \
+             ORDER BY namespace, application NULLS FIRST",
+            SCHEMA_COLUMNS
+        ))
+        .bind(namespace)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(ConfigSchema::from).collect())
+    }
+
+    async fn update_schema(
+        &self,
+        id: &str,
+        description: Option<&str>,
+        schema: serde_json::Value,
+        updated_by: &str,
+    ) -> Result<ConfigSchema> {
+        sqlx::query_as::<_, SchemaRow>(&format!(
+            "UPDATE config_schemas SET description = $2, schema = $3, updated_at = $4, \
+             updated_by = $5 WHERE id = $1 RETURNING {}",
+            SCHEMA_COLUMNS
+        ))
+        .bind(id)
+        .bind(description)
+        .bind(Json(&schema))
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(ConfigSchema::from)
+        .ok_or_else(|| config_common::Error::NotFound(format!("schema {}", id)))
+    }
+
+    async fn delete_schema(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM config_schemas WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn schemas_for(&self, namespace: &str, application: &str) -> Result<Vec<ConfigSchema>> {
+        let rows = sqlx::query_as::<_, SchemaRow>(&format!(
+            "SELECT {} FROM config_schemas WHERE namespace = $1 \
+             AND (application IS NULL OR application = $2) ORDER BY application NULLS FIRST",
+            SCHEMA_COLUMNS
+        ))
+        .bind(namespace)
+        .bind(application)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(ConfigSchema::from).collect())
+    }
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::SecretPathManager;
+use sqlx::types::Json;
+
+use crate::postgres::PgConfigStorage;
+
+#[async_trait]
+impl SecretPathManager for PgConfigStorage {
+    async fn get_secret_paths(&self, config_id: &str) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar::<_, Json<Vec<String>>>(
+            "SELECT paths FROM config_secret_paths WHERE config_id = $1",
+        )
+        .bind(config_id)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(paths.map(|paths| paths.0).unwrap_or_default())
+    }
+
+    async fn set_secret_paths(
+        &self,
+        config_id: &str,
+        paths: Vec<String>,
+        updated_by: &str,
+    ) -> Result<()> {
+        if paths.is_empty() {
+            sqlx::query("DELETE FROM config_secret_paths WHERE config_id = $1")
+                .bind(config_id)
+                .execute(self.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO config_secret_paths (config_id, paths, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (config_id) DO UPDATE
+            SET paths = EXCLUDED.paths, updated_at = EXCLUDED.updated_at,
+                updated_by = EXCLUDED.updated_by
+            "#,
+        )
+        .bind(config_id)
+        .bind(Json(&paths))
+        .bind(chrono::Utc::now().timestamp())
+        .bind(updated_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::{SecretShare, SecretShareManager};
+use sha2::{Digest, Sha256};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a secret share row
+const SHARE_COLUMNS: &str = "id, config_id, expires_at, created_at, created_by, consumed_at";
+
+#[derive(sqlx::FromRow)]
+struct ShareRow {
+    id: String,
+    config_id: String,
+    expires_at: i64,
+    created_at: i64,
+    created_by: String,
+    consumed_at: Option<i64>,
+}
+
+impl From<ShareRow> for SecretShare {
+    fn from(row: ShareRow) -> Self {
+        SecretShare {
+            id: row.id,
+            config_id: row.config_id,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            consumed_at: row.consumed_at,
+        }
+    }
+}
+
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn unusable_share() -> config_common::Error {
+    config_common::Error::Auth("share token is invalid, expired or already used".to_string())
+}
+
+#[async_trait]
+impl SecretShareManager for PgConfigStorage {
+    async fn create_share(
+        &self,
+        config_id: &str,
+        expires_at: i64,
+        created_by: &str,
+    ) -> Result<(SecretShare, String)> {
+        let now = chrono::Utc::now().timestamp();
+        // Shares past their expiry can never be used again
+        sqlx::query("DELETE FROM config_secret_shares WHERE expires_at <= $1")
+            .bind(now)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let share = SecretShare {
+            id: uuid::Uuid::new_v4().to_string(),
+            config_id: config_id.to_string(),
+            expires_at,
+            created_at: now,
+            created_by: created_by.to_string(),
+            consumed_at: None,
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO config_secret_shares (id, config_id, token_hash, expires_at,
+                created_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(&share.id)
+        .bind(&share.config_id)
+        .bind(token_hash(&token))
+        .bind(share.expires_at)
+        .bind(share.created_at)
+        .bind(&share.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok((share, token))
+    }
+
+    async fn get_share(&self, token: &str) -> Result<SecretShare> {
+        let row = sqlx::query_as::<_, ShareRow>(&format!(
+            "SELECT {} FROM config_secret_shares \
+             WHERE token_hash = $1 AND consumed_at IS NULL AND expires_at > $2",
+            SHARE_COLUMNS
+        ))
+        .bind(token_hash(token))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        row.map(SecretShare::from).ok_or_else(unusable_share)
+    }
+
+    async fn consume_share(&self, token: &str) -> Result<SecretShare> {
+        let now = chrono::Utc::now().timestamp();
+        // Consuming in the same statement that checks the share keeps it single-use
+        let row = sqlx::query_as::<_, ShareRow>(&format!(
+            "UPDATE config_secret_shares SET consumed_at = $2 \
+             WHERE token_hash = $1 AND consumed_at IS NULL AND expires_at > $2 \
+             RETURNING {}",
+            SHARE_COLUMNS
+        ))
+        .bind(token_hash(token))
+        .bind(now)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        row.map(SecretShare::from).ok_or_else(unusable_share)
+    }
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::stats::{EditorActivity, NamespaceStats, NamespaceStatsProvider, TOP_EDITORS};
+
+use crate::postgres::PgConfigStorage;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+#[async_trait]
+impl NamespaceStatsProvider for PgConfigStorage {
+    async fn namespace_stats(&self, namespace: &str) -> Result<NamespaceStats> {
+        let now = chrono::Utc::now().timestamp();
+
+        let (config_count, storage_bytes, last_change_at) =
+            sqlx::query_as::<_, (i64, i64, Option<i64>)>(
+                r#"
+                SELECT COUNT(*), COALESCE(SUM(octet_length(content)), 0)::BIGINT,
+                    MAX(updated_at)
+                FROM configs WHERE namespace = $1
+                "#,
+            )
+            .bind(namespace)
+            .fetch_one(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let (writes_24h, writes_7d) = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE v.created_at >= $2), COUNT(*)
+            FROM config_versions v JOIN configs c ON c.id = v.config_id
+            WHERE c.namespace = $1 AND v.created_at >= $3
+            "#,
+        )
+        .bind(namespace)
+        .bind(now - DAY_SECS)
+        .bind(now - 7 * DAY_SECS)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let editors = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT v.created_by, COUNT(*) AS writes
+            FROM config_versions v JOIN configs c ON c.id = v.config_id
+            WHERE c.namespace = $1 AND v.created_at >= $2
+            GROUP BY v.created_by
+            ORDER BY writes DESC, v.created_by
+            LIMIT $3
+            "#,
+        )
+        .bind(namespace)
+        .bind(now - 7 * DAY_SECS)
+        .bind(TOP_EDITORS as i64)
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(NamespaceStats {
+            namespace: namespace.to_string(),
+            config_count,
+            storage_bytes,
+            writes_24h,
+            writes_7d,
+            top_editors: editors
+                .into_iter()
+                .map(|(user, writes)| EditorActivity { user, writes })
+                .collect(),
+            last_change_at,
+            computed_at: now,
+        })
+    }
+}
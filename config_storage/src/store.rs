@@ -1,18 +1,31 @@
 use async_trait::async_trait;
 use config_common::{ConfigContent, ConfigMeta, Result};
-use config_core::{ConfigFilter, ConfigVersion};
+use config_core::{
+    ApprovalRequest, ApprovalStatus, ChangeSet, ChangeSetStatus, ConfigFilter, ConfigMetaStream,
+    ConfigSnapshot, ConfigVersion,
+};
 
 /// Storage trait for configuration data
 #[async_trait]
 pub trait ConfigStorage: Send + Sync {
-    /// Get configuration by ID
+    /// Get configuration by ID, possibly from a replica slightly behind the primary
     async fn get_config(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)>;
 
+    /// Get configuration by ID, seeing every committed write
+    async fn get_config_linearizable(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        self.get_config(id).await
+    }
+
     /// Create new configuration
     async fn create_config(&self, meta: ConfigMeta, content: ConfigContent) -> Result<ConfigMeta>;
 
     /// Update existing configuration
-    async fn update_config(&self, meta: ConfigMeta, content: ConfigContent) -> Result<ConfigMeta>;
+    async fn update_config(
+        &self,
+        meta: ConfigMeta,
+        content: ConfigContent,
+        change_reason: Option<&str>,
+    ) -> Result<ConfigMeta>;
 
     /// Delete configuration
     async fn delete_config(&self, id: &str) -> Result<bool>;
@@ -25,9 +38,49 @@ pub trait ConfigStorage: Send + Sync {
         page_number: i32,
     ) -> Result<(Vec<ConfigMeta>, i32)>;
 
+    /// Stream every configuration matching a filter, in listing order, from a cursor read as
+    /// the consumer takes rows
+    async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream>;
+
     /// Get configuration version history
     async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>>;
 
+    /// Get a single version with its content
+    async fn get_version(
+        &self,
+        config_id: &str,
+        version: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)>;
+
+    /// Get the latest version created at or before a Unix timestamp
+    async fn get_version_at(&self, config_id: &str, at: i64) -> Result<ConfigSnapshot>;
+
+    /// Get the latest version at or before a Unix timestamp of every config in a namespace
+    async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>>;
+
+    /// Point a tag at a version, replacing any previous target
+    async fn tag_version(
+        &self,
+        config_id: &str,
+        version: &str,
+        tag: &str,
+        created_by: &str,
+        created_at: i64,
+    ) -> Result<()>;
+
+    /// Pin or unpin a version
+    async fn set_version_pinned(&self, config_id: &str, version: &str, pinned: bool) -> Result<()>;
+
+    /// Remove a tag
+    async fn delete_tag(&self, config_id: &str, tag: &str) -> Result<bool>;
+
+    /// Get the version a tag points at
+    async fn get_tagged_version(
+        &self,
+        config_id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)>;
+
     /// Create new version
     async fn create_version(
         &self,
@@ -36,3 +89,28 @@ pub trait ConfigStorage: Send + Sync {
         content: ConfigContent,
     ) -> Result<()>;
 }
+
+/// Storage for change set drafts
+#[async_trait]
+pub trait ChangeSetStorage: Send + Sync {
+    /// Insert or replace a change set
+    async fn save_changeset(&self, changeset: &ChangeSet) -> Result<()>;
+
+    /// Get a change set by ID
+    async fn get_changeset(&self, id: &str) -> Result<ChangeSet>;
+
+    /// List change sets, newest first
+    async fn list_changesets(&self, status: Option<ChangeSetStatus>) -> Result<Vec<ChangeSet>>;
+}
+
+/// Storage for approval requests
+#[async_trait]
+pub trait ApprovalStorage: Send + Sync {
+    /// Insert an approval request or record its review
+    async fn save_approval(&self, approval: &ApprovalRequest) -> Result<()>;
+
+    async fn get_approval(&self, id: &str) -> Result<ApprovalRequest>;
+
+    /// List approval requests, newest first
+    async fn list_approvals(&self, status: Option<ApprovalStatus>) -> Result<Vec<ApprovalRequest>>;
+}
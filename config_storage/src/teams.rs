@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use config_common::Result;
+use config_core::teams::{Team, TeamManager};
+
+use crate::postgres::PgConfigStorage;
+
+/// Columns selected for a team row, with its members aggregated
+const TEAM_COLUMNS: &str = "t.name, t.description, t.created_at, t.created_by, \
+     COALESCE(ARRAY_AGG(m.member ORDER BY m.member) FILTER (WHERE m.member IS NOT NULL), \
+     '{}') AS members";
+
+#[derive(sqlx::FromRow)]
+struct TeamRow {
+    name: String,
+    description: Option<String>,
+    created_at: i64,
+    created_by: String,
+    members: Vec<String>,
+}
+
+impl From<TeamRow> for Team {
+    fn from(row: TeamRow) -> Self {
+        Team {
+            name: row.name,
+            description: row.description,
+            members: row.members,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+#[async_trait]
+impl TeamManager for PgConfigStorage {
+    async fn create_team(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        created_by: &str,
+    ) -> Result<Team> {
+        let team = Team {
+            name: name.to_string(),
+            description: description.map(String::from),
+            members: Vec::new(),
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO teams (name, description, created_at, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (name) DO NOTHING
+            "#,
+        )
+        .bind(&team.name)
+        .bind(&team.description)
+        .bind(team.created_at)
+        .bind(&team.created_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "team {}",
+                name
+            )));
+        }
+        Ok(team)
+    }
+
+    async fn get_team(&self, name: &str) -> Result<Team> {
+        sqlx::query_as::<_, TeamRow>(&format!(
+            r#"
+            SELECT {} FROM teams t
+            LEFT JOIN team_members m ON m.team = t.name
+            WHERE t.name = $1
+            GROUP BY t.name
+            "#,
+            TEAM_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?
+        .map(Team::from)
+        .ok_or_else(|| config_common::Error::NotFound(format!("team {}", name)))
+    }
+
+    async fn list_teams(&self) -> Result<Vec<Team>> {
+        let rows = sqlx::query_as::<_, TeamRow>(&format!(
+            r#"
+            SELECT {} FROM teams t
+            LEFT JOIN team_members m ON m.team = t.name
+            GROUP BY t.name
+            ORDER BY t.name
+            "#,
+            TEAM_COLUMNS
+        ))
+        .fetch_all(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Team::from).collect())
+    }
+
+    async fn delete_team(&self, name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM teams WHERE name = $1")
+            .bind(name)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn add_member(&self, team: &str, user: &str, added_by: &str) -> Result<Team> {
+        // Fails with not found before inserting for a team that doesn't exist
+        self.get_team(team).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO team_members (team, member, added_at, added_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (team, member) DO NOTHING
+            "#,
+        )
+        .bind(team)
+        .bind(user)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(added_by)
+        .execute(self.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        self.get_team(team).await
+    }
+
+    async fn remove_member(&self, team: &str, user: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM team_members WHERE team = $1 AND member = $2")
+            .bind(team)
+            .bind(user)
+            .execute(self.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,548 @@
+use async_trait::async_trait;
+use config_common::{ConfigContent, ConfigMeta, Result};
+use config_core::{
+    ConfigFilter, ConfigMetaStream, ConfigSnapshot, ConfigVersion, Tenant, TenantManager,
+};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::model::DatabaseConfig;
+use crate::postgres::PgConfigStorage;
+use crate::store::ConfigStorage;
+
+/// Prefix of the schema of a tenant, followed by its name
+const TENANT_SCHEMA_PREFIX: &str = "tenant_";
+
+/// Longest tenant name, keeping schema names within the 63 bytes Postgres allows
+const MAX_TENANT_NAME_LEN: usize = 48;
+
+/// Columns selected for a tenant row
+const TENANT_COLUMNS: &str = "name, schema_name, key_id, created_at, created_by";
+
+fn default_max_connections() -> u32 {
+    5
+}
+
+/// Tenant isolation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    /// Keep the configs of provisioned tenants in schemas of their own
+    #[serde(default)]
+    pub isolated: bool,
+    /// Connections in the pool of each tenant
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+}
+
+impl Default for TenancyConfig {
+    fn default() -> Self {
+        Self {
+            isolated: false,
+            max_connections: default_max_connections(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TenantRow {
+    name: String,
+    schema_name: String,
+    key_id: Option<String>,
+    created_at: i64,
+    created_by: String,
+}
+
+impl From<TenantRow> for Tenant {
+    fn from(row: TenantRow) -> Self {
+        Tenant {
+            name: row.name,
+            schema: row.schema_name,
+            key_id: row.key_id,
+            created_at: row.created_at,
+            created_by: row.created_by,
+        }
+    }
+}
+
+struct TenantEntry {
+    tenant: Tenant,
+    storage: Arc<PgConfigStorage>,
+}
+
+/// Config storage keeping the configs and versions of each tenant in its own schema. A
+/// tenant is only reached through connections acting as a role of the same name, which is
+/// granted nothing but the tables of that schema, so the database refuses its queries on
+/// another tenant's tables or the shared ones. The server's user needs `CREATEROLE` to
+/// provision the roles. Configs of departments that aren't tenants stay in the shared
+/// schema, as does everything besides configs.
+pub struct TenantStorage {
+    shared: Arc<PgConfigStorage>,
+    database: DatabaseConfig,
+    config: TenancyConfig,
+    tenants: RwLock<HashMap<String, Arc<TenantEntry>>>,
+}
+
+impl TenantStorage {
+    pub fn new(
+        shared: Arc<PgConfigStorage>,
+        database: DatabaseConfig,
+        config: TenancyConfig,
+    ) -> Self {
+        Self {
+            shared,
+            database,
+            config,
+            tenants: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Connect to the schemas of tenants provisioned so far, including by other nodes
+    pub async fn load(&self) -> Result<()> {
+        let rows =
+            sqlx::query_as::<_, TenantRow>(&format!("SELECT {} FROM tenants", TENANT_COLUMNS))
+                .fetch_all(self.shared.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        for row in rows {
+            if !self.tenants.read().await.contains_key(&row.name) {
+                self.connect(row.into()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn connect(&self, tenant: Tenant) -> Result<Arc<TenantEntry>> {
+        self.isolate(&tenant.schema).await?;
+        let pool = self
+            .database
+            .create_schema_pool(&tenant.schema, self.config.max_connections)
+            .await?;
+        let mut storage =
+            PgConfigStorage::new(Arc::new(pool)).with_compression(self.shared.compress_above());
+        if let Some((blobs, bytes)) = self.shared.blobs() {
+            storage = storage.with_blobs(blobs, bytes);
+        }
+        if let Some(replicas) = self.shared.replicas() {
+            let replicas = replicas.for_schema(&tenant.schema, self.config.max_connections)?;
+            storage = storage.with_replicas(Arc::new(replicas));
+        }
+        let entry = Arc::new(TenantEntry {
+            tenant,
+            storage: Arc::new(storage),
+        });
+        self.tenants
+            .write()
+            .await
+            .entry(entry.tenant.name.clone())
+            .or_insert(entry.clone());
+        Ok(entry)
+    }
+
+    /// Create the role of a tenant schema if missing, let the server act as it, and grant it
+    /// the schema's tables and only those; tables later migrations add are granted as well.
+    /// Idempotent, so it also covers tenants provisioned before they had roles.
+    async fn isolate(&self, schema: &str) -> Result<()> {
+        let statements = [
+            // Another node may be creating the same role
+            format!(
+                "DO $$ BEGIN CREATE ROLE \"{0}\" NOLOGIN; \
+                 EXCEPTION WHEN duplicate_object THEN NULL; END $$",
+                schema
+            ),
+            format!("GRANT \"{0}\" TO CURRENT_USER", schema),
+            format!("REVOKE ALL ON SCHEMA \"{0}\" FROM PUBLIC", schema),
+            format!("GRANT USAGE ON SCHEMA \"{0}\" TO \"{0}\"", schema),
+            format!(
+                "GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA \"{0}\" TO \"{0}\"",
+                schema
+            ),
+            format!(
+                "GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA \"{0}\" TO \"{0}\"",
+                schema
+            ),
+            format!(
+                "ALTER DEFAULT PRIVILEGES IN SCHEMA \"{0}\" \
+                 GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO \"{0}\"",
+                schema
+            ),
+            format!(
+                "ALTER DEFAULT PRIVILEGES IN SCHEMA \"{0}\" \
+                 GRANT USAGE, SELECT ON SEQUENCES TO \"{0}\"",
+                schema
+            ),
+        ];
+        let batch = statements.join(";\n");
+        sqlx::raw_sql(&batch)
+            .execute(self.shared.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// A tenant, connecting to it when another node provisioned it
+    async fn entry(&self, name: &str) -> Result<Option<Arc<TenantEntry>>> {
+        if let Some(entry) = self.tenants.read().await.get(name) {
+            return Ok(Some(entry.clone()));
+        }
+        let row = sqlx::query_as::<_, TenantRow>(&format!(
+            "SELECT {} FROM tenants WHERE name = $1",
+            TENANT_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.shared.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => Ok(Some(self.connect(row.into()).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Storage of a department's configs
+    async fn storage_for(&self, department: &str) -> Result<Arc<PgConfigStorage>> {
+        Ok(match self.entry(department).await? {
+            Some(entry) => entry.storage.clone(),
+            None => self.shared.clone(),
+        })
+    }
+
+    /// Storage holding a config
+    async fn storage_of(&self, config_id: &str) -> Result<Arc<PgConfigStorage>> {
+        let tenant: Option<String> =
+            sqlx::query_scalar("SELECT tenant FROM tenant_configs WHERE config_id = $1")
+                .bind(config_id)
+                .fetch_optional(self.shared.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        let Some(tenant) = tenant else {
+            return Ok(self.shared.clone());
+        };
+        let entry = self.entry(&tenant).await?.ok_or_else(|| {
+            config_common::Error::Internal(format!(
+                "config {} belongs to unknown tenant {}",
+                config_id, tenant
+            ))
+        })?;
+        Ok(entry.storage.clone())
+    }
+
+    /// The shared storage followed by that of every tenant
+    async fn all(&self) -> Result<Vec<Arc<PgConfigStorage>>> {
+        self.load().await?;
+        let mut storages = vec![self.shared.clone()];
+        storages.extend(
+            self.tenants
+                .read()
+                .await
+                .values()
+                .map(|entry| entry.storage.clone()),
+        );
+        Ok(storages)
+    }
+}
+
+#[async_trait]
+impl ConfigStorage for TenantStorage {
+    async fn get_config(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        self.storage_of(id).await?.get_config(id).await
+    }
+
+    async fn get_config_linearizable(&self, id: &str) -> Result<(ConfigMeta, ConfigContent)> {
+        self.storage_of(id).await?.get_config_linearizable(id).await
+    }
+
+    async fn create_config(&self, meta: ConfigMeta, content: ConfigContent) -> Result<ConfigMeta> {
+        let Some(entry) = self.entry(&meta.department).await? else {
+            return self.shared.create_config(meta, content).await;
+        };
+        sqlx::query(
+            "INSERT INTO tenant_configs (config_id, tenant) VALUES ($1, $2) \
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&meta.id)
+        .bind(&entry.tenant.name)
+        .execute(self.shared.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        entry.storage.create_config(meta, content).await
+    }
+
+    async fn update_config(
+        &self,
+        meta: ConfigMeta,
+        content: ConfigContent,
+        change_reason: Option<&str>,
+    ) -> Result<ConfigMeta> {
+        self.storage_of(&meta.id)
+            .await?
+            .update_config(meta, content, change_reason)
+            .await
+    }
+
+    async fn delete_config(&self, id: &str) -> Result<bool> {
+        let deleted = self.storage_of(id).await?.delete_config(id).await?;
+        sqlx::query("DELETE FROM tenant_configs WHERE config_id = $1")
+            .bind(id)
+            .execute(self.shared.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        Ok(deleted)
+    }
+
+    async fn list_configs(
+        &self,
+        filter: ConfigFilter,
+        page_size: i32,
+        page_number: i32,
+    ) -> Result<(Vec<ConfigMeta>, i32)> {
+        if let Some(department) = &filter.department {
+            return self
+                .storage_for(department)
+                .await?
+                .list_configs(filter, page_size, page_number)
+                .await;
+        }
+
+        // Every storage contributes up to the end of the page, then the pages are merged
+        let end = page_size * page_number.max(1);
+        let mut configs = Vec::new();
+        let mut total = 0;
+        for storage in self.all().await? {
+            let (page, count) = storage.list_configs(filter.clone(), end, 1).await?;
+            configs.extend(page);
+            total += count;
+        }
+        configs.sort_by(|a, b| {
+            (&a.namespace, &a.application, &a.environment, &a.name).cmp(&(
+                &b.namespace,
+                &b.application,
+                &b.environment,
+                &b.name,
+            ))
+        });
+        let configs = configs
+            .into_iter()
+            .skip((end - page_size) as usize)
+            .take(page_size as usize)
+            .collect();
+        Ok((configs, total))
+    }
+
+    async fn stream_configs(&self, filter: ConfigFilter) -> Result<ConfigMetaStream> {
+        if let Some(department) = &filter.department {
+            return self
+                .storage_for(department)
+                .await?
+                .stream_configs(filter)
+                .await;
+        }
+
+        // Storages one after another, each in listing order
+        let mut streams = Vec::new();
+        for storage in self.all().await? {
+            streams.push(storage.stream_configs(filter.clone()).await?);
+        }
+        Ok(stream::iter(streams).flatten().boxed())
+    }
+
+    async fn get_version_history(&self, id: &str) -> Result<Vec<ConfigVersion>> {
+        self.storage_of(id).await?.get_version_history(id).await
+    }
+
+    async fn get_version(
+        &self,
+        config_id: &str,
+        version: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        self.storage_of(config_id)
+            .await?
+            .get_version(config_id, version)
+            .await
+    }
+
+    async fn get_version_at(&self, config_id: &str, at: i64) -> Result<ConfigSnapshot> {
+        self.storage_of(config_id)
+            .await?
+            .get_version_at(config_id, at)
+            .await
+    }
+
+    async fn get_namespace_at(&self, namespace: &str, at: i64) -> Result<Vec<ConfigSnapshot>> {
+        let mut snapshots = Vec::new();
+        for storage in self.all().await? {
+            snapshots.extend(storage.get_namespace_at(namespace, at).await?);
+        }
+        Ok(snapshots)
+    }
+
+    async fn tag_version(
+        &self,
+        config_id: &str,
+        version: &str,
+        tag: &str,
+        created_by: &str,
+        created_at: i64,
+    ) -> Result<()> {
+        self.storage_of(config_id)
+            .await?
+            .tag_version(config_id, version, tag, created_by, created_at)
+            .await
+    }
+
+    async fn set_version_pinned(&self, config_id: &str, version: &str, pinned: bool) -> Result<()> {
+        self.storage_of(config_id)
+            .await?
+            .set_version_pinned(config_id, version, pinned)
+            .await
+    }
+
+    async fn delete_tag(&self, config_id: &str, tag: &str) -> Result<bool> {
+        self.storage_of(config_id)
+            .await?
+            .delete_tag(config_id, tag)
+            .await
+    }
+
+    async fn get_tagged_version(
+        &self,
+        config_id: &str,
+        tag: &str,
+    ) -> Result<(ConfigVersion, ConfigContent)> {
+        self.storage_of(config_id)
+            .await?
+            .get_tagged_version(config_id, tag)
+            .await
+    }
+
+    async fn create_version(
+        &self,
+        config_id: &str,
+        version: ConfigVersion,
+        content: ConfigContent,
+    ) -> Result<()> {
+        self.storage_of(config_id)
+            .await?
+            .create_version(config_id, version, content)
+            .await
+    }
+}
+
+#[async_trait]
+impl TenantManager for TenantStorage {
+    async fn provision_tenant(
+        &self,
+        name: &str,
+        key_id: Option<&str>,
+        created_by: &str,
+    ) -> Result<Tenant> {
+        if name.is_empty()
+            || name.len() > MAX_TENANT_NAME_LEN
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(config_common::Error::Validation(format!(
+                "tenant name must be 1 to {} lowercase letters, digits, '-' or '_'",
+                MAX_TENANT_NAME_LEN
+            )));
+        }
+        if self.entry(name).await?.is_some() {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "tenant {}",
+                name
+            )));
+        }
+        // Existing configs would be left behind in the shared schema
+        let filter = ConfigFilter {
+            department: Some(name.to_string()),
+            ..Default::default()
+        };
+        let (_, existing) = self.shared.list_configs(filter, 1, 1).await?;
+        if existing > 0 {
+            return Err(config_common::Error::Validation(format!(
+                "department {} already has {} configs in the shared schema",
+                name, existing
+            )));
+        }
+
+        // `a-b` and `a_b` map to the same schema, which must not be handed to both
+        let schema = format!("{}{}", TENANT_SCHEMA_PREFIX, name.replace('-', "_"));
+        let owner: Option<String> =
+            sqlx::query_scalar("SELECT name FROM tenants WHERE schema_name = $1")
+                .bind(&schema)
+                .fetch_optional(self.shared.pool())
+                .await
+                .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        if let Some(owner) = owner {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "schema {} of tenant {}",
+                schema, owner
+            )));
+        }
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS \"{}\"", schema))
+            .execute(self.shared.pool())
+            .await
+            .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        let pool = self.database.create_migration_pool(&schema).await?;
+        crate::migrate::run_tenant(&pool).await?;
+        pool.close().await;
+
+        let tenant = Tenant {
+            name: name.to_string(),
+            schema,
+            key_id: key_id.map(String::from),
+            created_at: chrono::Utc::now().timestamp(),
+            created_by: created_by.to_string(),
+        };
+        let result = sqlx::query(
+            r#"
+            INSERT INTO tenants (name, schema_name, key_id, created_at, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&tenant.name)
+        .bind(&tenant.schema)
+        .bind(&tenant.key_id)
+        .bind(tenant.created_at)
+        .bind(&tenant.created_by)
+        .execute(self.shared.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+        // Either the name or the schema was taken by a concurrent provisioning
+        if result.rows_affected() == 0 {
+            return Err(config_common::Error::AlreadyExists(format!(
+                "tenant {} or schema {}",
+                name, tenant.schema
+            )));
+        }
+
+        Ok(self.connect(tenant).await?.tenant.clone())
+    }
+
+    async fn get_tenant(&self, name: &str) -> Result<Tenant> {
+        self.entry(name)
+            .await?
+            .map(|entry| entry.tenant.clone())
+            .ok_or_else(|| config_common::Error::NotFound(format!("tenant {}", name)))
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<Tenant>> {
+        let rows = sqlx::query_as::<_, TenantRow>(&format!(
+            "SELECT {} FROM tenants ORDER BY name",
+            TENANT_COLUMNS
+        ))
+        .fetch_all(self.shared.pool())
+        .await
+        .map_err(|e| config_common::Error::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Tenant::from).collect())
+    }
+}
@@ -0,0 +1,221 @@
+use config_common::{ConfigContent, ConfigFormat, Result};
+use config_core::backup::BACKUP_FORMAT;
+use config_core::{BackupVerification, ConfigEncryption, SignedVerification, VerificationCheck};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashSet;
+
+use crate::blobs::BLOB_ENCODING;
+use crate::migrate::{MIGRATIONS_TABLE, MIGRATOR};
+use crate::restore::{table_problem, LoadedBackup};
+
+/// Algorithm of verification signatures
+pub const SIGNATURE_ALGORITHM: &str = "hmac-sha256";
+
+/// Tables whose content is sampled for decryption
+const CONTENT_TABLES: [&str; 2] = ["configs", "config_versions"];
+
+/// Content columns of a backed-up config or version
+#[derive(Deserialize)]
+struct StoredContent {
+    format: String,
+    content: String,
+    #[serde(default)]
+    content_encoding: Option<String>,
+    #[serde(default)]
+    is_encrypted: bool,
+}
+
+#[derive(Deserialize)]
+struct AppliedMigration {
+    version: i64,
+    #[serde(default)]
+    success: bool,
+}
+
+/// Check a backup without restoring it, decrypting up to `sample` of its encrypted contents
+pub(crate) async fn verify(
+    name: &str,
+    backup: &LoadedBackup,
+    encryption: Option<&dyn ConfigEncryption>,
+    sample: usize,
+    verified_by: &str,
+) -> BackupVerification {
+    let manifest = &backup.manifest;
+    let mut checks = vec![VerificationCheck {
+        name: "format".to_string(),
+        passed: manifest.format <= BACKUP_FORMAT,
+        detail: (manifest.format > BACKUP_FORMAT).then(|| {
+            format!(
+                "backup format {} is newer than the supported {}",
+                manifest.format, BACKUP_FORMAT
+            )
+        }),
+    }];
+
+    let (schema, schema_version) = check_schema(backup);
+    checks.push(schema);
+    for table in &manifest.tables {
+        let problem = table_problem(backup, table);
+        checks.push(VerificationCheck {
+            name: format!("checksum:{}", table.name),
+            passed: problem.is_none(),
+            detail: problem,
+        });
+    }
+
+    let (secrets, sampled, decrypted) = check_secrets(backup, encryption, sample).await;
+    checks.push(secrets);
+
+    BackupVerification {
+        backup: name.to_string(),
+        backup_created_at: manifest.created_at,
+        format: manifest.format,
+        schema_version,
+        verified_at: chrono::Utc::now().timestamp(),
+        verified_by: verified_by.to_string(),
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+        secrets_sampled: sampled,
+        secrets_decrypted: decrypted,
+    }
+}
+
+/// Sign a verification with the configured key
+pub fn sign(verification: BackupVerification, key: &str) -> Result<SignedVerification> {
+    let signature = signature(&verification, key)?;
+    Ok(SignedVerification {
+        verification,
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        signature,
+    })
+}
+
+/// Hex HMAC-SHA256 of the JSON encoding of a verification
+pub fn signature(verification: &BackupVerification, key: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+        .map_err(|e| config_common::Error::Internal(e.to_string()))?;
+    mac.update(&serde_json::to_vec(verification)?);
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// Compare the migrations recorded in the backup with those this server knows, so a backup
+/// taken by a newer server isn't restored under an older schema
+fn check_schema(backup: &LoadedBackup) -> (VerificationCheck, Option<i64>) {
+    let applied: Vec<i64> = backup
+        .rows(MIGRATIONS_TABLE)
+        .filter_map(|row| serde_json::from_str::<AppliedMigration>(row).ok())
+        .filter(|migration| migration.success)
+        .map(|migration| migration.version)
+        .collect();
+    let known: HashSet<i64> = MIGRATOR.iter().map(|m| m.version).collect();
+    let unknown: Vec<String> = applied
+        .iter()
+        .filter(|version| !known.contains(version))
+        .map(|version| version.to_string())
+        .collect();
+
+    let detail = if applied.is_empty() {
+        Some("backup predates schema migrations".to_string())
+    } else if !unknown.is_empty() {
+        Some(format!(
+            "backup has migrations this server doesn't know: {}",
+            unknown.join(", ")
+        ))
+    } else {
+        None
+    };
+    let check = VerificationCheck {
+        name: "schema".to_string(),
+        passed: unknown.is_empty(),
+        detail,
+    };
+    (check, applied.into_iter().max())
+}
+
+/// Decrypt a sample, spread evenly through the backup, of the content encrypted by this
+/// server. Content kept in the blob store is plaintext and client-sealed content can't be
+/// decrypted by the server, so neither is sampled.
+async fn check_secrets(
+    backup: &LoadedBackup,
+    encryption: Option<&dyn ConfigEncryption>,
+    sample: usize,
+) -> (VerificationCheck, usize, usize) {
+    let mut candidates = Vec::new();
+    for table in CONTENT_TABLES {
+        for row in backup.rows(table) {
+            let Ok(stored) = serde_json::from_str::<StoredContent>(row) else {
+                continue;
+            };
+            if stored.content_encoding.as_deref() == Some(BLOB_ENCODING) {
+                continue;
+            }
+            let Ok(format) = stored.format.parse::<ConfigFormat>() else {
+                continue;
+            };
+            let Ok(content) =
+                crate::compression::decode(stored.content, stored.content_encoding.as_deref())
+            else {
+                continue;
+            };
+            let content = ConfigContent {
+                format,
+                content,
+                is_encrypted: stored.is_encrypted,
+            };
+            if (content.is_encrypted && !config_core::recipients::is_client_sealed(&content))
+                || config_core::secrets::has_secret_fields(&content)
+            {
+                candidates.push((table, content));
+            }
+        }
+    }
+
+    let step = candidates.len().div_ceil(sample.max(1)).max(1);
+    let sampled: Vec<_> = candidates.into_iter().step_by(step).take(sample).collect();
+    let check = |passed: bool, detail: Option<String>| VerificationCheck {
+        name: "secrets".to_string(),
+        passed,
+        detail,
+    };
+    if sampled.is_empty() {
+        let detail = Some("backup holds no content encrypted by the server".to_string());
+        return (check(true, detail), 0, 0);
+    }
+    let Some(encryption) = encryption else {
+        let detail = Some("encryption is not configured".to_string());
+        return (check(false, detail), sampled.len(), 0);
+    };
+
+    let mut decrypted = 0;
+    let mut failure = None;
+    for (table, content) in &sampled {
+        let result = if content.is_encrypted {
+            encryption.decrypt(&content.content).await.map(|_| ())
+        } else {
+            config_core::secrets::decrypt_fields(content.clone(), encryption)
+                .await
+                .map(|_| ())
+        };
+        match result {
+            Ok(()) => decrypted += 1,
+            Err(e) => {
+                failure.get_or_insert_with(|| format!("content in {}: {}", table, e));
+            }
+        }
+    }
+    let detail = failure.map(|first| {
+        format!(
+            "{} of {} sampled contents failed to decrypt; {}",
+            sampled.len() - decrypted,
+            sampled.len(),
+            first
+        )
+    });
+    (
+        check(decrypted == sampled.len(), detail),
+        sampled.len(),
+        decrypted,
+    )
+}